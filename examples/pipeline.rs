@@ -0,0 +1,70 @@
+//! 最小集成示例：加载配置 → 执行流水线 → 打印统计
+//!
+//! 用法：
+//! ```bash
+//! cargo run --example pipeline -- [配置目录]
+//! ```
+//!
+//! 未指定配置目录时使用默认目录（Linux 下为 ~/.config/lianpkg）。
+
+use lianpkg::prelude::*;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let config_dir = std::env::args().nth(1).map(PathBuf::from);
+
+    // 1. 初始化配置目录（不存在时写入默认 config.toml / state.json）
+    let init = init_config(InitConfigInput {
+        config_dir,
+        use_exe_dir: false,
+    });
+    if !init.success {
+        eprintln!("init_config failed: {}", init.error.unwrap_or_default());
+        return ExitCode::FAILURE;
+    }
+
+    // 2. 加载配置
+    let loaded = load_config(LoadConfigInput {
+        config_path: init.config_path.clone(),
+    });
+    let Some(config) = loaded.config else {
+        eprintln!("load_config failed: {}", loaded.error.unwrap_or_default());
+        return ExitCode::FAILURE;
+    };
+    println!("config:   {}", init.config_path.display());
+    println!("workshop: {}", config.workshop_path.display());
+
+    // 3. 执行流水线
+    let on_progress = progress_callback(|p| println!("[{:>3}%] {}", p.progress, p.message));
+    let result = run_pipeline(RunPipelineInput {
+        config,
+        state_path: init.state_path,
+        wallpaper_ids: None,
+        reprocess_ids: None,
+        type_filter: None,
+        tag_filter: None,
+        overrides: None,
+        progress_callback: Some(on_progress),
+        debug_logger: None,
+        item_callback: None,
+        cancel: None,
+    });
+
+    // 4. 打印统计
+    let stats = &result.stats;
+    println!();
+    println!("wallpapers processed: {}", stats.wallpapers_processed);
+    println!("wallpapers skipped:   {}", stats.wallpapers_skipped);
+    println!("wallpapers filtered:  {}", stats.wallpapers_filtered);
+    println!("pkgs unpacked:        {}", stats.pkgs_unpacked);
+    println!("texs converted:       {}", stats.texs_converted);
+    println!("elapsed:              {} ms", stats.elapsed_ms);
+
+    if result.success {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("pipeline failed: {}", result.error.unwrap_or_default());
+        ExitCode::FAILURE
+    }
+}
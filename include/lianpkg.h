@@ -0,0 +1,55 @@
+/*
+ * lianpkg C ABI
+ *
+ * 构建：cargo rustc --lib --profile ffi --features ffi --crate-type cdylib
+ * 生成 target/ffi/liblianpkg.so / lianpkg.dll / liblianpkg.dylib。
+ * ffi profile 使用 panic = "unwind"，内部 panic 会被捕获并按失败返回；
+ * release profile 为 panic = "abort"，不能用来构建动态库。
+ *
+ * 所有函数返回 UTF-8 JSON 字符串，由 Rust 分配，
+ * 调用方用完后必须调用 lianpkg_free_string 释放。
+ * 入参错误或内部 panic 时返回 {"success": false, "error": "..."}。
+ */
+#ifndef LIANPKG_H
+#define LIANPKG_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* 一键流水线；config_dir 为 NULL 时使用默认配置目录。返回 QuickRunOutput */
+char *lianpkg_quick_run(const char *config_dir);
+
+/* 扫描壁纸；request 如 {"workshop_path": "...", "type_filter": ["scene"]}。返回 ScanWallpapersOutput */
+char *lianpkg_scan_wallpapers(const char *request);
+
+/* 预览 TEX；request 如 {"tex_path": "...", "output_format": "png"}。返回 PreviewTexOutput */
+char *lianpkg_preview_tex(const char *request);
+
+/* 释放上述函数返回的字符串；NULL 时不做任何事 */
+void lianpkg_free_string(char *ptr);
+
+/* lianpkg_decode_tex 输出的图像尺寸 */
+typedef struct LianpkgTexImage {
+    uint32_t width;
+    uint32_t height;
+} LianpkgTexImage;
+
+/*
+ * 把 TEX 第一层 mipmap 解码为 RGBA8 像素（width * height * 4 字节）。
+ * 失败时返回 NULL 且 *out_len 为 0；out_image 可为 NULL。
+ * 缓冲区由 Rust 分配，必须用 lianpkg_free_buffer(ptr, *out_len) 释放，不能交给 free()。
+ */
+uint8_t *lianpkg_decode_tex(const char *path, size_t *out_len, LianpkgTexImage *out_image);
+
+/* 释放 lianpkg_decode_tex 返回的缓冲区；len 必须与 out_len 一致；NULL 时不做任何事 */
+void lianpkg_free_buffer(uint8_t *ptr, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LIANPKG_H */
@@ -0,0 +1,357 @@
+//! `extern "C"` 导出函数
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::native::{paper, pipeline, tex};
+use crate::core::paper as core_paper;
+use crate::core::tex as core_tex;
+
+/// 预览 TEX 未指定质量时的默认值（与 CLI 一致）
+const DEFAULT_QUALITY: u8 = 85;
+
+/// [`lianpkg_decode_tex`] 输出的图像尺寸
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LianpkgTexImage {
+    /// 图像宽度（像素）
+    pub width: u32,
+    /// 图像高度（像素）
+    pub height: u32,
+}
+
+/// scan_wallpapers 的 JSON 入参
+#[derive(Debug, Deserialize)]
+struct ScanRequest {
+    workshop_path: PathBuf,
+    #[serde(default)]
+    type_filter: Option<Vec<String>>,
+    #[serde(default)]
+    tag_filter: Option<Vec<String>>,
+    #[serde(default)]
+    generate_thumbnails: Option<PathBuf>,
+    #[serde(default)]
+    cache: Option<PathBuf>,
+    /// 查找 .pkg 时向下搜索的子目录层数，缺省为 DEFAULT_PKG_DEPTH
+    #[serde(default)]
+    pkg_depth: Option<usize>,
+}
+
+/// preview_tex 的 JSON 入参
+#[derive(Debug, Deserialize)]
+struct PreviewRequest {
+    tex_path: PathBuf,
+    /// png / jpeg / webp / tiff，默认 png
+    #[serde(default)]
+    output_format: Option<String>,
+    #[serde(default)]
+    quality: Option<u8>,
+}
+
+/// 执行一键流水线，返回 JSON 序列化的 `QuickRunOutput`
+///
+/// `config_dir` 为 NULL 时使用默认配置目录。
+///
+/// # Safety
+///
+/// `config_dir` 必须为 NULL 或指向以 NUL 结尾的 UTF-8 字符串。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_quick_run(config_dir: *const c_char) -> *mut c_char {
+    json_guard(|| {
+        let config_dir = if config_dir.is_null() {
+            None
+        } else {
+            match read_str(config_dir) {
+                Ok(dir) => Some(PathBuf::from(dir)),
+                Err(e) => return error_json(&e),
+            }
+        };
+
+        to_c_json(&pipeline::quick_run(pipeline::QuickRunInput {
+            config_dir,
+            force_all: false,
+            progress_callback: None,
+        }))
+    })
+}
+
+/// 扫描壁纸，入参与返回值均为 JSON（返回 `ScanWallpapersOutput`）
+///
+/// 入参示例：`{"workshop_path": "/path/to/431960", "type_filter": ["scene"]}`
+///
+/// # Safety
+///
+/// `request` 必须为 NULL 或指向以 NUL 结尾的 UTF-8 字符串。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_scan_wallpapers(request: *const c_char) -> *mut c_char {
+    json_guard(|| {
+        let request: ScanRequest = match parse_request(request) {
+            Ok(r) => r,
+            Err(e) => return error_json(&e),
+        };
+
+        to_c_json(&paper::scan_wallpapers(paper::ScanWallpapersInput {
+            workshop_path: request.workshop_path,
+            type_filter: request.type_filter,
+            tag_filter: request.tag_filter,
+            generate_thumbnails: request.generate_thumbnails,
+            cache: request.cache,
+            pkg_depth: request.pkg_depth.unwrap_or(core_paper::DEFAULT_PKG_DEPTH),
+        }))
+    })
+}
+
+/// 预览 TEX 文件信息，入参与返回值均为 JSON（返回 `PreviewTexOutput`）
+///
+/// 入参示例：`{"tex_path": "/path/to/a.tex", "output_format": "png"}`
+///
+/// # Safety
+///
+/// `request` 必须为 NULL 或指向以 NUL 结尾的 UTF-8 字符串。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_preview_tex(request: *const c_char) -> *mut c_char {
+    json_guard(|| {
+        let request: PreviewRequest = match parse_request(request) {
+            Ok(r) => r,
+            Err(e) => return error_json(&e),
+        };
+
+        let quality = request.quality.unwrap_or(DEFAULT_QUALITY);
+        let output_format = match request.output_format.as_deref() {
+            None => core_tex::ImageOutputFormat::Png,
+            Some(name) => match core_tex::ImageOutputFormat::parse(name, quality) {
+                Some(format) => format,
+                None => return error_json(&format!("Unknown output format '{}'", name)),
+            },
+        };
+
+        to_c_json(&tex::preview_tex(tex::PreviewTexInput {
+            tex_path: request.tex_path,
+            output_format,
+        }))
+    })
+}
+
+/// 把 TEX 第一层 mipmap 解码为 RGBA8 像素，供 GUI 直接绘制而不写临时文件
+///
+/// 成功时返回长度为 `*out_len`（= width × height × 4）的缓冲区，尺寸写入 `out_image`；
+/// 失败（文件不存在、视频纹理、内部 panic 等）时返回 NULL 且 `*out_len` 为 0。
+/// 缓冲区由 Rust 分配，调用方必须用 [`lianpkg_free_buffer`] 连同长度一起释放，
+/// 不能交给 C 的 `free`。
+///
+/// # Safety
+///
+/// `path` 必须指向以 NUL 结尾的 UTF-8 字符串；`out_len` 必须为有效指针；
+/// `out_image` 为 NULL 或有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_decode_tex(
+    path: *const c_char,
+    out_len: *mut usize,
+    out_image: *mut LianpkgTexImage,
+) -> *mut u8 {
+    if out_len.is_null() {
+        return std::ptr::null_mut();
+    }
+    *out_len = 0;
+
+    let decoded = catch_unwind(|| {
+        let path = read_str(path).ok()?;
+        core_tex::decode_tex_to_rgba(core_tex::DecodeTexInput {
+            file_path: PathBuf::from(path),
+            strict_lz4: false,
+            mipmap: core_tex::MipmapSelection::First,
+            flip_vertical: false,
+        })
+        .ok()
+    });
+    let Ok(Some(decoded)) = decoded else {
+        return std::ptr::null_mut();
+    };
+
+    if !out_image.is_null() {
+        *out_image = LianpkgTexImage {
+            width: decoded.width,
+            height: decoded.height,
+        };
+    }
+    let buffer = decoded.rgba.into_boxed_slice();
+    *out_len = buffer.len();
+    Box::into_raw(buffer) as *mut u8
+}
+
+/// 释放 [`lianpkg_decode_tex`] 返回的缓冲区；传入 NULL 时不做任何事
+///
+/// # Safety
+///
+/// `ptr` 必须为 NULL 或 [`lianpkg_decode_tex`] 返回、且尚未释放的指针，
+/// `len` 必须等于当时写入 `out_len` 的值。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_free_buffer(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// 释放由本库返回的字符串；传入 NULL 时不做任何事
+///
+/// # Safety
+///
+/// `ptr` 必须为 NULL 或本库函数返回、且尚未释放的指针。
+#[no_mangle]
+pub unsafe extern "C" fn lianpkg_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+// ============================================================================
+// 内部工具函数
+// ============================================================================
+
+/// 捕获 panic，避免 unwind 穿过 FFI 边界（未定义行为）
+fn catch_unwind<T>(f: impl FnOnce() -> T) -> std::thread::Result<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+/// 执行返回 JSON 的导出函数体；panic 时返回 `{"success": false, "error": ...}`
+fn json_guard(f: impl FnOnce() -> *mut c_char) -> *mut c_char {
+    catch_unwind(f).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        error_json(&format!("Internal error: {}", message))
+    })
+}
+
+/// 读取 C 字符串
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err("Null pointer".to_string());
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// 读取并解析 JSON 入参
+unsafe fn parse_request<T: for<'de> Deserialize<'de>>(ptr: *const c_char) -> Result<T, String> {
+    serde_json::from_str(read_str(ptr)?).map_err(|e| format!("Invalid request JSON: {}", e))
+}
+
+/// 序列化为 JSON 并转移所有权给调用方
+fn to_c_json<T: Serialize>(value: &T) -> *mut c_char {
+    match serde_json::to_string(value) {
+        Ok(text) => into_c_string(text),
+        Err(e) => error_json(&format!("Failed to serialize result: {}", e)),
+    }
+}
+
+/// 构造 `{"success": false, "error": ...}`
+fn error_json(message: &str) -> *mut c_char {
+    into_c_string(serde_json::json!({ "success": false, "error": message }).to_string())
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+    // JSON 中的 NUL 均已转义，这里不会失败
+    CString::new(text).unwrap_or_default().into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::rgba_tex;
+
+    /// 调用导出函数并取回 JSON
+    fn call(f: unsafe extern "C" fn(*const c_char) -> *mut c_char, arg: &str) -> serde_json::Value {
+        let arg = CString::new(arg).unwrap();
+        unsafe {
+            let ptr = f(arg.as_ptr());
+            let value = serde_json::from_str(CStr::from_ptr(ptr).to_str().unwrap()).unwrap();
+            lianpkg_free_string(ptr);
+            value
+        }
+    }
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let workshop = std::env::temp_dir().join("lianpkg_ffi_workshop");
+        let _ = std::fs::remove_dir_all(&workshop);
+        std::fs::create_dir_all(workshop.join("100001")).unwrap();
+        std::fs::write(
+            workshop.join("100001").join("project.json"),
+            r#"{"title": "FFI", "type": "video"}"#,
+        )
+        .unwrap();
+
+        let request = serde_json::json!({ "workshop_path": workshop }).to_string();
+        let scan = call(lianpkg_scan_wallpapers, &request);
+        assert_eq!(scan["success"], true);
+        assert_eq!(scan["wallpapers"][0]["wallpaper_id"], "100001");
+        assert_eq!(scan["wallpapers"][0]["title"], "FFI");
+
+        let preview = call(lianpkg_preview_tex, r#"{"tex_path": "/nonexistent.tex"}"#);
+        assert_eq!(preview["success"], false);
+        assert_eq!(preview["error_kind"], "io");
+
+        let invalid = call(lianpkg_scan_wallpapers, "not json");
+        assert_eq!(invalid["success"], false);
+        assert!(invalid["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid request JSON"));
+
+        unsafe { lianpkg_free_string(std::ptr::null_mut()) };
+        let _ = std::fs::remove_dir_all(workshop);
+    }
+
+    #[test]
+    fn test_json_guard_reports_panic() {
+        let ptr = json_guard(|| panic!("boom"));
+        let value: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(ptr) }.to_str().unwrap()).unwrap();
+        unsafe { lianpkg_free_string(ptr) };
+        assert_eq!(value["success"], false);
+        assert_eq!(value["error"], "Internal error: boom");
+
+        assert!(catch_unwind::<()>(|| panic!("boom")).is_err());
+    }
+
+    #[test]
+    fn test_decode_tex_buffer_round_trip() {
+        let dir = std::env::temp_dir().join("lianpkg_ffi_decode");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = CString::new(dir.join("a.tex").to_str().unwrap()).unwrap();
+        let pixels = vec![1, 2, 3, 255, 4, 5, 6, 255];
+        std::fs::write(dir.join("a.tex"), rgba_tex((2, 1), &[vec![(2, 1, pixels)]])).unwrap();
+
+        unsafe {
+            let mut len = 0usize;
+            let mut image = LianpkgTexImage::default();
+            let ptr = lianpkg_decode_tex(path.as_ptr(), &mut len, &mut image);
+            assert!(!ptr.is_null());
+            assert_eq!((image.width, image.height, len), (2, 1, 8));
+
+            // 缓冲区是长度为 len 的 Box<[u8]>：按该布局取回所有权再交还给 lianpkg_free_buffer
+            let buffer = Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len));
+            assert_eq!(&buffer[..], &[1, 2, 3, 255, 4, 5, 6, 255]);
+            lianpkg_free_buffer(Box::into_raw(buffer) as *mut u8, len);
+        }
+
+        unsafe {
+            let mut len = 1usize;
+            let missing = CString::new("/nonexistent.tex").unwrap();
+            let ptr = lianpkg_decode_tex(missing.as_ptr(), &mut len, std::ptr::null_mut());
+            assert!(ptr.is_null());
+            assert_eq!(len, 0);
+            lianpkg_free_buffer(std::ptr::null_mut(), 0);
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1 +1,11 @@
-// 之后需要为 Flutter GUI 开发实现的 FFI 接口
\ No newline at end of file
+//! C ABI 导出（供 Flutter GUI 等通过 .so / .dll 调用）
+//!
+//! 需启用 `ffi` feature。所有接口均为 JSON 进、JSON 出：
+//! 返回的字符串由 Rust 分配，调用方用完后必须交给 [`lianpkg_free_string`] 释放。
+//! C 头文件见 `include/lianpkg.h`。
+
+#[cfg(feature = "ffi")]
+mod exports;
+
+#[cfg(feature = "ffi")]
+pub use exports::*;
@@ -1,3 +1,10 @@
+//! api 模块 - 对外接口层
+//!
+//! - `native`: 唯一的高级 API 实现，CLI、GUI 与库调用方共用
+//! - `ffi`: 基于 `native` 的 C ABI 导出（需启用 `ffi` feature）
+//! - `types`: 旧版结果类型，已弃用，仅为兼容保留
+
 pub mod ffi;
 pub mod native;
+#[deprecated(note = "legacy result types; use the XxxOutput structs in lianpkg::native")]
 pub mod types;
@@ -3,9 +3,156 @@
 //! 提供初始化、解析、保存等配置相关的便捷方法。
 //! 封装 core::cfg 的底层操作，提供更友好的 API。
 
-use crate::core::{cfg, path};
+use super::tex::ConflictPolicy;
+use crate::core::{cfg, paper, path};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// 分层配置的基础层文件名（与 config.toml 同目录，可选）
+pub const DEFAULT_CONFIG_FILE: &str = "default.toml";
+
+/// 环境变量覆盖值的类型
+#[derive(Debug, Clone, Copy)]
+enum EnvKind {
+    /// 字符串（路径、分级等）
+    Str,
+    /// 布尔值：true/false/1/0，忽略大小写
+    Bool,
+    /// 非负整数
+    Int,
+    /// 逗号分隔的字符串列表
+    List,
+}
+
+/// 可用环境变量覆盖的配置项：(变量名, 段, 键, 类型)
+const ENV_OVERRIDES: &[(&str, &str, &str, EnvKind)] = &[
+    (
+        "LIANPKG_WORKSHOP_PATH",
+        "wallpaper",
+        "workshop_path",
+        EnvKind::Str,
+    ),
+    ("LIANPKG_APP_ID", "wallpaper", "app_id", EnvKind::Str),
+    (
+        "LIANPKG_RAW_OUTPUT_PATH",
+        "wallpaper",
+        "raw_output_path",
+        EnvKind::Str,
+    ),
+    (
+        "LIANPKG_ENABLE_RAW",
+        "wallpaper",
+        "enable_raw_output",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_MAX_CONTENT_RATING",
+        "wallpaper",
+        "max_content_rating",
+        EnvKind::Str,
+    ),
+    ("LIANPKG_PKG_DEPTH", "wallpaper", "pkg_depth", EnvKind::Int),
+    (
+        "LIANPKG_PKG_TEMP_PATH",
+        "wallpaper",
+        "pkg_temp_path",
+        EnvKind::Str,
+    ),
+    (
+        "LIANPKG_UNPACKED_OUTPUT_PATH",
+        "unpack",
+        "unpacked_output_path",
+        EnvKind::Str,
+    ),
+    (
+        "LIANPKG_CLEAN_PKG_TEMP",
+        "unpack",
+        "clean_pkg_temp",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_CLEAN_UNPACKED",
+        "unpack",
+        "clean_unpacked",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_ARCHIVE_PKG_PATH",
+        "unpack",
+        "archive_pkg_path",
+        EnvKind::Str,
+    ),
+    ("LIANPKG_ONLY_TYPES", "unpack", "only_types", EnvKind::List),
+    (
+        "LIANPKG_TEX_OUTPUT_PATH",
+        "tex",
+        "converted_output_path",
+        EnvKind::Str,
+    ),
+    ("LIANPKG_TEX_STRICT", "tex", "strict", EnvKind::Bool),
+    ("LIANPKG_TEX_STRICT_LZ4", "tex", "strict_lz4", EnvKind::Bool),
+    (
+        "LIANPKG_TEX_ON_CONFLICT",
+        "tex",
+        "on_conflict",
+        EnvKind::Str,
+    ),
+    (
+        "LIANPKG_INCREMENTAL",
+        "pipeline",
+        "incremental",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_STRICT_INCREMENTAL",
+        "pipeline",
+        "strict_incremental",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_AUTO_UNPACK_PKG",
+        "pipeline",
+        "auto_unpack_pkg",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_AUTO_CONVERT_TEX",
+        "pipeline",
+        "auto_convert_tex",
+        EnvKind::Bool,
+    ),
+    (
+        "LIANPKG_FAIL_THRESHOLD",
+        "pipeline",
+        "fail_threshold",
+        EnvKind::Int,
+    ),
+    (
+        "LIANPKG_THUMBNAIL_SIZE",
+        "pipeline",
+        "thumbnail_size",
+        EnvKind::Int,
+    ),
+    (
+        "LIANPKG_MAX_MEMORY_MB",
+        "pipeline",
+        "max_memory_mb",
+        EnvKind::Int,
+    ),
+    (
+        "LIANPKG_KEEP_GOING",
+        "pipeline",
+        "keep_going",
+        EnvKind::Bool,
+    ),
+    ("LIANPKG_FAIL_FAST", "pipeline", "fail_fast", EnvKind::Bool),
+    (
+        "LIANPKG_CHECKPOINT_INTERVAL",
+        "pipeline",
+        "checkpoint_interval",
+        EnvKind::Int,
+    ),
+];
 
 // ============================================================================
 // 结构体定义
@@ -42,10 +189,16 @@ pub struct InitConfigOutput {
 pub struct RuntimeConfig {
     /// Workshop 路径
     pub workshop_path: PathBuf,
+    /// Steam App ID，None 表示 Wallpaper Engine（431960）
+    pub app_id: Option<String>,
     /// 原始壁纸输出路径
     pub raw_output_path: PathBuf,
     /// 是否启用原始壁纸输出
     pub enable_raw_output: bool,
+    /// 允许的最高内容分级，None 表示不过滤
+    pub max_content_rating: Option<paper::ContentRating>,
+    /// 查找 .pkg 时向下搜索的子目录层数（0 表示只看壁纸文件夹顶层）
+    pub pkg_depth: usize,
     /// Pkg 临时路径
     pub pkg_temp_path: PathBuf,
     /// 解包输出路径
@@ -54,21 +207,102 @@ pub struct RuntimeConfig {
     pub clean_pkg_temp: bool,
     /// 是否清理 unpacked
     pub clean_unpacked: bool,
+    /// PKG 归档目录，设置后清理前把 pkg_temp 中的 PKG 移动到这里
+    pub archive_pkg_path: Option<PathBuf>,
+    /// 只解包这些类型的壁纸（如 scene），None 表示不限
+    pub only_types: Option<Vec<String>>,
     /// Tex 转换输出路径（可选）
     pub converted_output_path: Option<PathBuf>,
+    /// 严格校验直通图片
+    pub tex_strict: bool,
+    /// LZ4 解压失败时不尝试其他解压策略
+    pub tex_strict_lz4: bool,
+    /// 多个 TEX 映射到同一输出路径时的处理策略
+    pub tex_on_conflict: ConflictPolicy,
     /// 流水线配置
     pub pipeline: PipelineConfig,
 }
 
 /// 流水线配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineConfig {
     /// 是否增量处理
     pub incremental: bool,
+    /// 增量模式下额外校验输出目录是否仍存在
+    pub strict_incremental: bool,
     /// 是否自动解包 pkg
     pub auto_unpack_pkg: bool,
     /// 是否自动转换 tex
     pub auto_convert_tex: bool,
+    /// 批处理失败熔断阈值，None 表示不启用
+    pub fail_threshold: Option<usize>,
+    /// 转换后生成缩略图的最大边长，None 表示不生成
+    pub thumbnail_size: Option<u32>,
+    /// 并行转换 TEX 的内存预算（MB），None 表示顺序转换
+    pub max_memory_mb: Option<u64>,
+    /// 某阶段失败后继续执行后续阶段，最后汇总错误
+    pub keep_going: bool,
+    /// 首个 PKG / TEX 失败即中止该批处理（等同 fail_threshold = 1）；
+    /// 为 false 时记录失败文件并继续处理其余文件
+    pub fail_fast: bool,
+    /// 每处理 N 个壁纸 / PKG 保存一次 state.json，0 表示只在结束时保存
+    pub checkpoint_interval: usize,
+}
+
+/// 默认值与 core::cfg 的默认模板保持一致
+/// config.toml 中缺失的字段也以此为准
+///
+/// 例外：缺失 `pipeline.incremental` 时一直视为启用（默认模板中显式写为 false）
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            workshop_path: path::expand_path_compat(&path::default_workshop_path(None)),
+            app_id: None,
+            raw_output_path: path::expand_path_compat(&path::default_raw_output_path()),
+            enable_raw_output: true,
+            max_content_rating: None,
+            pkg_depth: paper::DEFAULT_PKG_DEPTH,
+            pkg_temp_path: path::expand_path_compat(&path::default_pkg_temp_path()),
+            unpacked_output_path: path::expand_path_compat(&path::default_unpacked_output_path()),
+            clean_pkg_temp: true,
+            clean_unpacked: true,
+            archive_pkg_path: None,
+            only_types: None,
+            converted_output_path: None,
+            tex_strict: false,
+            tex_strict_lz4: false,
+            tex_on_conflict: ConflictPolicy::Rename,
+            pipeline: PipelineConfig::default(),
+        }
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            incremental: true,
+            strict_incremental: false,
+            auto_unpack_pkg: true,
+            auto_convert_tex: true,
+            fail_threshold: None,
+            thumbnail_size: None,
+            max_memory_mb: None,
+            keep_going: false,
+            fail_fast: true,
+            checkpoint_interval: 1,
+        }
+    }
+}
+
+impl PipelineConfig {
+    /// 实际生效的失败熔断阈值：fail_fast 时为 1，否则为 fail_threshold
+    pub fn effective_fail_threshold(&self) -> Option<usize> {
+        if self.fail_fast {
+            Some(1)
+        } else {
+            self.fail_threshold
+        }
+    }
 }
 
 /// 加载配置入参
@@ -131,7 +365,8 @@ pub struct SaveStateOutput {
 
 /// 初始化配置文件
 ///
-/// 确保 config.toml 和 state.json 都存在，不存在则创建默认内容
+/// 确保 config.toml 和 state.json 都存在，不存在则创建默认内容。
+/// 同目录存在 default.toml 时，新建的 config.toml 中所有配置项均为注释，取值由 default.toml 提供
 pub fn init_config(input: InitConfigInput) -> InitConfigOutput {
     // 确定配置目录
     let config_dir = input.config_dir.unwrap_or_else(|| {
@@ -149,10 +384,11 @@ pub fn init_config(input: InitConfigInput) -> InitConfigOutput {
     let config_path = config_dir.join("config.toml");
     let state_path = config_dir.join("state.json");
 
-    // 创建 config.toml
+    // 创建 config.toml（完整模板会遮蔽 default.toml 的全部取值，有基础层时只写注释模板）
+    let has_default = config_dir.join(DEFAULT_CONFIG_FILE).is_file();
     let config_result = cfg::create_config_toml(cfg::CreateConfigInput {
         path: config_path.clone(),
-        content: None,
+        content: has_default.then(|| cfg::layered_config_template(DEFAULT_CONFIG_FILE)),
     });
 
     // 创建 state.json
@@ -181,8 +417,11 @@ pub fn init_config(input: InitConfigInput) -> InitConfigOutput {
 
 /// 加载并解析 config.toml
 ///
-/// 将 TOML 配置文件解析为 RuntimeConfig 结构
+/// 将 TOML 配置文件解析为 RuntimeConfig 结构。
+/// 同目录存在 default.toml 时以其为基础层，config.toml 中的键深度合并覆盖其上
 pub fn load_config(input: LoadConfigInput) -> LoadConfigOutput {
+    let default_path = input.config_path.with_file_name(DEFAULT_CONFIG_FILE);
+
     // 读取文件
     let read_result = cfg::read_config_toml(cfg::ReadConfigInput {
         path: input.config_path,
@@ -199,8 +438,17 @@ pub fn load_config(input: LoadConfigInput) -> LoadConfigOutput {
         }
     };
 
-    // 解析 TOML
-    match parse_config_toml(&content) {
+    // 逐层覆盖：default.toml（可选）→ config.toml → LIANPKG_* 环境变量
+    let parsed = if default_path.is_file() {
+        layered_config(&default_path, &content)
+    } else {
+        toml::from_str(&content).map_err(|e| format!("TOML parse error: {}", e))
+    }
+    .and_then(|mut doc| {
+        apply_env_overrides(&mut doc, |name| std::env::var(name).ok())?;
+        parse_config_table(&doc)
+    });
+    match parsed {
         Ok(config) => LoadConfigOutput {
             success: true,
             config: Some(config),
@@ -231,10 +479,11 @@ pub fn load_state(input: LoadStateInput) -> LoadStateOutput {
         }
     };
 
-    match serde_json::from_str::<cfg::StateData>(&content) {
-        Ok(state) => LoadStateOutput {
+    // 旧版本结构在内存中升级，下次 save_state 时写回
+    match cfg::migrate_state(cfg::MigrateStateInput { content }) {
+        Ok(migrated) => LoadStateOutput {
             success: true,
-            state: Some(state),
+            state: Some(migrated.state),
             error: None,
         },
         Err(e) => LoadStateOutput {
@@ -289,6 +538,7 @@ pub fn add_processed_wallpaper(
     title: Option<String>,
     process_type: cfg::WallpaperProcessType,
     output_path: Option<String>,
+    stage: cfg::ProcessStage,
 ) {
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -303,9 +553,138 @@ pub fn add_processed_wallpaper(
         process_type,
         processed_at: now,
         output_path,
+        stage,
     });
 }
 
+/// 处理记录对应的预期输出目录
+///
+/// 优先使用记录中的 output_path，否则按当前配置推导；Skipped 没有输出，返回 None
+///
+/// 停在 Copied 阶段的记录尚无输出目录（PKG 还在 Pkg_Temp 中等待续跑），同样返回 None
+pub fn expected_output_dir(
+    config: &RuntimeConfig,
+    record: &cfg::ProcessedWallpaper,
+) -> Option<PathBuf> {
+    if record.stage == cfg::ProcessStage::Copied {
+        return None;
+    }
+    if let Some(ref p) = record.output_path {
+        return Some(PathBuf::from(p));
+    }
+
+    let id = &record.wallpaper_id;
+    match record.process_type {
+        cfg::WallpaperProcessType::Raw => Some(config.raw_output_path.join(id)),
+        cfg::WallpaperProcessType::Pkg => Some(config.unpacked_output_path.join(id)),
+        cfg::WallpaperProcessType::PkgTex => Some(match config.converted_output_path {
+            Some(ref converted) => converted.join(id),
+            None => config.unpacked_output_path.join(id).join("tex_converted"),
+        }),
+        cfg::WallpaperProcessType::Skipped => None,
+    }
+}
+
+/// 移除输出目录已不存在的处理记录，返回被移除的壁纸 ID
+///
+/// 用于严格增量模式：用户手动删除输出后，壁纸会被重新处理
+pub fn remove_stale_wallpapers(state: &mut cfg::StateData, config: &RuntimeConfig) -> Vec<String> {
+    let mut stale: Vec<String> = Vec::new();
+    for record in &state.processed_wallpapers {
+        let missing = expected_output_dir(config, record).is_some_and(|dir| !dir.exists());
+        if missing && !stale.contains(&record.wallpaper_id) {
+            stale.push(record.wallpaper_id.clone());
+        }
+    }
+    for id in &stale {
+        remove_processed_wallpaper(state, id);
+    }
+    stale
+}
+
+/// 找出 workshop 中已没有对应文件夹的处理记录，返回去重后的壁纸 ID
+///
+/// 用于清理取消订阅后残留的记录；workshop 目录无法读取时返回错误，避免误删全部记录
+pub fn find_unsubscribed_wallpapers(
+    state: &cfg::StateData,
+    workshop_path: &Path,
+) -> Result<Vec<String>, String> {
+    let dirs = paper::list_dirs(paper::ListDirsInput {
+        path: workshop_path.to_path_buf(),
+    })
+    .map_err(|e| format!("Failed to list workshop directory: {}", e))?
+    .dirs;
+
+    let mut missing: Vec<String> = Vec::new();
+    for record in &state.processed_wallpapers {
+        if !dirs.contains(&record.wallpaper_id) && !missing.contains(&record.wallpaper_id) {
+            missing.push(record.wallpaper_id.clone());
+        }
+    }
+    Ok(missing)
+}
+
+/// 移除壁纸的所有处理记录，返回移除条数
+pub fn remove_processed_wallpaper(state: &mut cfg::StateData, wallpaper_id: &str) -> usize {
+    let before = state.processed_wallpapers.len();
+    state
+        .processed_wallpapers
+        .retain(|w| w.wallpaper_id != wallpaper_id);
+    before - state.processed_wallpapers.len()
+}
+
+/// 更新壁纸最近一条记录的处理类型
+///
+/// 用于 TEX 转换完成后把 Pkg 回填为 PkgTex，返回是否找到对应记录
+pub fn update_process_type(
+    state: &mut cfg::StateData,
+    wallpaper_id: &str,
+    process_type: cfg::WallpaperProcessType,
+) -> bool {
+    match state
+        .processed_wallpapers
+        .iter_mut()
+        .rev()
+        .find(|w| w.wallpaper_id == wallpaper_id)
+    {
+        Some(record) => {
+            record.process_type = process_type;
+            true
+        }
+        None => false,
+    }
+}
+
+/// 更新壁纸最近一条记录的处理阶段，返回是否找到对应记录
+pub fn update_process_stage(
+    state: &mut cfg::StateData,
+    wallpaper_id: &str,
+    stage: cfg::ProcessStage,
+) -> bool {
+    match state
+        .processed_wallpapers
+        .iter_mut()
+        .rev()
+        .find(|w| w.wallpaper_id == wallpaper_id)
+    {
+        Some(record) => {
+            record.stage = stage;
+            true
+        }
+        None => false,
+    }
+}
+
+/// 未完成全部阶段的壁纸 ID（上次运行中断后留下的记录）
+pub fn unfinished_wallpapers(state: &cfg::StateData) -> Vec<String> {
+    state
+        .processed_wallpapers
+        .iter()
+        .filter(|w| w.stage != cfg::ProcessStage::Complete)
+        .map(|w| w.wallpaper_id.clone())
+        .collect()
+}
+
 /// 更新统计信息
 pub fn update_statistics(state: &mut cfg::StateData, wallpapers: u64, pkgs: u64, texs: u64) {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -327,39 +706,158 @@ pub fn update_statistics(state: &mut cfg::StateData, wallpapers: u64, pkgs: u64,
 // 内部工具函数
 // ============================================================================
 
-/// 解析 config.toml 内容为 RuntimeConfig
-fn parse_config_toml(content: &str) -> Result<RuntimeConfig, String> {
-    let doc: toml::Table =
+/// 读取 default.toml 作为基础层，并把 config.toml 的内容合并其上
+fn layered_config(default_path: &Path, content: &str) -> Result<toml::Table, String> {
+    let base_content = cfg::read_config_toml(cfg::ReadConfigInput {
+        path: default_path.to_path_buf(),
+    })
+    .map_err(|e| format!("Failed to read {}: {}", DEFAULT_CONFIG_FILE, e))?
+    .content;
+    let base: toml::Table = toml::from_str(&base_content)
+        .map_err(|e| format!("{} parse error: {}", DEFAULT_CONFIG_FILE, e))?;
+    let overlay: toml::Table =
         toml::from_str(content).map_err(|e| format!("TOML parse error: {}", e))?;
 
+    let mut merged = toml::Value::Table(base);
+    cfg::merge_toml_values(&mut merged, toml::Value::Table(overlay));
+    match merged {
+        toml::Value::Table(table) => Ok(table),
+        _ => Ok(toml::Table::new()),
+    }
+}
+
+/// 把已设置的 LIANPKG_* 环境变量写入配置表，覆盖文件中的同名键
+///
+/// 未设置或为空的变量会被忽略；`lookup` 通常为 `std::env::var`。
+fn apply_env_overrides(
+    doc: &mut toml::Table,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    for &(name, section, key, kind) in ENV_OVERRIDES {
+        let Some(raw) = lookup(name) else { continue };
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+
+        let value = match kind {
+            EnvKind::Str => toml::Value::String(raw.to_string()),
+            EnvKind::Bool => match raw.to_ascii_lowercase().as_str() {
+                "true" | "1" => toml::Value::Boolean(true),
+                "false" | "0" => toml::Value::Boolean(false),
+                _ => {
+                    return Err(format!(
+                        "Invalid value for {}: '{}' (expected true/false/1/0)",
+                        name, raw
+                    ))
+                }
+            },
+            EnvKind::Int => match raw.parse::<u32>() {
+                Ok(n) => toml::Value::Integer(n as i64),
+                Err(_) => {
+                    return Err(format!(
+                        "Invalid value for {}: '{}' (expected a non-negative integer)",
+                        name, raw
+                    ))
+                }
+            },
+            EnvKind::List => toml::Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| toml::Value::String(s.to_string()))
+                    .collect(),
+            ),
+        };
+
+        let table = doc
+            .entry(section)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        match table.as_table_mut() {
+            Some(table) => {
+                table.insert(key.to_string(), value);
+            }
+            None => return Err(format!("[{}] is not a table", section)),
+        }
+    }
+    Ok(())
+}
+
+/// 将已解析的 TOML 表转换为 RuntimeConfig
+fn parse_config_table(doc: &toml::Table) -> Result<RuntimeConfig, String> {
+    // 缺失字段的默认值（与默认模板一致）
+    let defaults = RuntimeConfig::default();
+
     // 解析 [wallpaper] 部分
     let wallpaper = doc
         .get("wallpaper")
         .and_then(|v| v.as_table())
         .ok_or("Missing [wallpaper] section")?;
 
-    let workshop_path = wallpaper
+    let app_id = match wallpaper
+        .get("app_id")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(id) if id.chars().all(|c| c.is_ascii_digit()) => Some(id.to_string()),
+        Some(id) => {
+            return Err(format!(
+                "Invalid app_id '{}' (expected a numeric Steam App ID)",
+                id
+            ))
+        }
+        None => None,
+    };
+
+    // workshop_path 缺失、留空或仍为默认 app 的路径时，按 app_id 重新定位
+    let workshop_path = match wallpaper
         .get("workshop_path")
         .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
         .map(path::expand_path_compat)
-        .unwrap_or_else(|| PathBuf::from(path::default_workshop_path()));
+    {
+        Some(p) if app_id.is_none() || p != defaults.workshop_path => p,
+        _ => path::expand_path_compat(&path::default_workshop_path(app_id.as_deref())),
+    };
 
     let raw_output_path = wallpaper
         .get("raw_output_path")
         .and_then(|v| v.as_str())
         .map(path::expand_path_compat)
-        .unwrap_or_else(|| PathBuf::from(path::default_raw_output_path()));
+        .unwrap_or(defaults.raw_output_path);
 
     let enable_raw_output = wallpaper
         .get("enable_raw_output")
         .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+        .unwrap_or(defaults.enable_raw_output);
+
+    let max_content_rating = match wallpaper
+        .get("max_content_rating")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    {
+        Some(s) => Some(paper::ContentRating::parse(s).ok_or_else(|| {
+            format!(
+                "Invalid max_content_rating '{}' (expected everyone/questionable/mature)",
+                s
+            )
+        })?),
+        None => None,
+    };
+
+    let pkg_depth = wallpaper
+        .get("pkg_depth")
+        .and_then(|v| v.as_integer())
+        .filter(|&n| n >= 0)
+        .map(|n| n as usize)
+        .unwrap_or(defaults.pkg_depth);
 
     let pkg_temp_path = wallpaper
         .get("pkg_temp_path")
         .and_then(|v| v.as_str())
         .map(path::expand_path_compat)
-        .unwrap_or_else(|| PathBuf::from(path::default_pkg_temp_path()));
+        .unwrap_or(defaults.pkg_temp_path);
 
     // 解析 [unpack] 部分
     let unpack = doc.get("unpack").and_then(|v| v.as_table());
@@ -368,17 +866,35 @@ fn parse_config_toml(content: &str) -> Result<RuntimeConfig, String> {
         .and_then(|u| u.get("unpacked_output_path"))
         .and_then(|v| v.as_str())
         .map(path::expand_path_compat)
-        .unwrap_or_else(|| PathBuf::from(path::default_unpacked_output_path()));
+        .unwrap_or(defaults.unpacked_output_path);
 
     let clean_pkg_temp = unpack
         .and_then(|u| u.get("clean_pkg_temp"))
         .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+        .unwrap_or(defaults.clean_pkg_temp);
 
     let clean_unpacked = unpack
         .and_then(|u| u.get("clean_unpacked"))
         .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+        .unwrap_or(defaults.clean_unpacked);
+
+    let archive_pkg_path = unpack
+        .and_then(|u| u.get("archive_pkg_path"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(path::expand_path_compat);
+
+    let only_types = unpack
+        .and_then(|u| u.get("only_types"))
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|types| !types.is_empty());
 
     // 解析 [tex] 部分
     let tex = doc.get("tex").and_then(|v| v.as_table());
@@ -389,6 +905,26 @@ fn parse_config_toml(content: &str) -> Result<RuntimeConfig, String> {
         .filter(|s| !s.is_empty())
         .map(path::expand_path_compat);
 
+    let tex_strict = tex
+        .and_then(|t| t.get("strict"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(defaults.tex_strict);
+
+    let tex_strict_lz4 = tex
+        .and_then(|t| t.get("strict_lz4"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(defaults.tex_strict_lz4);
+
+    let tex_on_conflict = match tex
+        .and_then(|t| t.get("on_conflict"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    {
+        Some(s) => ConflictPolicy::parse(s)
+            .ok_or_else(|| format!("Invalid on_conflict '{}' (expected rename/error)", s))?,
+        None => defaults.tex_on_conflict,
+    };
+
     // 解析 [pipeline] 部分
     let pipeline_section = doc.get("pipeline").and_then(|v| v.as_table());
 
@@ -396,26 +932,312 @@ fn parse_config_toml(content: &str) -> Result<RuntimeConfig, String> {
         incremental: pipeline_section
             .and_then(|p| p.get("incremental"))
             .and_then(|v| v.as_bool())
-            .unwrap_or(true),
+            .unwrap_or(defaults.pipeline.incremental),
+        strict_incremental: pipeline_section
+            .and_then(|p| p.get("strict_incremental"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.pipeline.strict_incremental),
         auto_unpack_pkg: pipeline_section
             .and_then(|p| p.get("auto_unpack_pkg"))
             .and_then(|v| v.as_bool())
-            .unwrap_or(true),
+            .unwrap_or(defaults.pipeline.auto_unpack_pkg),
         auto_convert_tex: pipeline_section
             .and_then(|p| p.get("auto_convert_tex"))
             .and_then(|v| v.as_bool())
-            .unwrap_or(true),
+            .unwrap_or(defaults.pipeline.auto_convert_tex),
+        fail_threshold: pipeline_section
+            .and_then(|p| p.get("fail_threshold"))
+            .and_then(|v| v.as_integer())
+            .filter(|&n| n > 0)
+            .map(|n| n as usize)
+            .or(defaults.pipeline.fail_threshold),
+        thumbnail_size: pipeline_section
+            .and_then(|p| p.get("thumbnail_size"))
+            .and_then(|v| v.as_integer())
+            .filter(|&n| n > 0)
+            .map(|n| n as u32)
+            .or(defaults.pipeline.thumbnail_size),
+        max_memory_mb: pipeline_section
+            .and_then(|p| p.get("max_memory_mb"))
+            .and_then(|v| v.as_integer())
+            .filter(|&n| n > 0)
+            .map(|n| n as u64)
+            .or(defaults.pipeline.max_memory_mb),
+        keep_going: pipeline_section
+            .and_then(|p| p.get("keep_going"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.pipeline.keep_going),
+        fail_fast: pipeline_section
+            .and_then(|p| p.get("fail_fast"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.pipeline.fail_fast),
+        checkpoint_interval: pipeline_section
+            .and_then(|p| p.get("checkpoint_interval"))
+            .and_then(|v| v.as_integer())
+            .filter(|&n| n >= 0)
+            .map(|n| n as usize)
+            .unwrap_or(defaults.pipeline.checkpoint_interval),
     };
 
     Ok(RuntimeConfig {
         workshop_path,
+        app_id,
         raw_output_path,
         enable_raw_output,
+        max_content_rating,
+        pkg_depth,
         pkg_temp_path,
         unpacked_output_path,
         clean_pkg_temp,
         clean_unpacked,
+        archive_pkg_path,
+        only_types,
         converted_output_path,
+        tex_strict,
+        tex_strict_lz4,
+        tex_on_conflict,
         pipeline,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 解析 config.toml 内容为 RuntimeConfig（不含环境变量层）
+    fn parse_config_toml(content: &str) -> Result<RuntimeConfig, String> {
+        let doc: toml::Table =
+            toml::from_str(content).map_err(|e| format!("TOML parse error: {}", e))?;
+        parse_config_table(&doc)
+    }
+
+    #[test]
+    fn test_parse_config_app_id() {
+        let config = parse_config_toml("[wallpaper]\napp_id = \"123\"\n").unwrap();
+        assert_eq!(config.app_id.as_deref(), Some("123"));
+        assert!(config.workshop_path.ends_with("workshop/content/123"));
+
+        // 显式配置的 workshop_path 优先
+        let config =
+            parse_config_toml("[wallpaper]\napp_id = \"123\"\nworkshop_path = \"/data/ws\"\n")
+                .unwrap();
+        assert_eq!(config.workshop_path, PathBuf::from("/data/ws"));
+
+        let config = parse_config_toml("[wallpaper]\n").unwrap();
+        assert_eq!(config.app_id, None);
+        assert!(config.workshop_path.ends_with("workshop/content/431960"));
+
+        let err = parse_config_toml("[wallpaper]\napp_id = \"abc\"\n").unwrap_err();
+        assert!(err.contains("Invalid app_id 'abc'"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_config_missing_incremental_defaults_to_true() {
+        let config = parse_config_toml("[wallpaper]\n").unwrap();
+        assert!(config.pipeline.incremental);
+
+        let config = parse_config_toml("[wallpaper]\n[pipeline]\nincremental = false\n").unwrap();
+        assert!(!config.pipeline.incremental);
+    }
+
+    #[test]
+    fn test_parse_config_pkg_depth() {
+        let config = parse_config_toml("[wallpaper]\n").unwrap();
+        assert_eq!(config.pkg_depth, paper::DEFAULT_PKG_DEPTH);
+
+        let config = parse_config_toml("[wallpaper]\npkg_depth = 0\n").unwrap();
+        assert_eq!(config.pkg_depth, 0);
+
+        // 负数视为未设置
+        let config = parse_config_toml("[wallpaper]\npkg_depth = -1\n").unwrap();
+        assert_eq!(config.pkg_depth, paper::DEFAULT_PKG_DEPTH);
+    }
+
+    #[test]
+    fn test_parse_config_expands_env_vars() {
+        std::env::set_var("LIANPKG_TEST_STEAM", "/mnt/steam");
+        let config = parse_config_toml(
+            "[wallpaper]\nworkshop_path = \"${LIANPKG_TEST_STEAM}/workshop\"\n\
+             raw_output_path = \"%LIANPKG_TEST_STEAM%/raw\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.workshop_path, PathBuf::from("/mnt/steam/workshop"));
+        assert_eq!(config.raw_output_path, PathBuf::from("/mnt/steam/raw"));
+    }
+
+    #[test]
+    fn test_find_unsubscribed_wallpapers() {
+        let workshop = std::env::temp_dir().join("lianpkg_find_unsubscribed");
+        let _ = std::fs::remove_dir_all(&workshop);
+        std::fs::create_dir_all(workshop.join("111")).unwrap();
+
+        let mut state = cfg::StateData::default();
+        for id in ["111", "222", "222", "333"] {
+            add_processed_wallpaper(
+                &mut state,
+                id.to_string(),
+                None,
+                cfg::WallpaperProcessType::Raw,
+                None,
+                cfg::ProcessStage::Complete,
+            );
+        }
+
+        let missing = find_unsubscribed_wallpapers(&state, &workshop).unwrap();
+        assert_eq!(missing, vec!["222", "333"]);
+
+        // workshop 不可读时不应把全部记录视为过期
+        let _ = std::fs::remove_dir_all(&workshop);
+        assert!(find_unsubscribed_wallpapers(&state, &workshop).is_err());
+    }
+
+    #[test]
+    fn test_load_config_layers_default_toml() {
+        let dir = std::env::temp_dir().join("lianpkg_layered_config");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[wallpaper]\nenable_raw_output = false\n[pipeline]\nincremental = true\n",
+        )
+        .unwrap();
+
+        // 无 default.toml 时保持单文件行为
+        let config = load_config(LoadConfigInput {
+            config_path: config_path.clone(),
+        })
+        .config
+        .unwrap();
+        assert!(!config.enable_raw_output);
+        // 未配置 fail_fast 时默认首个失败即中止
+        assert_eq!(config.pipeline.effective_fail_threshold(), Some(1));
+        assert!(config.workshop_path.ends_with("workshop/content/431960"));
+
+        std::fs::write(
+            dir.join(DEFAULT_CONFIG_FILE),
+            "[wallpaper]\nworkshop_path = \"/team/ws\"\nenable_raw_output = true\n\
+             [pipeline]\nincremental = false\nfail_fast = false\n",
+        )
+        .unwrap();
+        let config = load_config(LoadConfigInput {
+            config_path: config_path.clone(),
+        })
+        .config
+        .unwrap();
+        assert_eq!(config.workshop_path, PathBuf::from("/team/ws"));
+        assert!(!config.enable_raw_output);
+        assert!(config.pipeline.incremental);
+        assert!(!config.pipeline.fail_fast);
+
+        std::fs::write(dir.join(DEFAULT_CONFIG_FILE), "[wallpaper").unwrap();
+        let result = load_config(LoadConfigInput { config_path });
+        assert!(result.error.unwrap().contains(DEFAULT_CONFIG_FILE));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_init_config_keeps_default_toml_values() {
+        let dir = std::env::temp_dir().join("lianpkg_init_layered_config");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(DEFAULT_CONFIG_FILE),
+            "[wallpaper]\nworkshop_path = \"/team/ws\"\nenable_raw_output = false\n\
+             [tex]\non_conflict = \"error\"\n",
+        )
+        .unwrap();
+
+        let init = init_config(InitConfigInput {
+            config_dir: Some(dir.clone()),
+            use_exe_dir: false,
+        });
+        assert!(init.config_created);
+        let config = load_config(LoadConfigInput {
+            config_path: init.config_path.clone(),
+        })
+        .config
+        .unwrap();
+        assert_eq!(config.workshop_path, PathBuf::from("/team/ws"));
+        assert!(!config.enable_raw_output);
+        assert_eq!(config.tex_on_conflict, ConflictPolicy::Error);
+
+        // 取消注释的项覆盖 default.toml
+        let content = std::fs::read_to_string(&init.config_path).unwrap();
+        assert!(content.contains("# enable_raw_output = true"));
+        std::fs::write(
+            &init.config_path,
+            content.replace("# enable_raw_output = true", "enable_raw_output = true"),
+        )
+        .unwrap();
+        let config = load_config(LoadConfigInput {
+            config_path: init.config_path,
+        })
+        .config
+        .unwrap();
+        assert!(config.enable_raw_output);
+        assert_eq!(config.workshop_path, PathBuf::from("/team/ws"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_env_overrides_win_over_file() {
+        let env = |name: &str| match name {
+            "LIANPKG_WORKSHOP_PATH" => Some("/env/ws".to_string()),
+            "LIANPKG_ENABLE_RAW" => Some("FALSE".to_string()),
+            "LIANPKG_ONLY_TYPES" => Some("scene, video".to_string()),
+            "LIANPKG_FAIL_THRESHOLD" => Some("3".to_string()),
+            "LIANPKG_TEX_STRICT" => Some("1".to_string()),
+            "LIANPKG_TEX_STRICT_LZ4" => Some("true".to_string()),
+            "LIANPKG_APP_ID" => Some("".to_string()),
+            _ => None,
+        };
+        let mut doc: toml::Table = toml::from_str(
+            "[wallpaper]\nworkshop_path = \"/file/ws\"\nenable_raw_output = true\n\
+             [pipeline]\nfail_threshold = 10\n",
+        )
+        .unwrap();
+        apply_env_overrides(&mut doc, env).unwrap();
+        let config = parse_config_table(&doc).unwrap();
+
+        assert_eq!(config.workshop_path, PathBuf::from("/env/ws"));
+        assert!(!config.enable_raw_output);
+        assert_eq!(
+            config.only_types,
+            Some(vec!["scene".to_string(), "video".to_string()])
+        );
+        assert_eq!(config.pipeline.fail_threshold, Some(3));
+        assert!(config.tex_strict);
+        assert!(config.tex_strict_lz4);
+        // 空值视为未设置
+        assert_eq!(config.app_id, None);
+
+        let err = apply_env_overrides(&mut doc, |name| {
+            (name == "LIANPKG_KEEP_GOING").then(|| "yes".to_string())
+        })
+        .unwrap_err();
+        assert!(err.contains("LIANPKG_KEEP_GOING"));
+    }
+
+    #[test]
+    fn test_load_config_reads_env_overrides() {
+        let dir = std::env::temp_dir().join("lianpkg_env_config");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[wallpaper]\n[pipeline]\nmax_memory_mb = 64\n",
+        )
+        .unwrap();
+
+        std::env::set_var("LIANPKG_MAX_MEMORY_MB", "512");
+        let config = load_config(LoadConfigInput { config_path }).config;
+        std::env::remove_var("LIANPKG_MAX_MEMORY_MB");
+
+        assert_eq!(config.unwrap().pipeline.max_memory_mb, Some(512));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
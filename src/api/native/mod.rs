@@ -6,7 +6,7 @@
 //! ## 模块结构
 //!
 //! - `cfg`: 配置管理（初始化、加载、保存）
-//! - `paper`: 壁纸处理（扫描、复制）
+//! - `paper`: 壁纸处理（扫描、复制、打包）
 //! - `pkg`: PKG 处理（预览、解包）
 //! - `tex`: TEX 处理（预览、转换）
 //! - `pipeline`: 流水线执行（完整流程）
@@ -20,6 +20,9 @@
 //! let result = pipeline::quick_run(pipeline::QuickRunInput {
 //!     config_dir: None,  // 使用默认配置目录
 //!     force_all: false,  // 增量处理
+//!     progress_callback: Some(pipeline::progress_callback(|p| {
+//!         println!("[{}%] {}", p.progress, p.message);
+//!     })),
 //! });
 //!
 //! if result.success {
@@ -32,7 +35,10 @@
 //! use lianpkg::api::native::{cfg, paper, pkg, tex};
 //!
 //! // 1. 初始化配置
-//! let init = cfg::init_config(cfg::InitConfigInput { config_dir: None });
+//! let init = cfg::init_config(cfg::InitConfigInput {
+//!     config_dir: None,
+//!     use_exe_dir: false,
+//! });
 //!
 //! // 2. 加载配置
 //! let config = cfg::load_config(cfg::LoadConfigInput {
@@ -42,6 +48,11 @@
 //! // 3. 扫描壁纸
 //! let wallpapers = paper::scan_wallpapers(paper::ScanWallpapersInput {
 //!     workshop_path: config.workshop_path.clone(),
+//!     type_filter: None,
+//!     tag_filter: None,
+//!     generate_thumbnails: None,
+//!     cache: None,
+//!     pkg_depth: config.pkg_depth,
 //! });
 //!
 //! // 4. 复制壁纸
@@ -51,6 +62,13 @@
 //!     raw_output_path: config.raw_output_path,
 //!     pkg_temp_path: config.pkg_temp_path.clone(),
 //!     enable_raw: config.enable_raw_output,
+//!     max_rating: config.max_content_rating,
+//!     only_types: config.only_types.clone(),
+//!     type_filter: None,
+//!     tag_filter: None,
+//!     cache: None,
+//!     force: false,
+//!     pkg_depth: config.pkg_depth,
 //! });
 //!
 //! // 5. 解包 PKG
@@ -63,6 +81,9 @@
 //! let converted = tex::convert_all(tex::ConvertAllInput {
 //!     unpacked_path: config.unpacked_output_path,
 //!     output_path: config.converted_output_path,
+//!     strict: config.tex_strict,
+//!     in_place: false,
+//!     remove_source: false,
 //! });
 //! ```
 
@@ -72,17 +93,28 @@ pub mod pipeline;
 pub mod pkg;
 pub mod tex;
 
+mod utl;
+
+pub use utl::CancelFlag;
+
 // ============================================================================
 // 导出配置模块
 // ============================================================================
 pub use cfg::{
     add_processed_wallpaper,
+    expected_output_dir,
+    find_unsubscribed_wallpapers,
     // 接口
     init_config,
     is_wallpaper_processed,
     load_config,
     load_state,
+    remove_processed_wallpaper,
+    remove_stale_wallpapers,
     save_state,
+    unfinished_wallpapers,
+    update_process_stage,
+    update_process_type,
     update_statistics,
     // 结构体
     InitConfigInput,
@@ -101,10 +133,22 @@ pub use cfg::{
 // 导出壁纸模块
 // ============================================================================
 pub use paper::{
+    archive_converted,
     copy_wallpapers,
+    copy_wallpapers_with_callbacks,
+    copy_wallpapers_with_progress,
+    find_duplicates,
     get_wallpaper_detail,
+    id_matches,
+    id_pattern_matches,
+    rating_allowed,
     // 接口
     scan_wallpapers,
+    tags_allowed,
+    type_allowed,
+    CopiedCallback,
+    CopyProgress,
+    CopyProgressCallback,
     CopyResult,
     CopyResultType,
     CopyStats,
@@ -112,6 +156,12 @@ pub use paper::{
     CopyWallpapersOutput,
     ScanStats,
     // 结构体
+    ArchiveConvertedInput,
+    ArchiveConvertedOutput,
+    ArchiveResult,
+    DuplicateGroup,
+    FindDuplicatesInput,
+    FindDuplicatesOutput,
     ScanWallpapersInput,
     ScanWallpapersOutput,
     WallpaperInfo,
@@ -121,11 +171,20 @@ pub use paper::{
 // 导出 PKG 模块
 // ============================================================================
 pub use pkg::{
+    // 接口
+    archive_pkgs,
+    extract_entry,
     get_tex_files_from_unpacked,
     preview_pkg,
-    // 接口
     unpack_all,
+    unpack_all_with_callbacks,
+    unpack_all_with_progress,
     unpack_single,
+    ArchivePkgResult,
+    ArchivePkgsInput,
+    ArchivePkgsOutput,
+    ExtractEntryInput,
+    ExtractEntryOutput,
     PkgFileEntry,
     PkgPreview,
     PreviewPkgInput,
@@ -133,6 +192,11 @@ pub use pkg::{
     // 结构体
     UnpackAllInput,
     UnpackAllOutput,
+    UnpackEntryProgress,
+    // 回调类型
+    UnpackEntryProgressCallback,
+    UnpackProgress,
+    UnpackProgressCallback,
     UnpackResult,
     UnpackStats,
     UnpackedFile,
@@ -144,13 +208,21 @@ pub use pkg::{
 pub use tex::{
     // 接口
     convert_all,
+    convert_all_with_progress,
     convert_single,
+    make_thumbnail,
     preview_tex,
     // 结构体
+    ConflictPolicy,
     ConvertAllInput,
     ConvertAllOutput,
+    ConvertGroupStats,
+    ConvertProgress,
+    // 回调类型
+    ConvertProgressCallback,
     ConvertResult,
     ConvertStats,
+    OutputConflict,
     PreviewTexInput,
     PreviewTexOutput,
     TexPreview,
@@ -162,20 +234,32 @@ pub use tex::{
 pub use pipeline::{
     clean_unpacked_dir,
     copy_metadata_to_tex_converted,
+    detect_cross_device,
+    estimate_conversion,
     estimate_disk_usage,
+    no_progress,
+    progress_callback,
     quick_run,
     // 接口
     run_pipeline,
     run_pkg_only,
+    run_pkg_only_with_config,
     run_tex_only,
+    run_tex_only_with_config,
+    CrossDeviceHint,
     DebugLogCallback,
+    DiskEstimate,
     DebugLogEvent,
     DebugLogType,
+    EstimateConversionInput,
     EstimateDiskInput,
     EstimateDiskOutput,
+    ItemCallback,
+    PipelineItemEvent,
     PipelineOverrides,
     PipelineProgress,
     PipelineStage,
+    PipelineStageError,
     PipelineStats,
     // 回调类型
     ProgressCallback,
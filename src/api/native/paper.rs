@@ -1,11 +1,21 @@
 //! 壁纸处理高级接口
 //!
 //! 封装 core::paper 的底层操作，提供更友好的 API。
-//! 支持扫描、预览、复制等操作。
+//! 支持扫描、预览、复制、打包、查重等操作。
 
-use crate::core::paper;
+use super::utl::{is_cancelled, CancelFlag};
+use crate::core::{paper, path};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 扫描时生成的缩略图最长边（像素）
+pub const SCAN_THUMBNAIL_SIZE: u32 = 256;
+
+/// 扫描缓存格式版本，WallpaperInfo 结构或 mtime 计算方式变化时递增以丢弃旧缓存
+const SCAN_CACHE_VERSION: u32 = 2;
 
 // ============================================================================
 // 结构体定义
@@ -16,6 +26,16 @@ use std::path::PathBuf;
 pub struct ScanWallpapersInput {
     /// Workshop 路径
     pub workshop_path: PathBuf,
+    /// 只保留这些类型的壁纸（如 scene、video），None 表示不限
+    pub type_filter: Option<Vec<String>>,
+    /// 只保留带有任一指定标签的壁纸，None 表示不限
+    pub tag_filter: Option<Vec<String>>,
+    /// 缩略图缓存目录：指定时为每个壁纸的预览图生成 `{id}.png`，None 表示不生成
+    pub generate_thumbnails: Option<PathBuf>,
+    /// 扫描缓存文件（JSON）：修改时间未变的壁纸直接复用上次的元数据，None 表示不缓存
+    pub cache: Option<PathBuf>,
+    /// 查找 .pkg 时向下搜索的子目录层数，通常取 [`RuntimeConfig::pkg_depth`](super::RuntimeConfig::pkg_depth)
+    pub pkg_depth: usize,
 }
 
 /// 扫描壁纸返回值
@@ -38,10 +58,21 @@ pub struct WallpaperInfo {
     pub wallpaper_id: String,
     /// 壁纸标题
     pub title: Option<String>,
+    /// 壁纸描述
+    pub description: Option<String>,
+    /// 壁纸标签（project.json 缺失该字段时为空）
+    pub tags: Vec<String>,
     /// 壁纸类型（scene/video/web 等）
     pub wallpaper_type: Option<String>,
     /// 预览图路径
     pub preview_path: Option<PathBuf>,
+    /// 缩略图路径（仅在扫描时指定了缓存目录且生成成功时存在）
+    #[serde(default)]
+    pub thumbnail_path: Option<PathBuf>,
+    /// 内容分级（project.json 缺失该字段时为 Everyone）
+    pub content_rating: paper::ContentRating,
+    /// preset 引用的 base 壁纸 ID（仅 preset 类型）
+    pub preset_base: Option<String>,
     /// 是否包含 pkg 文件
     pub has_pkg: bool,
     /// pkg 文件列表
@@ -59,6 +90,31 @@ pub struct ScanStats {
     pub pkg_count: usize,
     /// 原始壁纸数（不含 pkg）
     pub raw_count: usize,
+    /// 被类型/标签过滤排除的壁纸数（不计入以上统计）
+    #[serde(default)]
+    pub excluded: usize,
+    /// 命中扫描缓存、未重新读取元数据的壁纸数
+    #[serde(default)]
+    pub cache_hits: usize,
+}
+
+/// 扫描缓存文件内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScanCache {
+    version: u32,
+    /// 生成缓存时的 pkg 搜索层数，与本次不同时整个缓存失效
+    #[serde(default)]
+    pkg_depth: usize,
+    /// 壁纸文件夹路径 → 缓存条目
+    entries: HashMap<String, ScanCacheEntry>,
+}
+
+/// 单个壁纸的扫描缓存
+#[derive(Debug, Serialize, Deserialize)]
+struct ScanCacheEntry {
+    /// 文件夹与 project.json 中较新的修改时间（纳秒）
+    mtime: u64,
+    info: WallpaperInfo,
 }
 
 /// 复制壁纸入参
@@ -74,6 +130,22 @@ pub struct CopyWallpapersInput {
     pub pkg_temp_path: PathBuf,
     /// 是否复制原始壁纸
     pub enable_raw: bool,
+    /// 允许的最高内容分级，None 表示不过滤
+    pub max_rating: Option<paper::ContentRating>,
+    /// 只复制这些类型的 pkg 壁纸（如 scene），None 表示不限；原始壁纸不受影响
+    pub only_types: Option<Vec<String>>,
+    /// 扫描时的类型过滤（对全部壁纸生效），None 表示不限
+    pub type_filter: Option<Vec<String>>,
+    /// 扫描时的标签过滤，None 表示不限
+    pub tag_filter: Option<Vec<String>>,
+    /// 扫描缓存文件，语义同 [`ScanWallpapersInput::cache`]
+    pub cache: Option<PathBuf>,
+    /// 忽略已有的原始壁纸输出，完整重新复制
+    pub force: bool,
+    /// 查找 .pkg 时向下搜索的子目录层数
+    pub pkg_depth: usize,
+    /// 取消标志，置位后复制完当前壁纸即停止，结果只包含已完成的部分
+    pub cancel: Option<CancelFlag>,
 }
 
 /// 复制壁纸返回值
@@ -83,6 +155,12 @@ pub struct CopyWallpapersOutput {
     pub success: bool,
     /// 复制结果列表
     pub results: Vec<CopyResult>,
+    /// 因内容分级被过滤的壁纸
+    pub filtered: Vec<WallpaperInfo>,
+    /// 因壁纸类型被跳过的 pkg 壁纸
+    pub type_skipped: Vec<WallpaperInfo>,
+    /// 被跳过的 preset 壁纸（只含参数覆盖，没有可提取的资源）
+    pub presets: Vec<WallpaperInfo>,
     /// 统计信息
     pub stats: CopyStats,
     /// 错误信息
@@ -96,6 +174,8 @@ pub struct CopyResult {
     pub wallpaper_id: String,
     /// 壁纸标题
     pub title: Option<String>,
+    /// 壁纸类型（scene/video/web 等）
+    pub wallpaper_type: Option<String>,
     /// 处理类型
     pub result_type: CopyResultType,
     /// 复制的 pkg 文件路径
@@ -113,6 +193,23 @@ pub enum CopyResultType {
     Skipped,
 }
 
+/// 复制进度（每开始处理一个壁纸报告一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyProgress {
+    /// 当前壁纸 ID
+    pub wallpaper_id: String,
+    /// 当前序号（从 1 开始）
+    pub current: usize,
+    /// 待处理壁纸总数（含会被过滤的壁纸）
+    pub total: usize,
+}
+
+/// 复制进度回调函数类型
+pub type CopyProgressCallback<'a> = &'a dyn Fn(CopyProgress);
+
+/// 单个壁纸复制完成回调函数类型（被过滤的壁纸不回调）
+pub type CopiedCallback<'a> = &'a dyn Fn(&CopyResult);
+
 /// 复制统计
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CopyStats {
@@ -122,8 +219,93 @@ pub struct CopyStats {
     pub pkg_copied: usize,
     /// 跳过数
     pub skipped: usize,
+    /// 因内容分级被过滤数
+    pub filtered: usize,
+    /// 因壁纸类型被跳过数
+    pub type_skipped: usize,
+    /// 跳过的 preset 壁纸数
+    pub presets: usize,
     /// 总 pkg 文件数
     pub total_pkg_files: usize,
+    /// 原始壁纸中新复制或更新的文件数（未变化的文件不会重复复制）
+    #[serde(default)]
+    pub files_updated: usize,
+}
+
+/// 打包转换结果入参
+#[derive(Debug, Clone)]
+pub struct ArchiveConvertedInput {
+    /// 转换输出目录（每个子目录为一个壁纸）
+    pub converted_path: PathBuf,
+    /// zip 输出目录
+    pub output_path: PathBuf,
+    /// 每个壁纸单独打包
+    pub per_wallpaper: bool,
+    /// 单独打包时文件名附带壁纸标题（`<id> - <title>.zip`）
+    pub with_title: bool,
+}
+
+/// 打包转换结果返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveConvertedOutput {
+    /// 是否成功（全部 zip 写入成功）
+    pub success: bool,
+    /// 每个 zip 的结果
+    pub results: Vec<ArchiveResult>,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/// 单个 zip 打包结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveResult {
+    /// 打包的源目录
+    pub source_path: PathBuf,
+    /// 输出 zip 路径
+    pub zip_path: PathBuf,
+    /// 是否成功
+    pub success: bool,
+    /// 写入的文件数量
+    pub file_count: usize,
+    /// zip 文件大小（字节）
+    pub zip_size: u64,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/// 查找重复壁纸入参
+#[derive(Debug, Clone)]
+pub struct FindDuplicatesInput {
+    /// Workshop 路径
+    pub workshop_path: PathBuf,
+    /// 只检查指定的壁纸 ID，None 表示全部
+    pub wallpaper_ids: Option<Vec<String>>,
+}
+
+/// 查找重复壁纸返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindDuplicatesOutput {
+    /// 是否成功
+    pub success: bool,
+    /// 疑似重复的壁纸组（每组至少 2 个）
+    pub groups: Vec<DuplicateGroup>,
+    /// 参与比对的壁纸数
+    pub checked: usize,
+    /// 无法计算指纹的壁纸（ID, 原因）
+    pub unreadable: Vec<(String, String)>,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/// 一组内容指纹相同的壁纸
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// 内容指纹（十六进制）
+    pub fingerprint: String,
+    /// 内容文件总大小（字节）
+    pub total_size: u64,
+    /// 组内壁纸，按 ID 排序
+    pub wallpapers: Vec<WallpaperInfo>,
 }
 
 // ============================================================================
@@ -132,7 +314,8 @@ pub struct CopyStats {
 
 /// 扫描 Workshop 目录下的所有壁纸
 ///
-/// 返回壁纸列表及其基本信息，用于预览和选择
+/// 返回壁纸列表及其基本信息，用于预览和选择。
+/// 指定 type_filter / tag_filter 时，不匹配的壁纸直接排除，仅计入 `stats.excluded`
 pub fn scan_wallpapers(input: ScanWallpapersInput) -> ScanWallpapersOutput {
     // 列出所有目录
     let list_result = match paper::list_dirs(paper::ListDirsInput {
@@ -152,35 +335,57 @@ pub fn scan_wallpapers(input: ScanWallpapersInput) -> ScanWallpapersOutput {
     let mut wallpapers = Vec::new();
     let mut stats = ScanStats::default();
 
+    // 旧缓存按需取出条目；新缓存只保留本次仍存在的文件夹
+    let mut old_cache = input
+        .cache
+        .as_deref()
+        .map(|path| load_scan_cache(path, input.pkg_depth))
+        .unwrap_or_default();
+    let mut new_cache = ScanCache {
+        version: SCAN_CACHE_VERSION,
+        pkg_depth: input.pkg_depth,
+        entries: HashMap::new(),
+    };
+
     for dir_name in list_result.dirs {
         let folder_path = input.workshop_path.join(&dir_name);
+        let key = folder_path.to_string_lossy().into_owned();
+        let mtime = folder_mtime(&folder_path, input.pkg_depth);
 
-        // 读取元数据（失败时使用默认值）
-        let (title, wallpaper_type, preview_path) = match paper::read_meta(paper::ReadMetaInput {
-            folder: folder_path.clone(),
-        }) {
-            Ok(r) => (
-                r.meta.title,
-                r.meta.wallpaper_type,
-                r.meta.preview.map(|p| folder_path.join(p)),
-            ),
-            Err(_) => (None, None, None),
+        let cached = old_cache
+            .entries
+            .remove(&key)
+            .filter(|entry| Some(entry.mtime) == mtime);
+        let mut wallpaper_info = match cached {
+            Some(entry) => {
+                stats.cache_hits += 1;
+                entry.info
+            }
+            None => build_wallpaper_info(dir_name, folder_path, input.pkg_depth),
         };
 
-        // 检查 pkg 文件
-        let pkg_result = paper::check_pkg(paper::CheckPkgInput {
-            folder: folder_path.clone(),
-        });
+        if let (Some(_), Some(mtime)) = (&input.cache, mtime) {
+            new_cache.entries.insert(
+                key,
+                ScanCacheEntry {
+                    mtime,
+                    info: wallpaper_info.clone(),
+                },
+            );
+        }
 
-        let wallpaper_info = WallpaperInfo {
-            wallpaper_id: dir_name,
-            title,
-            wallpaper_type,
-            preview_path,
-            has_pkg: pkg_result.has_pkg,
-            pkg_files: pkg_result.pkg_files,
-            folder_path,
-        };
+        if !type_allowed(
+            wallpaper_info.wallpaper_type.as_deref(),
+            input.type_filter.as_deref(),
+        ) || !tags_allowed(&wallpaper_info.tags, input.tag_filter.as_deref())
+        {
+            stats.excluded += 1;
+            continue;
+        }
+
+        if let Some(ref cache_dir) = input.generate_thumbnails {
+            wallpaper_info.thumbnail_path = cached_thumbnail(&wallpaper_info, cache_dir);
+        }
 
         // 更新统计
         stats.total_count += 1;
@@ -193,6 +398,11 @@ pub fn scan_wallpapers(input: ScanWallpapersInput) -> ScanWallpapersOutput {
         wallpapers.push(wallpaper_info);
     }
 
+    // 缓存只是加速手段，写入失败不影响扫描结果
+    if let Some(ref cache_path) = input.cache {
+        save_scan_cache(cache_path, &new_cache);
+    }
+
     ScanWallpapersOutput {
         success: true,
         wallpapers,
@@ -201,19 +411,107 @@ pub fn scan_wallpapers(input: ScanWallpapersInput) -> ScanWallpapersOutput {
     }
 }
 
+/// 读取扫描缓存，文件缺失、损坏、版本或 pkg 搜索层数不符时返回空缓存
+fn load_scan_cache(path: &Path, pkg_depth: usize) -> ScanCache {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<ScanCache>(&bytes).ok())
+        .filter(|cache| cache.version == SCAN_CACHE_VERSION && cache.pkg_depth == pkg_depth)
+        .unwrap_or_default()
+}
+
+/// 写入扫描缓存
+fn save_scan_cache(path: &Path, cache: &ScanCache) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_vec(cache) {
+        let _ = fs::write(path, content);
+    }
+}
+
+/// 壁纸文件夹的修改时间（纳秒）
+///
+/// 增删 pkg 等文件只更新其所在目录的时间，就地修改 project.json 则只更新文件本身；
+/// 取文件夹、project.json 与 pkg 搜索范围内（pkg_depth 层）各子目录中最新者
+fn folder_mtime(folder: &Path, pkg_depth: usize) -> Option<u64> {
+    let dir = path_mtime(folder)?;
+    let meta = path_mtime(&folder.join("project.json")).unwrap_or(0);
+    let subdirs = newest_subdir_mtime(folder, pkg_depth);
+    Some(dir.max(meta).max(subdirs))
+}
+
+/// dir 下至多 depth 层子目录中最新的修改时间（纳秒），没有子目录时为 0
+fn newest_subdir_mtime(dir: &Path, depth: usize) -> u64 {
+    if depth == 0 {
+        return 0;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|e| {
+            let path = e.path();
+            let own = path_mtime(&path).unwrap_or(0);
+            own.max(newest_subdir_mtime(&path, depth - 1))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// 文件或目录的修改时间（纳秒）
+fn path_mtime(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+}
+
 /// 复制壁纸到目标目录
 ///
 /// 可以选择复制全部或指定的壁纸
 pub fn copy_wallpapers(input: CopyWallpapersInput) -> CopyWallpapersOutput {
+    copy_wallpapers_with_progress(input, None)
+}
+
+/// 复制壁纸到目标目录（带进度回调）
+///
+/// 每开始处理一个壁纸调用一次 `on_progress`
+pub fn copy_wallpapers_with_progress(
+    input: CopyWallpapersInput,
+    on_progress: Option<CopyProgressCallback>,
+) -> CopyWallpapersOutput {
+    copy_wallpapers_with_callbacks(input, on_progress, None)
+}
+
+/// 复制壁纸到目标目录（带进度与完成回调）
+///
+/// 每开始处理一个壁纸调用一次 `on_progress`，每复制完一个壁纸调用一次 `on_copied`
+pub fn copy_wallpapers_with_callbacks(
+    input: CopyWallpapersInput,
+    on_progress: Option<CopyProgressCallback>,
+    on_copied: Option<CopiedCallback>,
+) -> CopyWallpapersOutput {
     // 先扫描获取壁纸列表
     let scan_result = scan_wallpapers(ScanWallpapersInput {
         workshop_path: input.workshop_path.clone(),
+        type_filter: input.type_filter.clone(),
+        tag_filter: input.tag_filter.clone(),
+        generate_thumbnails: None,
+        cache: input.cache.clone(),
+        pkg_depth: input.pkg_depth,
     });
 
     if !scan_result.success {
         return CopyWallpapersOutput {
             success: false,
             results: vec![],
+            filtered: vec![],
+            type_skipped: vec![],
+            presets: vec![],
             stats: CopyStats::default(),
             error: scan_result.error,
         };
@@ -224,21 +522,66 @@ pub fn copy_wallpapers(input: CopyWallpapersInput) -> CopyWallpapersOutput {
         Some(ids) => scan_result
             .wallpapers
             .into_iter()
-            .filter(|w| ids.contains(&w.wallpaper_id))
+            .filter(|w| id_matches(&w.wallpaper_id, ids))
             .collect(),
         None => scan_result.wallpapers,
     };
 
     let mut results = Vec::new();
+    let mut filtered = Vec::new();
+    let mut type_skipped = Vec::new();
+    let mut presets = Vec::new();
     let mut stats = CopyStats::default();
+    let total = wallpapers_to_process.len();
+
+    for (index, wallpaper) in wallpapers_to_process.into_iter().enumerate() {
+        if is_cancelled(input.cancel.as_ref()) {
+            break;
+        }
+        if let Some(callback) = on_progress {
+            callback(CopyProgress {
+                wallpaper_id: wallpaper.wallpaper_id.clone(),
+                current: index + 1,
+                total,
+            });
+        }
+
+        // 内容分级过滤
+        if !rating_allowed(wallpaper.content_rating, input.max_rating) {
+            stats.filtered += 1;
+            filtered.push(wallpaper);
+            continue;
+        }
+
+        // preset 只引用 base 壁纸并覆盖参数，复制出来是无意义的空壳
+        if wallpaper.preset_base.is_some() || wallpaper.wallpaper_type.as_deref() == Some("preset")
+        {
+            stats.presets += 1;
+            presets.push(wallpaper);
+            continue;
+        }
+
+        // 壁纸类型过滤：只影响含 pkg 的壁纸（决定是否进入解包）
+        if wallpaper.has_pkg
+            && !type_allowed(
+                wallpaper.wallpaper_type.as_deref(),
+                input.only_types.as_deref(),
+            )
+        {
+            stats.type_skipped += 1;
+            type_skipped.push(wallpaper);
+            continue;
+        }
 
-    for wallpaper in wallpapers_to_process {
         let process_result = paper::process_folder(paper::ProcessFolderInput {
             folder: wallpaper.folder_path.clone(),
             raw_output: input.raw_output_path.clone(),
             pkg_temp_output: input.pkg_temp_path.clone(),
             enable_raw: input.enable_raw,
+            force: input.force,
+            pkg_depth: input.pkg_depth,
         });
+        stats.files_updated += process_result.files_updated;
 
         let result_type = match process_result.result_type {
             paper::ProcessResultType::Raw => {
@@ -259,14 +602,21 @@ pub fn copy_wallpapers(input: CopyWallpapersInput) -> CopyWallpapersOutput {
         results.push(CopyResult {
             wallpaper_id: wallpaper.wallpaper_id,
             title: wallpaper.title,
+            wallpaper_type: wallpaper.wallpaper_type,
             result_type,
             pkg_files: process_result.pkg_files,
         });
+        if let (Some(callback), Some(result)) = (on_copied, results.last()) {
+            callback(result);
+        }
     }
 
     CopyWallpapersOutput {
         success: true,
         results,
+        filtered,
+        type_skipped,
+        presets,
         stats,
         error: None,
     }
@@ -276,6 +626,7 @@ pub fn copy_wallpapers(input: CopyWallpapersInput) -> CopyWallpapersOutput {
 pub fn get_wallpaper_detail(
     workshop_path: &std::path::Path,
     wallpaper_id: &str,
+    pkg_depth: usize,
 ) -> Option<WallpaperInfo> {
     let folder_path = workshop_path.join(wallpaper_id);
 
@@ -283,30 +634,602 @@ pub fn get_wallpaper_detail(
         return None;
     }
 
-    // 读取元数据（失败时使用默认值）
-    let (title, wallpaper_type, preview_path) = match paper::read_meta(paper::ReadMetaInput {
-        folder: folder_path.clone(),
-    }) {
-        Ok(r) => (
-            r.meta.title,
-            r.meta.wallpaper_type,
-            r.meta.preview.map(|p| folder_path.join(p)),
-        ),
-        Err(_) => (None, None, None),
-    };
+    Some(build_wallpaper_info(
+        wallpaper_id.to_string(),
+        folder_path,
+        pkg_depth,
+    ))
+}
+
+/// 读取单个壁纸文件夹的元数据与 pkg 信息
+///
+/// project.json 读取失败时使用默认值；preset 壁纸的类型统一标记为 preset
+fn build_wallpaper_info(
+    wallpaper_id: String,
+    folder_path: PathBuf,
+    pkg_depth: usize,
+) -> WallpaperInfo {
+    let (title, description, tags, wallpaper_type, preview_path, content_rating, preset_base) =
+        match paper::read_meta(paper::ReadMetaInput {
+            folder: folder_path.clone(),
+        }) {
+            Ok(r) => {
+                let is_preset = r.meta.is_preset();
+                (
+                    r.meta.title,
+                    r.meta.description.filter(|d| !d.is_empty()),
+                    r.meta.tags.unwrap_or_default(),
+                    if is_preset {
+                        Some("preset".to_string())
+                    } else {
+                        r.meta.wallpaper_type
+                    },
+                    r.meta.preview.map(|p| folder_path.join(p)),
+                    paper::ContentRating::from_meta(r.meta.contentrating.as_deref()),
+                    r.meta.dependency.filter(|_| is_preset),
+                )
+            }
+            Err(_) => (
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                paper::ContentRating::Everyone,
+                None,
+            ),
+        };
 
     // 检查 pkg 文件
     let pkg_result = paper::check_pkg(paper::CheckPkgInput {
         folder: folder_path.clone(),
+        max_depth: pkg_depth,
     });
 
-    Some(WallpaperInfo {
-        wallpaper_id: wallpaper_id.to_string(),
+    WallpaperInfo {
+        wallpaper_id,
         title,
+        description,
+        tags,
         wallpaper_type,
         preview_path,
+        thumbnail_path: None,
+        content_rating,
+        preset_base,
         has_pkg: pkg_result.has_pkg,
         pkg_files: pkg_result.pkg_files,
         folder_path,
+    }
+}
+
+/// 获取壁纸预览图的缩略图，缓存比预览图新时直接复用
+///
+/// 没有预览图或解码失败时返回 None
+fn cached_thumbnail(wallpaper: &WallpaperInfo, cache_dir: &Path) -> Option<PathBuf> {
+    let source = wallpaper.preview_path.as_ref().filter(|p| p.is_file())?;
+    let output = cache_dir.join(format!("{}.png", wallpaper.wallpaper_id));
+
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    if let (Some(thumb), Some(preview)) = (modified(&output), modified(source)) {
+        if thumb >= preview {
+            return Some(output);
+        }
+    }
+
+    paper::generate_thumbnail(paper::ThumbnailInput {
+        source: source.clone(),
+        max_dim: SCAN_THUMBNAIL_SIZE,
+        output,
     })
+    .ok()
+    .map(|o| o.output)
+}
+
+/// 判断壁纸分级是否在允许范围内
+///
+/// max_rating 为 None 时不过滤
+pub fn rating_allowed(
+    rating: paper::ContentRating,
+    max_rating: Option<paper::ContentRating>,
+) -> bool {
+    match max_rating {
+        Some(max) => rating <= max,
+        None => true,
+    }
+}
+
+/// 判断壁纸类型是否在允许列表中
+///
+/// only_types 为 None 时不过滤；比较忽略大小写，缺少类型的壁纸视为不匹配
+pub fn type_allowed(wallpaper_type: Option<&str>, only_types: Option<&[String]>) -> bool {
+    match only_types {
+        Some(types) => wallpaper_type
+            .map(|t| types.iter().any(|allowed| allowed.eq_ignore_ascii_case(t)))
+            .unwrap_or(false),
+        None => true,
+    }
+}
+
+/// 判断壁纸标签是否命中过滤列表
+///
+/// tag_filter 为 None 时不过滤；任一标签命中即可，比较忽略大小写
+pub fn tags_allowed(tags: &[String], tag_filter: Option<&[String]>) -> bool {
+    match tag_filter {
+        Some(filter) => tags
+            .iter()
+            .any(|tag| filter.iter().any(|wanted| wanted.eq_ignore_ascii_case(tag))),
+        None => true,
+    }
+}
+
+/// 判断壁纸 ID 是否匹配任一过滤模式
+///
+/// 多个模式取并集，单个模式的语法见 [`id_pattern_matches`]
+pub fn id_matches(wallpaper_id: &str, patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| id_pattern_matches(wallpaper_id, pattern))
+}
+
+/// 判断壁纸 ID 是否匹配单个过滤模式
+///
+/// - 含 `*` 时按通配匹配，如 `31*`
+/// - `起-止` 且两端均为数字时按数字闭区间匹配，如 `100-200`；无法解析为数字的 ID 不参与
+/// - 其它情况精确匹配
+pub fn id_pattern_matches(wallpaper_id: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim();
+    if pattern.contains('*') {
+        return wildcard_match(wallpaper_id, pattern);
+    }
+    if let Some((start, end)) = pattern.split_once('-') {
+        if let (Ok(start), Ok(end)) = (start.trim().parse::<u64>(), end.trim().parse::<u64>()) {
+            return wallpaper_id
+                .parse::<u64>()
+                .map(|id| start <= id && id <= end)
+                .unwrap_or(false);
+        }
+    }
+    wallpaper_id == pattern
+}
+
+/// `*` 通配匹配（匹配任意长度字符）
+fn wildcard_match(text: &str, pattern: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+
+    // 中间片段在首尾之间按顺序出现即可
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// 将转换结果打包为 zip
+///
+/// 整体模式输出 `<目录名>.zip`；单独模式为每个子目录输出 `<id>.zip`，
+/// 子目录下存在 `tex_converted` 时只打包该目录
+pub fn archive_converted(input: ArchiveConvertedInput) -> ArchiveConvertedOutput {
+    if !input.converted_path.is_dir() {
+        return ArchiveConvertedOutput {
+            success: false,
+            results: vec![],
+            error: Some(format!(
+                "Converted directory not found: {}",
+                input.converted_path.display()
+            )),
+        };
+    }
+
+    // (源目录, zip 路径)
+    let jobs: Vec<(PathBuf, PathBuf)> = if input.per_wallpaper {
+        let mut dirs = match paper::list_dirs(paper::ListDirsInput {
+            path: input.converted_path.clone(),
+        }) {
+            Ok(r) => r.dirs,
+            Err(e) => {
+                return ArchiveConvertedOutput {
+                    success: false,
+                    results: vec![],
+                    error: Some(format!("Failed to list wallpaper directories: {}", e)),
+                };
+            }
+        };
+        dirs.sort();
+
+        dirs.into_iter()
+            .map(|id| {
+                let dir = input.converted_path.join(&id);
+                let nested = dir.join("tex_converted");
+                let source = if nested.is_dir() { nested } else { dir };
+                let title = if input.with_title {
+                    paper::read_meta(paper::ReadMetaInput {
+                        folder: source.clone(),
+                    })
+                    .ok()
+                    .and_then(|r| r.meta.title)
+                } else {
+                    None
+                };
+                let zip_name = archive_file_name(&id, title.as_deref());
+                (source, input.output_path.join(zip_name))
+            })
+            .collect()
+    } else {
+        let name = input
+            .converted_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "converted".to_string());
+        vec![(
+            input.converted_path.clone(),
+            input.output_path.join(format!("{}.zip", name)),
+        )]
+    };
+
+    let results: Vec<ArchiveResult> = jobs
+        .into_iter()
+        .map(|(source_path, zip_path)| {
+            match paper::zip_dir(paper::ZipDirInput {
+                src_dir: source_path.clone(),
+                zip_path: zip_path.clone(),
+            }) {
+                Ok(r) => ArchiveResult {
+                    source_path,
+                    zip_path,
+                    success: true,
+                    file_count: r.file_count,
+                    zip_size: r.zip_size,
+                    error: None,
+                },
+                Err(e) => ArchiveResult {
+                    source_path,
+                    zip_path,
+                    success: false,
+                    file_count: 0,
+                    zip_size: 0,
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect();
+
+    ArchiveConvertedOutput {
+        success: results.iter().all(|r| r.success),
+        results,
+        error: None,
+    }
+}
+
+/// 查找疑似重复的壁纸
+///
+/// 按内容指纹（文件大小 + 首尾采样）分组，只报告不删除
+pub fn find_duplicates(input: FindDuplicatesInput) -> FindDuplicatesOutput {
+    let scan_result = scan_wallpapers(ScanWallpapersInput {
+        workshop_path: input.workshop_path,
+        type_filter: None,
+        tag_filter: None,
+        generate_thumbnails: None,
+        cache: None,
+        // 指纹只看文件内容，与 pkg 信息无关
+        pkg_depth: paper::DEFAULT_PKG_DEPTH,
+    });
+    if !scan_result.success {
+        return FindDuplicatesOutput {
+            success: false,
+            groups: vec![],
+            checked: 0,
+            unreadable: vec![],
+            error: scan_result.error,
+        };
+    }
+
+    let mut by_fingerprint: std::collections::HashMap<u64, (u64, Vec<WallpaperInfo>)> =
+        std::collections::HashMap::new();
+    let mut unreadable = Vec::new();
+    let mut checked = 0;
+
+    for wallpaper in scan_result.wallpapers {
+        if let Some(ref ids) = input.wallpaper_ids {
+            if !id_matches(&wallpaper.wallpaper_id, ids) {
+                continue;
+            }
+        }
+        checked += 1;
+        match paper::fingerprint(paper::FingerprintInput {
+            folder: wallpaper.folder_path.clone(),
+        }) {
+            Ok(fp) => by_fingerprint
+                .entry(fp.fingerprint)
+                .or_insert_with(|| (fp.total_size, Vec::new()))
+                .1
+                .push(wallpaper),
+            Err(e) => unreadable.push((wallpaper.wallpaper_id, e.to_string())),
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_fingerprint
+        .into_iter()
+        .filter(|(_, (_, wallpapers))| wallpapers.len() > 1)
+        .map(|(fingerprint, (total_size, mut wallpapers))| {
+            wallpapers.sort_by(|a, b| a.wallpaper_id.cmp(&b.wallpaper_id));
+            DuplicateGroup {
+                fingerprint: format!("{:016x}", fingerprint),
+                total_size,
+                wallpapers,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        a.wallpapers[0]
+            .wallpaper_id
+            .cmp(&b.wallpapers[0].wallpaper_id)
+    });
+
+    FindDuplicatesOutput {
+        success: true,
+        groups,
+        checked,
+        unreadable,
+        error: None,
+    }
+}
+
+/// 生成单个壁纸的 zip 文件名，标题经 sanitize_filename 清洗
+fn archive_file_name(id: &str, title: Option<&str>) -> String {
+    match title.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(title) => format!("{} - {}.zip", id, path::sanitize_filename(title)),
+        None => format!("{}.zip", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_type_and_tag_filter() {
+        let root = std::env::temp_dir().join("lianpkg_scan_type_tag_filter");
+        let _ = fs::remove_dir_all(&root);
+        let library = [
+            ("100010", "scene", r#"["Anime"]"#),
+            ("100011", "video", r#"["anime", "Nature"]"#),
+            ("100012", "Video", r#"["Nature"]"#),
+            ("100013", "web", r#"[]"#),
+        ];
+        for (id, wallpaper_type, tags) in library {
+            let wallpaper = root.join("workshop").join(id);
+            fs::create_dir_all(&wallpaper).unwrap();
+            fs::write(wallpaper.join("video.mp4"), b"data").unwrap();
+            fs::write(
+                wallpaper.join("project.json"),
+                format!(
+                    r#"{{"title":"{}","type":"{}","tags":{}}}"#,
+                    id, wallpaper_type, tags
+                ),
+            )
+            .unwrap();
+        }
+
+        let scan = |types: Option<&[&str]>, tags: Option<&[&str]>| {
+            let to_vec = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+            scan_wallpapers(ScanWallpapersInput {
+                workshop_path: root.join("workshop"),
+                type_filter: types.map(to_vec),
+                tag_filter: tags.map(to_vec),
+                generate_thumbnails: None,
+                cache: None,
+                pkg_depth: paper::DEFAULT_PKG_DEPTH,
+            })
+        };
+        let ids = |result: &ScanWallpapersOutput| -> Vec<String> {
+            let mut ids: Vec<_> = result
+                .wallpapers
+                .iter()
+                .map(|w| w.wallpaper_id.clone())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let videos = scan(Some(&["video"]), None);
+        assert_eq!(ids(&videos), vec!["100011", "100012"]);
+        assert_eq!(videos.stats.total_count, 2);
+        assert_eq!(videos.stats.excluded, 2);
+
+        let anime = scan(None, Some(&["ANIME"]));
+        assert_eq!(ids(&anime), vec!["100010", "100011"]);
+        let anime_videos = scan(Some(&["video"]), Some(&["anime"]));
+        assert_eq!(ids(&anime_videos), vec!["100011"]);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_scan_generates_thumbnails() {
+        let root = std::env::temp_dir().join("lianpkg_scan_thumbnails");
+        let _ = fs::remove_dir_all(&root);
+        let with_preview = root.join("workshop").join("100020");
+        let without_preview = root.join("workshop").join("100021");
+        for dir in [&with_preview, &without_preview] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(
+            with_preview.join("project.json"),
+            br#"{"title":"a","type":"video","preview":"preview.jpg"}"#,
+        )
+        .unwrap();
+        image::RgbImage::new(640, 360)
+            .save(with_preview.join("preview.jpg"))
+            .unwrap();
+        fs::write(without_preview.join("project.json"), br#"{"title":"b"}"#).unwrap();
+
+        let cache = root.join("thumbs");
+        let scan = || {
+            scan_wallpapers(ScanWallpapersInput {
+                workshop_path: root.join("workshop"),
+                type_filter: None,
+                tag_filter: None,
+                generate_thumbnails: Some(cache.clone()),
+                cache: None,
+                pkg_depth: paper::DEFAULT_PKG_DEPTH,
+            })
+        };
+        let result = scan();
+        let thumb = |result: &ScanWallpapersOutput, id: &str| {
+            result
+                .wallpapers
+                .iter()
+                .find(|w| w.wallpaper_id == id)
+                .unwrap()
+                .thumbnail_path
+                .clone()
+        };
+
+        let path = thumb(&result, "100020").unwrap();
+        assert_eq!(path, cache.join("100020.png"));
+        assert_eq!(
+            image::image_dimensions(&path).unwrap(),
+            (SCAN_THUMBNAIL_SIZE, 144)
+        );
+        assert_eq!(thumb(&result, "100021"), None);
+
+        // 缓存命中时不重新生成
+        let written = fs::metadata(&path).unwrap().modified().unwrap();
+        let again = scan();
+        assert_eq!(thumb(&again, "100020"), Some(path.clone()));
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), written);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_scan_cache_reuses_unchanged_wallpapers() {
+        let root = std::env::temp_dir().join("lianpkg_scan_cache");
+        let _ = fs::remove_dir_all(&root);
+        for id in ["100030", "100031", "100032"] {
+            let dir = root.join("workshop").join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("project.json"),
+                format!(r#"{{"title":"{}","type":"video"}}"#, id),
+            )
+            .unwrap();
+        }
+
+        let cache = root.join("scan_cache.json");
+        let scan = || {
+            scan_wallpapers(ScanWallpapersInput {
+                workshop_path: root.join("workshop"),
+                type_filter: None,
+                tag_filter: None,
+                generate_thumbnails: None,
+                cache: Some(cache.clone()),
+                pkg_depth: paper::DEFAULT_PKG_DEPTH,
+            })
+        };
+
+        assert_eq!(scan().stats.cache_hits, 0);
+        assert!(cache.exists());
+        let second = scan();
+        assert_eq!(second.stats.cache_hits, 3);
+        assert_eq!(second.stats.total_count, 3);
+
+        // 修改 project.json 后重新读取该壁纸
+        let meta = root.join("workshop/100030/project.json");
+        fs::write(&meta, br#"{"title":"renamed","type":"scene"}"#).unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&meta)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        fs::remove_dir_all(root.join("workshop/100032")).unwrap();
+
+        let third = scan();
+        assert_eq!(third.stats.cache_hits, 1);
+        assert_eq!(third.stats.total_count, 2);
+        let changed = third
+            .wallpapers
+            .iter()
+            .find(|w| w.wallpaper_id == "100030")
+            .unwrap();
+        assert_eq!(changed.title.as_deref(), Some("renamed"));
+        assert_eq!(changed.wallpaper_type.as_deref(), Some("scene"));
+
+        // 已删除的壁纸不再留在缓存中
+        let content = fs::read_to_string(&cache).unwrap();
+        assert!(!content.contains("100032"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_scan_cache_sees_pkg_added_in_subdirectory() {
+        let root = std::env::temp_dir().join("lianpkg_scan_cache_subdir");
+        let _ = fs::remove_dir_all(&root);
+        let nested = root.join("workshop/100033/scene/assets");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            root.join("workshop/100033/project.json"),
+            br#"{"title":"nested","type":"scene"}"#,
+        )
+        .unwrap();
+
+        let cache = root.join("scan_cache.json");
+        let scan = |pkg_depth| {
+            scan_wallpapers(ScanWallpapersInput {
+                workshop_path: root.join("workshop"),
+                type_filter: None,
+                tag_filter: None,
+                generate_thumbnails: None,
+                cache: Some(cache.clone()),
+                pkg_depth,
+            })
+        };
+        assert!(!scan(paper::DEFAULT_PKG_DEPTH).wallpapers[0].has_pkg);
+
+        // 在第二层子目录中新增 pkg，只有该子目录的时间会变化
+        fs::write(nested.join("scene.pkg"), b"pkg").unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::open(&nested)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let rescanned = scan(paper::DEFAULT_PKG_DEPTH);
+        assert_eq!(rescanned.stats.cache_hits, 0);
+        assert!(rescanned.wallpapers[0].has_pkg);
+
+        // 搜索层数变化时缓存失效，较浅的层数找不到第二层的 pkg
+        let shallow = scan(1);
+        assert_eq!(shallow.stats.cache_hits, 0);
+        assert!(!shallow.wallpapers[0].has_pkg);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_id_pattern_matches() {
+        let patterns = vec!["31*".to_string(), "100-200".to_string(), "999".to_string()];
+        assert!(id_matches("3100", &patterns));
+        assert!(id_matches("150", &patterns));
+        assert!(id_matches("200", &patterns));
+        assert!(id_matches("999", &patterns));
+        assert!(!id_matches("201", &patterns));
+        assert!(!id_matches("1310", &patterns));
+
+        assert!(id_pattern_matches("123456", "1*4*6"));
+        assert!(!id_pattern_matches("1246", "124*46"));
+        // 非数字 ID 不参与范围匹配
+        assert!(!id_pattern_matches("abc", "100-200"));
+        assert!(id_pattern_matches("my-wallpaper", "my-wallpaper"));
+    }
 }
@@ -8,24 +8,56 @@
 //! - `run_pipeline`: 执行完整流水线
 //! - `quick_run`: 快速执行（使用默认配置）
 //! - `estimate_disk_usage`: 预估磁盘使用量
-//! - `run_pkg_only`: 仅执行 PKG 解包
-//! - `run_tex_only`: 仅执行 TEX 转换
+//! - `run_pkg_only` / `run_pkg_only_with_config`: 仅执行 PKG 解包
+//! - `run_tex_only` / `run_tex_only_with_config`: 仅执行 TEX 转换
 
+use super::utl::{is_cancelled, CancelFlag};
 use super::{cfg as native_cfg, paper as native_paper, pkg as native_pkg, tex as native_tex};
-use crate::core::{cfg, disk, paper as core_paper};
+use crate::core::{cfg, disk, paper as core_paper, pkg as core_pkg, tex as core_tex};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// ============================================================================
+// 常量定义
+// ============================================================================
+
+/// 解包后大小相对 PKG 大小的膨胀系数（经验值）
+pub const UNPACK_RATIO: f64 = 1.5;
+/// 转换后大小相对 PKG 大小的膨胀系数（经验值，PNG 比 TEX 大）
+pub const CONVERT_RATIO: f64 = 2.0;
+/// state.json 中保留的预估对账记录条数
+const ESTIMATE_HISTORY_LIMIT: usize = 20;
+/// 扫描缓存文件名（与 state.json 同目录）
+pub const SCAN_CACHE_FILE: &str = "scan_cache.json";
 
 // ============================================================================
 // 回调类型定义
 // ============================================================================
 
-/// 进度回调函数类型
-pub type ProgressCallback<'a> = &'a dyn Fn(PipelineProgress);
+/// 进度回调函数类型（解包阶段会在工作线程上调用，需可跨线程共享）
+///
+/// 持有所有权的闭包，可捕获状态（如 channel 发送端、共享结构体），
+/// 也可随入参一起移交给其他线程。
+pub type ProgressCallback = Arc<dyn Fn(PipelineProgress) + Send + Sync>;
+
+/// 构造进度回调
+pub fn progress_callback(f: impl Fn(PipelineProgress) + Send + Sync + 'static) -> ProgressCallback {
+    Arc::new(f)
+}
+
+/// 不做任何事的进度回调
+pub fn no_progress() -> ProgressCallback {
+    progress_callback(|_| {})
+}
 
 /// Debug 日志回调函数类型
 pub type DebugLogCallback<'a> = &'a dyn Fn(DebugLogEvent);
 
+/// 单项结果回调函数类型
+pub type ItemCallback<'a> = &'a dyn Fn(PipelineItemEvent);
+
 // ============================================================================
 // 结构体定义
 // ============================================================================
@@ -38,12 +70,39 @@ pub struct RunPipelineInput<'a> {
     pub state_path: PathBuf,
     /// 要处理的壁纸 ID 列表，None 表示全部
     pub wallpaper_ids: Option<Vec<String>>,
+    /// 需要重新处理的壁纸 ID（先从 state 移除记录，不受增量跳过影响）
+    pub reprocess_ids: Option<Vec<String>>,
+    /// 只处理这些类型的壁纸（如 scene、video），与 wallpaper_ids 取交集；None 表示不限
+    pub type_filter: Option<Vec<String>>,
+    /// 只处理带有任一指定标签的壁纸，与 wallpaper_ids 取交集；None 表示不限
+    pub tag_filter: Option<Vec<String>>,
     /// 参数覆盖（CLI 参数优先级高于配置文件）
     pub overrides: Option<PipelineOverrides>,
     /// 进度回调（可选）
-    pub progress_callback: Option<ProgressCallback<'a>>,
+    pub progress_callback: Option<ProgressCallback>,
     /// 调试日志回调（可选）
     pub debug_logger: Option<DebugLogCallback<'a>>,
+    /// 单项结果回调（可选），每处理完一个壁纸 / PKG / TEX 调用一次
+    pub item_callback: Option<ItemCallback<'a>>,
+    /// 取消标志（可选），置位后处理完当前项即停止，保存 state.json 后返回
+    pub cancel: Option<CancelFlag>,
+}
+
+/// 单项处理结果事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineItemEvent {
+    /// 所属阶段
+    pub stage: PipelineStage,
+    /// 壁纸 ID
+    pub wallpaper_id: String,
+    /// 处理项目（PKG / TEX 路径），复制阶段为 None
+    pub item: Option<PathBuf>,
+    /// 是否成功
+    pub success: bool,
+    /// 输出路径
+    pub output_path: Option<PathBuf>,
+    /// 错误信息
+    pub error: Option<String>,
 }
 
 /// Debug 日志事件
@@ -87,14 +146,36 @@ pub struct PipelineOverrides {
     pub tex_output_path: Option<PathBuf>,
     /// 覆盖 enable_raw_output
     pub enable_raw: Option<bool>,
+    /// 覆盖 max_content_rating
+    pub max_rating: Option<core_paper::ContentRating>,
     /// 覆盖 clean_pkg_temp
     pub clean_pkg_temp: Option<bool>,
     /// 覆盖 clean_unpacked
     pub clean_unpacked: Option<bool>,
+    /// 覆盖 archive_pkg_path
+    pub archive_pkg_path: Option<PathBuf>,
+    /// 覆盖 only_types
+    pub only_types: Option<Vec<String>>,
     /// 覆盖 incremental
     pub incremental: Option<bool>,
+    /// 覆盖 strict_incremental
+    pub strict_incremental: Option<bool>,
     /// 覆盖 auto_convert_tex
     pub auto_convert_tex: Option<bool>,
+    /// 覆盖 tex_strict
+    pub tex_strict: Option<bool>,
+    /// 覆盖 tex_on_conflict
+    pub tex_on_conflict: Option<native_tex::ConflictPolicy>,
+    /// 覆盖 fail_threshold
+    pub fail_threshold: Option<usize>,
+    /// 覆盖 thumbnail_size
+    pub thumbnail_size: Option<u32>,
+    /// 覆盖 max_memory_mb
+    pub max_memory_mb: Option<u64>,
+    /// 覆盖 keep_going
+    pub keep_going: Option<bool>,
+    /// 覆盖 fail_fast
+    pub fail_fast: Option<bool>,
 }
 
 /// 流水线执行返回值
@@ -108,12 +189,31 @@ pub struct RunPipelineOutput {
     pub pkg_result: Option<native_pkg::UnpackAllOutput>,
     /// TEX 转换结果
     pub tex_result: Option<native_tex::ConvertAllOutput>,
+    /// PKG 归档结果（配置了 archive_pkg_path 时才有）
+    pub archive_result: Option<native_pkg::ArchivePkgsOutput>,
     /// 统计信息
     pub stats: PipelineStats,
+    /// 预估与实际占用对账（本次有 PKG 解包时才有）
+    pub estimate_record: Option<cfg::EstimateRecord>,
+    /// 各阶段错误汇总（单项失败与阶段中止）
+    pub stage_errors: Vec<PipelineStageError>,
+    /// 是否因取消而提前结束（已完成的部分已记录到 state.json）
+    pub cancelled: bool,
     /// 错误信息
     pub error: Option<String>,
 }
 
+/// 流水线阶段错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStageError {
+    /// 出错阶段
+    pub stage: PipelineStage,
+    /// 出错项目（PKG / TEX 路径），None 表示整个阶段失败
+    pub item: Option<String>,
+    /// 错误信息
+    pub message: String,
+}
+
 /// 流水线统计
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PipelineStats {
@@ -121,12 +221,55 @@ pub struct PipelineStats {
     pub wallpapers_processed: usize,
     /// 跳过的壁纸数（增量处理）
     pub wallpapers_skipped: usize,
+    /// 已有记录但输出目录缺失、被重新处理的壁纸数（严格增量）
+    pub wallpapers_stale: usize,
+    /// 上次运行中断、本次从中断阶段继续的壁纸数
+    pub wallpapers_resumed: usize,
+    /// 因内容分级被过滤的壁纸数
+    pub wallpapers_filtered: usize,
+    /// 因壁纸类型被跳过解包的壁纸数
+    pub wallpapers_type_skipped: usize,
+    /// 跳过的 preset 壁纸数
+    pub wallpapers_preset: usize,
     /// 解包的 PKG 数
     pub pkgs_unpacked: usize,
+    /// 归档的 PKG 数
+    pub pkgs_archived: usize,
     /// 转换的 TEX 数
     pub texs_converted: usize,
+    /// 解包 / 转换失败的文件（PKG 或 TEX 路径）
+    pub failed_files: Vec<PathBuf>,
+    /// 复制阶段耗时（毫秒）
+    pub copy_ms: u64,
+    /// 解包阶段耗时（毫秒）
+    pub unpack_ms: u64,
+    /// 转换阶段耗时（毫秒）
+    pub convert_ms: u64,
     /// 总耗时（毫秒）
     pub elapsed_ms: u64,
+    /// 清理 pkg_temp / unpacked 目录释放的空间（字节）
+    #[serde(default)]
+    pub freed_bytes: u64,
+}
+
+impl PipelineStats {
+    /// 平均每个壁纸耗时（毫秒），未处理壁纸时为 None
+    pub fn avg_ms_per_wallpaper(&self) -> Option<f64> {
+        (self.wallpapers_processed > 0)
+            .then(|| self.elapsed_ms as f64 / self.wallpapers_processed as f64)
+    }
+
+    /// 转换阶段每秒处理的 TEX 数，未转换时为 None
+    pub fn tex_per_sec(&self) -> Option<f64> {
+        (self.texs_converted > 0 && self.convert_ms > 0)
+            .then(|| self.texs_converted as f64 * 1000.0 / self.convert_ms as f64)
+    }
+
+    /// 解包阶段每秒处理的 PKG 数，未解包时为 None
+    pub fn pkg_per_sec(&self) -> Option<f64> {
+        (self.pkgs_unpacked > 0 && self.unpack_ms > 0)
+            .then(|| self.pkgs_unpacked as f64 * 1000.0 / self.unpack_ms as f64)
+    }
 }
 
 /// 流水线进度
@@ -162,12 +305,24 @@ pub enum PipelineStage {
 }
 
 /// 简化的流水线执行入参
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct QuickRunInput {
     /// 配置目录，None 则使用默认目录
     pub config_dir: Option<PathBuf>,
     /// 是否强制处理所有壁纸（忽略增量）
     pub force_all: bool,
+    /// 进度回调（可选）
+    pub progress_callback: Option<ProgressCallback>,
+}
+
+impl std::fmt::Debug for QuickRunInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuickRunInput")
+            .field("config_dir", &self.config_dir)
+            .field("force_all", &self.force_all)
+            .field("progress_callback", &self.progress_callback.is_some())
+            .finish()
+    }
 }
 
 /// 简化的流水线执行返回值
@@ -215,10 +370,57 @@ pub struct EstimateDiskOutput {
     pub available_space: Option<u64>,
     /// 空间是否充足
     pub space_sufficient: bool,
+    /// 跨文件系统的阶段（仅提示，不阻断）
+    pub cross_device: Vec<CrossDeviceHint>,
+    /// 按 PKG 内容得到的解包 / 转换预估明细
+    pub conversion: DiskEstimate,
     /// 错误信息
     pub error: Option<String>,
 }
 
+/// 按内容预估入参
+#[derive(Debug, Clone)]
+pub struct EstimateConversionInput {
+    /// 要预估的 PKG 文件
+    pub pkg_files: Vec<PathBuf>,
+}
+
+/// 按 PKG 内容得到的磁盘预估
+///
+/// 解包大小为条目大小之和；转换大小按每个 TEX 的格式计算（直通格式 ≈ 原大小，
+/// 其余按解码后的 RGBA 大小）。无法解析的 PKG / TEX 退回膨胀系数估算
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskEstimate {
+    /// 参与预估的 PKG 文件总大小（字节）
+    pub pkg_size: u64,
+    /// 预估解包大小（字节）
+    pub unpacked_size: u64,
+    /// 其中 TEX 条目总大小（字节）
+    pub tex_size: u64,
+    /// 预估转换产物大小（字节）
+    pub converted_size: u64,
+    /// TEX 条目数量
+    pub tex_count: usize,
+    /// 直通格式（视频 / 内嵌图片）的 TEX 数量
+    pub passthrough_count: usize,
+    /// 无法解析、按膨胀系数估算的 PKG 大小（字节）
+    pub fallback_size: u64,
+}
+
+/// 跨文件系统提示
+///
+/// 相邻阶段的目录位于不同文件系统时，无法使用重命名/硬链接，
+/// 只能完整复制数据，会带来额外 IO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossDeviceHint {
+    /// 阶段名（copy / unpack / convert）
+    pub stage: String,
+    /// 源目录
+    pub from: PathBuf,
+    /// 目标目录
+    pub to: PathBuf,
+}
+
 // ============================================================================
 // 磁盘预估接口
 // ============================================================================
@@ -234,6 +436,7 @@ pub fn estimate_disk_usage(input: EstimateDiskInput) -> EstimateDiskOutput {
     let estimate_result = core_paper::estimate(core_paper::EstimateInput {
         search_path: config.workshop_path.clone(),
         enable_raw: config.enable_raw_output,
+        pkg_depth: config.pkg_depth,
     });
 
     let pkg_size = estimate_result.pkg_size;
@@ -242,10 +445,12 @@ pub fn estimate_disk_usage(input: EstimateDiskInput) -> EstimateDiskOutput {
     // 预估各阶段大小
     // PKG 临时目录 = PKG 文件大小
     let estimated_pkg_temp = pkg_size;
-    // 解包后大小约为 PKG 大小的 1.5 倍（经验值）
-    let estimated_unpacked = (pkg_size as f64 * 1.5) as u64;
-    // 转换后大小约为 PKG 大小的 2.0 倍（经验值，PNG 比 TEX 大）
-    let estimated_converted = (pkg_size as f64 * 2.0) as u64;
+    // 解包与转换大小按 PKG 内的条目与 TEX 格式计算
+    let conversion = estimate_conversion(EstimateConversionInput {
+        pkg_files: estimate_result.pkg_files,
+    });
+    let estimated_unpacked = conversion.unpacked_size;
+    let estimated_converted = conversion.converted_size;
 
     // 峰值使用：所有临时文件同时存在
     let estimated_peak = estimated_pkg_temp + estimated_unpacked + estimated_converted + raw_size;
@@ -255,7 +460,7 @@ pub fn estimate_disk_usage(input: EstimateDiskInput) -> EstimateDiskOutput {
     if !config.clean_unpacked {
         estimated_final += estimated_unpacked;
     }
-    if !config.clean_pkg_temp {
+    if !config.clean_pkg_temp || config.archive_pkg_path.is_some() {
         estimated_final += estimated_pkg_temp;
     }
 
@@ -283,10 +488,120 @@ pub fn estimate_disk_usage(input: EstimateDiskInput) -> EstimateDiskOutput {
         estimated_final,
         available_space,
         space_sufficient,
+        cross_device: detect_cross_device(config),
+        conversion,
         error: None,
     }
 }
 
+/// 读取每个 PKG 的索引与其中 TEX 的文件头，预估解包与转换大小
+///
+/// 只读取索引和每个 TEX 开头的少量字节，不解包、不解码
+pub fn estimate_conversion(input: EstimateConversionInput) -> DiskEstimate {
+    let mut estimate = DiskEstimate::default();
+    for pkg_path in &input.pkg_files {
+        let size = std::fs::metadata(pkg_path).map(|m| m.len()).unwrap_or(0);
+        estimate.pkg_size += size;
+        if estimate_pkg_contents(pkg_path, &mut estimate).is_err() {
+            estimate.fallback_size += size;
+            estimate.unpacked_size += (size as f64 * UNPACK_RATIO) as u64;
+            estimate.converted_size += (size as f64 * CONVERT_RATIO) as u64;
+        }
+    }
+    estimate
+}
+
+/// 累加单个 PKG 的条目与 TEX 预估；PKG 本身无法解析时返回错误且不修改 estimate
+fn estimate_pkg_contents(pkg_path: &Path, estimate: &mut DiskEstimate) -> std::io::Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    /// 读取 TEX 文件头所需的字节数
+    const TEX_HEAD_LEN: u64 = 4096;
+
+    let pkg_info = core_pkg::parse_pkg(core_pkg::ParsePkgInput {
+        file_path: pkg_path.to_path_buf(),
+    })
+    .map_err(|e| std::io::Error::other(e.to_string()))?
+    .pkg_info;
+    let mut file = std::fs::File::open(crate::core::path::long_path(pkg_path))?;
+
+    let mut unpacked = 0u64;
+    let mut tex_size = 0u64;
+    let mut converted = 0u64;
+    let mut tex_count = 0;
+    let mut passthrough_count = 0;
+    for entry in &pkg_info.entries {
+        let size = entry.size as u64;
+        unpacked += size;
+        if !entry.name.to_lowercase().ends_with(".tex") {
+            continue;
+        }
+        tex_count += 1;
+        tex_size += size;
+
+        // LZ4 压缩条目或读取失败的 TEX 按膨胀系数估算
+        let mut head = Vec::new();
+        let start = pkg_info.data_start as u64 + entry.offset as u64;
+        let read = !entry.compressed
+            && file.seek(SeekFrom::Start(start)).is_ok()
+            && (&mut file)
+                .take(size.min(TEX_HEAD_LEN))
+                .read_to_end(&mut head)
+                .is_ok();
+        let tex = read
+            .then(|| core_tex::estimate_tex_output(core_tex::EstimateTexInput { head, size }).ok())
+            .flatten();
+        match tex {
+            Some(tex) => {
+                passthrough_count += tex.passthrough as usize;
+                converted += tex.output_size;
+            }
+            None => converted += (size as f64 * CONVERT_RATIO) as u64,
+        }
+    }
+
+    estimate.unpacked_size += unpacked;
+    estimate.tex_size += tex_size;
+    estimate.converted_size += converted;
+    estimate.tex_count += tex_count;
+    estimate.passthrough_count += passthrough_count;
+    Ok(())
+}
+
+/// 检测流水线各阶段的源/目标目录是否跨文件系统
+///
+/// 无法判断的路径对（如目录无法访问）视为同一文件系统
+pub fn detect_cross_device(config: &native_cfg::RuntimeConfig) -> Vec<CrossDeviceHint> {
+    let mut pairs = vec![
+        ("copy", &config.workshop_path, &config.pkg_temp_path),
+        (
+            "unpack",
+            &config.pkg_temp_path,
+            &config.unpacked_output_path,
+        ),
+    ];
+    if let Some(ref converted) = config.converted_output_path {
+        pairs.push(("convert", &config.unpacked_output_path, converted));
+    }
+
+    pairs
+        .into_iter()
+        .filter(|(_, from, to)| {
+            disk::same_device(disk::SameDeviceInput {
+                path_a: (*from).clone(),
+                path_b: (*to).clone(),
+            })
+            .map(|o| !o.same_device)
+            .unwrap_or(false)
+        })
+        .map(|(stage, from, to)| CrossDeviceHint {
+            stage: stage.to_string(),
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .collect()
+}
+
 // ============================================================================
 // 流水线执行接口
 // ============================================================================
@@ -299,6 +614,7 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
     let start_time = Instant::now();
 
     let mut stats = PipelineStats::default();
+    let mut stage_errors: Vec<PipelineStageError> = Vec::new();
 
     // 应用参数覆盖
     let mut config = input.config;
@@ -330,6 +646,28 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
         }
     };
 
+    // 辅助闭包：报告单项结果
+    let report_item = |event: PipelineItemEvent| {
+        if let Some(callback) = &input.item_callback {
+            callback(event);
+        }
+    };
+
+    // 跨文件系统检测（仅提示）
+    for hint in detect_cross_device(&config) {
+        debug_log(
+            DebugLogType::Return,
+            "disk",
+            "same_device",
+            &format!(
+                "cross-device {}: {} -> {} (no rename/hard link, full copy)",
+                hint.stage,
+                hint.from.display(),
+                hint.to.display()
+            ),
+        );
+    }
+
     // ========== 阶段1: 加载状态 ==========
     report_progress(PipelineStage::Init, 0, None, "Loading state...");
     debug_log(
@@ -346,16 +684,69 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
         &format!("processed_count={}", state.processed_wallpapers.len()),
     );
 
+    // 移除需要重新处理的壁纸记录，使其不被增量模式跳过
+    let reprocess_ids = input.reprocess_ids.unwrap_or_default();
+    if !reprocess_ids.is_empty() {
+        let removed: usize = reprocess_ids
+            .iter()
+            .map(|id| native_cfg::remove_processed_wallpaper(&mut state, id))
+            .sum();
+        debug_log(
+            DebugLogType::Return,
+            "native",
+            "remove_processed_wallpaper",
+            &format!("ids={:?}, removed_records={}", reprocess_ids, removed),
+        );
+    }
+
+    // 严格增量：输出目录已被删除的壁纸视为未处理
+    if config.pipeline.incremental && config.pipeline.strict_incremental {
+        let stale = native_cfg::remove_stale_wallpapers(&mut state, &config);
+        stats.wallpapers_stale = stale.len();
+        debug_log(
+            DebugLogType::Return,
+            "native",
+            "remove_stale_wallpapers",
+            &format!("stale={:?}", stale),
+        );
+    }
+
+    // 指定了 ID 列表时，重新处理的 ID 也需要纳入
+    let wallpaper_ids = input.wallpaper_ids.map(|mut ids| {
+        for id in &reprocess_ids {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    });
+
     // ========== 阶段2: 扫描壁纸 ==========
     report_progress(PipelineStage::Scanning, 10, None, "Scanning wallpapers...");
+    // 增量模式下复用上次的扫描结果，只重新读取修改过的壁纸
+    let scan_cache = config
+        .pipeline
+        .incremental
+        .then(|| input.state_path.with_file_name(SCAN_CACHE_FILE));
     debug_log(
         DebugLogType::Enter,
         "paper",
         "scan_wallpapers",
-        &format!("path={}", config.workshop_path.display()),
+        &format!(
+            "path={}, types={:?}, tags={:?}, cache={:?}",
+            config.workshop_path.display(),
+            input.type_filter,
+            input.tag_filter,
+            scan_cache
+        ),
     );
     let scan_result = native_paper::scan_wallpapers(native_paper::ScanWallpapersInput {
         workshop_path: config.workshop_path.clone(),
+        type_filter: input.type_filter.clone(),
+        tag_filter: input.tag_filter.clone(),
+        generate_thumbnails: None,
+        cache: scan_cache.clone(),
+        pkg_depth: config.pkg_depth,
     });
 
     if !scan_result.success {
@@ -370,7 +761,17 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
             paper_result: None,
             pkg_result: None,
             tex_result: None,
+            archive_result: None,
             stats,
+            estimate_record: None,
+            stage_errors: vec![PipelineStageError {
+                stage: PipelineStage::Scanning,
+                item: None,
+                message: scan_result
+                    .error
+                    .unwrap_or_else(|| "Failed to scan wallpapers".to_string()),
+            }],
+            cancelled: false,
             error: Some("Failed to scan wallpapers".to_string()),
         };
     }
@@ -379,23 +780,62 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
         "paper",
         "scan_wallpapers",
         &format!(
-            "total={}, pkg={}, raw={}",
-            scan_result.stats.total_count, scan_result.stats.pkg_count, scan_result.stats.raw_count
+            "total={}, pkg={}, raw={}, cache_hits={}",
+            scan_result.stats.total_count,
+            scan_result.stats.pkg_count,
+            scan_result.stats.raw_count,
+            scan_result.stats.cache_hits
         ),
     );
 
+    // 上次运行中断留下的记录：停在 Copied 的 PKG 仍在 Pkg_Temp 中，本次补做解包
+    let unfinished = native_cfg::unfinished_wallpapers(&state);
+    let resume_unpack = config.pipeline.auto_unpack_pkg
+        && config.pkg_temp_path.is_dir()
+        && state
+            .processed_wallpapers
+            .iter()
+            .any(|w| w.stage == cfg::ProcessStage::Copied);
+    stats.wallpapers_resumed = unfinished.len();
+    if !unfinished.is_empty() {
+        debug_log(
+            DebugLogType::Return,
+            "native",
+            "unfinished_wallpapers",
+            &format!("ids={:?}, resume_unpack={}", unfinished, resume_unpack),
+        );
+    }
+
     // 筛选待处理的壁纸（增量处理）
     let wallpapers_to_process: Vec<String> = filter_wallpapers(
         &scan_result.wallpapers,
         &state,
-        input.wallpaper_ids.as_ref(),
+        wallpaper_ids.as_ref(),
         config.pipeline.incremental,
     );
 
     stats.wallpapers_skipped = scan_result.wallpapers.len() - wallpapers_to_process.len();
 
+    // 辅助闭包：统计目录大小（用于预估对账）
+    let measure = |p: &PathBuf| disk::dir_size(disk::DirSizeInput { path: p.clone() }).size;
+
     // ========== 阶段3: 复制壁纸 ==========
+    let pkg_temp_before = measure(&config.pkg_temp_path);
+    let copy_start = Instant::now();
     report_progress(PipelineStage::Copying, 30, None, "Copying wallpapers...");
+    // 按壁纸推进 30% → 50%
+    let on_copy_progress = |p: native_paper::CopyProgress| {
+        let progress = 30 + ((p.current - 1) * 20 / p.total.max(1)) as u8;
+        report_progress(
+            PipelineStage::Copying,
+            progress,
+            Some(p.wallpaper_id.clone()),
+            &format!(
+                "Copying wallpaper {} ({}/{})",
+                p.wallpaper_id, p.current, p.total
+            ),
+        );
+    };
     debug_log(
         DebugLogType::Enter,
         "paper",
@@ -406,47 +846,129 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
             config.enable_raw_output
         ),
     );
-    let paper_result = native_paper::copy_wallpapers(native_paper::CopyWallpapersInput {
-        wallpaper_ids: Some(wallpapers_to_process.clone()),
-        workshop_path: config.workshop_path.clone(),
-        raw_output_path: config.raw_output_path.clone(),
-        pkg_temp_path: config.pkg_temp_path.clone(),
-        enable_raw: config.enable_raw_output,
-    });
+    // 每复制完一个壁纸就记录到 state，按检查点间隔写盘
+    let checkpoint = Mutex::new(StateCheckpoint::new(
+        state,
+        &input.state_path,
+        config.pipeline.checkpoint_interval,
+    ));
+    let on_copied = |result: &native_paper::CopyResult| {
+        let (process_type, stage) = match result.result_type {
+            native_paper::CopyResultType::Raw => {
+                (cfg::WallpaperProcessType::Raw, cfg::ProcessStage::Complete)
+            }
+            native_paper::CopyResultType::Pkg if config.pipeline.auto_unpack_pkg => {
+                (cfg::WallpaperProcessType::Pkg, cfg::ProcessStage::Copied)
+            }
+            native_paper::CopyResultType::Pkg => {
+                (cfg::WallpaperProcessType::Pkg, cfg::ProcessStage::Complete)
+            }
+            native_paper::CopyResultType::Skipped => (
+                cfg::WallpaperProcessType::Skipped,
+                cfg::ProcessStage::Complete,
+            ),
+        };
+        if let Ok(mut checkpoint) = checkpoint.lock() {
+            checkpoint.update(|state| {
+                native_cfg::add_processed_wallpaper(
+                    state,
+                    result.wallpaper_id.clone(),
+                    result.title.clone(),
+                    process_type,
+                    None,
+                    stage,
+                )
+            });
+        }
+    };
+    let paper_result = native_paper::copy_wallpapers_with_callbacks(
+        native_paper::CopyWallpapersInput {
+            wallpaper_ids: Some(wallpapers_to_process.clone()),
+            workshop_path: config.workshop_path.clone(),
+            raw_output_path: config.raw_output_path.clone(),
+            pkg_temp_path: config.pkg_temp_path.clone(),
+            enable_raw: config.enable_raw_output,
+            max_rating: config.max_content_rating,
+            only_types: config.only_types.clone(),
+            type_filter: input.type_filter.clone(),
+            tag_filter: input.tag_filter.clone(),
+            cache: scan_cache.clone(),
+            force: false,
+            pkg_depth: config.pkg_depth,
+            cancel: input.cancel.clone(),
+        },
+        Some(&on_copy_progress),
+        Some(&on_copied),
+    );
+    stats.copy_ms = copy_start.elapsed().as_millis() as u64;
 
     debug_log(
         DebugLogType::Return,
         "paper",
         "copy_wallpapers",
         &format!(
-            "raw={}, pkg={}, skipped={}",
+            "raw={}, pkg={}, skipped={}, filtered={}, type_skipped={}, presets={}",
             paper_result.stats.raw_copied,
             paper_result.stats.pkg_copied,
-            paper_result.stats.skipped
+            paper_result.stats.skipped,
+            paper_result.stats.filtered,
+            paper_result.stats.type_skipped,
+            paper_result.stats.presets
         ),
     );
 
     stats.wallpapers_processed = paper_result.results.len();
+    stats.wallpapers_filtered = paper_result.stats.filtered;
+    stats.wallpapers_type_skipped = paper_result.stats.type_skipped;
+    stats.wallpapers_preset = paper_result.stats.presets;
 
-    // 更新状态：记录已处理的壁纸
     for result in &paper_result.results {
-        let process_type = match result.result_type {
-            native_paper::CopyResultType::Raw => cfg::WallpaperProcessType::Raw,
-            native_paper::CopyResultType::Pkg => cfg::WallpaperProcessType::Pkg,
-            native_paper::CopyResultType::Skipped => cfg::WallpaperProcessType::Skipped,
+        let output_paths = match result.result_type {
+            native_paper::CopyResultType::Raw => {
+                vec![config.raw_output_path.join(&result.wallpaper_id)]
+            }
+            native_paper::CopyResultType::Pkg => result.pkg_files.clone(),
+            native_paper::CopyResultType::Skipped => vec![],
         };
-
-        native_cfg::add_processed_wallpaper(
-            &mut state,
-            result.wallpaper_id.clone(),
-            result.title.clone(),
-            process_type,
-            None,
-        );
+        if output_paths.is_empty() {
+            report_item(PipelineItemEvent {
+                stage: PipelineStage::Copying,
+                wallpaper_id: result.wallpaper_id.clone(),
+                item: None,
+                success: true,
+                output_path: None,
+                error: None,
+            });
+        }
+        for output_path in output_paths {
+            report_item(PipelineItemEvent {
+                stage: PipelineStage::Copying,
+                wallpaper_id: result.wallpaper_id.clone(),
+                item: None,
+                success: true,
+                output_path: Some(output_path),
+                error: None,
+            });
+        }
     }
 
     // ========== 阶段4: 解包 PKG ==========
-    let pkg_result = if config.pipeline.auto_unpack_pkg && paper_result.stats.pkg_copied > 0 {
+    let pkg_size = measure(&config.pkg_temp_path).saturating_sub(pkg_temp_before);
+    // 按本次复制的 PKG 内容预估，供结束后与实际占用对账
+    let copied_estimate = estimate_conversion(EstimateConversionInput {
+        pkg_files: paper_result
+            .results
+            .iter()
+            .filter(|r| r.result_type == native_paper::CopyResultType::Pkg)
+            .flat_map(|r| r.pkg_files.iter().cloned())
+            .collect(),
+    });
+    let unpacked_before = measure(&config.unpacked_output_path);
+    let unpack_start = Instant::now();
+    let pkg_result = if config.pipeline.auto_unpack_pkg
+        && (paper_result.stats.pkg_copied > 0 || resume_unpack)
+        && !is_cancelled(input.cancel.as_ref())
+    {
         report_progress(PipelineStage::Unpacking, 50, None, "Unpacking PKG files...");
         debug_log(
             DebugLogType::Enter,
@@ -458,10 +980,67 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
                 config.unpacked_output_path.display()
             ),
         );
-        let result = native_pkg::unpack_all(native_pkg::UnpackAllInput {
-            pkg_temp_path: config.pkg_temp_path.clone(),
-            unpacked_output_path: config.unpacked_output_path.clone(),
-        });
+        let on_unpack_progress = |p: native_pkg::UnpackProgress| {
+            // scene_name 取自 PKG 临时文件名前缀，即壁纸 ID（输出目录名可能带 -N 后缀）
+            if p.result.success {
+                let stage = if config.pipeline.auto_convert_tex {
+                    cfg::ProcessStage::Unpacked
+                } else {
+                    cfg::ProcessStage::Complete
+                };
+                if let Ok(mut checkpoint) = checkpoint.lock() {
+                    checkpoint.update(|state| {
+                        native_cfg::update_process_stage(state, &p.result.scene_name, stage);
+                    });
+                }
+            }
+            report_item(PipelineItemEvent {
+                stage: PipelineStage::Unpacking,
+                wallpaper_id: p.result.scene_name,
+                item: Some(p.result.pkg_path),
+                success: p.result.success,
+                output_path: Some(p.result.output_dir).filter(|_| p.result.success),
+                error: p.result.error,
+            });
+        };
+        // 按条目推进 50% → 70%：每个 PKG 记录自身完成比例，取平均值
+        let pkg_fractions: Mutex<(Vec<f64>, u8)> = Mutex::new((Vec::new(), 50));
+        let on_entry_progress = |p: native_pkg::UnpackEntryProgress| {
+            let Ok(mut guard) = pkg_fractions.lock() else {
+                return;
+            };
+            let (fractions, last) = &mut *guard;
+            fractions.resize(p.total_pkgs, 0.0);
+            fractions[p.pkg_index] = (p.entry_index + 1) as f64 / p.total_entries.max(1) as f64;
+            let done: f64 = fractions.iter().sum::<f64>() / p.total_pkgs.max(1) as f64;
+            let progress = 50 + (done * 20.0) as u8;
+            // 只在百分比前进时报告（持锁报告，保证并行时输出单调），避免大量条目刷屏
+            if progress > *last {
+                *last = progress;
+                report_progress(
+                    PipelineStage::Unpacking,
+                    progress,
+                    Some(p.pkg_name.clone()),
+                    &format!("Unpacking {}", p.pkg_name),
+                );
+            }
+        };
+        let result = native_pkg::unpack_all_with_callbacks(
+            native_pkg::UnpackAllInput {
+                pkg_temp_path: config.pkg_temp_path.clone(),
+                unpacked_output_path: config.unpacked_output_path.clone(),
+                fail_threshold: config.pipeline.effective_fail_threshold(),
+                duplicate_strategy: core_pkg::DuplicateStrategy::Overwrite,
+                jobs: None,
+                include: None,
+                exclude: None,
+                copy_scene_resources: false,
+                workshop_path: None,
+                cancel: input.cancel.clone(),
+            },
+            Some(&on_unpack_progress),
+            Some(&on_entry_progress),
+        );
         debug_log(
             DebugLogType::Return,
             "pkg",
@@ -475,57 +1054,81 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
             ),
         );
         stats.pkgs_unpacked = result.stats.pkg_success;
+        stats.failed_files.extend(
+            result
+                .results
+                .iter()
+                .filter(|r| !r.success)
+                .map(|r| r.pkg_path.clone()),
+        );
+        stage_errors.extend(unpack_errors(&result));
         Some(result)
     } else {
         None
     };
+    stats.unpack_ms = unpack_start.elapsed().as_millis() as u64;
+    let mut state = checkpoint
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_state();
+
+    // 非 keep_going 模式下，阶段整体失败（如熔断中止）后不再执行后续处理阶段
+    let halted = !config.pipeline.keep_going && stage_errors.iter().any(|e| e.item.is_none());
+    if halted {
+        debug_log(
+            DebugLogType::Error,
+            "pipeline",
+            "halt",
+            "stage failed, skipping remaining stages",
+        );
+    }
 
     // ========== 阶段5: 转换 TEX ==========
-    let tex_result = if config.pipeline.auto_convert_tex {
-        let should_convert = pkg_result
-            .as_ref()
-            .map(|r| r.stats.tex_files > 0)
-            .unwrap_or(false);
-
-        if should_convert {
-            report_progress(
-                PipelineStage::Converting,
-                70,
-                None,
-                "Converting TEX files...",
-            );
-            debug_log(
-                DebugLogType::Enter,
-                "tex",
-                "convert_all",
-                &format!(
-                    "input={}, output={:?}",
-                    config.unpacked_output_path.display(),
-                    config.converted_output_path
-                ),
-            );
-            let result = native_tex::convert_all(native_tex::ConvertAllInput {
-                unpacked_path: config.unpacked_output_path.clone(),
-                output_path: config.converted_output_path.clone(),
-            });
+    let unpacked_after_unpack = measure(&config.unpacked_output_path);
+    let converted_before = config
+        .converted_output_path
+        .as_ref()
+        .map(measure)
+        .unwrap_or(0);
+    // 按壁纸分组报告进度：70% → 85%
+    let on_tex_progress = |p: native_tex::ConvertProgress| {
+        let progress = 70 + (p.overall_current * 15 / p.overall_total.max(1)) as u8;
+        report_progress(
+            PipelineStage::Converting,
+            progress,
+            Some(p.group.clone()),
+            &format!(
+                "Wallpaper {} ({}/{}): {}/{} tex",
+                p.group, p.group_index, p.group_count, p.current, p.total
+            ),
+        );
+        if p.current == p.total {
             debug_log(
                 DebugLogType::Return,
                 "tex",
-                "convert_all",
-                &format!(
-                    "success={}, failed={}, images={}, videos={}",
-                    result.stats.tex_success,
-                    result.stats.tex_failed,
-                    result.stats.image_count,
-                    result.stats.video_count
-                ),
+                "convert_group",
+                &format!("wallpaper={}, tex={}", p.group, p.total),
             );
-            stats.texs_converted = result.stats.tex_success;
-            Some(result)
-        } else {
-            // 检查是否有待转换的 TEX（可能是之前解包但未转换的）
-            let tex_files = native_pkg::get_tex_files_from_unpacked(&config.unpacked_output_path);
-            if !tex_files.is_empty() {
+        }
+        report_item(PipelineItemEvent {
+            stage: PipelineStage::Converting,
+            wallpaper_id: p.group,
+            item: Some(p.result.input_path),
+            success: p.result.success,
+            output_path: Some(p.result.output_path).filter(|_| p.result.success),
+            error: p.result.error,
+        });
+    };
+
+    let convert_start = Instant::now();
+    let tex_result =
+        if config.pipeline.auto_convert_tex && !halted && !is_cancelled(input.cancel.as_ref()) {
+            let should_convert = pkg_result
+                .as_ref()
+                .map(|r| r.stats.tex_files > 0)
+                .unwrap_or(false);
+
+            if should_convert {
                 report_progress(
                     PipelineStage::Converting,
                     70,
@@ -537,15 +1140,33 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
                     "tex",
                     "convert_all",
                     &format!(
-                        "input={}, output={:?} (fallback)",
+                        "input={}, output={:?}",
                         config.unpacked_output_path.display(),
                         config.converted_output_path
                     ),
                 );
-                let result = native_tex::convert_all(native_tex::ConvertAllInput {
-                    unpacked_path: config.unpacked_output_path.clone(),
-                    output_path: config.converted_output_path.clone(),
-                });
+                let result = native_tex::convert_all_with_progress(
+                    native_tex::ConvertAllInput {
+                        unpacked_path: config.unpacked_output_path.clone(),
+                        output_path: config.converted_output_path.clone(),
+                        strict: config.tex_strict,
+                        strict_lz4: config.tex_strict_lz4,
+                        in_place: false,
+                        remove_source: false,
+                        fail_threshold: config.pipeline.effective_fail_threshold(),
+                        thumbnail_size: config.pipeline.thumbnail_size,
+                        jobs: None,
+                        max_memory_mb: config.pipeline.max_memory_mb,
+                        missing_only: false,
+                        on_conflict: config.tex_on_conflict,
+                        mipmap: core_tex::MipmapSelection::First,
+                        animate: None,
+                        output_format: core_tex::ImageOutputFormat::Png,
+                        flip_vertical: false,
+                        cancel: input.cancel.clone(),
+                    },
+                    Some(&on_tex_progress),
+                );
                 debug_log(
                     DebugLogType::Return,
                     "tex",
@@ -559,17 +1180,180 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
                     ),
                 );
                 stats.texs_converted = result.stats.tex_success;
+                stats.failed_files.extend(failed_tex_files(&result));
+                stage_errors.extend(convert_errors(&result));
                 Some(result)
             } else {
-                None
+                // 检查是否有待转换的 TEX（可能是之前解包但未转换的）
+                let tex_files =
+                    native_pkg::get_tex_files_from_unpacked(&config.unpacked_output_path);
+                if !tex_files.is_empty() {
+                    report_progress(
+                        PipelineStage::Converting,
+                        70,
+                        None,
+                        "Converting TEX files...",
+                    );
+                    debug_log(
+                        DebugLogType::Enter,
+                        "tex",
+                        "convert_all",
+                        &format!(
+                            "input={}, output={:?} (fallback)",
+                            config.unpacked_output_path.display(),
+                            config.converted_output_path
+                        ),
+                    );
+                    let result = native_tex::convert_all_with_progress(
+                        native_tex::ConvertAllInput {
+                            unpacked_path: config.unpacked_output_path.clone(),
+                            output_path: config.converted_output_path.clone(),
+                            strict: config.tex_strict,
+                            strict_lz4: config.tex_strict_lz4,
+                            in_place: false,
+                            remove_source: false,
+                            fail_threshold: config.pipeline.effective_fail_threshold(),
+                            thumbnail_size: config.pipeline.thumbnail_size,
+                            jobs: None,
+                            max_memory_mb: config.pipeline.max_memory_mb,
+                            missing_only: false,
+                            on_conflict: config.tex_on_conflict,
+                            mipmap: core_tex::MipmapSelection::First,
+                            animate: None,
+                            output_format: core_tex::ImageOutputFormat::Png,
+                            flip_vertical: false,
+                            cancel: input.cancel.clone(),
+                        },
+                        Some(&on_tex_progress),
+                    );
+                    debug_log(
+                        DebugLogType::Return,
+                        "tex",
+                        "convert_all",
+                        &format!(
+                            "success={}, failed={}, images={}, videos={}",
+                            result.stats.tex_success,
+                            result.stats.tex_failed,
+                            result.stats.image_count,
+                            result.stats.video_count
+                        ),
+                    );
+                    stats.texs_converted = result.stats.tex_success;
+                    stats.failed_files.extend(failed_tex_files(&result));
+                    stage_errors.extend(convert_errors(&result));
+                    Some(result)
+                } else {
+                    None
+                }
+            }
+        } else {
+            None
+        };
+    stats.convert_ms = convert_start.elapsed().as_millis() as u64;
+
+    // 取消后跳过收尾的归档、对账与中间产物清理，保留可续跑的进度
+    let cancelled = is_cancelled(input.cancel.as_ref());
+    if cancelled {
+        debug_log(
+            DebugLogType::Error,
+            "pipeline",
+            "cancel",
+            "cancel requested, skipping remaining stages",
+        );
+    }
+
+    // ========== 回填处理类型 ==========
+    // TEX 按解包输出目录名分组；同一壁纸的多个 PKG 会解包到 `{id}`、`{id}-1` 等目录，
+    // 按 scene_name（壁纸 ID）汇总其全部目录，全部解包且转换成功的壁纸记为 PkgTex
+    if let (Some(pkg_res), Some(tex_res)) = (&pkg_result, &tex_result) {
+        let mut per_wallpaper: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for unpacked in &pkg_res.results {
+            let (success, failed) = per_wallpaper.entry(&unpacked.scene_name).or_default();
+            if !unpacked.success {
+                *failed += 1;
+                continue;
+            }
+            let dir_name = unpacked.output_dir.file_name().map(|n| n.to_string_lossy());
+            for group in tex_res
+                .groups
+                .iter()
+                .filter(|g| Some(g.group.as_str()) == dir_name.as_deref())
+            {
+                *success += group.tex_success;
+                *failed += group.tex_failed;
+            }
+        }
+        for (wallpaper_id, (success, failed)) in per_wallpaper {
+            if success > 0 && failed == 0 {
+                native_cfg::update_process_type(
+                    &mut state,
+                    wallpaper_id,
+                    cfg::WallpaperProcessType::PkgTex,
+                );
             }
         }
+    }
+
+    // 流水线未中止时所有阶段都已跑完（含上次中断留下的 PKG / TEX），记录标记为完成
+    if !halted && !cancelled {
+        for record in state
+            .processed_wallpapers
+            .iter_mut()
+            .filter(|w| w.stage != cfg::ProcessStage::Complete)
+        {
+            record.stage = cfg::ProcessStage::Complete;
+        }
+    }
+
+    // ========== 预估对账 ==========
+    // 在清理前统计实际占用，与按 PKG 内容得到的预估对比
+    let estimate_record = if pkg_result.is_some() && pkg_size > 0 && !cancelled {
+        let actual_unpacked = unpacked_after_unpack.saturating_sub(unpacked_before);
+        let actual_converted = match config.converted_output_path {
+            Some(ref p) => measure(p).saturating_sub(converted_before),
+            None => measure(&config.unpacked_output_path).saturating_sub(unpacked_after_unpack),
+        };
+        let record = cfg::EstimateRecord {
+            recorded_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            pkg_size,
+            estimated_unpacked: copied_estimate.unpacked_size,
+            actual_unpacked,
+            estimated_converted: if tex_result.is_some() {
+                copied_estimate.converted_size
+            } else {
+                0
+            },
+            actual_converted,
+        };
+        debug_log(
+            DebugLogType::Return,
+            "pipeline",
+            "estimate_check",
+            &format!(
+                "pkg={}, unpacked={}/{} (est/actual), converted={}/{}",
+                record.pkg_size,
+                record.estimated_unpacked,
+                record.actual_unpacked,
+                record.estimated_converted,
+                record.actual_converted
+            ),
+        );
+        state.estimate_history.push(record);
+        let overflow = state
+            .estimate_history
+            .len()
+            .saturating_sub(ESTIMATE_HISTORY_LIMIT);
+        state.estimate_history.drain(..overflow);
+        Some(record)
     } else {
         None
     };
 
     // ========== 阶段5.5: 复制元数据 ==========
-    if tex_result.is_some() {
+    if tex_result.is_some() && !cancelled {
         report_progress(PipelineStage::Cleanup, 85, None, "Copying metadata...");
         debug_log(
             DebugLogType::Enter,
@@ -588,28 +1372,82 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
     // ========== 阶段6: 清理 ==========
     report_progress(PipelineStage::Cleanup, 90, None, "Cleaning up...");
 
+    // 归档 pkg_temp 中的 PKG（先于清理，移动而非删除）
+    let archive_result = match config.archive_pkg_path {
+        Some(ref archive_path) if config.pkg_temp_path.is_dir() && !cancelled => {
+            debug_log(
+                DebugLogType::Enter,
+                "pkg",
+                "archive_pkgs",
+                &format!(
+                    "input={}, output={}",
+                    config.pkg_temp_path.display(),
+                    archive_path.display()
+                ),
+            );
+            let result = native_pkg::archive_pkgs(native_pkg::ArchivePkgsInput {
+                pkg_temp_path: config.pkg_temp_path.clone(),
+                archive_path: archive_path.clone(),
+            });
+            debug_log(
+                DebugLogType::Return,
+                "pkg",
+                "archive_pkgs",
+                &format!("archived={}, failed={}", result.archived, result.failed),
+            );
+            stats.pkgs_archived = result.archived;
+            stage_errors.extend(archive_errors(&result));
+            Some(result)
+        }
+        _ => None,
+    };
+    // 归档有失败时保留 pkg_temp，避免未归档的 PKG 被一并删除
+    let archive_ok = archive_result.as_ref().is_none_or(|r| r.success);
+
     // 清理 pkg_temp 目录
-    if config.clean_pkg_temp {
+    if config.clean_pkg_temp && archive_ok {
         debug_log(
             DebugLogType::Enter,
             "pipeline",
             "clean_pkg_temp",
             &config.pkg_temp_path.display().to_string(),
         );
+        let before = measure(&config.pkg_temp_path);
         let _ = std::fs::remove_dir_all(&config.pkg_temp_path);
-        debug_log(DebugLogType::Return, "pipeline", "clean_pkg_temp", "done");
+        let freed = before.saturating_sub(measure(&config.pkg_temp_path));
+        stats.freed_bytes += freed;
+        debug_log(
+            DebugLogType::Return,
+            "pipeline",
+            "clean_pkg_temp",
+            &format!("freed={}", freed),
+        );
+        // 取消时还未解包的 PKG 随 pkg_temp 一起删除，移除其记录以便下次重新复制
+        if cancelled {
+            state
+                .processed_wallpapers
+                .retain(|w| w.stage != cfg::ProcessStage::Copied);
+        }
     }
 
     // 清理 unpacked 目录（保留 tex_converted）
-    if config.clean_unpacked {
+    if config.clean_unpacked && !cancelled {
         debug_log(
             DebugLogType::Enter,
             "pipeline",
             "clean_unpacked",
             "keeping tex_converted",
         );
+        let before = measure(&config.unpacked_output_path);
         clean_unpacked_dir(&config.unpacked_output_path);
-        debug_log(DebugLogType::Return, "pipeline", "clean_unpacked", "done");
+        let freed = before.saturating_sub(measure(&config.unpacked_output_path));
+        stats.freed_bytes += freed;
+        debug_log(
+            DebugLogType::Return,
+            "pipeline",
+            "clean_unpacked",
+            &format!("freed={}", freed),
+        );
     }
 
     // ========== 阶段7: 保存状态 ==========
@@ -636,14 +1474,178 @@ pub fn run_pipeline(input: RunPipelineInput) -> RunPipelineOutput {
 
     report_progress(PipelineStage::Done, 100, None, "Pipeline completed");
 
+    // 默认模式只有阶段中止才算失败；keep_going 模式下任何错误都算失败
+    let failed = if config.pipeline.keep_going {
+        !stage_errors.is_empty()
+    } else {
+        halted
+    };
+    let error = if halted {
+        stage_errors
+            .iter()
+            .find(|e| e.item.is_none())
+            .map(|e| e.message.clone())
+    } else if failed {
+        Some(format!("{} stage error(s)", stage_errors.len()))
+    } else if cancelled {
+        Some("Cancelled by user".to_string())
+    } else {
+        None
+    };
+
     RunPipelineOutput {
-        success: true,
+        success: !failed && !cancelled,
         paper_result: Some(paper_result),
         pkg_result,
         tex_result,
+        archive_result,
         stats,
-        error: None,
+        estimate_record,
+        stage_errors,
+        cancelled,
+        error,
+    }
+}
+
+/// 运行中的 state.json 检查点
+///
+/// 持有流水线运行中的状态数据，每累计 `interval` 次变更写盘一次，
+/// 中途被中断时 state.json 仍能反映已完成的工作；interval 为 0 时只在结束时保存
+struct StateCheckpoint<'a> {
+    /// 当前状态（运行结束后由 into_state 取回，做最终保存）
+    state: cfg::StateData,
+    /// 检查点写入的 state.json 路径
+    state_path: &'a Path,
+    /// 每多少次变更写盘一次，0 表示不做中途写盘
+    interval: usize,
+    /// 上次写盘后累计的变更次数
+    pending: usize,
+}
+
+impl<'a> StateCheckpoint<'a> {
+    fn new(state: cfg::StateData, state_path: &'a Path, interval: usize) -> Self {
+        Self {
+            state,
+            state_path,
+            interval,
+            pending: 0,
+        }
+    }
+
+    /// 修改状态，达到间隔时写盘
+    fn update(&mut self, f: impl FnOnce(&mut cfg::StateData)) {
+        f(&mut self.state);
+        if self.interval == 0 {
+            return;
+        }
+        self.pending += 1;
+        if self.pending >= self.interval {
+            self.pending = 0;
+            let _ = native_cfg::save_state(native_cfg::SaveStateInput {
+                state_path: self.state_path.to_path_buf(),
+                state: self.state.clone(),
+            });
+        }
+    }
+
+    /// 取回最终状态
+    fn into_state(self) -> cfg::StateData {
+        self.state
+    }
+}
+
+/// 收集 PKG 归档的错误
+fn archive_errors(result: &native_pkg::ArchivePkgsOutput) -> Vec<PipelineStageError> {
+    let mut errors: Vec<PipelineStageError> = result
+        .results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| PipelineStageError {
+            stage: PipelineStage::Cleanup,
+            item: Some(r.pkg_path.display().to_string()),
+            message: r
+                .error
+                .clone()
+                .unwrap_or_else(|| "Archive failed".to_string()),
+        })
+        .collect();
+    if let Some(e) = result.error.as_ref().filter(|_| errors.is_empty()) {
+        errors.push(PipelineStageError {
+            stage: PipelineStage::Cleanup,
+            item: None,
+            message: e.clone(),
+        });
+    }
+    errors
+}
+
+/// 收集 PKG 解包阶段的错误
+fn unpack_errors(result: &native_pkg::UnpackAllOutput) -> Vec<PipelineStageError> {
+    let mut errors: Vec<PipelineStageError> = result
+        .results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| PipelineStageError {
+            stage: PipelineStage::Unpacking,
+            item: Some(r.pkg_path.display().to_string()),
+            message: r
+                .error
+                .clone()
+                .unwrap_or_else(|| "Unpack failed".to_string()),
+        })
+        .collect();
+    // 单项失败已逐条记录，只有熔断或无法归因到单项的错误才算阶段整体失败
+    if let Some(e) = result
+        .error
+        .as_ref()
+        .filter(|_| result.aborted || errors.is_empty())
+    {
+        errors.push(PipelineStageError {
+            stage: PipelineStage::Unpacking,
+            item: None,
+            message: e.clone(),
+        });
+    }
+    errors
+}
+
+/// 收集 TEX 转换阶段的错误
+fn convert_errors(result: &native_tex::ConvertAllOutput) -> Vec<PipelineStageError> {
+    let mut errors: Vec<PipelineStageError> = result
+        .results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| PipelineStageError {
+            stage: PipelineStage::Converting,
+            item: Some(r.input_path.display().to_string()),
+            message: r
+                .error
+                .clone()
+                .unwrap_or_else(|| "Convert failed".to_string()),
+        })
+        .collect();
+    // 单项失败已逐条记录，只有熔断或无法归因到单项的错误才算阶段整体失败
+    if let Some(e) = result
+        .error
+        .as_ref()
+        .filter(|_| result.aborted || errors.is_empty())
+    {
+        errors.push(PipelineStageError {
+            stage: PipelineStage::Converting,
+            item: None,
+            message: e.clone(),
+        });
     }
+    errors
+}
+
+/// 转换失败的 TEX 路径
+fn failed_tex_files(result: &native_tex::ConvertAllOutput) -> impl Iterator<Item = PathBuf> + '_ {
+    result
+        .results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| r.input_path.clone())
 }
 
 /// 快速执行流水线
@@ -682,9 +1684,14 @@ pub fn quick_run(input: QuickRunInput) -> QuickRunOutput {
         config,
         state_path: init_result.state_path,
         wallpaper_ids: None,
+        reprocess_ids: None,
+        type_filter: None,
+        tag_filter: None,
         overrides: None,
-        progress_callback: None,
+        progress_callback: input.progress_callback,
         debug_logger: None,
+        item_callback: None,
+        cancel: None,
     });
 
     QuickRunOutput {
@@ -695,24 +1702,77 @@ pub fn quick_run(input: QuickRunInput) -> QuickRunOutput {
 }
 
 /// 仅执行 PKG 解包阶段
+///
+/// 失败处理使用默认的流水线配置（首个失败即中止），需要继续处理时使用 [`run_pkg_only_with_config`]
 pub fn run_pkg_only(
     pkg_temp_path: PathBuf,
     unpacked_output_path: PathBuf,
+) -> native_pkg::UnpackAllOutput {
+    run_pkg_only_with_config(
+        pkg_temp_path,
+        unpacked_output_path,
+        &native_cfg::PipelineConfig::default(),
+    )
+}
+
+/// 仅执行 PKG 解包阶段（失败熔断取自 `pipeline` 的 fail_fast / fail_threshold）
+pub fn run_pkg_only_with_config(
+    pkg_temp_path: PathBuf,
+    unpacked_output_path: PathBuf,
+    pipeline: &native_cfg::PipelineConfig,
 ) -> native_pkg::UnpackAllOutput {
     native_pkg::unpack_all(native_pkg::UnpackAllInput {
         pkg_temp_path,
         unpacked_output_path,
+        fail_threshold: pipeline.effective_fail_threshold(),
+        duplicate_strategy: core_pkg::DuplicateStrategy::Overwrite,
+        jobs: None,
+        include: None,
+        exclude: None,
+        copy_scene_resources: false,
+        workshop_path: None,
+        cancel: None,
     })
 }
 
 /// 仅执行 TEX 转换阶段
+///
+/// 失败处理使用默认的流水线配置（首个失败即中止），需要继续处理时使用 [`run_tex_only_with_config`]
 pub fn run_tex_only(
     unpacked_path: PathBuf,
     output_path: Option<PathBuf>,
+) -> native_tex::ConvertAllOutput {
+    run_tex_only_with_config(
+        unpacked_path,
+        output_path,
+        &native_cfg::PipelineConfig::default(),
+    )
+}
+
+/// 仅执行 TEX 转换阶段（失败熔断取自 `pipeline` 的 fail_fast / fail_threshold）
+pub fn run_tex_only_with_config(
+    unpacked_path: PathBuf,
+    output_path: Option<PathBuf>,
+    pipeline: &native_cfg::PipelineConfig,
 ) -> native_tex::ConvertAllOutput {
     native_tex::convert_all(native_tex::ConvertAllInput {
         unpacked_path,
         output_path,
+        strict: false,
+        strict_lz4: false,
+        in_place: false,
+        remove_source: false,
+        fail_threshold: pipeline.effective_fail_threshold(),
+        thumbnail_size: None,
+        jobs: None,
+        max_memory_mb: None,
+        missing_only: false,
+        on_conflict: native_tex::ConflictPolicy::Rename,
+        mipmap: core_tex::MipmapSelection::First,
+        animate: None,
+        output_format: core_tex::ImageOutputFormat::Png,
+        flip_vertical: false,
+        cancel: None,
     })
 }
 
@@ -740,18 +1800,51 @@ fn apply_overrides(config: &mut native_cfg::RuntimeConfig, overrides: &PipelineO
     if let Some(enable) = overrides.enable_raw {
         config.enable_raw_output = enable;
     }
+    if let Some(rating) = overrides.max_rating {
+        config.max_content_rating = Some(rating);
+    }
     if let Some(clean) = overrides.clean_pkg_temp {
         config.clean_pkg_temp = clean;
     }
     if let Some(clean) = overrides.clean_unpacked {
         config.clean_unpacked = clean;
     }
+    if let Some(ref p) = overrides.archive_pkg_path {
+        config.archive_pkg_path = Some(p.clone());
+    }
+    if let Some(ref types) = overrides.only_types {
+        config.only_types = Some(types.clone());
+    }
     if let Some(inc) = overrides.incremental {
         config.pipeline.incremental = inc;
     }
+    if let Some(strict) = overrides.strict_incremental {
+        config.pipeline.strict_incremental = strict;
+    }
+    if let Some(keep_going) = overrides.keep_going {
+        config.pipeline.keep_going = keep_going;
+    }
     if let Some(convert) = overrides.auto_convert_tex {
         config.pipeline.auto_convert_tex = convert;
     }
+    if let Some(strict) = overrides.tex_strict {
+        config.tex_strict = strict;
+    }
+    if let Some(policy) = overrides.tex_on_conflict {
+        config.tex_on_conflict = policy;
+    }
+    if let Some(threshold) = overrides.fail_threshold {
+        config.pipeline.fail_threshold = Some(threshold).filter(|&n| n > 0);
+    }
+    if let Some(size) = overrides.thumbnail_size {
+        config.pipeline.thumbnail_size = Some(size).filter(|&n| n > 0);
+    }
+    if let Some(mb) = overrides.max_memory_mb {
+        config.pipeline.max_memory_mb = Some(mb).filter(|&n| n > 0);
+    }
+    if let Some(fail_fast) = overrides.fail_fast {
+        config.pipeline.fail_fast = fail_fast;
+    }
 }
 
 /// 筛选待处理的壁纸
@@ -766,7 +1859,7 @@ fn filter_wallpapers(
         .filter(|w| {
             // 检查是否在指定列表中
             let in_list = match ids {
-                Some(filter_ids) => filter_ids.contains(&w.wallpaper_id),
+                Some(filter_ids) => native_paper::id_matches(&w.wallpaper_id, filter_ids),
                 None => true,
             };
             // 增量模式检查是否已处理
@@ -897,3 +1990,678 @@ pub fn clean_unpacked_dir(unpacked_path: &PathBuf) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{pkg_bytes, tex_bytes};
+    use std::fs;
+
+    #[test]
+    fn test_estimate_conversion_reads_tex_headers() {
+        let root = std::env::temp_dir().join("lianpkg_estimate_conversion");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        // 2×2 RGBA8888：解码后 16 字节；flags 带视频位的纹理按原大小直通
+        let mut video = tex_bytes();
+        video[22..26].copy_from_slice(&32u32.to_le_bytes());
+        let pkg = pkg_bytes(&[
+            ("scene.json", b"{}".to_vec()),
+            ("materials/a.tex", tex_bytes()),
+            ("materials/v.tex", video.clone()),
+        ]);
+        let good = root.join("good.pkg");
+        fs::write(&good, &pkg).unwrap();
+        let bad = root.join("bad.pkg");
+        fs::write(&bad, b"not a pkg at all").unwrap();
+
+        let estimate = estimate_conversion(EstimateConversionInput {
+            pkg_files: vec![good],
+        });
+        let tex_len = tex_bytes().len() as u64;
+        assert_eq!(estimate.pkg_size, pkg.len() as u64);
+        assert_eq!(estimate.unpacked_size, 2 + tex_len * 2);
+        assert_eq!(estimate.tex_size, tex_len * 2);
+        assert_eq!((estimate.tex_count, estimate.passthrough_count), (2, 1));
+        assert_eq!(estimate.converted_size, 16 + tex_len);
+        assert_eq!(estimate.fallback_size, 0);
+
+        // 无法解析的 PKG 退回膨胀系数
+        let fallback = estimate_conversion(EstimateConversionInput {
+            pkg_files: vec![bad],
+        });
+        assert_eq!(fallback.fallback_size, 16);
+        assert_eq!(fallback.converted_size, (16.0 * CONVERT_RATIO) as u64);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_run_pipeline_records_pkg_tex() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_pkg_tex");
+        let _ = fs::remove_dir_all(&root);
+
+        let wallpaper = root.join("workshop").join("100001");
+        fs::create_dir_all(&wallpaper).unwrap();
+        fs::write(
+            wallpaper.join("project.json"),
+            r#"{"title": "Scene", "type": "scene"}"#,
+        )
+        .unwrap();
+        let pkg = pkg_bytes(&[
+            ("scene.json", b"{}".to_vec()),
+            ("materials/a.tex", tex_bytes()),
+        ]);
+        fs::write(wallpaper.join("scene.pkg"), &pkg).unwrap();
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            ..Default::default()
+        };
+        let state_path = root.join("state.json");
+
+        let events = std::cell::RefCell::new(Vec::new());
+        let on_item = |e: PipelineItemEvent| events.borrow_mut().push(e);
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&progress);
+        let on_progress = progress_callback(move |p| sink.lock().unwrap().push(p));
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: state_path.clone(),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: None,
+            progress_callback: Some(on_progress),
+            debug_logger: None,
+            item_callback: Some(&on_item),
+            cancel: None,
+        });
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.stats.texs_converted, 1);
+        // 清理掉的 pkg_temp 与解包中间产物计入释放空间
+        assert!(result.stats.freed_bytes >= pkg.len() as u64);
+
+        // 进度单调推进，复制阶段按壁纸上报当前项
+        let progress = progress.lock().unwrap().clone();
+        assert!(progress.windows(2).all(|w| w[0].progress <= w[1].progress));
+        assert!(progress
+            .iter()
+            .any(|p| p.stage == PipelineStage::Copying
+                && p.current_item.as_deref() == Some("100001")));
+        assert_eq!(progress.last().map(|p| p.progress), Some(100));
+        let format_counts = &result.tex_result.as_ref().unwrap().stats.format_counts;
+        assert_eq!(format_counts.get("RGBA8888"), Some(&1));
+
+        // 每个阶段逐项上报
+        let events = events.into_inner();
+        let stages: Vec<_> = events.iter().map(|e| e.stage.clone()).collect();
+        assert_eq!(
+            stages,
+            [
+                PipelineStage::Copying,
+                PipelineStage::Unpacking,
+                PipelineStage::Converting
+            ]
+        );
+        assert!(events
+            .iter()
+            .all(|e| e.wallpaper_id == "100001" && e.success && e.output_path.is_some()));
+
+        let state = native_cfg::load_state(native_cfg::LoadStateInput { state_path })
+            .state
+            .unwrap();
+        let record = state
+            .processed_wallpapers
+            .iter()
+            .find(|w| w.wallpaper_id == "100001")
+            .unwrap();
+        assert_eq!(record.process_type, cfg::WallpaperProcessType::PkgTex);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_pkg_tex_covers_all_pkgs_of_wallpaper() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_pkg_tex_multi");
+        let _ = fs::remove_dir_all(&root);
+
+        // 两个 PKG 解包到 100002 与 100002-1，其中一个含损坏的 TEX
+        let wallpaper = root.join("workshop").join("100002");
+        fs::create_dir_all(&wallpaper).unwrap();
+        fs::write(
+            wallpaper.join("project.json"),
+            r#"{"title": "Scene", "type": "scene"}"#,
+        )
+        .unwrap();
+        let good = pkg_bytes(&[("materials/a.tex", tex_bytes())]);
+        let bad = pkg_bytes(&[("materials/b.tex", b"TEXV0005".to_vec())]);
+        fs::write(wallpaper.join("extra.pkg"), good).unwrap();
+        fs::write(wallpaper.join("scene.pkg"), bad).unwrap();
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            clean_unpacked: false,
+            ..Default::default()
+        };
+        let state_path = root.join("state.json");
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: state_path.clone(),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: None,
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+        let groups: Vec<_> = result
+            .tex_result
+            .as_ref()
+            .unwrap()
+            .groups
+            .iter()
+            .map(|g| (g.group.clone(), g.tex_success, g.tex_failed))
+            .collect();
+        assert_eq!(
+            groups,
+            [("100002".to_string(), 1, 0), ("100002-1".to_string(), 0, 1)]
+        );
+
+        let state = native_cfg::load_state(native_cfg::LoadStateInput { state_path })
+            .state
+            .unwrap();
+        let record = state
+            .processed_wallpapers
+            .iter()
+            .find(|w| w.wallpaper_id == "100002")
+            .unwrap();
+        assert_ne!(record.process_type, cfg::WallpaperProcessType::PkgTex);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_cancel_stops_after_current_item() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_cancel");
+        let _ = fs::remove_dir_all(&root);
+        for id in ["100001", "100002"] {
+            let wallpaper = root.join("workshop").join(id);
+            fs::create_dir_all(&wallpaper).unwrap();
+            fs::write(wallpaper.join("project.json"), r#"{"type": "scene"}"#).unwrap();
+            let pkg = pkg_bytes(&[("materials/a.tex", tex_bytes())]);
+            fs::write(wallpaper.join("scene.pkg"), pkg).unwrap();
+        }
+        let state_path = root.join("state.json");
+
+        // 开始复制第一个壁纸时请求取消
+        let cancel = CancelFlag::default();
+        let flag = Arc::clone(&cancel);
+        let result = run_pipeline(RunPipelineInput {
+            config: native_cfg::RuntimeConfig {
+                workshop_path: root.join("workshop"),
+                raw_output_path: root.join("raw"),
+                pkg_temp_path: root.join("pkg_temp"),
+                unpacked_output_path: root.join("unpacked"),
+                ..Default::default()
+            },
+            state_path: state_path.clone(),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: None,
+            progress_callback: Some(progress_callback(move |p| {
+                if p.stage == PipelineStage::Copying && p.current_item.is_some() {
+                    flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+            })),
+            debug_logger: None,
+            item_callback: None,
+            cancel: Some(cancel),
+        });
+
+        assert!(result.cancelled);
+        assert!(!result.success);
+        assert_eq!(result.paper_result.as_ref().unwrap().results.len(), 1);
+        assert!(result.pkg_result.is_none());
+        assert!(result.tex_result.is_none());
+        // pkg_temp 按配置清理，未解包的壁纸不留记录，下次重新复制
+        assert!(!root.join("pkg_temp").exists());
+        let state = native_cfg::load_state(native_cfg::LoadStateInput { state_path })
+            .state
+            .unwrap();
+        assert!(state.processed_wallpapers.is_empty());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_resumes_interrupted_run() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_resume");
+        let _ = fs::remove_dir_all(&root);
+
+        let wallpaper = root.join("workshop").join("100002");
+        fs::create_dir_all(&wallpaper).unwrap();
+        fs::write(wallpaper.join("project.json"), r#"{"type": "scene"}"#).unwrap();
+        let pkg = pkg_bytes(&[("materials/a.tex", tex_bytes())]);
+        fs::write(wallpaper.join("scene.pkg"), &pkg).unwrap();
+
+        // 模拟上次运行在复制后被中断：PKG 已在 Pkg_Temp，记录停在 Copied
+        let pkg_temp = root.join("pkg_temp");
+        fs::create_dir_all(&pkg_temp).unwrap();
+        fs::write(
+            pkg_temp.join(crate::core::path::pkg_temp_dest("100002", "scene.pkg")),
+            &pkg,
+        )
+        .unwrap();
+        let state_path = root.join("state.json");
+        let mut state = cfg::StateData::default();
+        native_cfg::add_processed_wallpaper(
+            &mut state,
+            "100002".to_string(),
+            None,
+            cfg::WallpaperProcessType::Pkg,
+            None,
+            cfg::ProcessStage::Copied,
+        );
+        native_cfg::save_state(native_cfg::SaveStateInput {
+            state_path: state_path.clone(),
+            state,
+        });
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: pkg_temp,
+            unpacked_output_path: root.join("unpacked"),
+            ..Default::default()
+        };
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: state_path.clone(),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: Some(PipelineOverrides {
+                incremental: Some(true),
+                ..Default::default()
+            }),
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+        assert!(result.success, "{:?}", result.error);
+
+        // 不重新复制，直接从解包阶段继续
+        assert_eq!(result.stats.wallpapers_resumed, 1);
+        assert_eq!(result.stats.wallpapers_skipped, 1);
+        assert!(result.paper_result.unwrap().results.is_empty());
+        assert_eq!(result.stats.pkgs_unpacked, 1);
+        assert_eq!(result.stats.texs_converted, 1);
+
+        let state = native_cfg::load_state(native_cfg::LoadStateInput { state_path })
+            .state
+            .unwrap();
+        assert_eq!(state.processed_wallpapers.len(), 1);
+        assert_eq!(
+            state.processed_wallpapers[0].stage,
+            cfg::ProcessStage::Complete
+        );
+        assert_eq!(
+            state.processed_wallpapers[0].process_type,
+            cfg::WallpaperProcessType::PkgTex
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_state_checkpoint_interval() {
+        let root = std::env::temp_dir().join("lianpkg_state_checkpoint");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let state_path = root.join("state.json");
+        let saved = || {
+            native_cfg::load_state(native_cfg::LoadStateInput {
+                state_path: state_path.clone(),
+            })
+            .state
+            .map(|s| s.processed_wallpapers.len())
+        };
+        let add = |checkpoint: &mut StateCheckpoint, id: &str| {
+            checkpoint.update(|state| {
+                native_cfg::add_processed_wallpaper(
+                    state,
+                    id.to_string(),
+                    None,
+                    cfg::WallpaperProcessType::Raw,
+                    None,
+                    cfg::ProcessStage::Complete,
+                )
+            });
+        };
+
+        let mut checkpoint = StateCheckpoint::new(cfg::StateData::default(), &state_path, 2);
+        add(&mut checkpoint, "1");
+        assert_eq!(saved(), None);
+        add(&mut checkpoint, "2");
+        assert_eq!(saved(), Some(2));
+        add(&mut checkpoint, "3");
+        assert_eq!(saved(), Some(2));
+
+        // 间隔为 0 时不写盘
+        let _ = fs::remove_file(&state_path);
+        let mut checkpoint = StateCheckpoint::new(cfg::StateData::default(), &state_path, 0);
+        add(&mut checkpoint, "1");
+        assert_eq!(saved(), None);
+        assert_eq!(checkpoint.into_state().processed_wallpapers.len(), 1);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_keep_going() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_keep_going");
+
+        let run = |keep_going: bool, fail_threshold: Option<usize>, fail_fast: bool| {
+            let _ = fs::remove_dir_all(&root);
+            let wallpaper = root.join("workshop").join("100002");
+            fs::create_dir_all(&wallpaper).unwrap();
+            // 条目数据被截断的 PKG
+            let mut pkg = pkg_bytes(&[("scene.json", b"{}".to_vec())]);
+            pkg.truncate(pkg.len() - 1);
+            fs::write(wallpaper.join("scene.pkg"), pkg).unwrap();
+            // 之前解包但未转换的 TEX
+            let leftover = root.join("unpacked").join("100003").join("materials");
+            fs::create_dir_all(&leftover).unwrap();
+            fs::write(leftover.join("a.tex"), tex_bytes()).unwrap();
+
+            let config = native_cfg::RuntimeConfig {
+                workshop_path: root.join("workshop"),
+                raw_output_path: root.join("raw"),
+                pkg_temp_path: root.join("pkg_temp"),
+                unpacked_output_path: root.join("unpacked"),
+                clean_unpacked: false,
+                ..Default::default()
+            };
+            run_pipeline(RunPipelineInput {
+                config,
+                state_path: root.join("state.json"),
+                wallpaper_ids: None,
+                reprocess_ids: None,
+                type_filter: None,
+                tag_filter: None,
+                overrides: Some(PipelineOverrides {
+                    fail_threshold,
+                    keep_going: Some(keep_going),
+                    fail_fast: Some(fail_fast),
+                    ..Default::default()
+                }),
+                progress_callback: None,
+                debug_logger: None,
+                item_callback: None,
+                cancel: None,
+            })
+        };
+
+        // 默认：单项失败只记录，不中止后续阶段
+        let result = run(false, None, false);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.stats.texs_converted, 1);
+        assert_eq!(result.stage_errors.len(), 1);
+        assert!(result.stage_errors[0].item.is_some());
+        assert_eq!(result.stats.failed_files.len(), 1);
+        assert!(result.stats.failed_files[0]
+            .to_string_lossy()
+            .ends_with("scene.pkg"));
+
+        // 默认：解包熔断后跳过转换阶段；fail_fast 等同阈值为 1
+        for result in [run(false, Some(1), false), run(false, None, true)] {
+            assert!(!result.success);
+            assert!(result.tex_result.is_none());
+            assert!(result
+                .stage_errors
+                .iter()
+                .any(|e| e.stage == PipelineStage::Unpacking && e.item.is_none()));
+        }
+
+        // keep_going：继续转换，汇总错误
+        let result = run(true, Some(1), false);
+        assert!(!result.success);
+        assert_eq!(result.stats.texs_converted, 1);
+        assert_eq!(result.stage_errors.len(), 2);
+        assert!(result
+            .stage_errors
+            .iter()
+            .all(|e| e.stage == PipelineStage::Unpacking));
+        assert!(root.join("unpacked").join("100003").is_dir());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_fallback_convert_records_failed_tex_once() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_fallback_failed");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("workshop")).unwrap();
+        // 之前解包但未转换、且已损坏的 TEX
+        let leftover = root.join("unpacked").join("100005").join("materials");
+        fs::create_dir_all(&leftover).unwrap();
+        fs::write(leftover.join("bad.tex"), b"not a tex").unwrap();
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            clean_unpacked: false,
+            ..Default::default()
+        };
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: root.join("state.json"),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: None,
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+
+        assert!(result.tex_result.is_some());
+        assert_eq!(result.stats.failed_files, vec![leftover.join("bad.tex")]);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_archive_pkg() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_archive_pkg");
+        let _ = fs::remove_dir_all(&root);
+        let wallpaper = root.join("workshop").join("100004");
+        fs::create_dir_all(&wallpaper).unwrap();
+        let pkg = pkg_bytes(&[("scene.json", b"{}".to_vec())]);
+        fs::write(wallpaper.join("scene.pkg"), &pkg).unwrap();
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            ..Default::default()
+        };
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: root.join("state.json"),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: Some(PipelineOverrides {
+                archive_pkg_path: Some(root.join("archive")),
+                ..Default::default()
+            }),
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.stats.pkgs_archived, 1);
+
+        // 按壁纸 ID 归档，去掉暂存时加的 ID 前缀
+        let archived = root.join("archive").join("100004").join("scene.pkg");
+        assert_eq!(fs::read(archived).unwrap(), pkg);
+        assert!(!root.join("pkg_temp").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_only_types() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_only_types");
+        let _ = fs::remove_dir_all(&root);
+        let pkg = pkg_bytes(&[("scene.json", b"{}".to_vec())]);
+        for (id, wallpaper_type) in [("100005", "scene"), ("100006", "video")] {
+            let wallpaper = root.join("workshop").join(id);
+            fs::create_dir_all(&wallpaper).unwrap();
+            fs::write(wallpaper.join("scene.pkg"), &pkg).unwrap();
+            fs::write(
+                wallpaper.join("project.json"),
+                format!(r#"{{"title":"{}","type":"{}"}}"#, id, wallpaper_type),
+            )
+            .unwrap();
+        }
+
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            ..Default::default()
+        };
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: root.join("state.json"),
+            wallpaper_ids: None,
+            reprocess_ids: None,
+            type_filter: None,
+            tag_filter: None,
+            overrides: Some(PipelineOverrides {
+                only_types: Some(vec!["Scene".to_string()]),
+                clean_unpacked: Some(false),
+                ..Default::default()
+            }),
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.stats.wallpapers_type_skipped, 1);
+        assert_eq!(result.stats.pkgs_unpacked, 1);
+
+        let paper_result = result.paper_result.unwrap();
+        assert_eq!(paper_result.type_skipped[0].wallpaper_id, "100006");
+        assert_eq!(
+            paper_result.results[0].wallpaper_type.as_deref(),
+            Some("scene")
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_run_pipeline_type_filter_with_ids() {
+        let root = std::env::temp_dir().join("lianpkg_pipeline_type_tag_filter");
+        let _ = fs::remove_dir_all(&root);
+        let library = [
+            ("100010", "scene", r#"["Anime"]"#),
+            ("100011", "video", r#"["anime", "Nature"]"#),
+            ("100012", "Video", r#"["Nature"]"#),
+            ("100013", "web", r#"[]"#),
+        ];
+        for (id, wallpaper_type, tags) in library {
+            let wallpaper = root.join("workshop").join(id);
+            fs::create_dir_all(&wallpaper).unwrap();
+            fs::write(wallpaper.join("video.mp4"), b"data").unwrap();
+            fs::write(
+                wallpaper.join("project.json"),
+                format!(
+                    r#"{{"title":"{}","type":"{}","tags":{}}}"#,
+                    id, wallpaper_type, tags
+                ),
+            )
+            .unwrap();
+        }
+
+        // type_filter 与 --ids 取交集
+        let config = native_cfg::RuntimeConfig {
+            workshop_path: root.join("workshop"),
+            raw_output_path: root.join("raw"),
+            pkg_temp_path: root.join("pkg_temp"),
+            unpacked_output_path: root.join("unpacked"),
+            ..Default::default()
+        };
+        let result = run_pipeline(RunPipelineInput {
+            config,
+            state_path: root.join("state.json"),
+            wallpaper_ids: Some(vec!["100010".to_string(), "100012".to_string()]),
+            reprocess_ids: None,
+            type_filter: Some(vec!["video".to_string()]),
+            tag_filter: None,
+            overrides: None,
+            progress_callback: None,
+            debug_logger: None,
+            item_callback: None,
+            cancel: None,
+        });
+        assert!(result.success, "{:?}", result.error);
+        let copied: Vec<_> = result
+            .paper_result
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| r.wallpaper_id)
+            .collect();
+        assert_eq!(copied, vec!["100012"]);
+        assert!(root.join("raw").join("100012").exists());
+        assert!(!root.join("raw").join("100010").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_pipeline_stats_rates() {
+        let stats = PipelineStats {
+            wallpapers_processed: 4,
+            pkgs_unpacked: 2,
+            texs_converted: 30,
+            unpack_ms: 500,
+            convert_ms: 1500,
+            elapsed_ms: 2000,
+            ..Default::default()
+        };
+        assert_eq!(stats.avg_ms_per_wallpaper(), Some(500.0));
+        assert_eq!(stats.pkg_per_sec(), Some(4.0));
+        assert_eq!(stats.tex_per_sec(), Some(20.0));
+        assert_eq!(PipelineStats::default().tex_per_sec(), None);
+    }
+}
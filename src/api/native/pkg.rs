@@ -2,10 +2,13 @@
 //!
 //! 封装 core::pkg 的底层操作，提供批量解包等便捷方法。
 
+use super::utl::{is_cancelled, resolve_jobs, run_parallel, CancelFlag, FailBreaker};
+use crate::core::error::{CoreError, CoreErrorKind};
 use crate::core::{path, pkg};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // ============================================================================
 // 结构体定义
@@ -18,6 +21,22 @@ pub struct UnpackAllInput {
     pub pkg_temp_path: PathBuf,
     /// 解包输出目录
     pub unpacked_output_path: PathBuf,
+    /// 失败熔断阈值，None 或 0 表示不启用
+    pub fail_threshold: Option<usize>,
+    /// 同名条目的处理策略
+    pub duplicate_strategy: pkg::DuplicateStrategy,
+    /// 并行解包的 PKG 数，None 表示使用全部 CPU 核心，1 为逐个顺序解包
+    pub jobs: Option<usize>,
+    /// 只解包匹配这些 glob 的条目（大小写不敏感），None 表示全部
+    pub include: Option<Vec<String>>,
+    /// 跳过匹配这些 glob 的条目（大小写不敏感）
+    pub exclude: Option<Vec<String>>,
+    /// 解包后从 Workshop 场景目录复制非 PKG 资源（project.json、音频等）
+    pub copy_scene_resources: bool,
+    /// Workshop 目录（场景目录为 `<workshop_path>/<场景名>`），仅 `copy_scene_resources` 时使用
+    pub workshop_path: Option<PathBuf>,
+    /// 取消标志，置位后解包完当前 PKG 即停止，结果只包含已完成的部分
+    pub cancel: Option<CancelFlag>,
 }
 
 /// 批量解包返回值
@@ -29,6 +48,8 @@ pub struct UnpackAllOutput {
     pub results: Vec<UnpackResult>,
     /// 统计信息
     pub stats: UnpackStats,
+    /// 是否因失败熔断而提前中止
+    pub aborted: bool,
     /// 错误信息
     pub error: Option<String>,
 }
@@ -42,16 +63,68 @@ pub struct UnpackResult {
     pub pkg_name: String,
     /// 场景名称（从 PKG stem 提取）
     pub scene_name: String,
-    /// 输出目录
+    /// 输出目录（批量解包时同一场景名的其余 PKG 追加 `-1`、`-2` 后缀）
     pub output_dir: PathBuf,
     /// 是否成功
     pub success: bool,
     /// 解包的文件信息
     pub files: Vec<UnpackedFile>,
+    /// 同名条目数量（不含首次出现）
+    pub duplicate_count: usize,
+    /// 被 include/exclude 过滤跳过的条目数量
+    pub skipped_count: usize,
+    /// 名称不安全（空名称、`..`、绝对路径）而被拒绝写出的条目名
+    pub rejected_entries: Vec<String>,
+    /// 看似 LZ4 压缩但解压失败、按原始数据写出的条目名
+    pub undecoded_entries: Vec<String>,
+    /// 从场景目录复制的资源文件数
+    pub resources_copied: usize,
+    /// 复制场景资源时的错误（不影响解包本身的成功状态）
+    pub resource_errors: Vec<String>,
     /// 错误信息
     pub error: Option<String>,
+    /// 错误类别（失败时由 core 错误提供，可按类别分支）
+    pub error_kind: Option<CoreErrorKind>,
 }
 
+/// 批量解包进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackProgress {
+    /// 已处理的 PKG 数
+    pub current: usize,
+    /// PKG 总数
+    pub total: usize,
+    /// 刚处理完的 PKG 结果
+    pub result: UnpackResult,
+}
+
+/// 解包进度回调函数类型
+pub type UnpackProgressCallback<'a> = &'a dyn Fn(UnpackProgress);
+
+/// 批量解包中的条目级进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackEntryProgress {
+    /// 所属 PKG 在扫描结果中的序号（从 0 开始）
+    pub pkg_index: usize,
+    /// PKG 总数
+    pub total_pkgs: usize,
+    /// 所属 PKG 文件名
+    pub pkg_name: String,
+    /// 刚写完的条目序号（从 0 开始）
+    pub entry_index: usize,
+    /// 该 PKG 的条目总数
+    pub total_entries: usize,
+    /// 刚写完的条目名
+    pub entry_name: String,
+    /// 该 PKG 至此累计写入的字节数
+    pub bytes_written: u64,
+}
+
+/// 条目级进度回调函数类型
+///
+/// 并行解包时在工作线程上调用，不同 PKG 的事件可能交错到达
+pub type UnpackEntryProgressCallback<'a> = &'a (dyn Fn(UnpackEntryProgress) + Sync);
+
 /// 解包后的文件信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnpackedFile {
@@ -78,6 +151,42 @@ pub struct UnpackStats {
     pub total_files: usize,
     /// TEX 文件数
     pub tex_files: usize,
+    /// 同名条目数（不含首次出现）
+    pub duplicate_entries: usize,
+    /// 被 include/exclude 过滤跳过的条目数
+    pub skipped_entries: usize,
+    /// 名称不安全而被拒绝写出的条目数
+    pub rejected_entries: usize,
+    /// 从场景目录复制的资源文件数
+    pub resources_copied: usize,
+    /// 复制场景资源失败的次数
+    pub resource_errors: usize,
+}
+
+/// 提取单个条目入参
+#[derive(Debug, Clone)]
+pub struct ExtractEntryInput {
+    /// PKG 文件路径
+    pub pkg_path: PathBuf,
+    /// 条目名（先按完整名称精确匹配，找不到时按大小写不敏感的 glob 匹配）
+    pub entry: String,
+    /// 输出目录，条目按其在 PKG 中的相对路径写入
+    pub output_dir: PathBuf,
+}
+
+/// 提取单个条目返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractEntryOutput {
+    /// 是否成功
+    pub success: bool,
+    /// 写出的文件
+    pub files: Vec<UnpackedFile>,
+    /// 未找到条目时与之相近的条目名
+    pub suggestions: Vec<String>,
+    /// 错误信息
+    pub error: Option<String>,
+    /// 错误类别
+    pub error_kind: Option<CoreErrorKind>,
 }
 
 /// 预览 PKG 入参
@@ -85,6 +194,10 @@ pub struct UnpackStats {
 pub struct PreviewPkgInput {
     /// PKG 文件路径
     pub pkg_path: PathBuf,
+    /// 只列出匹配这些 glob 的条目，None 表示全部
+    pub include: Option<Vec<String>>,
+    /// 不列出匹配这些 glob 的条目
+    pub exclude: Option<Vec<String>>,
 }
 
 /// 预览 PKG 返回值
@@ -94,8 +207,12 @@ pub struct PreviewPkgOutput {
     pub success: bool,
     /// PKG 信息
     pub pkg_info: Option<PkgPreview>,
+    /// 是否因版本不可识别而不支持（不是有效的 PKG 或格式未知）
+    pub unsupported: bool,
     /// 错误信息
     pub error: Option<String>,
+    /// 错误类别（失败时由 core 错误提供，可按类别分支）
+    pub error_kind: Option<CoreErrorKind>,
 }
 
 /// PKG 预览信息
@@ -105,10 +222,18 @@ pub struct PkgPreview {
     pub version: String,
     /// 文件数量
     pub file_count: u32,
-    /// 文件列表
+    /// 文件列表（已按 include/exclude 过滤）
     pub files: Vec<PkgFileEntry>,
+    /// 被过滤掉的条目数量
+    pub skipped_count: usize,
     /// TEX 文件数量
     pub tex_count: usize,
+    /// 列出条目的总大小（字节，即解包将写出的数据量；LZ4 条目按压缩后大小计）
+    pub total_size: u64,
+    /// 其中 .tex 条目的总大小（字节），决定后续转换的输出量
+    pub tex_size: u64,
+    /// 重复出现的条目名（解包时会相互覆盖）
+    pub duplicate_names: Vec<String>,
 }
 
 /// PKG 中的文件条目
@@ -120,6 +245,47 @@ pub struct PkgFileEntry {
     pub size: u32,
     /// 是否是 TEX 文件
     pub is_tex: bool,
+    /// 是否为 LZ4 压缩条目（解包时自动解压）
+    pub compressed: bool,
+}
+
+/// 归档 PKG 入参
+#[derive(Debug, Clone)]
+pub struct ArchivePkgsInput {
+    /// Pkg 临时目录
+    pub pkg_temp_path: PathBuf,
+    /// 归档目录（按壁纸 ID 分子目录）
+    pub archive_path: PathBuf,
+}
+
+/// 归档 PKG 返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivePkgsOutput {
+    /// 是否全部归档成功
+    pub success: bool,
+    /// 逐个 PKG 的归档结果
+    pub results: Vec<ArchivePkgResult>,
+    /// 成功归档数
+    pub archived: usize,
+    /// 归档失败数
+    pub failed: usize,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/// 单个 PKG 归档结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivePkgResult {
+    /// 暂存目录中的 PKG 路径
+    pub pkg_path: PathBuf,
+    /// 所属壁纸 ID
+    pub wallpaper_id: String,
+    /// 归档后的路径
+    pub archive_path: PathBuf,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息
+    pub error: Option<String>,
 }
 
 // ============================================================================
@@ -130,13 +296,46 @@ pub struct PkgFileEntry {
 ///
 /// 扫描 pkg_temp_path 下所有 .pkg 文件并解包到 unpacked_output_path
 pub fn unpack_all(input: UnpackAllInput) -> UnpackAllOutput {
+    unpack_all_with_progress(input, None)
+}
+
+/// 批量解包 PKG 文件（带进度回调）
+///
+/// 每处理一个 PKG 调用一次 `on_progress`
+pub fn unpack_all_with_progress(
+    input: UnpackAllInput,
+    on_progress: Option<UnpackProgressCallback>,
+) -> UnpackAllOutput {
+    unpack_all_with_callbacks(input, on_progress, None)
+}
+
+/// 批量解包 PKG 文件（带 PKG 级与条目级进度回调）
+///
+/// 每处理完一个 PKG 调用一次 `on_progress`，每写完一个条目调用一次 `on_entry`
+pub fn unpack_all_with_callbacks(
+    input: UnpackAllInput,
+    on_progress: Option<UnpackProgressCallback>,
+    on_entry: Option<UnpackEntryProgressCallback>,
+) -> UnpackAllOutput {
     // 确保输出目录存在
     if let Err(e) = path::ensure_dir_compat(&input.unpacked_output_path) {
         return UnpackAllOutput {
             success: false,
             results: vec![],
             stats: UnpackStats::default(),
-            error: Some(e),
+            aborted: false,
+            error: Some(e.to_string()),
+        };
+    }
+
+    // 无效的过滤模式对每个 PKG 都会失败，提前报告
+    if let Err(e) = pkg::EntryFilter::new(input.include.as_deref(), input.exclude.as_deref()) {
+        return UnpackAllOutput {
+            success: false,
+            results: vec![],
+            stats: UnpackStats::default(),
+            aborted: false,
+            error: Some(e.to_string()),
         };
     }
 
@@ -148,91 +347,113 @@ pub fn unpack_all(input: UnpackAllInput) -> UnpackAllOutput {
                 success: false,
                 results: vec![],
                 stats: UnpackStats::default(),
+                aborted: false,
                 error: Some(e),
             };
         }
     };
 
-    let mut results = Vec::new();
+    let mut results: Vec<(usize, UnpackResult)> = Vec::with_capacity(pkg_files.len());
     let mut stats = UnpackStats::default();
+    let mut breaker = FailBreaker::new(input.fail_threshold);
+    let total = pkg_files.len();
+    let output_dirs = assign_output_dirs(&pkg_files);
 
-    for pkg_path in pkg_files {
-        stats.pkg_processed += 1;
-
-        let pkg_name = pkg_path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-
-        let scene_name = path::scene_name_from_pkg_stem(
-            pkg_path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default()
-                .as_str(),
-        );
-
-        let output_dir = input.unpacked_output_path.join(&scene_name);
-
-        // 执行解包
-        let unpack_result = pkg::unpack_pkg(pkg::UnpackPkgInput {
-            file_path: pkg_path.clone(),
-            output_base: output_dir.clone(),
-        });
-
-        match unpack_result {
-            Ok(result) => {
-                stats.pkg_success += 1;
-
-                let files: Vec<UnpackedFile> = result
-                    .extracted_files
+    // 统计在调用线程上按完成顺序累加，与并行度无关
+    let indexed: Vec<(usize, &PathBuf, &(String, String))> = pkg_files
+        .iter()
+        .zip(&output_dirs)
+        .enumerate()
+        .map(|(i, (pkg_path, dir))| (i, pkg_path, dir))
+        .collect();
+    run_parallel(
+        &indexed,
+        resolve_jobs(input.jobs),
+        |&(pkg_index, pkg_path, (scene_name, dir_name))| {
+            let pkg_name = pkg_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let forward = |p: pkg::UnpackProgress| {
+                if let Some(callback) = on_entry {
+                    callback(UnpackEntryProgress {
+                        pkg_index,
+                        total_pkgs: total,
+                        pkg_name: pkg_name.clone(),
+                        entry_index: p.entry_index,
+                        total_entries: p.total_entries,
+                        entry_name: p.entry_name,
+                        bytes_written: p.bytes_written,
+                    });
+                }
+            };
+            let mut result = unpack_one(
+                pkg_path.clone(),
+                scene_name.clone(),
+                input.unpacked_output_path.join(dir_name),
+                input.duplicate_strategy,
+                (input.include.as_deref(), input.exclude.as_deref()),
+                on_entry.map(|_| &forward as pkg::UnpackProgressCallback),
+            );
+            if let (true, true, Some(workshop)) = (
+                result.success,
+                input.copy_scene_resources,
+                &input.workshop_path,
+            ) {
+                let unpacked: HashSet<&Path> = result
+                    .files
                     .iter()
-                    .map(|f| {
-                        let is_tex = f.entry_name.to_lowercase().ends_with(".tex");
-                        if is_tex {
-                            stats.tex_files += 1;
-                        }
-                        stats.total_files += 1;
-
-                        UnpackedFile {
-                            name: f.entry_name.clone(),
-                            output_path: f.output_path.clone(),
-                            size: f.size,
-                            is_tex,
-                        }
-                    })
+                    .map(|f| f.output_path.as_path())
                     .collect();
-
-                results.push(UnpackResult {
-                    pkg_path,
-                    pkg_name,
-                    scene_name,
-                    output_dir,
-                    success: true,
-                    files,
-                    error: None,
-                });
+                let (copied, errors) =
+                    copy_scene_resources(&workshop.join(scene_name), &result.output_dir, &unpacked);
+                result.resources_copied = copied;
+                result.resource_errors = errors;
             }
-            Err(e) => {
+            result
+        },
+        &mut |index, result: UnpackResult| {
+            stats.pkg_processed += 1;
+            if result.success {
+                stats.pkg_success += 1;
+                stats.duplicate_entries += result.duplicate_count;
+                stats.skipped_entries += result.skipped_count;
+                stats.rejected_entries += result.rejected_entries.len();
+                stats.resources_copied += result.resources_copied;
+                stats.resource_errors += result.resource_errors.len();
+                stats.total_files += result.files.len();
+                stats.tex_files += result.files.iter().filter(|f| f.is_tex).count();
+            } else {
                 stats.pkg_failed += 1;
-                results.push(UnpackResult {
-                    pkg_path,
-                    pkg_name,
-                    scene_name,
-                    output_dir,
-                    success: false,
-                    files: vec![],
-                    error: Some(e.to_string()),
+            }
+
+            if let Some(callback) = on_progress {
+                callback(UnpackProgress {
+                    current: stats.pkg_processed,
+                    total,
+                    result: result.clone(),
                 });
             }
-        }
-    }
 
+            let abort = breaker.record(result.success);
+            results.push((index, result));
+            abort || is_cancelled(input.cancel.as_ref())
+        },
+    );
+
+    // 恢复扫描顺序
+    results.sort_by_key(|(i, _)| *i);
+    let results: Vec<UnpackResult> = results.into_iter().map(|(_, r)| r).collect();
+
+    let aborted = breaker.tripped();
     UnpackAllOutput {
         success: stats.pkg_failed == 0,
         results,
         stats,
-        error: if stats.pkg_failed > 0 {
+        aborted,
+        error: if aborted {
+            Some(breaker.abort_message("PKG"))
+        } else if stats.pkg_failed > 0 {
             Some(format!("{} PKG files failed to unpack", stats.pkg_failed))
         } else {
             None
@@ -244,6 +465,19 @@ pub fn unpack_all(input: UnpackAllInput) -> UnpackAllOutput {
 ///
 /// 不执行解包，只解析显示 PKG 包含的文件列表
 pub fn preview_pkg(input: PreviewPkgInput) -> PreviewPkgOutput {
+    let filter = match pkg::EntryFilter::new(input.include.as_deref(), input.exclude.as_deref()) {
+        Ok(f) => f,
+        Err(e) => {
+            return PreviewPkgOutput {
+                success: false,
+                pkg_info: None,
+                unsupported: false,
+                error: Some(e.to_string()),
+                error_kind: Some(e.kind()),
+            };
+        }
+    };
+
     let pkg_info = match pkg::parse_pkg(pkg::ParsePkgInput {
         file_path: input.pkg_path,
     }) {
@@ -252,7 +486,9 @@ pub fn preview_pkg(input: PreviewPkgInput) -> PreviewPkgOutput {
             return PreviewPkgOutput {
                 success: false,
                 pkg_info: None,
+                unsupported: matches!(e, CoreError::Unsupported { .. }),
                 error: Some(e.to_string()),
+                error_kind: Some(e.kind()),
             };
         }
     };
@@ -260,48 +496,266 @@ pub fn preview_pkg(input: PreviewPkgInput) -> PreviewPkgOutput {
     let files: Vec<PkgFileEntry> = pkg_info
         .entries
         .iter()
+        .filter(|e| filter.allows(&e.name))
         .map(|e| PkgFileEntry {
             name: e.name.clone(),
             size: e.size,
             is_tex: e.name.to_lowercase().ends_with(".tex"),
+            compressed: e.compressed,
         })
         .collect();
 
     let tex_count = files.iter().filter(|f| f.is_tex).count();
+    let total_size = files.iter().map(|f| f.size as u64).sum();
+    let tex_size = files
+        .iter()
+        .filter(|f| f.is_tex)
+        .map(|f| f.size as u64)
+        .sum();
+    let duplicate_names = pkg_info.duplicate_names();
 
     PreviewPkgOutput {
         success: true,
         pkg_info: Some(PkgPreview {
             version: pkg_info.version,
             file_count: pkg_info.file_count,
+            skipped_count: pkg_info.entries.len() - files.len(),
             files,
             tex_count,
+            total_size,
+            tex_size,
+            duplicate_names,
         }),
+        unsupported: false,
         error: None,
+        error_kind: None,
     }
 }
 
 /// 解包单个 PKG 文件
-pub fn unpack_single(pkg_path: PathBuf, output_base: PathBuf) -> UnpackResult {
-    let pkg_name = pkg_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
+///
+/// include / exclude 为条目名 glob（大小写不敏感），被过滤的条目计入 skipped_count
+pub fn unpack_single(
+    pkg_path: PathBuf,
+    output_base: PathBuf,
+    duplicate_strategy: pkg::DuplicateStrategy,
+    include: Option<&[String]>,
+    exclude: Option<&[String]>,
+) -> UnpackResult {
+    let scene_name = scene_name_of(&pkg_path);
+    let output_dir = output_base.join(&scene_name);
+    unpack_one(
+        pkg_path,
+        scene_name,
+        output_dir,
+        duplicate_strategy,
+        (include, exclude),
+        None,
+    )
+}
+
+/// 从 PKG 中提取单个条目（或匹配 glob 的若干条目），不解包其余内容
+///
+/// 只读取索引与匹配条目的数据；找不到时在 suggestions 中列出名称相近的条目
+pub fn extract_entry(input: ExtractEntryInput) -> ExtractEntryOutput {
+    let fail = |e: CoreError, suggestions| ExtractEntryOutput {
+        success: false,
+        files: vec![],
+        suggestions,
+        error: Some(e.to_string()),
+        error_kind: Some(e.kind()),
+    };
+
+    let pkg_info = match pkg::parse_pkg(pkg::ParsePkgInput {
+        file_path: input.pkg_path.clone(),
+    }) {
+        Ok(r) => r.pkg_info,
+        Err(e) => return fail(e, vec![]),
+    };
+
+    let exact: Vec<&pkg::PkgEntry> = pkg_info
+        .entries
+        .iter()
+        .filter(|e| e.name == input.entry)
+        .collect();
+    let matches = if exact.is_empty() {
+        let filter = match pkg::EntryFilter::new(Some(std::slice::from_ref(&input.entry)), None) {
+            Ok(f) => f,
+            Err(e) => return fail(e, vec![]),
+        };
+        pkg_info
+            .entries
+            .iter()
+            .filter(|e| filter.allows(&e.name))
+            .collect()
+    } else {
+        // 同名条目以最后一个为准，与默认的覆盖策略一致
+        exact[exact.len() - 1..].to_vec()
+    };
+
+    if matches.is_empty() {
+        let e = CoreError::not_found_with_path(
+            format!("No entry matches '{}'", input.entry),
+            input.pkg_path.display().to_string(),
+        );
+        return fail(e, similar_entry_names(&pkg_info, &input.entry));
+    }
+
+    let mut files = Vec::new();
+    for entry in matches {
+        match extract_one(&input.pkg_path, &pkg_info, entry, &input.output_dir) {
+            Ok(file) => files.push(file),
+            Err(e) => return fail(e, vec![]),
+        }
+    }
+
+    ExtractEntryOutput {
+        success: true,
+        files,
+        suggestions: vec![],
+        error: None,
+        error_kind: None,
+    }
+}
 
-    let scene_name = path::scene_name_from_pkg_stem(
+/// 只读取条目自身的数据并交给 unpack_entry 写出
+fn extract_one(
+    pkg_path: &Path,
+    pkg_info: &pkg::PkgInfo,
+    entry: &pkg::PkgEntry,
+    output_dir: &Path,
+) -> Result<UnpackedFile, CoreError> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    if !pkg::is_safe_entry_name(&entry.name) {
+        return Err(CoreError::validation(format!(
+            "Refusing to extract unsafe entry name: {:?}",
+            entry.name
+        )));
+    }
+    let io_err =
+        |e: std::io::Error| CoreError::io_with_path(e.to_string(), pkg_path.display().to_string());
+    let mut file = fs::File::open(path::long_path(pkg_path)).map_err(io_err)?;
+    let start = pkg_info.data_start as u64 + entry.offset as u64;
+    if start + entry.size as u64 > file.metadata().map_err(io_err)?.len() {
+        return Err(CoreError::invalid_pkg(format!(
+            "Entry {} has invalid offset/size",
+            entry.name
+        )));
+    }
+    let mut data = vec![0u8; entry.size as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut data))
+        .map_err(io_err)?;
+
+    let output_path = output_dir.join(&entry.name);
+    pkg::unpack_entry(pkg::UnpackEntryInput {
+        pkg_data: data,
+        data_start: 0,
+        entry: pkg::PkgEntry {
+            offset: 0,
+            ..entry.clone()
+        },
+        output_path: output_path.clone(),
+    })?;
+
+    let size = fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0);
+    Ok(UnpackedFile {
+        name: entry.name.clone(),
+        output_path,
+        size: u32::try_from(size).unwrap_or(u32::MAX),
+        is_tex: entry.name.to_lowercase().ends_with(".tex"),
+    })
+}
+
+/// 与查询名称相近的条目（按文件名包含关系匹配，最多 5 个）
+fn similar_entry_names(pkg_info: &pkg::PkgInfo, query: &str) -> Vec<String> {
+    let stem_of = |name: &str| {
+        Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    };
+    let query_stem = stem_of(query);
+    if query_stem.is_empty() {
+        return vec![];
+    }
+    // 同名条目可能不相邻，按首次出现去重并保持 PKG 中的顺序
+    let mut seen = HashSet::new();
+    pkg_info
+        .entries
+        .iter()
+        .filter(|e| {
+            let stem = stem_of(&e.name);
+            !stem.is_empty() && (stem.contains(&query_stem) || query_stem.contains(&stem))
+        })
+        .map(|e| e.name.clone())
+        .filter(|name| seen.insert(name.clone()))
+        .take(5)
+        .collect()
+}
+
+/// PKG 对应的场景名（暂存文件名 `<ID>_<name>.pkg` 中的壁纸 ID）
+fn scene_name_of(pkg_path: &Path) -> String {
+    path::scene_name_from_pkg_stem(
         pkg_path
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default()
             .as_str(),
-    );
+    )
+}
 
-    let output_dir = output_base.join(&scene_name);
+/// 为批量解包的每个 PKG 分配 (场景名, 输出目录名)
+///
+/// 目录名默认为场景名；多个 PKG 提取出同一场景名时，按排序后的顺序第一个使用场景名，
+/// 其余依次追加 `-1`、`-2`（避开其它 PKG 本身的场景名），重复运行得到相同的目录
+fn assign_output_dirs(pkg_files: &[PathBuf]) -> Vec<(String, String)> {
+    let scene_names: Vec<String> = pkg_files.iter().map(|p| scene_name_of(p)).collect();
+    let natural: HashSet<&str> = scene_names.iter().map(String::as_str).collect();
+    let mut taken: HashSet<String> = HashSet::new();
+
+    scene_names
+        .iter()
+        .map(|scene_name| {
+            let mut dir_name = scene_name.clone();
+            let mut n = 1;
+            while taken.contains(&dir_name)
+                || (dir_name != *scene_name && natural.contains(dir_name.as_str()))
+            {
+                dir_name = format!("{}-{}", scene_name, n);
+                n += 1;
+            }
+            taken.insert(dir_name.clone());
+            (scene_name.clone(), dir_name)
+        })
+        .collect()
+}
 
-    let unpack_result = pkg::unpack_pkg(pkg::UnpackPkgInput {
-        file_path: pkg_path.clone(),
-        output_base: output_dir.clone(),
-    });
+/// 解包单个 PKG 到 output_dir，filter 为 (include, exclude)，可选条目级进度回调
+fn unpack_one(
+    pkg_path: PathBuf,
+    scene_name: String,
+    output_dir: PathBuf,
+    duplicate_strategy: pkg::DuplicateStrategy,
+    (include, exclude): (Option<&[String]>, Option<&[String]>),
+    on_entry: Option<pkg::UnpackProgressCallback>,
+) -> UnpackResult {
+    let pkg_name = pkg_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let unpack_result = pkg::unpack_pkg_with_progress(
+        pkg::UnpackPkgInput {
+            file_path: pkg_path.clone(),
+            output_base: output_dir.clone(),
+            duplicate_strategy,
+            include: include.map(<[String]>::to_vec),
+            exclude: exclude.map(<[String]>::to_vec),
+        },
+        on_entry,
+    );
 
     match unpack_result {
         Ok(result) => {
@@ -323,7 +777,14 @@ pub fn unpack_single(pkg_path: PathBuf, output_base: PathBuf) -> UnpackResult {
                 output_dir,
                 success: true,
                 files,
+                duplicate_count: result.duplicate_count,
+                skipped_count: result.skipped_count,
+                rejected_entries: result.rejected_entries,
+                undecoded_entries: result.undecoded_entries,
+                resources_copied: 0,
+                resource_errors: vec![],
                 error: None,
+                error_kind: None,
             }
         }
         Err(e) => UnpackResult {
@@ -333,7 +794,69 @@ pub fn unpack_single(pkg_path: PathBuf, output_base: PathBuf) -> UnpackResult {
             output_dir,
             success: false,
             files: vec![],
+            duplicate_count: 0,
+            skipped_count: 0,
+            rejected_entries: vec![],
+            undecoded_entries: vec![],
+            resources_copied: 0,
+            resource_errors: vec![],
             error: Some(e.to_string()),
+            error_kind: Some(e.kind()),
+        },
+    }
+}
+
+/// 将 pkg_temp_path 中的 PKG 移动到归档目录
+///
+/// 暂存文件名 `<ID>_<name>.pkg` 归档为 `<archive_path>/<ID>/<name>.pkg`，
+/// 同名文件直接覆盖；跨文件系统时退化为复制后删除
+pub fn archive_pkgs(input: ArchivePkgsInput) -> ArchivePkgsOutput {
+    let pkg_files = match find_pkg_files(&input.pkg_temp_path) {
+        Ok(files) => files,
+        Err(e) => {
+            return ArchivePkgsOutput {
+                success: false,
+                results: vec![],
+                archived: 0,
+                failed: 0,
+                error: Some(e),
+            };
+        }
+    };
+
+    let mut results = Vec::with_capacity(pkg_files.len());
+    for pkg_path in pkg_files {
+        let file_name = pkg_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let wallpaper_id = scene_name_of(&pkg_path);
+        let archived_name = file_name
+            .strip_prefix(&format!("{}_", wallpaper_id))
+            .unwrap_or(&file_name)
+            .to_string();
+        let dest = input.archive_path.join(&wallpaper_id).join(archived_name);
+
+        let error = move_file(&pkg_path, &dest).err();
+        results.push(ArchivePkgResult {
+            pkg_path,
+            wallpaper_id,
+            archive_path: dest,
+            success: error.is_none(),
+            error,
+        });
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    ArchivePkgsOutput {
+        success: failed == 0,
+        archived: results.len() - failed,
+        failed,
+        results,
+        error: if failed > 0 {
+            Some(format!("{} PKG files failed to archive", failed))
+        } else {
+            None
         },
     }
 }
@@ -365,6 +888,82 @@ pub fn get_tex_files_from_unpacked(unpacked_path: &PathBuf) -> Vec<PathBuf> {
 // 内部工具函数
 // ============================================================================
 
+/// 移动文件，rename 失败（如跨文件系统）时复制后删除源文件
+fn move_file(src: &Path, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        path::ensure_dir_compat(parent).map_err(|e| e.to_string())?;
+    }
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    fs::copy(src, dest)
+        .and_then(|_| fs::remove_file(src))
+        .map_err(|e| format!("Failed to move {}: {}", src.display(), e))
+}
+
+/// 把场景目录下的非 PKG 文件复制到解包目录，返回 (复制的文件数, 错误信息)
+///
+/// 保持相对路径；本次解包写出的文件（`unpacked`）跳过，不覆盖解包内容，
+/// 其余已存在的文件（如上次复制的旧 project.json）用场景目录中的版本刷新。
+/// 场景目录不存在时视为没有可复制的资源
+fn copy_scene_resources(
+    scene_dir: &Path,
+    output_dir: &Path,
+    unpacked: &HashSet<&Path>,
+) -> (usize, Vec<String>) {
+    let mut errors = Vec::new();
+    let copied = if scene_dir.is_dir() {
+        copy_resources_into(scene_dir, output_dir, unpacked, &mut errors)
+    } else {
+        0
+    };
+    (copied, errors)
+}
+
+/// copy_scene_resources 的递归部分，错误追加到 errors 后继续复制其余文件
+fn copy_resources_into(
+    src_dir: &Path,
+    output_dir: &Path,
+    unpacked: &HashSet<&Path>,
+    errors: &mut Vec<String>,
+) -> usize {
+    let entries = match fs::read_dir(src_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(format!("Failed to read {}: {}", src_dir.display(), e));
+            return 0;
+        }
+    };
+
+    let mut copied = 0;
+    for entry in entries.flatten() {
+        let src = entry.path();
+        let dest = output_dir.join(entry.file_name());
+        if src.is_dir() {
+            copied += copy_resources_into(&src, &dest, unpacked, errors);
+            continue;
+        }
+        let is_pkg = src
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase() == "pkg")
+            .unwrap_or(false);
+        if is_pkg || unpacked.contains(dest.as_path()) {
+            continue;
+        }
+        let result = path::ensure_dir_compat(output_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                fs::copy(&src, &dest)
+                    .map_err(|e| format!("Failed to copy {}: {}", src.display(), e))
+            });
+        match result {
+            Ok(_) => copied += 1,
+            Err(e) => errors.push(e),
+        }
+    }
+    copied
+}
+
 /// 查找目录下所有 PKG 文件
 fn find_pkg_files(dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
     let mut pkg_files = Vec::new();
@@ -388,5 +987,308 @@ fn find_pkg_files(dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
         }
     }
 
+    // 排序保证目录分配与结果顺序不依赖文件系统的遍历顺序
+    pkg_files.sort();
     Ok(pkg_files)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{pkg_bytes, tex_bytes};
+
+    #[test]
+    fn test_unpack_all_colliding_scene_names() {
+        let root = std::env::temp_dir().join("lianpkg_unpack_all_collide");
+        let _ = fs::remove_dir_all(&root);
+        let temp = root.join("temp");
+        fs::create_dir_all(&temp).unwrap();
+        for (name, content) in [
+            ("100020_scene.pkg", "scene"),
+            ("100020_extra.pkg", "extra"),
+            ("100020-1_other.pkg", "other"),
+        ] {
+            let pkg = pkg_bytes(&[("scene.json", content.as_bytes().to_vec())]);
+            fs::write(temp.join(name), pkg).unwrap();
+        }
+
+        let run = || {
+            unpack_all(UnpackAllInput {
+                pkg_temp_path: temp.clone(),
+                unpacked_output_path: root.join("out"),
+                fail_threshold: None,
+                duplicate_strategy: pkg::DuplicateStrategy::Overwrite,
+                jobs: Some(4),
+                include: None,
+                exclude: None,
+                copy_scene_resources: false,
+                workshop_path: None,
+                cancel: None,
+            })
+        };
+        let dirs = |o: &UnpackAllOutput| {
+            o.results
+                .iter()
+                .map(|r| {
+                    (
+                        r.pkg_name.clone(),
+                        r.scene_name.clone(),
+                        r.output_dir.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = run();
+        assert!(first.success, "{:?}", first.error);
+        let out = root.join("out");
+        assert_eq!(
+            dirs(&first),
+            [
+                (
+                    "100020-1_other.pkg".to_string(),
+                    "100020-1".to_string(),
+                    out.join("100020-1")
+                ),
+                (
+                    "100020_extra.pkg".to_string(),
+                    "100020".to_string(),
+                    out.join("100020")
+                ),
+                (
+                    "100020_scene.pkg".to_string(),
+                    "100020".to_string(),
+                    out.join("100020-2")
+                ),
+            ]
+        );
+        // 三个 PKG 的文件都保留下来
+        let read = |dir: &str| fs::read_to_string(out.join(dir).join("scene.json")).unwrap();
+        assert_eq!(read("100020-1"), "other");
+        assert_eq!(read("100020"), "extra");
+        assert_eq!(read("100020-2"), "scene");
+
+        // 重复运行分配到相同目录
+        assert_eq!(dirs(&run()), dirs(&first));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_unpack_all_parallel_matches_sequential() {
+        let root = std::env::temp_dir().join("lianpkg_unpack_all_parallel");
+        let _ = fs::remove_dir_all(&root);
+        let temp = root.join("temp");
+        fs::create_dir_all(&temp).unwrap();
+        for i in 0..6 {
+            let pkg = pkg_bytes(&[
+                ("scene.json", b"{}".to_vec()),
+                ("materials/a.tex", tex_bytes()),
+                ("materials/b.tex", tex_bytes()),
+            ]);
+            fs::write(temp.join(format!("10001{}_scene.pkg", i)), pkg).unwrap();
+        }
+        // 条目超出数据区的 PKG 解包失败
+        let mut broken = pkg_bytes(&[("materials/c.tex", tex_bytes())]);
+        broken.truncate(broken.len() - 8);
+        fs::write(temp.join("100019_broken.pkg"), broken).unwrap();
+
+        let run = |jobs| {
+            unpack_all(UnpackAllInput {
+                pkg_temp_path: temp.clone(),
+                unpacked_output_path: root.join(format!("out_{:?}", jobs)),
+                fail_threshold: None,
+                duplicate_strategy: pkg::DuplicateStrategy::Overwrite,
+                jobs,
+                include: None,
+                exclude: None,
+                copy_scene_resources: false,
+                workshop_path: None,
+                cancel: None,
+            })
+        };
+        let sequential = run(Some(1));
+        let parallel = run(Some(4));
+
+        assert_eq!(parallel.stats.pkg_processed, 7);
+        assert_eq!(parallel.stats.pkg_success, 6);
+        assert_eq!(parallel.stats.pkg_failed, 1);
+        assert_eq!(parallel.stats.total_files, 18);
+        assert_eq!(parallel.stats.tex_files, 12);
+        assert_eq!(parallel.stats.total_files, sequential.stats.total_files);
+        assert_eq!(parallel.stats.tex_files, sequential.stats.tex_files);
+        // 结果保持扫描顺序，与顺序解包一致
+        let names = |o: &UnpackAllOutput| {
+            o.results
+                .iter()
+                .map(|r| (r.pkg_name.clone(), r.files.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&parallel), names(&sequential));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_unpack_all_copies_scene_resources() {
+        let root = std::env::temp_dir().join("lianpkg_unpack_all_resources");
+        let _ = fs::remove_dir_all(&root);
+        let temp = root.join("temp");
+        let scene = root.join("workshop").join("100001");
+        fs::create_dir_all(&temp).unwrap();
+        fs::create_dir_all(scene.join("sounds")).unwrap();
+        fs::write(scene.join("project.json"), b"{\"type\":\"scene\"}").unwrap();
+        fs::write(scene.join("scene.json"), b"workshop").unwrap();
+        fs::write(scene.join("sounds").join("a.mp3"), b"mp3").unwrap();
+        fs::write(scene.join("scene.pkg"), b"pkg").unwrap();
+        // 上次复制留下的旧资源会被刷新
+        let out = root.join("out").join("100001");
+        fs::create_dir_all(&out).unwrap();
+        fs::write(out.join("project.json"), b"stale").unwrap();
+        // 无法写出的资源报告错误，不影响其余文件
+        fs::write(scene.join("readme.txt"), b"readme").unwrap();
+        fs::create_dir_all(out.join("readme.txt")).unwrap();
+        fs::write(
+            temp.join("100001_scene.pkg"),
+            pkg_bytes(&[("scene.json", b"unpacked".to_vec())]),
+        )
+        .unwrap();
+
+        let result = unpack_all(UnpackAllInput {
+            pkg_temp_path: temp,
+            unpacked_output_path: root.join("out"),
+            fail_threshold: None,
+            duplicate_strategy: pkg::DuplicateStrategy::Overwrite,
+            jobs: Some(1),
+            include: None,
+            exclude: None,
+            copy_scene_resources: true,
+            workshop_path: Some(root.join("workshop")),
+            cancel: None,
+        });
+
+        assert!(result.success);
+        assert_eq!(result.stats.resources_copied, 2);
+        assert_eq!(result.stats.resource_errors, 1);
+        assert!(result.results[0].resource_errors[0].contains("readme.txt"));
+        assert_eq!(
+            fs::read(out.join("project.json")).unwrap(),
+            b"{\"type\":\"scene\"}"
+        );
+        assert!(out.join("sounds").join("a.mp3").exists());
+        // PKG 本身不复制，解包出的同名文件不被覆盖
+        assert!(!out.join("scene.pkg").exists());
+        assert_eq!(fs::read(out.join("scene.json")).unwrap(), b"unpacked");
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_unpack_all_reports_entry_progress() {
+        let root = std::env::temp_dir().join("lianpkg_unpack_entry_progress");
+        let _ = fs::remove_dir_all(&root);
+        let temp = root.join("temp");
+        fs::create_dir_all(&temp).unwrap();
+        for i in 0..3 {
+            let pkg = pkg_bytes(&[
+                ("scene.json", b"{}".to_vec()),
+                ("materials/a.tex", tex_bytes()),
+            ]);
+            fs::write(temp.join(format!("20001{}_scene.pkg", i)), pkg).unwrap();
+        }
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let on_entry = |p: UnpackEntryProgress| events.lock().unwrap().push(p);
+        let result = unpack_all_with_callbacks(
+            UnpackAllInput {
+                pkg_temp_path: temp,
+                unpacked_output_path: root.join("out"),
+                fail_threshold: None,
+                duplicate_strategy: pkg::DuplicateStrategy::Overwrite,
+                jobs: Some(2),
+                include: None,
+                exclude: None,
+                copy_scene_resources: false,
+                workshop_path: None,
+                cancel: None,
+            },
+            None,
+            Some(&on_entry),
+        );
+        assert!(result.success);
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 6);
+        assert!(events
+            .iter()
+            .all(|e| e.total_pkgs == 3 && e.total_entries == 2));
+        // 每个 PKG 按条目顺序报告，累计字节数递增
+        for pkg_index in 0..3 {
+            let per_pkg: Vec<_> = events.iter().filter(|e| e.pkg_index == pkg_index).collect();
+            assert_eq!(per_pkg.len(), 2);
+            assert_eq!(per_pkg[0].entry_name, "scene.json");
+            assert_eq!(per_pkg[0].bytes_written, 2);
+            assert_eq!(per_pkg[1].entry_index, 1);
+            assert_eq!(per_pkg[1].bytes_written, 2 + tex_bytes().len() as u64);
+        }
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_extract_entry() {
+        let root = std::env::temp_dir().join("lianpkg_extract_entry");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let pkg_path = root.join("scene.pkg");
+        let files: [(&str, &[u8]); 6] = [
+            ("materials/sky.tex", b"old"),
+            ("models/skybox.json", b"{}"),
+            ("materials/sky.tex", b"new"),
+            ("materials/cloud.tex", b"cloud"),
+            ("scene.json", b"{}"),
+            ("../evil.txt", b"evil"),
+        ];
+        fs::write(&pkg_path, pkg_bytes(&files)).unwrap();
+        let out = root.join("out");
+        let extract = |entry: &str| {
+            extract_entry(ExtractEntryInput {
+                pkg_path: pkg_path.clone(),
+                entry: entry.to_string(),
+                output_dir: out.clone(),
+            })
+        };
+        let names =
+            |o: &ExtractEntryOutput| o.files.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+
+        // 精确匹配：同名条目以最后一个为准
+        let exact = extract("materials/sky.tex");
+        assert!(exact.success, "{:?}", exact.error);
+        assert_eq!(names(&exact), ["materials/sky.tex"]);
+        assert!(exact.files[0].is_tex);
+        assert_eq!(fs::read(out.join("materials/sky.tex")).unwrap(), b"new");
+
+        // glob 匹配（大小写不敏感）
+        let glob = extract("MATERIALS/c*");
+        assert!(glob.success, "{:?}", glob.error);
+        assert_eq!(names(&glob), ["materials/cloud.tex"]);
+        assert_eq!(glob.files[0].size, 5);
+
+        // 找不到时给出去重后的相近条目
+        let missing = extract("sky.png");
+        assert!(!missing.success);
+        assert_eq!(missing.error_kind, Some(CoreErrorKind::NotFound));
+        assert_eq!(
+            missing.suggestions,
+            ["materials/sky.tex", "models/skybox.json"]
+        );
+
+        // 不安全的条目名拒绝写出
+        let unsafe_name = extract("../evil.txt");
+        assert!(!unsafe_name.success);
+        assert_eq!(unsafe_name.error_kind, Some(CoreErrorKind::Validation));
+        assert!(!root.join("evil.txt").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
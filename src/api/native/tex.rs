@@ -2,9 +2,13 @@
 //!
 //! 封装 core::tex 的底层操作，提供批量转换等便捷方法。
 
+use super::utl::{is_cancelled, resolve_jobs, run_parallel, CancelFlag, FailBreaker, MemoryBudget};
+use crate::core::error::CoreErrorKind;
 use crate::core::{path, tex};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::Read;
 use std::path::PathBuf;
 
 // ============================================================================
@@ -18,6 +22,81 @@ pub struct ConvertAllInput {
     pub unpacked_path: PathBuf,
     /// 转换输出目录，None 则输出到解包目录下的 tex_converted 子目录
     pub output_path: Option<PathBuf>,
+    /// 严格校验直通图片（完整解码验证）
+    pub strict: bool,
+    /// LZ4 按声明大小解压失败时直接报告错误，不尝试其他解压策略
+    pub strict_lz4: bool,
+    /// 就地模式：输出写到每个 TEX 所在目录（同名不同扩展），忽略 output_path
+    pub in_place: bool,
+    /// 转换成功后删除原 TEX（通常与 in_place 配合使用）
+    pub remove_source: bool,
+    /// 失败熔断阈值，None 或 0 表示不启用
+    pub fail_threshold: Option<usize>,
+    /// 缩略图最大边长，None 表示不生成（缩略图写入输出图片旁的 thumbs/ 目录）
+    pub thumbnail_size: Option<u32>,
+    /// 并行转换的线程数，None 或 0 使用全部 CPU 核心，1 为逐个顺序转换
+    pub jobs: Option<usize>,
+    /// 并行转换的内存预算（MB），None 或 0 表示不限制
+    ///
+    /// 启用后每个 TEX 在解码→编码→写盘期间占用预估额度，
+    /// 额度不足时等待，预算越紧并发越低
+    pub max_memory_mb: Option<u64>,
+    /// 只转换输出尚不存在的 TEX，已有输出的计入 tex_skipped
+    pub missing_only: bool,
+    /// 多个 TEX 映射到同一输出路径时的处理策略
+    pub on_conflict: ConflictPolicy,
+    /// 导出哪些 mipmap 层级
+    pub mipmap: tex::MipmapSelection,
+    /// 将多帧 TEX 合成为动图（GIF / APNG），None 表示逐帧导出
+    pub animate: Option<tex::AnimateOutput>,
+    /// 纹理解码后的输出图片格式（PNG / JPEG / WebP / TIFF）
+    pub output_format: tex::ImageOutputFormat,
+    /// 上下翻转输出图片（直通图片解码后重新编码，视频不受影响）
+    pub flip_vertical: bool,
+    /// 取消标志，置位后转换完当前 TEX 即停止，结果只包含已完成的部分
+    pub cancel: Option<CancelFlag>,
+}
+
+/// 输出路径冲突时的处理策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// 在文件名后追加序号（`_1`、`_2` …）
+    #[default]
+    Rename,
+    /// 后出现的 TEX 报告为失败，不写出
+    Error,
+}
+
+impl ConflictPolicy {
+    /// 从字符串解析策略（大小写不敏感）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "rename" => Some(ConflictPolicy::Rename),
+            "error" => Some(ConflictPolicy::Error),
+            _ => None,
+        }
+    }
+
+    /// 获取策略名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConflictPolicy::Rename => "rename",
+            ConflictPolicy::Error => "error",
+        }
+    }
+}
+
+/// 输出路径冲突记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConflict {
+    /// 发生冲突的 TEX
+    pub input_path: PathBuf,
+    /// 先占用该输出路径的 TEX
+    pub existing_input: PathBuf,
+    /// 冲突的输出路径（不含扩展名）
+    pub output_path: PathBuf,
+    /// Rename 策略下实际使用的输出路径，Error 策略为 None
+    pub resolved_path: Option<PathBuf>,
 }
 
 /// 批量转换返回值
@@ -29,29 +108,84 @@ pub struct ConvertAllOutput {
     pub results: Vec<ConvertResult>,
     /// 统计信息
     pub stats: ConvertStats,
+    /// 按壁纸分组的统计（依据 unpacked 下的一级子目录）
+    pub groups: Vec<ConvertGroupStats>,
+    /// 是否因失败熔断而提前中止
+    pub aborted: bool,
+    /// 输出路径冲突记录（写出前检测）
+    pub conflicts: Vec<OutputConflict>,
     /// 错误信息
     pub error: Option<String>,
 }
 
+/// 单个壁纸的转换统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConvertGroupStats {
+    /// 分组名（unpacked 下的一级子目录名，通常为壁纸 ID）
+    pub group: String,
+    /// 该壁纸的 TEX 总数
+    pub tex_total: usize,
+    /// 成功转换数
+    pub tex_success: usize,
+    /// 失败数
+    pub tex_failed: usize,
+    /// 图片输出数
+    pub image_count: usize,
+    /// 视频输出数
+    pub video_count: usize,
+}
+
+/// 批量转换进度（按壁纸分组）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertProgress {
+    /// 当前分组名
+    pub group: String,
+    /// 当前分组序号（从 1 开始）
+    pub group_index: usize,
+    /// 分组总数
+    pub group_count: usize,
+    /// 当前分组内已处理的 TEX 数
+    pub current: usize,
+    /// 当前分组的 TEX 总数
+    pub total: usize,
+    /// 全部已处理的 TEX 数
+    pub overall_current: usize,
+    /// 全部 TEX 总数
+    pub overall_total: usize,
+    /// 刚处理完的 TEX 结果
+    pub result: ConvertResult,
+}
+
+/// 转换进度回调函数类型
+pub type ConvertProgressCallback<'a> = &'a dyn Fn(ConvertProgress);
+
 /// 单个 TEX 转换结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConvertResult {
     /// 输入 TEX 文件路径
     pub input_path: PathBuf,
-    /// 输出文件路径
+    /// 输出文件路径（导出多个 mipmap 时为第一个）
     pub output_path: PathBuf,
+    /// 导出多个 mipmap 时其余层级的输出路径
+    pub extra_output_paths: Vec<PathBuf>,
     /// 是否成功
     pub success: bool,
     /// 输出格式
     pub format: Option<String>,
     /// TEX 信息
     pub tex_info: Option<TexPreview>,
+    /// 缩略图路径（启用缩略图且生成成功时）
+    pub thumbnail_path: Option<PathBuf>,
+    /// 请求 JPEG 但纹理含透明像素，改为输出 PNG
+    pub alpha_fallback: bool,
     /// 错误信息
     pub error: Option<String>,
+    /// 错误类别（失败时由 core 错误提供，可按类别分支）
+    pub error_kind: Option<CoreErrorKind>,
 }
 
 /// 转换统计
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ConvertStats {
     /// 处理的 TEX 文件数
     pub tex_processed: usize,
@@ -59,12 +193,22 @@ pub struct ConvertStats {
     pub tex_success: usize,
     /// 失败数
     pub tex_failed: usize,
-    /// 跳过数（非 TEX 格式等）
+    /// 跳过数（missing_only 模式下输出已存在）
     pub tex_skipped: usize,
     /// 图片输出数
     pub image_count: usize,
     /// 视频输出数
     pub video_count: usize,
+    /// 转换后删除的原 TEX 数
+    pub tex_removed: usize,
+    /// 生成的缩略图数
+    pub thumbnail_count: usize,
+    /// 缩略图生成失败数（不影响转换结果）
+    pub thumbnail_failed: usize,
+    /// 按 TEX 格式（DXT5 / JPEG 等）统计的成功转换数
+    pub format_counts: BTreeMap<String, usize>,
+    /// 因含透明像素从 JPEG 回退为 PNG 的 TEX 数
+    pub alpha_fallback_count: usize,
 }
 
 /// 预览 TEX 入参
@@ -72,6 +216,8 @@ pub struct ConvertStats {
 pub struct PreviewTexInput {
     /// TEX 文件路径
     pub tex_path: PathBuf,
+    /// 计划使用的输出图片格式（决定推荐输出扩展名）
+    pub output_format: tex::ImageOutputFormat,
 }
 
 /// 预览 TEX 返回值
@@ -83,6 +229,8 @@ pub struct PreviewTexOutput {
     pub tex_info: Option<TexPreview>,
     /// 错误信息
     pub error: Option<String>,
+    /// 错误类别（失败时由 core 错误提供，可按类别分支）
+    pub error_kind: Option<CoreErrorKind>,
 }
 
 /// TEX 预览信息
@@ -92,6 +240,12 @@ pub struct TexPreview {
     pub version: String,
     /// 格式类型
     pub format: String,
+    /// 文件头中的原始 format 值
+    pub header_format: u32,
+    /// FreeImage 格式值（-1 表示非内嵌图片）
+    pub image_format: i32,
+    /// 格式类别
+    pub format_kind: tex::FormatKind,
     /// 图像宽度
     pub width: u32,
     /// 图像高度
@@ -100,6 +254,8 @@ pub struct TexPreview {
     pub image_count: usize,
     /// Mipmap 数量
     pub mipmap_count: usize,
+    /// 动画帧数
+    pub frame_count: usize,
     /// 是否 LZ4 压缩
     pub is_compressed: bool,
     /// 是否视频
@@ -118,92 +274,454 @@ pub struct TexPreview {
 ///
 /// 扫描 unpacked_path 下所有 .tex 文件并转换
 pub fn convert_all(input: ConvertAllInput) -> ConvertAllOutput {
-    // 查找所有 TEX 文件
-    let tex_files = find_tex_files(&input.unpacked_path);
+    convert_all_with_progress(input, None)
+}
 
-    if tex_files.is_empty() {
+/// 批量转换 TEX 文件（带分组进度回调）
+///
+/// 按 unpacked_path 下的一级子目录（壁纸）分组依次转换，
+/// 每处理一个 TEX 调用一次 `on_progress`
+pub fn convert_all_with_progress(
+    input: ConvertAllInput,
+    on_progress: Option<ConvertProgressCallback>,
+) -> ConvertAllOutput {
+    // 查找所有 TEX 文件并按壁纸分组
+    let mut tex_files = find_tex_files(&input.unpacked_path);
+    tex_files.sort();
+
+    // 写出前统一分配输出路径，检测多个 TEX 映射到同一路径
+    let (mut jobs, conflicts) = plan_outputs(tex_files, &input);
+
+    let mut stats = ConvertStats::default();
+
+    // 只补转换缺失的输出
+    if input.missing_only {
+        let before = jobs.len();
+        jobs.retain(|job| !output_exists(job, &input));
+        stats.tex_skipped = before - jobs.len();
+    }
+
+    if jobs.is_empty() {
         return ConvertAllOutput {
             success: true,
             results: vec![],
-            stats: ConvertStats::default(),
+            stats,
+            groups: vec![],
+            aborted: false,
+            conflicts,
             error: None,
         };
     }
 
-    let mut results = Vec::new();
-    let mut stats = ConvertStats::default();
+    let overall_total = jobs.len();
+    let grouped = group_by_wallpaper(jobs, &input.unpacked_path);
+    let group_count = grouped.len();
 
-    for tex_path in tex_files {
-        stats.tex_processed += 1;
-
-        // 确定输出路径
-        let output_path =
-            determine_output_path(&tex_path, &input.unpacked_path, &input.output_path);
-
-        // 执行转换
-        let convert_result = tex::convert_tex(tex::ConvertTexInput {
-            file_path: tex_path.clone(),
-            output_path: output_path.clone(),
-        });
+    let mut results = Vec::new();
+    let mut groups = Vec::with_capacity(group_count);
+    let mut breaker = FailBreaker::new(input.fail_threshold);
+    let workers = resolve_jobs(input.jobs);
+    let budget = input
+        .max_memory_mb
+        .filter(|&mb| mb > 0)
+        .map(|mb| MemoryBudget::new(mb * 1024 * 1024));
 
-        match convert_result {
-            Ok(result) => {
-                stats.tex_success += 1;
+    for (group_index, (group, files)) in grouped.into_iter().enumerate() {
+        let mut group_stats = ConvertGroupStats {
+            group: group.clone(),
+            tex_total: files.len(),
+            ..Default::default()
+        };
+        // (组内序号, 结果)，并行时按完成顺序到达，组结束后恢复原顺序
+        let mut group_results: Vec<(usize, ConvertResult)> = Vec::with_capacity(files.len());
 
-                let tex_info = {
-                    let info = &result.tex_info;
-                    if info.is_video {
-                        stats.video_count += 1;
-                    } else {
-                        stats.image_count += 1;
-                    }
+        let mut on_done = |index: usize, result: ConvertResult, job_stats: ConvertStats| {
+            add_stats(&mut stats, job_stats);
+            if result.success {
+                group_stats.tex_success += 1;
+                match result.tex_info {
+                    Some(ref info) if info.is_video => group_stats.video_count += 1,
+                    _ => group_stats.image_count += 1,
+                }
+            } else {
+                group_stats.tex_failed += 1;
+            }
+            let abort = breaker.record(result.success);
 
-                    TexPreview {
-                        version: info.version.clone(),
-                        format: info.format.clone(),
-                        width: info.width,
-                        height: info.height,
-                        image_count: info.image_count,
-                        mipmap_count: info.mipmap_count,
-                        is_compressed: info.is_compressed,
-                        is_video: info.is_video,
-                        data_size: info.data_size,
-                        recommended_output: if info.is_video { "mp4" } else { "png" }.to_string(),
-                    }
-                };
-
-                results.push(ConvertResult {
-                    input_path: tex_path,
-                    output_path: result.converted_file.output_path,
-                    success: true,
-                    format: Some(result.converted_file.format),
-                    tex_info: Some(tex_info),
-                    error: None,
+            if let Some(callback) = on_progress {
+                callback(ConvertProgress {
+                    group: group.clone(),
+                    group_index: group_index + 1,
+                    group_count,
+                    current: group_results.len() + 1,
+                    total: group_stats.tex_total,
+                    overall_current: stats.tex_processed,
+                    overall_total,
+                    result: result.clone(),
                 });
             }
-            Err(e) => {
-                stats.tex_failed += 1;
-                results.push(ConvertResult {
-                    input_path: tex_path,
-                    output_path,
-                    success: false,
-                    format: None,
-                    tex_info: None,
-                    error: Some(e.to_string()),
-                });
+            group_results.push((index, result));
+
+            abort || is_cancelled(input.cancel.as_ref())
+        };
+
+        if workers > 1 {
+            convert_parallel(&files, &input, workers, budget.as_ref(), &mut on_done);
+        } else {
+            for (i, job) in files.into_iter().enumerate() {
+                let mut job_stats = ConvertStats::default();
+                let result = convert_one(job, &input, &mut job_stats);
+                if on_done(i, result, job_stats) {
+                    break;
+                }
             }
         }
+
+        group_results.sort_by_key(|(i, _)| *i);
+        results.extend(group_results.into_iter().map(|(_, r)| r));
+        groups.push(group_stats);
+        if breaker.tripped() || is_cancelled(input.cancel.as_ref()) {
+            break;
+        }
     }
 
+    let aborted = breaker.tripped();
+    let error = if aborted {
+        Some(breaker.abort_message("TEX"))
+    } else if stats.tex_failed > 0 {
+        Some(format!("{} TEX files failed to convert", stats.tex_failed))
+    } else {
+        None
+    };
     ConvertAllOutput {
         success: stats.tex_failed == 0,
         results,
         stats,
-        error: if stats.tex_failed > 0 {
-            Some(format!("{} TEX files failed to convert", stats.tex_failed))
-        } else {
-            None
+        groups,
+        aborted,
+        conflicts,
+        error,
+    }
+}
+
+/// 在固定数量的工作线程上并行转换一组 TEX
+///
+/// 设置了内存预算时，每个任务先按预估解码占用申请额度，转换写盘完成后归还；
+/// 结果回到调用线程由 `on_done` 处理，其返回 true 时不再派发新任务。
+/// 输出路径在派发前已统一分配，工作线程只会并发创建目录（`create_dir_all` 可安全并发）
+fn convert_parallel(
+    files: &[TexJob],
+    input: &ConvertAllInput,
+    workers: usize,
+    budget: Option<&MemoryBudget>,
+    on_done: &mut dyn FnMut(usize, ConvertResult, ConvertStats) -> bool,
+) {
+    run_parallel(
+        files,
+        workers,
+        |job| {
+            let _permit = budget.map(|b| b.acquire(estimate_decode_bytes(&job.tex_path)));
+            let mut job_stats = ConvertStats::default();
+            let result = convert_one(job.clone(), input, &mut job_stats);
+            (result, job_stats)
         },
+        &mut |index, (result, job_stats)| on_done(index, result, job_stats),
+    );
+}
+
+/// 预估转换单个 TEX 时驻留的内存（字节）
+///
+/// 按文件头中的尺寸计算解码后的 RGBA 大小（W×H×4×图像数），再加上原始数据与 LZ4 解压后的
+/// 纹理数据（不超过 RGBA 大小）；直通格式只驻留原始数据。文件头无法解析时按 9 倍文件大小估算
+fn estimate_decode_bytes(tex_path: &std::path::Path) -> u64 {
+    // 足够覆盖文件头与图像容器头
+    const TEX_HEAD_LEN: u64 = 4096;
+
+    let Ok(mut file) = fs::File::open(tex_path) else {
+        return 0;
+    };
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let mut head = Vec::new();
+    let estimate = (&mut file)
+        .take(TEX_HEAD_LEN)
+        .read_to_end(&mut head)
+        .ok()
+        .and_then(|_| tex::estimate_tex_output(tex::EstimateTexInput { head, size }).ok());
+    match estimate {
+        Some(e) if e.passthrough => size,
+        Some(e) => size.saturating_add(e.output_size.saturating_mul(2)),
+        None => size.saturating_mul(9),
+    }
+}
+
+/// 累加单个任务的统计
+fn add_stats(total: &mut ConvertStats, job: ConvertStats) {
+    total.tex_processed += job.tex_processed;
+    total.tex_success += job.tex_success;
+    total.tex_failed += job.tex_failed;
+    total.tex_skipped += job.tex_skipped;
+    total.image_count += job.image_count;
+    total.video_count += job.video_count;
+    total.tex_removed += job.tex_removed;
+    total.thumbnail_count += job.thumbnail_count;
+    total.thumbnail_failed += job.thumbnail_failed;
+    total.alpha_fallback_count += job.alpha_fallback_count;
+    for (format, count) in job.format_counts {
+        *total.format_counts.entry(format).or_insert(0) += count;
+    }
+}
+
+/// 单个待转换的 TEX 及其分配好的输出路径
+#[derive(Debug, Clone)]
+struct TexJob {
+    tex_path: PathBuf,
+    /// 输出路径（不含扩展名，扩展名由转换结果决定）
+    output_path: PathBuf,
+    /// Error 策略下与之冲突的先到 TEX，设置后不执行转换
+    conflict_with: Option<PathBuf>,
+}
+
+/// 为每个 TEX 分配输出路径并检测冲突
+///
+/// 默认输出目录会把子目录展平，不同目录下的同名 TEX 会映射到同一文件；
+/// 路径按小写比较，避免在大小写不敏感的文件系统上互相覆盖。
+/// Rename 策略选序号时同时跳过磁盘上已有的输出，重复运行不会覆盖之前的 `_N` 文件
+fn plan_outputs(
+    tex_files: Vec<PathBuf>,
+    input: &ConvertAllInput,
+) -> (Vec<TexJob>, Vec<OutputConflict>) {
+    let mut used: HashMap<String, PathBuf> = HashMap::new();
+    let mut jobs = Vec::with_capacity(tex_files.len());
+    let mut conflicts = Vec::new();
+    let key = |p: &std::path::Path| p.to_string_lossy().to_lowercase();
+
+    for tex_path in tex_files {
+        // 就地模式下写到 TEX 旁边
+        let output_path = if input.in_place {
+            tex_path.with_extension("")
+        } else {
+            determine_output_path(&tex_path, &input.unpacked_path, &input.output_path)
+        };
+
+        let Some(existing) = used.get(&key(&output_path)).cloned() else {
+            used.insert(key(&output_path), tex_path.clone());
+            jobs.push(TexJob {
+                tex_path,
+                output_path,
+                conflict_with: None,
+            });
+            continue;
+        };
+
+        let resolved = match input.on_conflict {
+            ConflictPolicy::Rename => {
+                let stem = output_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let renamed = (1..)
+                    .map(|n| output_path.with_file_name(format!("{}_{}", stem, n)))
+                    .find(|p| !used.contains_key(&key(p)) && !output_stem_exists(p))
+                    .unwrap_or_default();
+                used.insert(key(&renamed), tex_path.clone());
+                Some(renamed)
+            }
+            ConflictPolicy::Error => None,
+        };
+
+        conflicts.push(OutputConflict {
+            input_path: tex_path.clone(),
+            existing_input: existing.clone(),
+            output_path: output_path.clone(),
+            resolved_path: resolved.clone(),
+        });
+        jobs.push(TexJob {
+            tex_path,
+            conflict_with: resolved.is_none().then_some(existing),
+            output_path: resolved.unwrap_or(output_path),
+        });
+    }
+
+    (jobs, conflicts)
+}
+
+/// 判断 TEX 的转换输出是否已存在
+///
+/// 输出扩展名取决于 TEX 内的格式，先解析元数据得到 convert_tex 实际会写出的扩展名；
+/// 合成动图时检查动图文件，多图像或导出全部 mipmap 时以第一个输出文件为准。
+/// 无法解析的 TEX 视为缺失，交给转换阶段报告错误
+/// 输出目录中是否已有以该路径为主名的文件（任意扩展名，含多图像的 `-img{i}` 输出）
+fn output_stem_exists(output: &std::path::Path) -> bool {
+    let (Some(parent), Some(name)) = (output.parent(), output.file_name()) else {
+        return false;
+    };
+    let name = name.to_string_lossy().to_lowercase();
+    let multi_prefix = format!("{}-img", name);
+    let Ok(entries) = fs::read_dir(path::long_path(parent)) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let entry_path = entry.path();
+        let stem = entry_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        stem == name || stem.starts_with(&multi_prefix)
+    })
+}
+
+fn output_exists(job: &TexJob, input: &ConvertAllInput) -> bool {
+    let Ok(parsed) = tex::parse_tex(tex::ParseTexInput {
+        file_path: job.tex_path.clone(),
+    }) else {
+        return false;
+    };
+
+    if let Some(animate) = input.animate {
+        if parsed.tex_info.frame_count > 1 && !parsed.tex_info.is_video {
+            return job
+                .output_path
+                .with_extension(animate.extension())
+                .is_file();
+        }
+    }
+
+    // 请求 JPEG 时含透明像素的纹理会回退为 PNG，两种扩展名都算已有输出
+    let mut extensions = vec![recommended_output(&parsed.tex_info, input.output_format)];
+    if parsed.tex_info.is_texture
+        && matches!(input.output_format, tex::ImageOutputFormat::Jpeg { .. })
+    {
+        extensions.push(tex::ImageOutputFormat::Png.extension().to_string());
+    }
+
+    extensions.iter().any(|ext| {
+        let mut output = job.output_path.with_extension(ext);
+        if parsed.tex_info.image_count > 1 {
+            output = tex::image_output_path(&output, 0);
+        }
+        match input.mipmap {
+            tex::MipmapSelection::All => tex::mipmap_output_path(&output, 0).is_file(),
+            _ => output.is_file(),
+        }
+    })
+}
+
+/// 转换单个 TEX 并累计统计
+fn convert_one(job: TexJob, input: &ConvertAllInput, stats: &mut ConvertStats) -> ConvertResult {
+    stats.tex_processed += 1;
+    let TexJob {
+        tex_path,
+        output_path,
+        conflict_with,
+    } = job;
+
+    // Error 策略：输出路径已被其它 TEX 占用，不写出
+    if let Some(existing) = conflict_with {
+        stats.tex_failed += 1;
+        return ConvertResult {
+            input_path: tex_path,
+            output_path,
+            extra_output_paths: Vec::new(),
+            success: false,
+            format: None,
+            tex_info: None,
+            thumbnail_path: None,
+            alpha_fallback: false,
+            error: Some(format!("Output path conflicts with {}", existing.display())),
+            error_kind: Some(CoreErrorKind::Validation),
+        };
+    }
+
+    // 执行转换
+    let convert_result = tex::convert_tex(tex::ConvertTexInput {
+        file_path: tex_path.clone(),
+        output_path: output_path.clone(),
+        strict: input.strict,
+        strict_lz4: input.strict_lz4,
+        mipmap: input.mipmap,
+        animate: input.animate,
+        output_format: input.output_format,
+        flip_vertical: input.flip_vertical,
+    });
+
+    match convert_result {
+        Ok(result) => {
+            stats.tex_success += 1;
+            let (primary, extra_output_paths) = split_outputs(&result.converted_files);
+            let alpha_fallback = result.converted_files.iter().any(|f| f.alpha_fallback);
+            if alpha_fallback {
+                stats.alpha_fallback_count += 1;
+            }
+
+            // 删除原 TEX（输出与输入不同才删除）
+            if input.remove_source
+                && !result
+                    .converted_files
+                    .iter()
+                    .any(|f| f.output_path == tex_path)
+                && fs::remove_file(&tex_path).is_ok()
+            {
+                stats.tex_removed += 1;
+            }
+
+            let tex_info = {
+                let info = &result.tex_info;
+                if info.is_video {
+                    stats.video_count += 1;
+                } else {
+                    stats.image_count += 1;
+                }
+                *stats.format_counts.entry(info.format.clone()).or_insert(0) += 1;
+
+                tex_preview(info, input.output_format)
+            };
+
+            // 视频不生成缩略图
+            let thumbnail_path = match input.thumbnail_size {
+                Some(size) if !tex_info.is_video => {
+                    match make_thumbnail(&primary.output_path, size) {
+                        Ok(p) => {
+                            stats.thumbnail_count += 1;
+                            Some(p)
+                        }
+                        Err(_) => {
+                            stats.thumbnail_failed += 1;
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            ConvertResult {
+                input_path: tex_path,
+                output_path: primary.output_path.clone(),
+                extra_output_paths,
+                success: true,
+                format: Some(primary.format.clone()),
+                tex_info: Some(tex_info),
+                thumbnail_path,
+                alpha_fallback,
+                error: None,
+                error_kind: None,
+            }
+        }
+        Err(e) => {
+            stats.tex_failed += 1;
+            ConvertResult {
+                input_path: tex_path,
+                output_path,
+                extra_output_paths: Vec::new(),
+                success: false,
+                format: None,
+                tex_info: None,
+                thumbnail_path: None,
+                alpha_fallback: false,
+                error: Some(e.to_string()),
+                error_kind: Some(e.kind()),
+            }
+        }
     }
 }
 
@@ -218,74 +736,148 @@ pub fn preview_tex(input: PreviewTexInput) -> PreviewTexOutput {
             let info = result.tex_info;
             PreviewTexOutput {
                 success: true,
-                tex_info: Some(TexPreview {
-                    version: info.version,
-                    format: info.format,
-                    width: info.width,
-                    height: info.height,
-                    image_count: info.image_count,
-                    mipmap_count: info.mipmap_count,
-                    is_compressed: info.is_compressed,
-                    is_video: info.is_video,
-                    data_size: info.data_size,
-                    recommended_output: if info.is_video { "mp4" } else { "png" }.to_string(),
-                }),
+                tex_info: Some(tex_preview(&info, input.output_format)),
                 error: None,
+                error_kind: None,
             }
         }
         Err(e) => PreviewTexOutput {
             success: false,
             tex_info: None,
             error: Some(e.to_string()),
+            error_kind: Some(e.kind()),
         },
     }
 }
 
 /// 转换单个 TEX 文件
-pub fn convert_single(tex_path: PathBuf, output_path: PathBuf) -> ConvertResult {
-    match tex::convert_tex(tex::ConvertTexInput {
-        file_path: tex_path.clone(),
-        output_path: output_path.clone(),
-    }) {
+pub fn convert_single(input: tex::ConvertTexInput) -> ConvertResult {
+    let tex_path = input.file_path.clone();
+    let output_path = input.output_path.clone();
+    let output_format = input.output_format;
+    match tex::convert_tex(input) {
         Ok(result) => {
-            let info = &result.tex_info;
-            let tex_info = TexPreview {
-                version: info.version.clone(),
-                format: info.format.clone(),
-                width: info.width,
-                height: info.height,
-                image_count: info.image_count,
-                mipmap_count: info.mipmap_count,
-                is_compressed: info.is_compressed,
-                is_video: info.is_video,
-                data_size: info.data_size,
-                recommended_output: if info.is_video { "mp4" } else { "png" }.to_string(),
-            };
+            let (primary, extra_output_paths) = split_outputs(&result.converted_files);
+            let alpha_fallback = result.converted_files.iter().any(|f| f.alpha_fallback);
+            let tex_info = tex_preview(&result.tex_info, output_format);
 
             ConvertResult {
                 input_path: tex_path,
-                output_path: result.converted_file.output_path,
+                output_path: primary.output_path.clone(),
+                extra_output_paths,
                 success: true,
-                format: Some(result.converted_file.format),
+                format: Some(primary.format.clone()),
                 tex_info: Some(tex_info),
+                thumbnail_path: None,
+                alpha_fallback,
                 error: None,
+                error_kind: None,
             }
         }
         Err(e) => ConvertResult {
             input_path: tex_path,
             output_path,
+            extra_output_paths: Vec::new(),
             success: false,
             format: None,
             tex_info: None,
+            thumbnail_path: None,
+            alpha_fallback: false,
             error: Some(e.to_string()),
+            error_kind: Some(e.kind()),
         },
     }
 }
 
+/// 为转换后的图片生成缩略图
+///
+/// 缩略图写入图片所在目录下的 `thumbs/`，最长边缩放到 max_size，返回缩略图路径
+pub fn make_thumbnail(image_path: &std::path::Path, max_size: u32) -> Result<PathBuf, String> {
+    let output_dir = image_path
+        .parent()
+        .unwrap_or(std::path::Path::new("."))
+        .join("thumbs");
+
+    tex::make_thumbnail(tex::MakeThumbnailInput {
+        image_path: image_path.to_path_buf(),
+        output_dir,
+        max_size,
+    })
+    .map(|o| o.thumbnail_path)
+    .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // 内部工具函数
 // ============================================================================
 
+/// 由核心层 TexInfo 构建预览信息，推荐输出按计划使用的输出格式给出
+fn tex_preview(info: &tex::TexInfo, output_format: tex::ImageOutputFormat) -> TexPreview {
+    TexPreview {
+        version: info.version.clone(),
+        format: info.format.clone(),
+        header_format: info.header_format,
+        image_format: info.image_format,
+        format_kind: info.format_kind,
+        width: info.width,
+        height: info.height,
+        image_count: info.image_count,
+        mipmap_count: info.mipmap_count,
+        frame_count: info.frame_count,
+        is_compressed: info.is_compressed,
+        is_video: info.is_video,
+        data_size: info.data_size,
+        recommended_output: recommended_output(info, output_format),
+    }
+}
+
+/// 转换实际写出的扩展名：纹理格式取决于输出图片格式，直通图片与视频保持原格式
+fn recommended_output(info: &tex::TexInfo, output_format: tex::ImageOutputFormat) -> String {
+    if info.is_texture {
+        output_format.extension().to_string()
+    } else {
+        info.output_extension.clone()
+    }
+}
+
+/// 拆分 convert_tex 写出的文件：第一个作为主输出，其余路径单独列出
+///
+/// convert_tex 成功时至少写出一个文件
+fn split_outputs(files: &[tex::ConvertedFile]) -> (&tex::ConvertedFile, Vec<PathBuf>) {
+    let extra = files[1..].iter().map(|f| f.output_path.clone()).collect();
+    (&files[0], extra)
+}
+
+/// 按 unpacked 下的一级子目录对 TEX 文件分组（输入需已排序，保持顺序）
+fn group_by_wallpaper(
+    jobs: Vec<TexJob>,
+    unpacked_path: &std::path::Path,
+) -> Vec<(String, Vec<TexJob>)> {
+    let mut groups: Vec<(String, Vec<TexJob>)> = Vec::new();
+
+    for job in jobs {
+        let group = job
+            .tex_path
+            .strip_prefix(unpacked_path)
+            .ok()
+            .and_then(|rel| {
+                let mut components = rel.components();
+                let first = components.next()?;
+                // 直接位于根目录的文件不属于任何壁纸
+                components.next()?;
+                Some(first.as_os_str().to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+
+        match groups.last_mut() {
+            Some((last, files)) if *last == group => files.push(job),
+            _ => groups.push((group, vec![job])),
+        }
+    }
+
+    groups
+}
+
 /// 查找目录下所有 TEX 文件
 fn find_tex_files(dir: &PathBuf) -> Vec<PathBuf> {
     let mut tex_files = Vec::new();
@@ -351,3 +943,194 @@ fn determine_output_path(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{push_mipmap, tex_bytes, tex_head};
+
+    #[test]
+    fn test_estimate_decode_bytes_uses_header_size() {
+        let dir = std::env::temp_dir().join("lianpkg_estimate_decode");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // 256×256 DXT5，数据很小（如 LZ4 压缩后）：按解码后的 RGBA 大小估算，而不是文件大小
+        let mut dxt = tex_head("TEXV0005", &[4, 0, 256, 256, 256, 256, 0], b"TEXB0002\0", 1);
+        dxt.extend_from_slice(&1i32.to_le_bytes());
+        push_mipmap(&mut dxt, 256, 256, &[0; 64]);
+        fs::write(dir.join("dxt.tex"), &dxt).unwrap();
+        let estimate = estimate_decode_bytes(&dir.join("dxt.tex"));
+        assert!(estimate >= 256 * 256 * 4, "{}", estimate);
+
+        let small = tex_bytes();
+        fs::write(dir.join("small.tex"), &small).unwrap();
+        assert_eq!(
+            estimate_decode_bytes(&dir.join("small.tex")),
+            small.len() as u64 + 2 * 2 * 2 * 4
+        );
+
+        // 无法解析的文件按文件大小估算
+        fs::write(dir.join("bad.tex"), b"garbage").unwrap();
+        assert_eq!(estimate_decode_bytes(&dir.join("bad.tex")), 7 * 9);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_convert_all_missing_only() {
+        let root = std::env::temp_dir().join("lianpkg_convert_missing_only");
+        let _ = fs::remove_dir_all(&root);
+        let scene = root.join("100007").join("materials");
+        fs::create_dir_all(&scene).unwrap();
+        fs::write(scene.join("a.tex"), tex_bytes()).unwrap();
+        fs::write(scene.join("b.tex"), tex_bytes()).unwrap();
+
+        let input = |missing_only| ConvertAllInput {
+            unpacked_path: root.clone(),
+            output_path: None,
+            strict: false,
+            strict_lz4: false,
+            in_place: false,
+            remove_source: false,
+            fail_threshold: None,
+            thumbnail_size: None,
+            jobs: None,
+            max_memory_mb: None,
+            missing_only,
+            on_conflict: ConflictPolicy::Rename,
+            mipmap: tex::MipmapSelection::First,
+            animate: None,
+            output_format: tex::ImageOutputFormat::Png,
+            flip_vertical: false,
+            cancel: None,
+        };
+        let first = convert_all(input(false));
+        assert_eq!(first.stats.tex_success, 2);
+
+        // 删除其中一张输出后只补转换这一张
+        fs::remove_file(&first.results[0].output_path).unwrap();
+        let second = convert_all(input(true));
+        assert_eq!(second.stats.tex_success, 1);
+        assert_eq!(second.stats.tex_skipped, 1);
+        assert!(first.results[0].output_path.is_file());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_convert_all_parallel_matches_sequential() {
+        let root = std::env::temp_dir().join("lianpkg_convert_all_parallel");
+        let _ = fs::remove_dir_all(&root);
+        for id in ["100008", "100009"] {
+            let scene = root.join("unpacked").join(id).join("materials");
+            fs::create_dir_all(&scene).unwrap();
+            for i in 0..5 {
+                fs::write(scene.join(format!("t{}.tex", i)), tex_bytes()).unwrap();
+            }
+            fs::write(scene.join("broken.tex"), b"TEXV0005").unwrap();
+        }
+
+        let run = |jobs: Option<usize>| {
+            convert_all(ConvertAllInput {
+                unpacked_path: root.join("unpacked"),
+                output_path: Some(root.join(format!("out_{:?}", jobs))),
+                strict: false,
+                strict_lz4: false,
+                in_place: false,
+                remove_source: false,
+                fail_threshold: None,
+                thumbnail_size: None,
+                jobs,
+                max_memory_mb: None,
+                missing_only: false,
+                on_conflict: ConflictPolicy::Rename,
+                mipmap: tex::MipmapSelection::First,
+                animate: None,
+                output_format: tex::ImageOutputFormat::Png,
+                flip_vertical: false,
+                cancel: None,
+            })
+        };
+        let sequential = run(Some(1));
+        let parallel = run(Some(4));
+
+        assert_eq!(parallel.stats.tex_success, 10);
+        assert_eq!(parallel.stats.tex_failed, 2);
+        assert_eq!(parallel.stats.image_count, sequential.stats.image_count);
+        // 结果保持扫描顺序，与顺序转换一致
+        let names = |o: &ConvertAllOutput| {
+            o.results
+                .iter()
+                .map(|r| (r.input_path.clone(), r.success))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&parallel), names(&sequential));
+        assert!(parallel
+            .results
+            .iter()
+            .filter(|r| r.success)
+            .all(|r| r.output_path.is_file()));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_convert_all_output_conflicts() {
+        let root = std::env::temp_dir().join("lianpkg_convert_conflicts");
+        let _ = fs::remove_dir_all(&root);
+        // 默认输出目录会展平子目录，两个同名 TEX 映射到同一文件
+        for sub in ["a", "b"] {
+            let dir = root.join("100008").join("materials").join(sub);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("same.tex"), tex_bytes()).unwrap();
+        }
+
+        let input = |on_conflict| ConvertAllInput {
+            unpacked_path: root.clone(),
+            output_path: None,
+            strict: false,
+            strict_lz4: false,
+            in_place: false,
+            remove_source: false,
+            fail_threshold: None,
+            thumbnail_size: None,
+            jobs: None,
+            max_memory_mb: None,
+            missing_only: false,
+            on_conflict,
+            mipmap: tex::MipmapSelection::First,
+            animate: None,
+            output_format: tex::ImageOutputFormat::Png,
+            flip_vertical: false,
+            cancel: None,
+        };
+
+        let renamed = convert_all(input(ConflictPolicy::Rename));
+        assert_eq!(renamed.stats.tex_success, 2);
+        assert_eq!(renamed.conflicts.len(), 1);
+        assert_ne!(
+            renamed.results[0].output_path,
+            renamed.results[1].output_path
+        );
+        assert!(renamed.results.iter().all(|r| r.output_path.is_file()));
+        assert_eq!(
+            renamed.results[1].output_path.file_stem().unwrap(),
+            "same_1"
+        );
+
+        // 再次运行时 `same_1` 已在磁盘上，改用下一个序号而不覆盖
+        let rerun = convert_all(input(ConflictPolicy::Rename));
+        assert_eq!(rerun.stats.tex_success, 2);
+        assert_eq!(rerun.results[1].output_path.file_stem().unwrap(), "same_2");
+        assert!(renamed.results[1].output_path.is_file());
+
+        let errored = convert_all(input(ConflictPolicy::Error));
+        assert_eq!(errored.stats.tex_success, 1);
+        assert_eq!(errored.stats.tex_failed, 1);
+        assert_eq!(errored.conflicts.len(), 1);
+        assert!(errored.conflicts[0].resolved_path.is_none());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
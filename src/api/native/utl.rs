@@ -0,0 +1,251 @@
+//! native 层内部工具
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+
+// ============================================================================
+// 取消
+// ============================================================================
+
+/// 取消标志：置为 true 后，批量操作处理完当前项即停止
+pub type CancelFlag = Arc<AtomicBool>;
+
+/// 是否已请求取消
+pub(crate) fn is_cancelled(cancel: Option<&CancelFlag>) -> bool {
+    cancel.is_some_and(|flag| flag.load(Ordering::SeqCst))
+}
+
+// ============================================================================
+// 失败熔断
+// ============================================================================
+
+/// 批处理失败熔断器
+///
+/// 阈值为 N 时，满足以下任一条件即熔断：
+/// - 连续失败达到 N 次
+/// - 累计失败达到 N 次，且失败数多于成功数
+///
+/// 阈值为 None 或 0 时不启用
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FailBreaker {
+    threshold: Option<usize>,
+    consecutive: usize,
+    failed: usize,
+    succeeded: usize,
+}
+
+impl FailBreaker {
+    pub(crate) fn new(threshold: Option<usize>) -> Self {
+        Self {
+            threshold: threshold.filter(|&n| n > 0),
+            ..Default::default()
+        }
+    }
+
+    /// 记录一次结果，返回是否应当中止
+    pub(crate) fn record(&mut self, success: bool) -> bool {
+        if success {
+            self.succeeded += 1;
+            self.consecutive = 0;
+        } else {
+            self.failed += 1;
+            self.consecutive += 1;
+        }
+        self.tripped()
+    }
+
+    /// 是否已熔断
+    pub(crate) fn tripped(&self) -> bool {
+        match self.threshold {
+            Some(n) => self.consecutive >= n || (self.failed >= n && self.failed > self.succeeded),
+            None => false,
+        }
+    }
+
+    /// 熔断时的报告信息
+    pub(crate) fn abort_message(&self, kind: &str) -> String {
+        format!(
+            "Aborted after {} {} failures ({} consecutive): suspected incompatible format",
+            self.failed, kind, self.consecutive
+        )
+    }
+}
+
+// ============================================================================
+// 内存预算
+// ============================================================================
+
+/// 内存预算信号量
+///
+/// 并行任务按预估占用申请额度，额度不足时阻塞等待，从而限制同时驻留的缓冲总量。
+/// 单个任务的申请会被截断到总额度，保证超大任务也能独占运行而不会死锁。
+#[derive(Debug)]
+pub(crate) struct MemoryBudget {
+    total: u64,
+    used: Mutex<u64>,
+    released: Condvar,
+}
+
+/// 已申请的额度，drop 时归还
+pub(crate) struct BudgetPermit<'a> {
+    budget: &'a MemoryBudget,
+    bytes: u64,
+}
+
+impl MemoryBudget {
+    pub(crate) fn new(total: u64) -> Self {
+        Self {
+            total: total.max(1),
+            used: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// 申请额度，不足时阻塞到其他任务归还
+    pub(crate) fn acquire(&self, bytes: u64) -> BudgetPermit<'_> {
+        let bytes = bytes.min(self.total);
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        while *used + bytes > self.total {
+            used = self.released.wait(used).unwrap_or_else(|e| e.into_inner());
+        }
+        *used += bytes;
+        BudgetPermit {
+            budget: self,
+            bytes,
+        }
+    }
+}
+
+impl Drop for BudgetPermit<'_> {
+    fn drop(&mut self) {
+        let mut used = self.budget.used.lock().unwrap_or_else(|e| e.into_inner());
+        *used -= self.bytes;
+        self.budget.released.notify_all();
+    }
+}
+
+// ============================================================================
+// 并行任务
+// ============================================================================
+
+/// 解析并行任务数：None 或 0 时使用可用 CPU 核心数
+pub(crate) fn resolve_jobs(jobs: Option<usize>) -> usize {
+    jobs.filter(|&n| n > 0).unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
+}
+
+/// 在固定数量的工作线程上并行处理一组任务
+///
+/// 结果经通道按完成顺序回到调用线程，由 `on_done(序号, 结果)` 处理，
+/// 其返回 true 时不再派发新任务；workers 不超过 1 时在当前线程顺序执行
+pub(crate) fn run_parallel<T: Sync, R: Send>(
+    items: &[T],
+    workers: usize,
+    work: impl Fn(&T) -> R + Sync,
+    on_done: &mut dyn FnMut(usize, R) -> bool,
+) {
+    let workers = workers.min(items.len());
+    if workers <= 1 {
+        for (index, item) in items.iter().enumerate() {
+            if on_done(index, work(item)) {
+                break;
+            }
+        }
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (next, stop, work) = (&next, &stop, &work);
+            scope.spawn(move || loop {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if tx.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        for (index, result) in rx {
+            if on_done(index, result) {
+                stop.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_fail_breaker_disabled() {
+        let mut breaker = FailBreaker::new(None);
+        assert!(!(0..100).any(|_| breaker.record(false)));
+        let mut breaker = FailBreaker::new(Some(0));
+        assert!(!(0..100).any(|_| breaker.record(false)));
+    }
+
+    #[test]
+    fn test_fail_breaker_consecutive() {
+        let mut breaker = FailBreaker::new(Some(3));
+        assert!(!breaker.record(false));
+        assert!(!breaker.record(false));
+        assert!(breaker.record(false));
+    }
+
+    #[test]
+    fn test_fail_breaker_cumulative() {
+        // 失败被成功打断，但失败占多数
+        let mut breaker = FailBreaker::new(Some(3));
+        assert!(!breaker.record(false));
+        assert!(!breaker.record(false));
+        assert!(!breaker.record(true));
+        assert!(breaker.record(false));
+
+        // 成功占多数时零散失败不熔断
+        let mut breaker = FailBreaker::new(Some(3));
+        for _ in 0..5 {
+            breaker.record(true);
+            assert!(!breaker.record(false));
+        }
+    }
+
+    #[test]
+    fn test_memory_budget_limits_concurrent_usage() {
+        let budget = MemoryBudget::new(100);
+        let in_use = AtomicU64::new(0);
+        let peak = AtomicU64::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let _permit = budget.acquire(40);
+                    let now = in_use.fetch_add(40, Ordering::SeqCst) + 40;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                    in_use.fetch_sub(40, Ordering::SeqCst);
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 100);
+
+        // 超出总额度的申请被截断，独占运行
+        let _permit = budget.acquire(1000);
+    }
+}
@@ -1,3 +1,8 @@
+//! 旧版 API 的结果类型（已弃用）
+//!
+//! 当前没有任何接口构造或返回这些类型，仅为兼容 `lianpkg::types` 路径保留，
+//! 新代码请使用 `native` 中各接口自身的 XxxOutput 结构体
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
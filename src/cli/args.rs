@@ -23,6 +23,18 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub debug: bool,
 
+    /// 跳过所有确认提示（自动回答 yes）
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+
+    /// 以 JSON 输出命令结果（不输出装饰信息，便于脚本调用）
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// 同时把终端输出（去除颜色）追加写入该文件
+    #[arg(long, value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -53,6 +65,22 @@ pub enum Command {
     /// 状态查看
     #[command(visible_alias = "s")]
     Status(StatusArgs),
+
+    /// 导出解包后场景的依赖关系
+    Deps(DepsArgs),
+
+    /// 将转换结果打包为 zip
+    Archive(ArchiveArgs),
+
+    /// 汇总概览（状态记录 + 当前 workshop 扫描）
+    Summary(SummaryArgs),
+
+    /// 清理临时与输出目录
+    Clean(CleanArgs),
+
+    /// 生成 shell 补全脚本（输出到 stdout，供打包安装）
+    #[command(hide = true)]
+    Completions(CompletionsArgs),
 }
 
 // ============================================================================
@@ -77,10 +105,30 @@ pub struct WallpaperArgs {
     #[arg(long = "no-raw")]
     pub no_raw: bool,
 
-    /// 只处理指定的壁纸 ID（逗号分隔）
+    /// 完整重新复制原始壁纸（默认只复制新增或变化的文件）
+    #[arg(long, conflicts_with = "no_raw")]
+    pub force: bool,
+
+    /// 只处理指定的壁纸 ID（逗号分隔，支持 31* 通配与 100-200 数字范围）
     #[arg(short = 'i', long, value_name = "IDS", value_delimiter = ',')]
     pub ids: Option<Vec<String>>,
 
+    /// 允许的最高内容分级（超过的壁纸将被过滤）
+    #[arg(long, value_name = "RATING", value_parser = ["everyone", "questionable", "mature"])]
+    pub max_rating: Option<String>,
+
+    /// 只解包指定类型的 pkg 壁纸（逗号分隔，如 scene），其余计入类型跳过
+    #[arg(long = "only-type", value_name = "TYPES", value_delimiter = ',')]
+    pub only_type: Option<Vec<String>>,
+
+    /// 只保留指定类型的壁纸（逗号分隔，如 scene,video；忽略大小写），与 --ids 同时生效
+    #[arg(long = "type", value_name = "TYPES", value_delimiter = ',', conflicts_with = "find_dupes")]
+    pub type_filter: Option<Vec<String>>,
+
+    /// 只保留带有任一指定标签的壁纸（逗号分隔，忽略大小写），与 --ids 同时生效
+    #[arg(long = "tag", value_name = "TAGS", value_delimiter = ',', conflicts_with = "find_dupes")]
+    pub tag_filter: Option<Vec<String>>,
+
     /// 预览模式（列出壁纸，不执行复制）
     #[arg(short = 'p', long)]
     pub preview: bool,
@@ -88,6 +136,14 @@ pub struct WallpaperArgs {
     /// 详细预览（显示完整元数据）
     #[arg(short = 'v', long)]
     pub verbose: bool,
+
+    /// 按内容指纹报告疑似重复的壁纸（只报告，不复制也不删除）
+    #[arg(long, conflicts_with = "preview")]
+    pub find_dupes: bool,
+
+    /// 把扫描到的壁纸元数据与统计导出为 JSON 清单（不执行复制）
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["preview", "find_dupes"])]
+    pub export: Option<PathBuf>,
 }
 
 // ============================================================================
@@ -111,6 +167,34 @@ pub struct PkgArgs {
     /// 详细预览
     #[arg(short = 'v', long)]
     pub verbose: bool,
+
+    /// 失败熔断：连续或累计失败达到 N 次时提前中止（0 表示不启用）
+    #[arg(long, value_name = "N")]
+    pub fail_threshold: Option<usize>,
+
+    /// 同名条目处理策略：overwrite 覆盖 / rename 加序号保存
+    #[arg(long, value_name = "MODE", default_value = "overwrite", value_parser = ["overwrite", "rename"])]
+    pub on_duplicate: String,
+
+    /// 并行解包的 PKG 数（默认使用全部 CPU 核心，1 为逐个顺序解包）
+    #[arg(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// 只解包 / 列出匹配 glob 的条目（大小写不敏感，可重复，如 '*.tex'）
+    #[arg(long, value_name = "GLOB")]
+    pub filter: Option<Vec<String>>,
+
+    /// 跳过匹配 glob 的条目（大小写不敏感，可重复）
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Option<Vec<String>>,
+
+    /// 只提取单个条目（完整条目名或 glob，如 materials/foo.tex），不解包其余内容
+    #[arg(long, value_name = "ENTRY", conflicts_with = "preview")]
+    pub extract: Option<String>,
+
+    /// 批量解包后从 Workshop 场景目录复制非 PKG 资源（project.json、音频等），不覆盖解包内容
+    #[arg(long, conflicts_with_all = ["preview", "extract"])]
+    pub copy_resources: bool,
 }
 
 // ============================================================================
@@ -134,11 +218,83 @@ pub struct TexArgs {
     /// 详细预览
     #[arg(short = 'v', long)]
     pub verbose: bool,
+
+    /// 以 JSON 输出 .tex 的格式信息（文件头 format 值、格式类别、推荐输出等），只解析不解码，等同 --preview --json
+    #[arg(long, conflicts_with_all = ["preview", "open", "output", "in_place"])]
+    pub info: bool,
+
+    /// 转换单个 .tex 到临时目录后用系统默认程序打开
+    #[arg(long, conflicts_with_all = ["preview", "output", "in_place"])]
+    pub open: bool,
+
+    /// 严格校验内嵌图片（完整解码，无法校验或损坏时报告失败）
+    #[arg(long)]
+    pub strict: bool,
+
+    /// LZ4 按声明大小解压失败时直接报告失败，不尝试其他解压策略
+    #[arg(long = "strict-lz4")]
+    pub strict_lz4: bool,
+
+    /// 就地转换：输出写到每个 .tex 所在目录（同名不同扩展）
+    #[arg(long = "in-place", conflicts_with = "output")]
+    pub in_place: bool,
+
+    /// 转换成功后删除原 .tex 文件（需配合 --in-place）
+    #[arg(long = "remove-tex", requires = "in_place")]
+    pub remove_tex: bool,
+
+    /// 失败熔断：连续或累计失败达到 N 次时提前中止（0 表示不启用）
+    #[arg(long, value_name = "N")]
+    pub fail_threshold: Option<usize>,
+
+    /// 生成缩略图到输出图片旁的 thumbs/ 目录，最长边缩放到 SIZE 像素（0 表示不生成）
+    #[arg(long, value_name = "SIZE")]
+    pub thumbnail: Option<u32>,
+
+    /// 并行转换时同时驻留的解码缓冲不超过 MB 兆字节（0 表示不限制）
+    #[arg(long, value_name = "MB")]
+    pub max_memory: Option<u64>,
+
+    /// 并行转换的线程数（默认使用全部 CPU 核心，1 为逐个顺序转换）
+    #[arg(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// 多个 .tex 映射到同一输出路径时的处理：rename 追加序号，error 报告失败
+    #[arg(long, value_name = "POLICY", value_parser = ["rename", "error"])]
+    pub on_conflict: Option<String>,
+
+    /// 只转换输出尚不存在的 TEX（补齐被删除的图片），已有输出的跳过
+    #[arg(long = "missing-only")]
+    pub missing_only: bool,
+
+    /// 导出的 mipmap 层级：first（默认）、largest、all（每层写为 name_mipN）或层级序号
+    #[arg(long, value_name = "LEVEL")]
+    pub mipmap: Option<String>,
+
+    /// 将多帧 TEX（动画精灵 / 多图像）合成为单个动图：gif 或 apng
+    #[arg(long, value_name = "FORMAT", value_parser = ["gif", "apng"])]
+    pub animate: Option<String>,
+
+    /// 动图帧率（默认 24，需配合 --animate）
+    #[arg(long, value_name = "N", requires = "animate")]
+    pub fps: Option<u16>,
+
+    /// 纹理解码后的输出格式：png（默认）、jpeg、webp 或 tiff（含透明像素的纹理不会写为 jpeg）
+    #[arg(long, value_name = "FORMAT", value_parser = ["png", "jpeg", "jpg", "webp", "tiff"])]
+    pub format: Option<String>,
+
+    /// 上下翻转输出图片（纹理上下颠倒时使用；内嵌图片会重新编码，视频不受影响）
+    #[arg(long)]
+    pub flip: bool,
+
+    /// JPEG 质量 1-100（默认 85，需配合 --format）
+    #[arg(long, value_name = "N", requires = "format", value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub quality: Option<u8>,
 }
 
 // ============================================================================
 /// Auto 模式参数
-#[derive(Args, Debug, Default)]
+#[derive(Args, Debug, Default, Clone)]
 pub struct AutoArgs {
     /// 壁纸源目录
     #[arg(short = 's', long, value_name = "PATH")]
@@ -168,10 +324,46 @@ pub struct AutoArgs {
     #[arg(long = "no-tex")]
     pub no_tex: bool,
 
+    /// 严格校验 TEX 内嵌图片
+    #[arg(long)]
+    pub strict: bool,
+
+    /// 多个 TEX 映射到同一输出路径时的处理：rename 追加序号，error 报告失败
+    #[arg(long, value_name = "POLICY", value_parser = ["rename", "error"])]
+    pub on_conflict: Option<String>,
+
+    /// 失败熔断：连续或累计失败达到 N 次时提前中止（0 表示不启用）
+    #[arg(long, value_name = "N")]
+    pub fail_threshold: Option<usize>,
+
+    /// 生成缩略图到输出图片旁的 thumbs/ 目录，最长边缩放到 SIZE 像素（0 表示不生成）
+    #[arg(long, value_name = "SIZE")]
+    pub thumbnail: Option<u32>,
+
+    /// 并行转换时同时驻留的解码缓冲不超过 MB 兆字节（0 表示不限制）
+    #[arg(long, value_name = "MB")]
+    pub max_memory: Option<u64>,
+
+    /// 某阶段失败后继续执行后续阶段，最后汇总错误并以非零状态退出
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// 首个 PKG / TEX 失败即中止（默认行为，覆盖配置中的 fail_fast = false）
+    #[arg(long, conflicts_with = "keep_going")]
+    pub fail_fast: bool,
+
+    /// 单个 PKG / TEX 失败只记录失败文件并继续处理其余文件
+    #[arg(long = "no-fail-fast", conflicts_with = "fail_fast")]
+    pub no_fail_fast: bool,
+
     /// 保留 PKG 临时目录
     #[arg(long = "no-clean-temp")]
     pub no_clean_temp: bool,
 
+    /// 清理前把 PKG 临时目录中的 PKG 移动到该目录归档（按壁纸 ID 分子目录）
+    #[arg(long = "archive-pkg", value_name = "DIR")]
+    pub archive_pkg: Option<PathBuf>,
+
     /// 保留解包中间产物
     #[arg(long = "no-clean-unpacked")]
     pub no_clean_unpacked: bool,
@@ -180,10 +372,38 @@ pub struct AutoArgs {
     #[arg(short = 'I', long)]
     pub incremental: bool,
 
-    /// 只处理指定壁纸 ID（逗号分隔）
+    /// 严格增量：已记录但输出目录已被删除的壁纸重新处理（需配合 -I）
+    #[arg(long, requires = "incremental")]
+    pub strict_incremental: bool,
+
+    /// 只处理指定壁纸 ID（逗号分隔，支持 31* 通配与 100-200 数字范围）
     #[arg(short = 'i', long, value_name = "IDS", value_delimiter = ',')]
     pub ids: Option<Vec<String>>,
 
+    /// 重新处理指定壁纸 ID（逗号分隔）：先移除处理记录，不受增量跳过影响
+    #[arg(long, value_name = "IDS", value_delimiter = ',')]
+    pub reprocess: Option<Vec<String>>,
+
+    /// 从文件读取壁纸 ID（每行一个，忽略空行和 # 注释），与 --ids 合并
+    #[arg(long = "ids-file", value_name = "FILE")]
+    pub ids_file: Option<PathBuf>,
+
+    /// 允许的最高内容分级（超过的壁纸将被过滤）
+    #[arg(long, value_name = "RATING", value_parser = ["everyone", "questionable", "mature"])]
+    pub max_rating: Option<String>,
+
+    /// 只解包指定类型的 pkg 壁纸（逗号分隔，如 scene），其余计入类型跳过
+    #[arg(long = "only-type", value_name = "TYPES", value_delimiter = ',')]
+    pub only_type: Option<Vec<String>>,
+
+    /// 只处理指定类型的壁纸（逗号分隔，如 scene,video；忽略大小写），与 --ids 同时生效
+    #[arg(long = "type", value_name = "TYPES", value_delimiter = ',')]
+    pub type_filter: Option<Vec<String>>,
+
+    /// 只处理带有任一指定标签的壁纸（逗号分隔，忽略大小写），与 --ids 同时生效
+    #[arg(long = "tag", value_name = "TAGS", value_delimiter = ',')]
+    pub tag_filter: Option<Vec<String>>,
+
     /// 仅显示计划执行的操作（不实际执行）
     #[arg(short = 'n', long)]
     pub dry_run: bool,
@@ -191,6 +411,14 @@ pub struct AutoArgs {
     /// 精简输出模式（只显示关键信息）
     #[arg(short = 'q', long)]
     pub quiet: bool,
+
+    /// 以 NDJSON 流输出：每处理完一个壁纸 / PKG / TEX 输出一行 JSON，最后输出汇总行
+    #[arg(long, conflicts_with = "dry_run")]
+    pub ndjson: bool,
+
+    /// 处理完成后持续监听壁纸源目录，新增或修改的壁纸自动增量处理（Ctrl+C 退出）
+    #[arg(long, conflicts_with_all = ["dry_run", "ndjson"])]
+    pub watch: bool,
 }
 
 // ============================================================================
@@ -223,9 +451,12 @@ pub enum ConfigCommand {
         key: String,
         /// 配置项值
         value: String,
+        /// 修改前备份 config.toml
+        #[arg(long)]
+        backup: bool,
     },
     
-    /// 重置为默认配置
+    /// 重置为默认配置（会先备份当前配置）
     Reset {
         /// 跳过确认
         #[arg(long, short = 'y')]
@@ -234,6 +465,9 @@ pub enum ConfigCommand {
     
     /// 用 $EDITOR 打开配置文件
     Edit,
+
+    /// 检查配置中的路径是否可用
+    Validate,
 }
 
 // ============================================================================
@@ -254,7 +488,106 @@ pub struct StatusArgs {
     #[arg(long)]
     pub clear: bool,
 
+    /// 移除指定壁纸的处理记录（逗号分隔），下次增量运行时重新处理
+    #[arg(long, value_name = "IDS", value_delimiter = ',', conflicts_with = "clear")]
+    pub reprocess: Option<Vec<String>>,
+
+    /// 移除 workshop 中已不存在（取消订阅）的壁纸的处理记录
+    #[arg(long, conflicts_with_all = ["clear", "reprocess"])]
+    pub prune: bool,
+
+    /// 只列出 --prune 将移除的记录，不修改 state
+    #[arg(short = 'n', long, requires = "prune")]
+    pub dry_run: bool,
+
     /// 跳过确认（与 --clear 配合）
     #[arg(long, short = 'y')]
     pub yes: bool,
 }
+
+// ============================================================================
+// Deps 模式参数
+// ============================================================================
+
+#[derive(Args, Debug)]
+pub struct DepsArgs {
+    /// 解包后的壁纸目录（包含 scene.json）
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// 以 Graphviz DOT 格式输出
+    #[arg(long)]
+    pub dot: bool,
+}
+
+// ============================================================================
+// Archive 模式参数
+// ============================================================================
+
+#[derive(Args, Debug)]
+pub struct ArchiveArgs {
+    /// 转换输出目录（默认从配置读取）
+    #[arg(value_name = "PATH")]
+    pub path: Option<PathBuf>,
+
+    /// zip 输出目录（默认为输入目录的上级目录）
+    #[arg(short = 'o', long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// 每个壁纸单独打包为 <id>.zip
+    #[arg(long)]
+    pub per_wallpaper: bool,
+
+    /// 单独打包时文件名附带壁纸标题（<id> - <title>.zip）
+    #[arg(long, requires = "per_wallpaper")]
+    pub with_title: bool,
+}
+
+// ============================================================================
+// Summary 模式参数
+// ============================================================================
+
+#[derive(Args, Debug)]
+pub struct SummaryArgs {
+    /// 壁纸源目录（默认从配置读取）
+    #[arg(value_name = "PATH")]
+    pub path: Option<PathBuf>,
+}
+
+// ============================================================================
+// Clean 模式参数
+// ============================================================================
+
+#[derive(Args, Debug)]
+pub struct CleanArgs {
+    /// 清理 PKG 临时目录（pkg_temp_path）
+    #[arg(long)]
+    pub pkg_temp: bool,
+
+    /// 清理解包目录中的中间产物（保留各壁纸的 tex_converted/）
+    #[arg(long)]
+    pub unpacked: bool,
+
+    /// 清理原始壁纸输出目录（raw_output_path）
+    #[arg(long)]
+    pub raw: bool,
+
+    /// 清理以上全部目录，包括 tex_converted/
+    #[arg(long)]
+    pub all: bool,
+
+    /// 跳过确认
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+// ============================================================================
+// Completions 模式参数
+// ============================================================================
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// 目标 shell（bash / zsh / fish / powershell / elvish）
+    #[arg(value_name = "SHELL")]
+    pub shell: clap_complete::Shell,
+}
@@ -0,0 +1,135 @@
+//! Archive 模式处理器（打包转换结果）
+
+use super::super::args::ArchiveArgs;
+use super::super::output as out;
+use lianpkg::api::native::{self, paper};
+use std::path::PathBuf;
+
+/// 执行 archive 命令
+pub fn run(args: &ArchiveArgs, config_path: Option<PathBuf>) -> Result<(), String> {
+    // 加载配置
+    out::debug_api_enter(
+        "native",
+        "init_config",
+        &format!("config_path={:?}", config_path),
+    );
+    let use_exe_dir = config_path.is_none();
+    let init_result = native::init_config(native::InitConfigInput {
+        config_dir: config_path.map(|p| p.parent().unwrap_or(&p).to_path_buf()),
+        use_exe_dir,
+    });
+    out::debug_api_return(&format!(
+        "config_path={}",
+        init_result.config_path.display()
+    ));
+
+    out::debug_api_enter(
+        "native",
+        "load_config",
+        &format!("path={}", init_result.config_path.display()),
+    );
+    let config_result = native::load_config(native::LoadConfigInput {
+        config_path: init_result.config_path.clone(),
+    });
+    out::debug_api_return(&format!("loaded={}", config_result.config.is_some()));
+
+    let config = config_result.config.ok_or("Failed to load config")?;
+
+    // 未配置独立转换目录时，转换结果位于 Pkg_Unpacked/<id>/tex_converted
+    let input_path = args.path.clone().unwrap_or_else(|| {
+        config
+            .converted_output_path
+            .clone()
+            .unwrap_or_else(|| config.unpacked_output_path.clone())
+    });
+
+    if !input_path.is_dir() {
+        return Err(format!(
+            "Input directory does not exist: {}",
+            input_path.display()
+        ));
+    }
+
+    let output_path = args.output.clone().unwrap_or_else(|| {
+        input_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| input_path.clone())
+    });
+
+    out::title("Archive");
+    out::path_info("Input", &input_path);
+    out::path_info("Output", &output_path);
+    out::info(if args.per_wallpaper {
+        "Mode: one zip per wallpaper"
+    } else {
+        "Mode: single zip"
+    });
+    out::blank();
+
+    out::debug_api_enter(
+        "paper",
+        "archive_converted",
+        &format!(
+            "input={}, per_wallpaper={}",
+            input_path.display(),
+            args.per_wallpaper
+        ),
+    );
+    let result = paper::archive_converted(paper::ArchiveConvertedInput {
+        converted_path: input_path,
+        output_path,
+        per_wallpaper: args.per_wallpaper,
+        with_title: args.with_title,
+    });
+
+    if let Some(ref error) = result.error {
+        out::debug_api_error(error);
+        return Err(error.clone());
+    }
+    out::debug_api_return(&format!("archives={}", result.results.len()));
+
+    if out::is_json() {
+        out::json(&result);
+        return Ok(());
+    }
+
+    if result.results.is_empty() {
+        out::warning("No wallpaper directories found");
+        return Ok(());
+    }
+
+    for r in &result.results {
+        if r.success {
+            out::info(&format!(
+                "{} ({} files, {})",
+                r.zip_path.display(),
+                r.file_count,
+                out::format_size(r.zip_size)
+            ));
+        } else {
+            out::error(&format!(
+                "{}: {}",
+                r.source_path.display(),
+                r.error.as_deref().unwrap_or("unknown error")
+            ));
+        }
+    }
+
+    let failed = result.results.iter().filter(|r| !r.success).count();
+    let total_size: u64 = result.results.iter().map(|r| r.zip_size).sum();
+
+    out::subtitle("Results");
+    out::stat("Archives", result.results.len() - failed);
+    out::stat("Failed", failed);
+    out::stat("Total Size", out::format_size(total_size));
+    out::blank();
+
+    if failed > 0 {
+        out::warning(&format!("{} archives failed", failed));
+    } else {
+        out::success("Archive completed!");
+    }
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
 //! Auto 模式处理器（全自动流水线）
 //!
 //! 调用 api::pipeline 执行完整的 paper → pkg → tex 流程
-//! 支持 -d 调试追踪和 -q 精简输出
+//! 支持 -d 调试追踪、-q 精简输出和 --ndjson 流式输出，--watch 在完成后转入监听模式
 
 use super::super::args::AutoArgs;
-use super::super::logger;
+use super::super::logger::{self, outln};
 use super::super::output as out;
+use super::super::signal;
+use super::watch;
 use lianpkg::api::native::{self, paper, pipeline};
+use lianpkg::core::cfg as core_cfg;
+use lianpkg::core::paper as core_paper;
+use serde::Serialize;
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -14,6 +19,20 @@ use std::time::Instant;
 pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String> {
     let start_time = Instant::now();
 
+    // 合并 --ids 与 --ids-file
+    let mut args = merge_ids_file(args)?;
+    if args.ndjson && out::is_json() {
+        return Err("--ndjson cannot be combined with --json".to_string());
+    }
+    if args.watch && out::is_json() {
+        return Err("--watch cannot be combined with --json".to_string());
+    }
+    // 监听模式依赖 state.json 跳过未变化的壁纸
+    args.incremental |= args.watch;
+    // NDJSON / JSON 占用 stdout，其余输出按精简模式处理
+    args.quiet |= args.ndjson || out::is_json();
+    let args = &args;
+
     // 设置 quiet 模式（仅 auto 支持）
     logger::set_quiet(args.quiet);
 
@@ -59,12 +78,12 @@ pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String>
     if !args.quiet {
         run_dry_run_preview(&config, args, &init_result.state_path)?;
 
-        println!();
+        out::blank();
         if !out::confirm("Continue with the execution?") {
             out::info("Operation cancelled by user.");
             return Ok(());
         }
-        println!();
+        out::blank();
     }
 
     // ========== 阶段2: 磁盘空间预估 ==========
@@ -84,11 +103,26 @@ pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String>
             for id in ids {
                 out::info(&format!("  - {}", id));
             }
-            println!();
+            out::blank();
+        }
+
+        if let Some(ref ids) = args.reprocess {
+            out::info(&format!("Reprocessing: {}", ids.join(", ")));
+            out::blank();
+        }
+
+        if let Some(ref types) = args.type_filter {
+            out::info(&format!("Type filter: {}", types.join(", ")));
+        }
+        if let Some(ref tags) = args.tag_filter {
+            out::info(&format!("Tag filter: {}", tags.join(", ")));
+        }
+        if args.type_filter.is_some() || args.tag_filter.is_some() {
+            out::blank();
         }
 
         show_config(&config);
-        println!();
+        out::blank();
         out::subtitle("Executing Pipeline");
     }
 
@@ -97,34 +131,50 @@ pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String>
     let overrides = build_pipeline_overrides(args);
 
     // 定义进度回调
-    let progress_callback = |progress: pipeline::PipelineProgress| {
+    let progress_callback = pipeline::progress_callback(|progress| {
         if !logger::is_quiet() {
             out::progress(&progress.message, progress.progress.into(), 100);
         }
-    };
+    });
 
     // 定义 debug 日志回调
     let debug_callback = |event: pipeline::DebugLogEvent| {
         render_debug_event(&event);
     };
 
-    // 调用 pipeline API
+    // 定义 NDJSON 单项结果回调
+    let item_callback = |event: pipeline::PipelineItemEvent| {
+        print_ndjson_line(&event);
+    };
+
+    // 调用 pipeline API（期间第一次 Ctrl+C 只请求取消）
+    signal::set_cancellable(true);
     let result = pipeline::run_pipeline(pipeline::RunPipelineInput {
         config: config.clone(),
-        state_path: init_result.state_path,
+        state_path: init_result.state_path.clone(),
         wallpaper_ids: args.ids.clone(),
-        overrides: Some(overrides),
+        reprocess_ids: args.reprocess.clone(),
+        type_filter: args.type_filter.clone(),
+        tag_filter: args.tag_filter.clone(),
+        overrides: Some(overrides.clone()),
         progress_callback: if args.quiet {
             None
         } else {
-            Some(&progress_callback)
+            Some(progress_callback)
         },
         debug_logger: if logger::is_debug() {
             Some(&debug_callback)
         } else {
             None
         },
+        item_callback: if args.ndjson {
+            Some(&item_callback)
+        } else {
+            None
+        },
+        cancel: Some(signal::cancel_flag()),
     });
+    signal::set_cancellable(false);
 
     // ========== 计算耗时 ==========
     let elapsed_secs = start_time.elapsed().as_secs_f64();
@@ -132,18 +182,35 @@ pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String>
     // ========== 清理进度条 ==========
     if !args.quiet {
         out::clear_progress();
-        println!();
+        out::blank();
     }
 
     // ========== 检查结果 ==========
-    if !result.success {
+    // keep_going 模式下先输出完整报告，再按是否有错误决定退出码；监听模式不因首次运行失败而退出；
+    // 取消时同样输出已完成部分的报告
+    if !result.success
+        && !config.pipeline.keep_going
+        && !args.ndjson
+        && !args.watch
+        && !result.cancelled
+    {
         return Err(result
             .error
             .unwrap_or_else(|| "Pipeline failed".to_string()));
     }
 
     // ========== 输出结果 ==========
-    if args.quiet {
+    if out::is_json() {
+        out::json(&result);
+    } else if args.ndjson {
+        print_ndjson_line(&NdjsonSummary {
+            stage: pipeline::PipelineStage::Done,
+            success: result.success,
+            stats: &result.stats,
+            stage_errors: &result.stage_errors,
+            error: result.error.as_deref(),
+        });
+    } else if args.quiet {
         print_quiet_summary(&config, &result, elapsed_secs, &disk_info);
     } else {
         print_full_summary(&result, elapsed_secs);
@@ -152,9 +219,68 @@ pub fn run(args: &AutoArgs, config_path: Option<PathBuf>) -> Result<(), String>
     // 重置 quiet 模式
     logger::set_quiet(false);
 
+    if result.cancelled {
+        out::warning(&format!(
+            "Cancelled by user: {} wallpapers copied, {} PKG unpacked, {} TEX converted; progress saved to state.json (rerun with -I to resume)",
+            result.stats.wallpapers_processed,
+            result.stats.pkgs_unpacked,
+            result.stats.texs_converted
+        ));
+        return Ok(());
+    }
+
+    if args.watch {
+        if !result.success {
+            out::error(result.error.as_deref().unwrap_or("Pipeline failed"));
+        }
+        out::blank();
+        return watch::run(&config, args, &overrides, &init_result.state_path);
+    }
+
+    if !result.success {
+        return Err(result
+            .error
+            .unwrap_or_else(|| "Pipeline failed".to_string()));
+    }
+
     Ok(())
 }
 
+/// 读取 --ids-file 并与 --ids 合并（保持顺序、去重）
+fn merge_ids_file(args: &AutoArgs) -> Result<AutoArgs, String> {
+    let mut merged = args.clone();
+    let Some(ref path) = args.ids_file else {
+        return Ok(merged);
+    };
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read ids file {}: {}", path.display(), e))?;
+
+    let mut ids = args.ids.clone().unwrap_or_default();
+    for line in content.lines() {
+        let id = line.split('#').next().unwrap_or("").trim();
+        if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+
+    out::debug_verbose(
+        "IDs File",
+        &format!("{} ({} IDs)", path.display(), ids.len()),
+    );
+    merged.ids = Some(ids);
+    Ok(merged)
+}
+
+/// 增量模式下该壁纸是否会因已处理而被跳过（--reprocess 指定的除外）
+fn is_skipped_by_state(state: &core_cfg::StateData, args: &AutoArgs, wallpaper_id: &str) -> bool {
+    let reprocess = args
+        .reprocess
+        .as_ref()
+        .is_some_and(|ids| ids.iter().any(|id| id == wallpaper_id));
+    !reprocess && native::is_wallpaper_processed(state, wallpaper_id)
+}
+
 /// 应用 CLI 参数覆盖到配置
 fn apply_cli_overrides(config: &mut native::RuntimeConfig, args: &AutoArgs) {
     if let Some(ref p) = args.search {
@@ -175,17 +301,53 @@ fn apply_cli_overrides(config: &mut native::RuntimeConfig, args: &AutoArgs) {
     if args.no_raw {
         config.enable_raw_output = false;
     }
+    if let Some(rating) = parse_max_rating(args) {
+        config.max_content_rating = Some(rating);
+    }
+    if let Some(ref types) = args.only_type {
+        config.only_types = Some(types.clone());
+    }
     if args.no_clean_temp {
         config.clean_pkg_temp = false;
     }
     if args.no_clean_unpacked {
         config.clean_unpacked = false;
     }
+    if let Some(ref p) = args.archive_pkg {
+        config.archive_pkg_path = Some(p.clone());
+    }
     // -I 启用增量，无 -I 则禁用
     config.pipeline.incremental = args.incremental;
+    if args.strict_incremental {
+        config.pipeline.strict_incremental = true;
+    }
     if args.no_tex {
         config.pipeline.auto_convert_tex = false;
     }
+    if args.strict {
+        config.tex_strict = true;
+    }
+    if let Some(policy) = parse_on_conflict(args) {
+        config.tex_on_conflict = policy;
+    }
+    if let Some(threshold) = args.fail_threshold {
+        config.pipeline.fail_threshold = Some(threshold).filter(|&n| n > 0);
+    }
+    if let Some(size) = args.thumbnail {
+        config.pipeline.thumbnail_size = Some(size).filter(|&n| n > 0);
+    }
+    if let Some(mb) = args.max_memory {
+        config.pipeline.max_memory_mb = Some(mb).filter(|&n| n > 0);
+    }
+    if args.keep_going {
+        config.pipeline.keep_going = true;
+    }
+    if args.fail_fast {
+        config.pipeline.fail_fast = true;
+    }
+    if args.no_fail_fast {
+        config.pipeline.fail_fast = false;
+    }
 }
 
 /// 构建 pipeline 参数覆盖
@@ -197,6 +359,7 @@ fn build_pipeline_overrides(args: &AutoArgs) -> pipeline::PipelineOverrides {
         unpacked_output_path: args.unpacked_output.clone(),
         tex_output_path: args.tex_output.clone(),
         enable_raw: if args.no_raw { Some(false) } else { None },
+        max_rating: parse_max_rating(args),
         clean_pkg_temp: if args.no_clean_temp {
             Some(false)
         } else {
@@ -207,8 +370,52 @@ fn build_pipeline_overrides(args: &AutoArgs) -> pipeline::PipelineOverrides {
         } else {
             None
         },
+        archive_pkg_path: args.archive_pkg.clone(),
+        only_types: args.only_type.clone(),
         incremental: Some(args.incremental),
+        strict_incremental: if args.strict_incremental {
+            Some(true)
+        } else {
+            None
+        },
         auto_convert_tex: if args.no_tex { Some(false) } else { None },
+        tex_strict: if args.strict { Some(true) } else { None },
+        tex_on_conflict: parse_on_conflict(args),
+        fail_threshold: args.fail_threshold,
+        thumbnail_size: args.thumbnail,
+        max_memory_mb: args.max_memory,
+        keep_going: if args.keep_going { Some(true) } else { None },
+        fail_fast: match (args.fail_fast, args.no_fail_fast) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+    }
+}
+
+/// 解析 --max-rating 参数
+fn parse_max_rating(args: &AutoArgs) -> Option<core_paper::ContentRating> {
+    args.max_rating
+        .as_deref()
+        .and_then(core_paper::ContentRating::parse)
+}
+
+/// 解析 --on-conflict 参数
+fn parse_on_conflict(args: &AutoArgs) -> Option<native::ConflictPolicy> {
+    args.on_conflict
+        .as_deref()
+        .and_then(native::ConflictPolicy::parse)
+}
+
+/// preset 壁纸提示：说明 base 壁纸是否在本次处理结果中
+fn preset_hint(wp: &paper::WallpaperInfo, results: &[paper::CopyResult]) -> String {
+    let title = wp.title.as_deref().unwrap_or("(untitled)");
+    match wp.preset_base {
+        Some(ref base) if results.iter().any(|r| &r.wallpaper_id == base) => {
+            format!("{} → base {} (processed)", title, base)
+        }
+        Some(ref base) => format!("{} → base {} (not processed in this run)", title, base),
+        None => format!("{} → base unknown", title),
     }
 }
 
@@ -251,6 +458,21 @@ fn estimate_disk_usage(
         if config.enable_raw_output {
             out::stat_icon("🖼", "Raw Files", out::format_size(estimate.raw_size));
         }
+        out::stat_icon(
+            "🗂",
+            "Estimated Unpacked",
+            out::format_size(estimate.estimated_unpacked),
+        );
+        out::stat_icon(
+            "🎨",
+            "Estimated Converted",
+            format!(
+                "{} ({} TEX, {} passthrough)",
+                out::format_size(estimate.estimated_converted),
+                estimate.conversion.tex_count,
+                estimate.conversion.passthrough_count
+            ),
+        );
         out::stat_icon(
             "📈",
             "Estimated Peak",
@@ -280,7 +502,16 @@ fn estimate_disk_usage(
                 out::success("Disk space OK");
             }
         }
-        println!();
+
+        for hint in &estimate.cross_device {
+            out::warning(&format!(
+                "Cross-device {}: {} → {} (different filesystems, data will be fully copied)",
+                hint.stage,
+                hint.from.display(),
+                hint.to.display()
+            ));
+        }
+        out::blank();
     }
 
     Ok(DiskEstimate {
@@ -288,6 +519,26 @@ fn estimate_disk_usage(
     })
 }
 
+/// --ndjson 汇总行
+#[derive(Serialize)]
+struct NdjsonSummary<'a> {
+    stage: pipeline::PipelineStage,
+    success: bool,
+    stats: &'a pipeline::PipelineStats,
+    stage_errors: &'a [pipeline::PipelineStageError],
+    error: Option<&'a str>,
+}
+
+/// 输出一行 NDJSON 并立即刷新，便于管道下游实时消费
+fn print_ndjson_line<T: Serialize>(value: &T) {
+    use std::io::Write;
+    if let Ok(line) = serde_json::to_string(value) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+}
+
 /// -q 精简输出
 fn print_quiet_summary(
     config: &native::RuntimeConfig,
@@ -298,14 +549,14 @@ fn print_quiet_summary(
     let version = env!("CARGO_PKG_VERSION");
     let wallpaper_count = result.stats.wallpapers_processed;
 
-    println!(
+    outln!(
         "LianPkg v{} | {} wallpapers | ~{} peak",
         version,
         wallpaper_count,
         out::format_size(disk_info.estimated_peak)
     );
 
-    println!("Output: {}", config.unpacked_output_path.display());
+    outln!("Output: {}", config.unpacked_output_path.display());
 
     let pkg_count = result.stats.pkgs_unpacked;
     let tex_count = result.stats.texs_converted;
@@ -315,10 +566,33 @@ fn print_quiet_summary(
         .map(|r| r.stats.image_count)
         .unwrap_or(0);
 
-    println!(
-        "Done in {:.1}s | {} PKG → {} TEX → {} images",
-        elapsed_secs, pkg_count, tex_count, image_count
+    let rate = result
+        .stats
+        .tex_per_sec()
+        .map(|r| format!(" | {:.1} tex/s", r))
+        .unwrap_or_default();
+
+    outln!(
+        "Done in {:.1}s | {} PKG → {} TEX → {} images{}",
+        elapsed_secs,
+        pkg_count,
+        tex_count,
+        image_count,
+        rate
     );
+
+    if !result.stats.failed_files.is_empty() {
+        outln!("{} file(s) failed", result.stats.failed_files.len());
+        for path in &result.stats.failed_files {
+            outln!("  {}", path.display());
+        }
+    }
+    for e in &result.stage_errors {
+        match e.item {
+            Some(ref item) => outln!("[{}] {}: {}", stage_name(&e.stage), item, e.message),
+            None => outln!("[{}] {}", stage_name(&e.stage), e.message),
+        }
+    }
 }
 
 /// 完整输出
@@ -328,10 +602,50 @@ fn print_full_summary(result: &pipeline::RunPipelineOutput, elapsed_secs: f64) {
     out::subtitle("Wallpaper Extraction");
     out::stat("Processed", result.stats.wallpapers_processed);
     out::stat("Skipped (incremental)", result.stats.wallpapers_skipped);
+    if result.stats.wallpapers_stale > 0 {
+        out::stat(
+            "Output Missing (reprocessed)",
+            result.stats.wallpapers_stale,
+        );
+    }
+    if result.stats.wallpapers_resumed > 0 {
+        out::stat("Resumed (interrupted run)", result.stats.wallpapers_resumed);
+    }
+    out::stat("Filtered (rating)", result.stats.wallpapers_filtered);
+    if result.stats.wallpapers_type_skipped > 0 {
+        out::stat("Skipped (type)", result.stats.wallpapers_type_skipped);
+    }
+    if result.stats.wallpapers_preset > 0 {
+        out::stat("Skipped (preset)", result.stats.wallpapers_preset);
+    }
 
     if let Some(ref paper_res) = result.paper_result {
         out::stat("Raw Copied", paper_res.stats.raw_copied);
         out::stat("PKG Copied", paper_res.stats.pkg_copied);
+
+        for wp in &paper_res.filtered {
+            out::info(&format!(
+                "Filtered: {} {} [{}]",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                wp.content_rating.name()
+            ));
+        }
+        for wp in &paper_res.type_skipped {
+            out::info(&format!(
+                "Skipped (type): {} {} [{}]",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                wp.wallpaper_type.as_deref().unwrap_or("unknown")
+            ));
+        }
+        for wp in &paper_res.presets {
+            out::info(&format!(
+                "Preset: {} {}",
+                wp.wallpaper_id,
+                preset_hint(wp, &paper_res.results)
+            ));
+        }
     }
 
     if let Some(ref pkg_res) = result.pkg_result {
@@ -339,6 +653,9 @@ fn print_full_summary(result: &pipeline::RunPipelineOutput, elapsed_secs: f64) {
         out::stat("PKGs Unpacked", pkg_res.stats.pkg_success);
         out::stat("Files Extracted", pkg_res.stats.total_files);
         out::stat("TEX Files", pkg_res.stats.tex_files);
+        if pkg_res.aborted {
+            out::error(pkg_res.error.as_deref().unwrap_or("Aborted"));
+        }
     }
 
     if let Some(ref tex_res) = result.tex_result {
@@ -346,13 +663,160 @@ fn print_full_summary(result: &pipeline::RunPipelineOutput, elapsed_secs: f64) {
         out::stat("TEXs Converted", tex_res.stats.tex_success);
         out::stat("Images", tex_res.stats.image_count);
         out::stat("Videos", tex_res.stats.video_count);
+        if tex_res.stats.thumbnail_count + tex_res.stats.thumbnail_failed > 0 {
+            out::stat("Thumbnails", tex_res.stats.thumbnail_count);
+        }
+        if tex_res.stats.thumbnail_failed > 0 {
+            out::stat("Thumbnails Failed", tex_res.stats.thumbnail_failed);
+        }
+        if tex_res.aborted {
+            out::error(tex_res.error.as_deref().unwrap_or("Aborted"));
+        }
+
+        for group in &tex_res.groups {
+            let name = if group.group.is_empty() {
+                "(root)"
+            } else {
+                &group.group
+            };
+            let line = format!(
+                "{}: {} images, {} videos ({}/{} tex)",
+                name, group.image_count, group.video_count, group.tex_success, group.tex_total
+            );
+            if group.tex_failed > 0 {
+                out::warning(&format!("{}, {} failed", line, group.tex_failed));
+            } else {
+                out::info(&line);
+            }
+        }
+
+        if !tex_res.stats.format_counts.is_empty() {
+            out::subtitle("Format Distribution");
+            out::format_distribution(&tex_res.stats.format_counts);
+        }
+        super::tex::print_conflicts(&tex_res.conflicts);
+    }
+
+    if let Some(ref archive_res) = result.archive_result {
+        out::subtitle("PKG Archive");
+        out::stat("PKGs Archived", archive_res.archived);
+        if archive_res.failed > 0 {
+            out::stat("Archive Failed", archive_res.failed);
+            out::warning("PKG temp directory kept because some PKGs were not archived");
+        }
+    }
+
+    if let Some(ref record) = result.estimate_record {
+        out::subtitle("Estimate vs Actual");
+        out::stat("PKG Size", out::format_size(record.pkg_size));
+        out::stat(
+            "Unpacked",
+            format_estimate(
+                record.estimated_unpacked,
+                record.actual_unpacked,
+                record.unpack_ratio(),
+            ),
+        );
+        if record.estimated_converted > 0 {
+            out::stat(
+                "Converted",
+                format_estimate(
+                    record.estimated_converted,
+                    record.actual_converted,
+                    record.convert_ratio(),
+                ),
+            );
+        }
+    }
+
+    if result.stats.freed_bytes > 0 {
+        out::subtitle("Cleanup");
+        out::stat("Freed", out::format_size(result.stats.freed_bytes));
+    }
+
+    if !result.stats.failed_files.is_empty() {
+        out::subtitle("Failed Files");
+        out::stat("Count", result.stats.failed_files.len());
+        for path in &result.stats.failed_files {
+            out::warning(&path.display().to_string());
+        }
+    }
+
+    if !result.stage_errors.is_empty() {
+        out::subtitle("Stage Errors");
+        for e in &result.stage_errors {
+            let stage = stage_name(&e.stage);
+            match e.item {
+                Some(ref item) => out::warning(&format!("[{}] {}: {}", stage, item, e.message)),
+                None => out::error(&format!("[{}] {}", stage, e.message)),
+            }
+        }
     }
 
     out::subtitle("Performance");
     out::stat("Total Time", format!("{:.2}s", elapsed_secs));
+    let stats = &result.stats;
+    out::stat(
+        "Copy Time",
+        format!("{:.2}s", stats.copy_ms as f64 / 1000.0),
+    );
+    if stats.pkgs_unpacked > 0 {
+        out::stat(
+            "Unpack Time",
+            format!("{:.2}s", stats.unpack_ms as f64 / 1000.0),
+        );
+    }
+    if stats.texs_converted > 0 {
+        out::stat(
+            "Convert Time",
+            format!("{:.2}s", stats.convert_ms as f64 / 1000.0),
+        );
+    }
+    if let Some(ms) = stats.avg_ms_per_wallpaper() {
+        out::stat("Avg per Wallpaper", format!("{:.2}s", ms / 1000.0));
+    }
+    if let Some(rate) = stats.pkg_per_sec() {
+        out::stat("PKG Rate", format!("{:.1} pkg/s", rate));
+    }
+    if let Some(rate) = stats.tex_per_sec() {
+        out::stat("TEX Rate", format!("{:.1} tex/s", rate));
+    }
+
+    out::blank();
+    if result.success {
+        out::success("Auto mode completed successfully!");
+    } else {
+        out::error(&format!(
+            "Auto mode completed with {} error(s)",
+            result.stage_errors.len()
+        ));
+    }
+}
+
+/// 阶段显示名
+fn stage_name(stage: &pipeline::PipelineStage) -> &'static str {
+    match stage {
+        pipeline::PipelineStage::Init => "Init",
+        pipeline::PipelineStage::Scanning => "Scan",
+        pipeline::PipelineStage::Copying => "Copy",
+        pipeline::PipelineStage::Unpacking => "PKG Unpack",
+        pipeline::PipelineStage::Converting => "TEX Conversion",
+        pipeline::PipelineStage::Cleanup => "Cleanup",
+        pipeline::PipelineStage::Done => "Done",
+    }
+}
 
-    println!();
-    out::success("Auto mode completed successfully!");
+/// 格式化"预估 vs 实际"一行
+fn format_estimate(estimated: u64, actual: u64, ratio: Option<f64>) -> String {
+    let ratio = ratio
+        .map(|r| format!(" (actual ratio {:.2}x)", r))
+        .unwrap_or_default();
+    format!(
+        "{} est. / {} actual{}",
+        out::format_size(estimated),
+        out::format_size(actual),
+        ratio
+    )
 }
 
 /// 显示配置信息
@@ -365,12 +829,47 @@ fn show_config(config: &native::RuntimeConfig) {
     if let Some(ref p) = config.converted_output_path {
         out::path_info("TEX Output", p);
     }
+    if let Some(ref p) = config.archive_pkg_path {
+        out::path_info("PKG Archive", p);
+    }
 
     out::subtitle_icon("⚙", "Options");
     out::option_bool("Enable Raw", config.enable_raw_output);
+    out::stat_icon(
+        "🔞",
+        "Max Rating",
+        config
+            .max_content_rating
+            .map(|r| r.name())
+            .unwrap_or("(no filter)"),
+    );
+    if let Some(ref types) = config.only_types {
+        out::stat_icon("🎬", "Only Types", types.join(", "));
+    }
     out::option_bool("Auto Unpack PKG", config.pipeline.auto_unpack_pkg);
     out::option_bool("Auto Convert TEX", config.pipeline.auto_convert_tex);
+    out::option_bool("Strict TEX Images", config.tex_strict);
+    out::stat_icon("🗂", "On Conflict", config.tex_on_conflict.name());
     out::option_bool("Incremental", config.pipeline.incremental);
+    if config.pipeline.incremental {
+        out::option_bool("Strict Incremental", config.pipeline.strict_incremental);
+    }
+    out::stat_icon(
+        "🧯",
+        "Fail Threshold",
+        config
+            .pipeline
+            .effective_fail_threshold()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "(off)".to_string()),
+    );
+    if let Some(size) = config.pipeline.thumbnail_size {
+        out::stat_icon("🖼", "Thumbnail Size", format!("{}px", size));
+    }
+    if let Some(mb) = config.pipeline.max_memory_mb {
+        out::stat_icon("🧠", "TEX Memory Budget", format!("{} MB", mb));
+    }
+    out::option_bool("Keep Going", config.pipeline.keep_going);
     out::option_bool("Clean PKG Temp", config.clean_pkg_temp);
     out::option_bool("Clean Unpacked", config.clean_unpacked);
 }
@@ -383,10 +882,10 @@ fn run_dry_run(
 ) -> Result<(), String> {
     out::title("Auto Mode (Dry Run)");
     out::warning("This is a dry run - no actual operations will be performed");
-    println!();
+    out::blank();
 
     show_config(config);
-    println!();
+    out::blank();
 
     // 扫描壁纸
     out::subtitle_icon("🔍", "Wallpaper Scan");
@@ -397,6 +896,11 @@ fn run_dry_run(
     );
     let scan_result = paper::scan_wallpapers(paper::ScanWallpapersInput {
         workshop_path: config.workshop_path.clone(),
+        type_filter: args.type_filter.clone(),
+        tag_filter: args.tag_filter.clone(),
+        generate_thumbnails: None,
+        cache: None,
+        pkg_depth: config.pkg_depth,
     });
 
     if !scan_result.success {
@@ -411,6 +915,31 @@ fn run_dry_run(
     out::stat_icon("📦", "Total Wallpapers", scan_result.stats.total_count);
     out::stat_icon("📁", "PKG Wallpapers", scan_result.stats.pkg_count);
     out::stat_icon("🖼", "Raw Wallpapers", scan_result.stats.raw_count);
+    if args.type_filter.is_some() || args.tag_filter.is_some() {
+        out::stat_icon("🏷", "Excluded (type/tag)", scan_result.stats.excluded);
+    }
+    if config.max_content_rating.is_some() {
+        let filtered = scan_result
+            .wallpapers
+            .iter()
+            .filter(|w| !paper::rating_allowed(w.content_rating, config.max_content_rating))
+            .count();
+        out::stat_icon("🔞", "Filtered (rating)", filtered);
+    }
+    if config.only_types.is_some() {
+        let type_skipped = scan_result
+            .wallpapers
+            .iter()
+            .filter(|w| {
+                w.has_pkg
+                    && !paper::type_allowed(
+                        w.wallpaper_type.as_deref(),
+                        config.only_types.as_deref(),
+                    )
+            })
+            .count();
+        out::stat_icon("🎬", "Skipped (type)", type_skipped);
+    }
 
     // 增量处理统计
     if args.incremental {
@@ -418,15 +947,23 @@ fn run_dry_run(
             state_path: state_path.to_path_buf(),
         });
 
-        if let Some(state) = state_result.state {
+        if let Some(mut state) = state_result.state {
             let processed_count = state.processed_wallpapers.len();
+            let stale = if config.pipeline.strict_incremental {
+                native::remove_stale_wallpapers(&mut state, config)
+            } else {
+                vec![]
+            };
             let to_process = scan_result
                 .wallpapers
                 .iter()
-                .filter(|w| !native::is_wallpaper_processed(&state, &w.wallpaper_id))
+                .filter(|w| !is_skipped_by_state(&state, args, &w.wallpaper_id))
                 .count();
 
             out::stat("Already Processed", processed_count);
+            if config.pipeline.strict_incremental {
+                out::stat("Output Missing", stale.len());
+            }
             out::stat("To Be Processed", to_process);
         }
     }
@@ -435,22 +972,37 @@ fn run_dry_run(
     if let Some(ref ids) = args.ids {
         out::subtitle("Selected Wallpapers");
         for id in ids {
-            let found = scan_result.wallpapers.iter().any(|w| &w.wallpaper_id == id);
-            if found {
+            let matched = scan_result
+                .wallpapers
+                .iter()
+                .filter(|w| paper::id_pattern_matches(&w.wallpaper_id, id))
+                .count();
+            if matched == 0 {
+                out::warning(&format!("✗ {} not found", id));
+            } else if scan_result.wallpapers.iter().any(|w| &w.wallpaper_id == id) {
                 out::info(&format!("✓ {} found", id));
             } else {
-                out::warning(&format!("✗ {} not found", id));
+                out::info(&format!("✓ {} matched {} wallpapers", id, matched));
             }
         }
     }
 
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "dry_run": true,
+            "config": config,
+            "scan": scan_result.stats,
+        }));
+        return Ok(());
+    }
+
     // 磁盘预估
     estimate_disk_usage(config, false)?;
 
     // 执行计划
     show_execution_plan(config);
 
-    println!();
+    out::blank();
     out::success("Dry run completed. Run without --dry-run to execute.");
 
     Ok(())
@@ -464,10 +1016,10 @@ fn run_dry_run_preview(
 ) -> Result<(), String> {
     out::title("Auto Mode Preview");
     out::warning("Please review the configuration before execution");
-    println!();
+    out::blank();
 
     show_config(config);
-    println!();
+    out::blank();
 
     // 扫描壁纸
     out::subtitle_icon("🔍", "Wallpaper Scan");
@@ -478,6 +1030,11 @@ fn run_dry_run_preview(
     );
     let scan_result = paper::scan_wallpapers(paper::ScanWallpapersInput {
         workshop_path: config.workshop_path.clone(),
+        type_filter: args.type_filter.clone(),
+        tag_filter: args.tag_filter.clone(),
+        generate_thumbnails: None,
+        cache: None,
+        pkg_depth: config.pkg_depth,
     });
 
     if !scan_result.success {
@@ -492,6 +1049,31 @@ fn run_dry_run_preview(
     out::stat_icon("📦", "Total Wallpapers", scan_result.stats.total_count);
     out::stat_icon("📁", "PKG Wallpapers", scan_result.stats.pkg_count);
     out::stat_icon("🖼", "Raw Wallpapers", scan_result.stats.raw_count);
+    if args.type_filter.is_some() || args.tag_filter.is_some() {
+        out::stat_icon("🏷", "Excluded (type/tag)", scan_result.stats.excluded);
+    }
+    if config.max_content_rating.is_some() {
+        let filtered = scan_result
+            .wallpapers
+            .iter()
+            .filter(|w| !paper::rating_allowed(w.content_rating, config.max_content_rating))
+            .count();
+        out::stat_icon("🔞", "Filtered (rating)", filtered);
+    }
+    if config.only_types.is_some() {
+        let type_skipped = scan_result
+            .wallpapers
+            .iter()
+            .filter(|w| {
+                w.has_pkg
+                    && !paper::type_allowed(
+                        w.wallpaper_type.as_deref(),
+                        config.only_types.as_deref(),
+                    )
+            })
+            .count();
+        out::stat_icon("🎬", "Skipped (type)", type_skipped);
+    }
 
     // 增量处理统计
     if args.incremental {
@@ -499,15 +1081,23 @@ fn run_dry_run_preview(
             state_path: state_path.to_path_buf(),
         });
 
-        if let Some(state) = state_result.state {
+        if let Some(mut state) = state_result.state {
             let processed_count = state.processed_wallpapers.len();
+            let stale = if config.pipeline.strict_incremental {
+                native::remove_stale_wallpapers(&mut state, config)
+            } else {
+                vec![]
+            };
             let to_process = scan_result
                 .wallpapers
                 .iter()
-                .filter(|w| !native::is_wallpaper_processed(&state, &w.wallpaper_id))
+                .filter(|w| !is_skipped_by_state(&state, args, &w.wallpaper_id))
                 .count();
 
             out::stat("Already Processed", processed_count);
+            if config.pipeline.strict_incremental {
+                out::stat("Output Missing", stale.len());
+            }
             out::stat("To Be Processed", to_process);
         }
     }
@@ -516,11 +1106,17 @@ fn run_dry_run_preview(
     if let Some(ref ids) = args.ids {
         out::subtitle("Selected Wallpapers");
         for id in ids {
-            let found = scan_result.wallpapers.iter().any(|w| &w.wallpaper_id == id);
-            if found {
+            let matched = scan_result
+                .wallpapers
+                .iter()
+                .filter(|w| paper::id_pattern_matches(&w.wallpaper_id, id))
+                .count();
+            if matched == 0 {
+                out::warning(&format!("✗ {} not found", id));
+            } else if scan_result.wallpapers.iter().any(|w| &w.wallpaper_id == id) {
                 out::info(&format!("✓ {} found", id));
             } else {
-                out::warning(&format!("✗ {} not found", id));
+                out::info(&format!("✓ {} matched {} wallpapers", id, matched));
             }
         }
     }
@@ -580,6 +1176,11 @@ fn show_execution_plan(config: &native::RuntimeConfig) {
         step += 1;
     }
 
+    if let Some(ref p) = config.archive_pkg_path {
+        out::step(step, &format!("Move PKG files to archive {}", p.display()));
+        step += 1;
+    }
+
     if config.clean_pkg_temp {
         out::step(step, "Clean PKG temp directory");
         step += 1;
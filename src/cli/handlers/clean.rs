@@ -0,0 +1,169 @@
+//! Clean 模式处理器（清理临时与输出目录）
+
+use super::super::args::CleanArgs;
+use super::super::output as out;
+use lianpkg::api::native;
+use lianpkg::core::{cfg, disk};
+use std::path::{Path, PathBuf};
+
+/// 一个待清理的目录
+struct CleanTarget {
+    /// 显示名称
+    label: &'static str,
+    /// 目录路径
+    path: PathBuf,
+    /// 是否保留各壁纸的 tex_converted/
+    keep_converted: bool,
+}
+
+/// 执行 clean 命令
+pub fn run(args: &CleanArgs, config_path: Option<PathBuf>) -> Result<(), String> {
+    if !(args.pkg_temp || args.unpacked || args.raw || args.all) {
+        return Err("Nothing to clean: specify --pkg-temp, --unpacked, --raw or --all".to_string());
+    }
+
+    // 加载配置
+    out::debug_api_enter(
+        "native",
+        "init_config",
+        &format!("config_path={:?}", config_path),
+    );
+    let use_exe_dir = config_path.is_none();
+    let init_result = native::init_config(native::InitConfigInput {
+        config_dir: config_path.map(|p| p.parent().unwrap_or(&p).to_path_buf()),
+        use_exe_dir,
+    });
+    out::debug_api_return(&format!(
+        "config_path={}",
+        init_result.config_path.display()
+    ));
+
+    let config = native::load_config(native::LoadConfigInput {
+        config_path: init_result.config_path.clone(),
+    })
+    .config
+    .ok_or("Failed to load config")?;
+
+    let mut targets = Vec::new();
+    if args.pkg_temp || args.all {
+        targets.push(CleanTarget {
+            label: "PKG Temp",
+            path: config.pkg_temp_path.clone(),
+            keep_converted: false,
+        });
+    }
+    if args.unpacked || args.all {
+        targets.push(CleanTarget {
+            label: "Unpacked",
+            path: config.unpacked_output_path.clone(),
+            keep_converted: !args.all,
+        });
+    }
+    if args.raw || args.all {
+        targets.push(CleanTarget {
+            label: "Raw Output",
+            path: config.raw_output_path.clone(),
+            keep_converted: false,
+        });
+    }
+
+    // 安全检查：拒绝清理根目录或包含 workshop 的目录
+    for target in &targets {
+        check_safe_target(&target.path, &config.workshop_path)?;
+    }
+
+    // 只处理实际存在的目录
+    let targets: Vec<(CleanTarget, u64)> = targets
+        .into_iter()
+        .filter(|t| t.path.is_dir())
+        .map(|t| {
+            let size = disk::dir_size(disk::DirSizeInput {
+                path: t.path.clone(),
+            })
+            .size;
+            (t, size)
+        })
+        .collect();
+
+    if targets.is_empty() {
+        if out::is_json() {
+            out::json(&serde_json::json!({
+                "success": true,
+                "cleaned": [],
+                "freed_bytes": 0,
+            }));
+        } else {
+            out::info("Nothing to clean");
+        }
+        return Ok(());
+    }
+
+    out::title("LianPkg Clean");
+    for (target, size) in &targets {
+        out::path_info(target.label, &target.path);
+        out::stat("Size", out::format_size(*size));
+    }
+    if targets.iter().any(|(t, _)| t.keep_converted) {
+        out::info("tex_converted/ directories will be kept (use --all to remove them)");
+    }
+
+    if !args.yes && !out::confirm("Delete these directories?") {
+        return Err("Operation cancelled".to_string());
+    }
+
+    let mut freed = 0u64;
+    let mut cleaned = Vec::new();
+    for (target, before) in &targets {
+        out::debug_api_enter("clean", target.label, &target.path.display().to_string());
+        if target.keep_converted {
+            native::clean_unpacked_dir(&target.path);
+        } else {
+            cfg::clear_lianpkg(cfg::ClearInput {
+                dir_path: target.path.clone(),
+            })
+            .map_err(|e| format!("Failed to clean {}: {}", target.path.display(), e))?;
+        }
+        let after = disk::dir_size(disk::DirSizeInput {
+            path: target.path.clone(),
+        })
+        .size;
+        let target_freed = before.saturating_sub(after);
+        out::debug_api_return(&format!("freed={}", target_freed));
+
+        freed += target_freed;
+        cleaned.push(serde_json::json!({
+            "path": target.path,
+            "freed_bytes": target_freed,
+        }));
+    }
+
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "success": true,
+            "cleaned": cleaned,
+            "freed_bytes": freed,
+        }));
+        return Ok(());
+    }
+
+    out::success(&format!(
+        "Cleaned {} directories, freed {}",
+        cleaned.len(),
+        out::format_size(freed)
+    ));
+    Ok(())
+}
+
+/// 拒绝清理文件系统根目录，以及与 workshop 目录重合或包含它的目录
+fn check_safe_target(path: &Path, workshop_path: &Path) -> Result<(), String> {
+    if path.parent().is_none() || path.as_os_str().is_empty() {
+        return Err(format!("Refusing to clean '{}'", path.display()));
+    }
+    if workshop_path.starts_with(path) {
+        return Err(format!(
+            "Refusing to clean '{}': it contains the workshop directory",
+            path.display()
+        ));
+    }
+    Ok(())
+}
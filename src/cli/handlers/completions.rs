@@ -0,0 +1,44 @@
+//! Completions 模式处理器（shell 补全脚本）
+
+use super::super::args::{Cli, CompletionsArgs};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// 执行 completions 命令：把补全脚本写到 stdout
+pub fn run(args: &CompletionsArgs) -> Result<(), String> {
+    // 先生成到缓冲区：clap_complete 直接写 stdout 时遇到断开的管道会 panic
+    let mut script = Vec::new();
+    generate(args.shell, &mut script);
+
+    let mut stdout = std::io::stdout().lock();
+    match stdout.write_all(&script).and_then(|_| stdout.flush()) {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+            Err(format!("Failed to write completions: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 生成指定 shell 的补全脚本
+fn generate(shell: Shell, writer: &mut dyn Write) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, writer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_completions_for_each_shell() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut script = Vec::new();
+            generate(shell, &mut script);
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains("lianpkg"), "{:?}", shell);
+            assert!(script.contains("wallpaper"), "{:?}", shell);
+        }
+    }
+}
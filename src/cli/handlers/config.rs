@@ -1,9 +1,10 @@
 //! Config 模式处理器
 
 use super::super::args::{ConfigArgs, ConfigCommand};
+use super::super::logger::outln;
 use super::super::output as out;
 use lianpkg::api::native;
-use lianpkg::core::{cfg, path};
+use lianpkg::core::{cfg, disk, path};
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -24,9 +25,12 @@ pub fn run(args: &ConfigArgs, config_path: Option<PathBuf>) -> Result<(), String
         Some(ConfigCommand::Show) => show_config(&init_result.config_path),
         Some(ConfigCommand::Path) => show_path(&init_result.config_path, &init_result.state_path),
         Some(ConfigCommand::Get { key }) => get_config(&init_result.config_path, key),
-        Some(ConfigCommand::Set { key, value }) => set_config(&init_result.config_path, key, value),
+        Some(ConfigCommand::Set { key, value, backup }) => {
+            set_config(&init_result.config_path, key, value, *backup)
+        }
         Some(ConfigCommand::Reset { yes }) => reset_config(&init_result.config_path, *yes),
         Some(ConfigCommand::Edit) => edit_config(&init_result.config_path),
+        Some(ConfigCommand::Validate) => validate_config(&init_result.config_path),
         None => show_config(&init_result.config_path),
     }
 }
@@ -35,7 +39,7 @@ pub fn run(args: &ConfigArgs, config_path: Option<PathBuf>) -> Result<(), String
 fn show_config(config_path: &std::path::Path) -> Result<(), String> {
     out::title("Configuration");
     out::path_info("Config File", config_path);
-    println!();
+    out::blank();
 
     // 读取并显示配置
     let content = match cfg::read_config_toml(cfg::ReadConfigInput {
@@ -50,44 +54,268 @@ fn show_config(config_path: &std::path::Path) -> Result<(), String> {
         config_path: config_path.to_path_buf(),
     });
 
+    if out::is_json() {
+        return match load_result.config {
+            Some(config) => {
+                out::json(&config);
+                Ok(())
+            }
+            None => Err(format!(
+                "Failed to load config: {}",
+                load_result.error.unwrap_or_default()
+            )),
+        };
+    }
+
     if let Some(config) = load_result.config {
         out::subtitle("[wallpaper]");
-        out::stat("workshop_path", config.workshop_path.display());
-        out::stat("raw_output_path", config.raw_output_path.display());
+        let wallpaper_count = native::scan_wallpapers(native::ScanWallpapersInput {
+            workshop_path: config.workshop_path.clone(),
+            type_filter: None,
+            tag_filter: None,
+            generate_thumbnails: None,
+            cache: None,
+            pkg_depth: config.pkg_depth,
+        })
+        .stats
+        .total_count;
+        path_stat(
+            "workshop_path",
+            &config.workshop_path,
+            false,
+            Some(format!("({} wallpapers)", wallpaper_count)),
+        );
+        out::stat(
+            "app_id",
+            config.app_id.as_deref().unwrap_or(path::DEFAULT_APP_ID),
+        );
+        path_stat("raw_output_path", &config.raw_output_path, true, None);
         out::stat("enable_raw_output", config.enable_raw_output);
-        out::stat("pkg_temp_path", config.pkg_temp_path.display());
+        out::stat(
+            "max_content_rating",
+            config
+                .max_content_rating
+                .map(|r| r.name())
+                .unwrap_or("(no filter)"),
+        );
+        out::stat("pkg_depth", config.pkg_depth);
+        path_stat("pkg_temp_path", &config.pkg_temp_path, true, None);
 
         out::subtitle("[unpack]");
-        out::stat(
+        path_stat(
             "unpacked_output_path",
-            config.unpacked_output_path.display(),
+            &config.unpacked_output_path,
+            true,
+            None,
         );
         out::stat("clean_pkg_temp", config.clean_pkg_temp);
         out::stat("clean_unpacked", config.clean_unpacked);
-
-        out::subtitle("[tex]");
+        match config.archive_pkg_path {
+            Some(ref p) => path_stat("archive_pkg_path", p, true, None),
+            None => out::stat("archive_pkg_path", "(off)"),
+        }
         out::stat(
-            "converted_output_path",
+            "only_types",
             config
-                .converted_output_path
-                .map(|p| p.display().to_string())
-                .unwrap_or_else(|| "(auto)".to_string()),
+                .only_types
+                .as_ref()
+                .map(|t| t.join(", "))
+                .unwrap_or_else(|| "(no filter)".to_string()),
         );
 
+        out::subtitle("[tex]");
+        match config.converted_output_path {
+            Some(ref p) => path_stat("converted_output_path", p, true, None),
+            None => out::stat("converted_output_path", "(auto)"),
+        }
+        out::stat("strict", config.tex_strict);
+        out::stat("strict_lz4", config.tex_strict_lz4);
+        out::stat("on_conflict", config.tex_on_conflict.name());
+
         out::subtitle("[pipeline]");
         out::stat("incremental", config.pipeline.incremental);
+        out::stat("strict_incremental", config.pipeline.strict_incremental);
         out::stat("auto_unpack_pkg", config.pipeline.auto_unpack_pkg);
         out::stat("auto_convert_tex", config.pipeline.auto_convert_tex);
+        out::stat(
+            "fail_threshold",
+            config.pipeline.fail_threshold.unwrap_or(0),
+        );
+        out::stat(
+            "thumbnail_size",
+            config.pipeline.thumbnail_size.unwrap_or(0),
+        );
+        out::stat("max_memory_mb", config.pipeline.max_memory_mb.unwrap_or(0));
+        out::stat("keep_going", config.pipeline.keep_going);
+        out::stat("fail_fast", config.pipeline.fail_fast);
+        out::stat("checkpoint_interval", config.pipeline.checkpoint_interval);
     } else {
         // 直接显示原始内容
-        println!("{}", content);
+        outln!("{}", content);
+    }
+
+    Ok(())
+}
+
+/// 显示路径配置项及其状态（存在 / 缺失 / 不可写）
+fn path_stat(label: &str, path: &std::path::Path, output: bool, extra: Option<String>) {
+    let access = disk::check_access(disk::CheckAccessInput {
+        path: path.to_path_buf(),
+    });
+    let mut value = format!(
+        "{} {}",
+        path.display(),
+        out::path_status_badge(access.exists, access.writable, output)
+    );
+    if let Some(extra) = extra.filter(|_| access.exists) {
+        value = format!("{} {}", value, extra);
+    }
+    out::stat(label, value);
+}
+
+/// 检查配置：workshop_path 可读、输出路径可写（或可创建）、转换输出不在 PKG 临时目录内
+///
+/// 任一检查失败时返回错误（非零退出码）
+fn validate_config(config_path: &std::path::Path) -> Result<(), String> {
+    out::title("Validate Configuration");
+    out::path_info("Config File", config_path);
+    out::blank();
+
+    let load_result = native::load_config(native::LoadConfigInput {
+        config_path: config_path.to_path_buf(),
+    });
+    let config = match load_result.config {
+        Some(config) if load_result.success => config,
+        _ => {
+            return Err(format!(
+                "Failed to load config: {}",
+                load_result.error.unwrap_or_default()
+            ))
+        }
+    };
+    out::success("config.toml parsed");
+
+    let mut checks: Vec<(bool, String)> = Vec::new();
+    let mut check = |ok: bool, message: String| {
+        if ok {
+            out::success(&message);
+        } else {
+            out::error(&message);
+        }
+        checks.push((ok, message));
+    };
+
+    // 输入：workshop_path 必须存在且可读
+    let workshop = &config.workshop_path;
+    match std::fs::read_dir(workshop) {
+        Ok(_) => check(
+            true,
+            format!("workshop_path readable: {}", workshop.display()),
+        ),
+        Err(_) if !workshop.exists() => check(
+            false,
+            format!("workshop_path does not exist: {}", workshop.display()),
+        ),
+        Err(e) => check(
+            false,
+            format!("workshop_path not readable: {} ({})", workshop.display(), e),
+        ),
+    }
+
+    // 输出：已存在或可在最近的父目录下创建
+    let mut outputs = vec![
+        ("raw_output_path", config.raw_output_path.clone()),
+        ("pkg_temp_path", config.pkg_temp_path.clone()),
+        ("unpacked_output_path", config.unpacked_output_path.clone()),
+    ];
+    outputs.extend(
+        config
+            .converted_output_path
+            .clone()
+            .map(|p| ("converted_output_path", p)),
+    );
+    outputs.extend(
+        config
+            .archive_pkg_path
+            .clone()
+            .map(|p| ("archive_pkg_path", p)),
+    );
+    for (label, output) in outputs {
+        let access = disk::check_access(disk::CheckAccessInput {
+            path: output.clone(),
+        });
+        if access.exists && !access.is_dir {
+            check(
+                false,
+                format!("{} is not a directory: {}", label, output.display()),
+            );
+        } else if !access.writable {
+            let state = if access.exists {
+                "not writable"
+            } else {
+                "cannot be created"
+            };
+            check(false, format!("{} {}: {}", label, state, output.display()));
+        } else {
+            let state = if access.exists {
+                "writable"
+            } else {
+                "will be created"
+            };
+            check(true, format!("{} {}: {}", label, state, output.display()));
+        }
+    }
+
+    // 转换输出放在 PKG 临时目录内会在清理临时目录时被一并删除
+    if let Some(ref converted) = config.converted_output_path {
+        let inside = path_is_within(converted, &config.pkg_temp_path);
+        check(
+            !inside,
+            if inside {
+                format!(
+                    "converted_output_path is inside pkg_temp_path: {}",
+                    converted.display()
+                )
+            } else {
+                "converted_output_path is outside pkg_temp_path".to_string()
+            },
+        );
+    }
+
+    let failed = checks.iter().filter(|(ok, _)| !ok).count();
+    if out::is_json() {
+        let checks: Vec<_> = checks
+            .iter()
+            .map(|(ok, message)| serde_json::json!({ "ok": ok, "message": message }))
+            .collect();
+        out::json(&serde_json::json!({ "success": failed == 0, "checks": checks }));
     }
 
+    out::blank();
+    if failed > 0 {
+        return Err(format!("{} config check(s) failed", failed));
+    }
+    out::success("Config looks good");
     Ok(())
 }
 
+/// 判断 path 是否位于 base 之内（按绝对路径的组件比较，路径可以尚不存在）
+fn path_is_within(path: &std::path::Path, base: &std::path::Path) -> bool {
+    let absolute = |p: &std::path::Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    absolute(path).starts_with(absolute(base))
+}
+
 /// 显示配置文件路径
 fn show_path(config_path: &std::path::Path, state_path: &std::path::Path) -> Result<(), String> {
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "config_path": config_path,
+            "state_path": state_path,
+            "config_dir": config_path.parent().unwrap_or(config_path),
+        }));
+        return Ok(());
+    }
+
     out::title("Configuration Paths");
     out::path_info("Config File", config_path);
     out::path_info("State File", state_path);
@@ -99,7 +327,7 @@ fn show_path(config_path: &std::path::Path, state_path: &std::path::Path) -> Res
     // 默认路径
     out::subtitle("Default Paths");
     out::stat("Default Config Dir", path::default_config_dir().display());
-    out::stat("Default Workshop", path::default_workshop_path());
+    out::stat("Default Workshop", path::default_workshop_path(None));
     out::stat("Default Raw Output", path::default_raw_output_path());
     out::stat("Default PKG Temp", path::default_pkg_temp_path());
     out::stat("Default Unpacked", path::default_unpacked_output_path());
@@ -125,8 +353,12 @@ fn get_config(config_path: &std::path::Path, key: &str) -> Result<(), String> {
     let value = find_value(&doc, &parts);
 
     match value {
+        Some(v) if out::is_json() => {
+            out::json(v);
+            Ok(())
+        }
         Some(v) => {
-            println!("{}", format_toml_value(v));
+            outln!("{}", format_toml_value(v));
             Ok(())
         }
         None => Err(format!("Key '{}' not found", key)),
@@ -134,18 +366,45 @@ fn get_config(config_path: &std::path::Path, key: &str) -> Result<(), String> {
 }
 
 /// 设置配置项
-fn set_config(config_path: &std::path::Path, key: &str, value: &str) -> Result<(), String> {
-    match cfg::update_config_toml(cfg::UpdateConfigInput {
+fn set_config(
+    config_path: &std::path::Path,
+    key: &str,
+    value: &str,
+    backup: bool,
+) -> Result<(), String> {
+    let previous = cfg::read_config_toml(cfg::ReadConfigInput {
+        path: config_path.to_path_buf(),
+    })
+    .map_err(|e| format!("Failed to read config: {}", e))?
+    .content;
+
+    if backup {
+        backup_config(config_path)?;
+    }
+
+    cfg::update_config_toml(cfg::UpdateConfigInput {
         path: config_path.to_path_buf(),
         key: key.to_string(),
         value: value.to_string(),
-    }) {
-        Ok(_) => {
-            out::success(&format!("Set {} = {}", key, value));
-            Ok(())
-        }
-        Err(e) => Err(format!("Failed to update config: {}", e)),
+    })
+    .map_err(|e| format!("Failed to update config: {}", e))?;
+
+    // 使用与其它命令相同的解析路径校验，失败则回滚
+    let load_result = native::load_config(native::LoadConfigInput {
+        config_path: config_path.to_path_buf(),
+    });
+    if !load_result.success {
+        std::fs::write(config_path, previous)
+            .map_err(|e| format!("Failed to restore config: {}", e))?;
+        return Err(format!(
+            "Invalid value for {}: {} (config unchanged)",
+            key,
+            load_result.error.unwrap_or_default()
+        ));
     }
+
+    out::success(&format!("Set {} = {}", key, value));
+    Ok(())
 }
 
 /// 重置配置
@@ -157,6 +416,11 @@ fn reset_config(config_path: &std::path::Path, yes: bool) -> Result<(), String>
         }
     }
 
+    // 先备份，避免丢失用户的路径与注释
+    if config_path.exists() {
+        backup_config(config_path)?;
+    }
+
     // 删除现有配置
     let _ = cfg::delete_config_toml(cfg::DeleteConfigInput {
         path: config_path.to_path_buf(),
@@ -176,6 +440,16 @@ fn reset_config(config_path: &std::path::Path, yes: bool) -> Result<(), String>
     }
 }
 
+/// 备份配置文件并提示备份路径
+fn backup_config(config_path: &std::path::Path) -> Result<(), String> {
+    let output = cfg::backup_config(cfg::BackupConfigInput {
+        path: config_path.to_path_buf(),
+    })
+    .map_err(|e| format!("Failed to back up config: {}", e))?;
+    out::info(&format!("Backup saved to {}", output.backup_path.display()));
+    Ok(())
+}
+
 /// 用编辑器打开配置
 fn edit_config(config_path: &PathBuf) -> Result<(), String> {
     // 获取编辑器
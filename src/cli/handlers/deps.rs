@@ -0,0 +1,70 @@
+//! Deps 模式处理器（场景依赖关系）
+
+use super::super::args::DepsArgs;
+use super::super::logger::outln;
+use super::super::output as out;
+use lianpkg::core::paper;
+
+/// 执行 deps 命令
+pub fn run(args: &DepsArgs) -> Result<(), String> {
+    if !args.path.is_dir() {
+        return Err(format!("Directory does not exist: {}", args.path.display()));
+    }
+
+    out::debug_api_enter(
+        "paper",
+        "resolve_deps",
+        &format!("root={}", args.path.display()),
+    );
+    let result = paper::resolve_deps(paper::ResolveDepsInput {
+        root: args.path.clone(),
+    })
+    .map_err(|e| {
+        out::debug_api_error(&e.to_string());
+        e.to_string()
+    })?;
+    out::debug_api_return(&format!("missing={}", result.missing_count));
+
+    // DOT 输出只打印图本身，便于重定向给 graphviz
+    if args.dot {
+        outln!("{}", result.root.to_dot().trim_end());
+        return Ok(());
+    }
+
+    if out::is_json() {
+        out::json(&result);
+        return Ok(());
+    }
+
+    out::title("Scene Dependencies");
+    out::path_info("Root", &args.path);
+    out::blank();
+
+    print_node(&result.root, 0);
+    out::blank();
+
+    if result.missing_count > 0 {
+        out::warning(&format!(
+            "{} referenced files not found",
+            result.missing_count
+        ));
+    } else {
+        out::success("All references resolved");
+    }
+
+    Ok(())
+}
+
+/// 按缩进打印依赖树
+fn print_node(node: &paper::DepNode, depth: usize) {
+    outln!(
+        "  {}{:9} {} {}",
+        "  ".repeat(depth),
+        node.kind.name(),
+        node.path,
+        out::missing_badge(!node.exists)
+    );
+    for child in &node.children {
+        print_node(child, depth + 1);
+    }
+}
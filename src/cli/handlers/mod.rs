@@ -4,5 +4,11 @@ pub mod wallpaper;
 pub mod pkg;
 pub mod tex;
 pub mod auto;
+pub mod watch;
 pub mod config;
 pub mod status;
+pub mod deps;
+pub mod archive;
+pub mod summary;
+pub mod clean;
+pub mod completions;
@@ -1,11 +1,22 @@
 //! PKG 模式处理器
 
 use super::super::args::PkgArgs;
+use super::super::logger::outln;
 use super::super::output as out;
 use lianpkg::api::native::{self, pkg};
 use lianpkg::core::path;
+use lianpkg::core::pkg as core_pkg;
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// --json 目录预览中的单个 PKG
+#[derive(Serialize)]
+struct PkgPreviewEntry {
+    path: PathBuf,
+    #[serde(flatten)]
+    preview: pkg::PreviewPkgOutput,
+}
 
 /// 执行 pkg 命令
 pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
@@ -48,6 +59,11 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         .clone()
         .unwrap_or_else(|| config.unpacked_output_path.clone());
 
+    let duplicate_strategy = match args.on_duplicate.as_str() {
+        "rename" => core_pkg::DuplicateStrategy::Rename,
+        _ => core_pkg::DuplicateStrategy::Overwrite,
+    };
+
     // 判断输入类型
     if !input_path.exists() {
         return Err(format!(
@@ -58,27 +74,44 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
 
     // 预览模式
     if args.preview {
-        return run_preview(&input_path, args.verbose);
+        return run_preview(&input_path, args);
+    }
+
+    // 单条目提取
+    if let Some(entry) = &args.extract {
+        return run_extract(&input_path, entry, &output_path);
+    }
+
+    let is_single_pkg =
+        input_path.is_file() && input_path.extension().map(|e| e == "pkg").unwrap_or(false);
+    if args.copy_resources && is_single_pkg {
+        return Err("--copy-resources only applies to directory input".to_string());
     }
 
     // 执行解包
     out::title("PKG Unpack");
     out::path_info("Input", &input_path);
     out::path_info("Output", &output_path);
-    println!();
+    out::blank();
 
     // 确保输出目录存在
     let _ = path::ensure_dir_compat(&output_path);
 
     // 判断是单文件还是目录
-    if input_path.is_file() && input_path.extension().map(|e| e == "pkg").unwrap_or(false) {
+    if is_single_pkg {
         // 单文件解包
         out::debug_api_enter(
             "pkg",
             "unpack_single",
             &format!("input={}", input_path.display()),
         );
-        let result = pkg::unpack_single(input_path.clone(), output_path);
+        let result = pkg::unpack_single(
+            input_path.clone(),
+            output_path,
+            duplicate_strategy,
+            args.filter.as_deref(),
+            args.exclude.as_deref(),
+        );
 
         if !result.success {
             out::debug_api_error(result.error.as_deref().unwrap_or("Unknown error"));
@@ -90,13 +123,33 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
             result.files.len()
         ));
 
+        if out::is_json() {
+            out::json(&result);
+            return Ok(());
+        }
+
         out::subtitle("Results");
         out::stat("Scene", &result.scene_name);
         out::stat("Files Extracted", result.files.len());
 
         let tex_count = result.files.iter().filter(|f| f.is_tex).count();
         out::stat("TEX Files", tex_count);
-        println!();
+        if result.duplicate_count > 0 {
+            out::stat("Duplicate Entries", result.duplicate_count);
+        }
+        if result.skipped_count > 0 {
+            out::stat("Entries Skipped (filter)", result.skipped_count);
+        }
+        for name in &result.rejected_entries {
+            out::warning(&format!("Skipped unsafe entry name: {:?}", name));
+        }
+        for name in &result.undecoded_entries {
+            out::warning(&format!(
+                "Failed to decompress LZ4 entry, wrote raw data: {}",
+                name
+            ));
+        }
+        out::blank();
         out::success("PKG unpack completed!");
     } else {
         // 目录批量解包
@@ -108,6 +161,16 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         let result = pkg::unpack_all(pkg::UnpackAllInput {
             pkg_temp_path: input_path,
             unpacked_output_path: output_path,
+            fail_threshold: args
+                .fail_threshold
+                .or(config.pipeline.effective_fail_threshold()),
+            duplicate_strategy,
+            jobs: args.jobs,
+            include: args.filter.clone(),
+            exclude: args.exclude.clone(),
+            copy_scene_resources: args.copy_resources,
+            workshop_path: args.copy_resources.then(|| config.workshop_path.clone()),
+            cancel: None,
         });
 
         if !result.success && result.stats.pkg_success == 0 {
@@ -119,15 +182,40 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
             result.stats.pkg_processed, result.stats.pkg_success, result.stats.pkg_failed
         ));
 
+        if out::is_json() {
+            out::json(&result);
+            return Ok(());
+        }
+
         out::subtitle("Results");
         out::stat("PKGs Processed", result.stats.pkg_processed);
         out::stat("PKGs Success", result.stats.pkg_success);
         out::stat("PKGs Failed", result.stats.pkg_failed);
         out::stat("Total Files", result.stats.total_files);
         out::stat("TEX Files", result.stats.tex_files);
-        println!();
+        if result.stats.duplicate_entries > 0 {
+            out::stat("Duplicate Entries", result.stats.duplicate_entries);
+        }
+        if result.stats.skipped_entries > 0 {
+            out::stat("Entries Skipped (filter)", result.stats.skipped_entries);
+        }
+        if result.stats.rejected_entries > 0 {
+            out::stat(
+                "Entries Rejected (unsafe name)",
+                result.stats.rejected_entries,
+            );
+        }
+        if args.copy_resources {
+            out::stat("Scene Resources Copied", result.stats.resources_copied);
+            for error in result.results.iter().flat_map(|r| &r.resource_errors) {
+                out::warning(error);
+            }
+        }
+        out::blank();
 
-        if result.stats.pkg_failed > 0 {
+        if result.aborted {
+            out::error(result.error.as_deref().unwrap_or("Aborted"));
+        } else if result.stats.pkg_failed > 0 {
             out::warning(&format!(
                 "{} PKG files failed to unpack",
                 result.stats.pkg_failed
@@ -140,28 +228,124 @@ pub fn run(args: &PkgArgs, config_path: Option<PathBuf>) -> Result<(), String> {
 }
 
 /// 预览模式
-fn run_preview(input_path: &PathBuf, verbose: bool) -> Result<(), String> {
+fn run_preview(input_path: &PathBuf, args: &PkgArgs) -> Result<(), String> {
+    if out::is_json() {
+        return run_preview_json(input_path, args);
+    }
+
     out::title("PKG Preview");
     out::path_info("Input", input_path);
-    println!();
+    out::blank();
 
     if input_path.is_file() {
         // 单文件预览
-        preview_single_pkg(input_path, verbose)?;
+        preview_single_pkg(input_path, args.verbose, args)?;
     } else {
         // 目录预览
-        preview_directory(input_path, verbose)?;
+        preview_directory(input_path, args.verbose, args)?;
     }
 
     Ok(())
 }
 
+/// --json 预览：单文件输出预览结果，目录输出带路径的预览列表
+fn run_preview_json(input_path: &PathBuf, args: &PkgArgs) -> Result<(), String> {
+    let preview = |pkg_path: &PathBuf| {
+        pkg::preview_pkg(pkg::PreviewPkgInput {
+            pkg_path: pkg_path.clone(),
+            include: args.filter.clone(),
+            exclude: args.exclude.clone(),
+        })
+    };
+
+    if input_path.is_file() {
+        out::json(&preview(input_path));
+    } else {
+        let entries: Vec<PkgPreviewEntry> = find_pkg_files(input_path)?
+            .into_iter()
+            .map(|path| PkgPreviewEntry {
+                preview: preview(&path),
+                path,
+            })
+            .collect();
+        out::json(&entries);
+    }
+    Ok(())
+}
+
+/// 从单个 PKG 中提取指定条目
+fn run_extract(input_path: &Path, entry: &str, output_path: &Path) -> Result<(), String> {
+    if !input_path.is_file() {
+        return Err(format!(
+            "--extract requires a .pkg file, got: {}",
+            input_path.display()
+        ));
+    }
+
+    out::debug_api_enter(
+        "pkg",
+        "extract_entry",
+        &format!("input={}, entry={}", input_path.display(), entry),
+    );
+    let result = pkg::extract_entry(pkg::ExtractEntryInput {
+        pkg_path: input_path.to_path_buf(),
+        entry: entry.to_string(),
+        output_dir: output_path.to_path_buf(),
+    });
+
+    if out::is_json() {
+        out::json(&result);
+        return if result.success {
+            Ok(())
+        } else {
+            Err(result.error.unwrap_or_default())
+        };
+    }
+
+    if !result.success {
+        out::debug_api_error(result.error.as_deref().unwrap_or("Unknown error"));
+        let mut message = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        if !result.suggestions.is_empty() {
+            message.push_str(&format!(
+                "\n  Did you mean: {}",
+                result.suggestions.join(", ")
+            ));
+        }
+        return Err(message);
+    }
+    out::debug_api_return(&format!("files={}", result.files.len()));
+
+    for file in &result.files {
+        out::success(&format!(
+            "Extracted {} ({}) -> {}",
+            file.name,
+            out::format_size(file.size as u64),
+            file.output_path.display()
+        ));
+    }
+    Ok(())
+}
+
 /// 预览单个 PKG 文件
-fn preview_single_pkg(pkg_path: &std::path::Path, verbose: bool) -> Result<(), String> {
+fn preview_single_pkg(
+    pkg_path: &std::path::Path,
+    verbose: bool,
+    args: &PkgArgs,
+) -> Result<(), String> {
     let result = pkg::preview_pkg(pkg::PreviewPkgInput {
         pkg_path: pkg_path.to_path_buf(),
+        include: args.filter.clone(),
+        exclude: args.exclude.clone(),
     });
 
+    if result.unsupported {
+        out::warning(&format!(
+            "Unsupported PKG: {}",
+            result.error.as_deref().unwrap_or("unknown format")
+        ));
+        out::blank();
+        return Ok(());
+    }
     if !result.success {
         return Err(result
             .error
@@ -174,7 +358,26 @@ fn preview_single_pkg(pkg_path: &std::path::Path, verbose: bool) -> Result<(), S
         "Version: {} | Files: {} | TEX: {}",
         info.version, info.file_count, info.tex_count
     ));
-    println!();
+    out::info(&format!(
+        "Total Size: {} | TEX Size: {}",
+        out::format_size(info.total_size),
+        out::format_size(info.tex_size)
+    ));
+    if info.skipped_count > 0 {
+        out::info(&format!(
+            "Showing {} matching entries ({} filtered out)",
+            info.files.len(),
+            info.skipped_count
+        ));
+    }
+    if !info.duplicate_names.is_empty() {
+        out::warning(&format!(
+            "{} duplicate entry names (later entries overwrite earlier ones when unpacking): {}",
+            info.duplicate_names.len(),
+            info.duplicate_names.join(", ")
+        ));
+    }
+    out::blank();
 
     if verbose {
         out::subtitle("Files");
@@ -184,32 +387,46 @@ fn preview_single_pkg(pkg_path: &std::path::Path, verbose: bool) -> Result<(), S
             } else {
                 String::new()
             };
-            println!(
-                "    {:30} {:>10}  {}",
+            outln!(
+                "    {:30} {:>10}  {}{}{}",
                 file.name,
                 out::format_size(file.size as u64),
-                tex_mark
+                tex_mark,
+                out::compressed_badge(file.compressed),
+                out::duplicate_badge(info.duplicate_names.contains(&file.name))
             );
         }
     } else {
-        out::table_header(&[("Name", 30), ("Size", 12), ("Type", 8)]);
+        out::table_header(&[("Name", 30), ("Size", 12), ("Type", 12)]);
 
         for file in &info.files {
-            let type_str = if file.is_tex { "TEX" } else { "-" };
+            let marks: Vec<&str> = [
+                (file.is_tex, "TEX"),
+                (file.compressed, "LZ4"),
+                (info.duplicate_names.contains(&file.name), "DUP"),
+            ]
+            .into_iter()
+            .filter_map(|(on, mark)| on.then_some(mark))
+            .collect();
+            let type_str = if marks.is_empty() {
+                "-".to_string()
+            } else {
+                marks.join(" ")
+            };
             out::table_row(&[
                 (&file.name, 30),
                 (&out::format_size(file.size as u64), 12),
-                (type_str, 8),
+                (&type_str, 12),
             ]);
         }
     }
 
-    println!();
+    out::blank();
     Ok(())
 }
 
 /// 预览目录中的所有 PKG
-fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
+fn preview_directory(dir_path: &PathBuf, verbose: bool, args: &PkgArgs) -> Result<(), String> {
     let pkg_files = find_pkg_files(dir_path)?;
 
     if pkg_files.is_empty() {
@@ -218,44 +435,62 @@ fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
     }
 
     out::info(&format!("Found {} PKG files", pkg_files.len()));
-    println!();
+    out::blank();
 
     if verbose {
         // 详细模式：每个 PKG 单独显示
         for pkg_path in &pkg_files {
             out::subtitle(&pkg_path.file_name().unwrap_or_default().to_string_lossy());
-            if let Err(e) = preview_single_pkg(pkg_path, false) {
+            if let Err(e) = preview_single_pkg(pkg_path, false, args) {
                 out::error(&format!("Failed to preview: {}", e));
             }
         }
     } else {
         // 简洁模式：表格汇总
-        out::table_header(&[("File", 35), ("Version", 10), ("Files", 8), ("TEX", 6)]);
+        out::table_header(&[
+            ("File", 35),
+            ("Version", 12),
+            ("Files", 8),
+            ("TEX", 6),
+            ("Size", 12),
+        ]);
+        let mut total_size = 0u64;
 
         for pkg_path in &pkg_files {
             let result = pkg::preview_pkg(pkg::PreviewPkgInput {
                 pkg_path: pkg_path.clone(),
+                include: args.filter.clone(),
+                exclude: args.exclude.clone(),
             });
 
             if result.success {
                 if let Some(info) = result.pkg_info {
                     let filename = pkg_path.file_name().unwrap_or_default().to_string_lossy();
 
+                    total_size += info.total_size;
                     out::table_row(&[
                         (&filename, 35),
-                        (&info.version, 10),
-                        (&info.file_count.to_string(), 8),
+                        (&info.version, 12),
+                        (&info.files.len().to_string(), 8),
                         (&info.tex_count.to_string(), 6),
+                        (&out::format_size(info.total_size), 12),
                     ]);
                 }
             } else {
                 let filename = pkg_path.file_name().unwrap_or_default().to_string_lossy();
-                out::table_row(&[(&filename, 35), ("ERROR", 10), ("-", 8), ("-", 6)]);
+                let status = if result.unsupported {
+                    "UNSUPPORTED"
+                } else {
+                    "ERROR"
+                };
+                out::table_row(&[(&filename, 35), (status, 12), ("-", 8), ("-", 6), ("-", 12)]);
             }
         }
+        out::blank();
+        out::stat("Total Size", out::format_size(total_size));
     }
 
-    println!();
+    out::blank();
     Ok(())
 }
 
@@ -2,6 +2,7 @@
 
 use std::path::PathBuf;
 use super::super::args::StatusArgs;
+use super::super::logger::outln;
 use super::super::output as out;
 use lianpkg::api::native;
 use lianpkg::core::cfg;
@@ -24,6 +25,21 @@ pub fn run(args: &StatusArgs, config_path: Option<PathBuf>) -> Result<(), String
         return clear_status(&init_result.state_path, args.yes);
     }
 
+    // 移除指定壁纸的处理记录
+    if let Some(ref ids) = args.reprocess {
+        return reprocess_wallpapers(&init_result.state_path, ids);
+    }
+
+    // 清理已取消订阅的壁纸记录
+    if args.prune {
+        let config = native::load_config(native::LoadConfigInput {
+            config_path: init_result.config_path.clone(),
+        })
+        .config
+        .ok_or("Failed to load config")?;
+        return prune_status(&init_result.state_path, &config.workshop_path, args.dry_run);
+    }
+
     // 加载状态
     let state_result = native::load_state(native::LoadStateInput {
         state_path: init_result.state_path.clone(),
@@ -31,6 +47,11 @@ pub fn run(args: &StatusArgs, config_path: Option<PathBuf>) -> Result<(), String
 
     let state = state_result.state.unwrap_or_default();
 
+    if out::is_json() {
+        out::json(&state);
+        return Ok(());
+    }
+
     // 列出已处理壁纸
     if args.list {
         return list_processed(&state);
@@ -44,7 +65,7 @@ pub fn run(args: &StatusArgs, config_path: Option<PathBuf>) -> Result<(), String
 fn show_status(state: &cfg::StateData, state_path: &std::path::Path, full: bool) -> Result<(), String> {
     out::title("LianPkg Status");
     out::path_info("State File", state_path);
-    println!();
+    out::blank();
 
     // 上次运行时间
     if let Some(last_run) = state.last_run {
@@ -55,7 +76,7 @@ fn show_status(state: &cfg::StateData, state_path: &std::path::Path, full: bool)
     }
 
     out::stat("Total Runs", state.statistics.total_runs);
-    println!();
+    out::blank();
 
     // 处理统计
     out::subtitle("Processing Statistics");
@@ -89,7 +110,7 @@ fn show_status(state: &cfg::StateData, state_path: &std::path::Path, full: bool)
         out::subtitle("Recent Wallpapers (Last 5)");
         
         let mut recent: Vec<_> = state.processed_wallpapers.iter().collect();
-        recent.sort_by(|a, b| b.processed_at.cmp(&a.processed_at));
+        recent.sort_by_key(|w| std::cmp::Reverse(w.processed_at));
         
         for wp in recent.iter().take(5) {
             let title = wp.title.as_deref().unwrap_or("(untitled)");
@@ -101,11 +122,35 @@ fn show_status(state: &cfg::StateData, state_path: &std::path::Path, full: bool)
                 cfg::WallpaperProcessType::Skipped => "Skipped",
             };
             
-            println!("    {} {} [{}] @ {}", wp.wallpaper_id, title, type_str, time);
+            outln!("    {} {} [{}] @ {}", wp.wallpaper_id, title, type_str, time);
         }
     }
 
-    println!();
+    // 预估对账（平均实际膨胀系数）
+    if full && !state.estimate_history.is_empty() {
+        out::subtitle("Estimate Accuracy");
+        out::stat("Records", state.estimate_history.len());
+
+        let average = |ratios: Vec<f64>| {
+            if ratios.is_empty() {
+                "-".to_string()
+            } else {
+                format!("{:.2}x", ratios.iter().sum::<f64>() / ratios.len() as f64)
+            }
+        };
+        let unpack: Vec<f64> = state.estimate_history.iter()
+            .filter_map(|r| r.unpack_ratio())
+            .collect();
+        let convert: Vec<f64> = state.estimate_history.iter()
+            .filter(|r| r.estimated_converted > 0)
+            .filter_map(|r| r.convert_ratio())
+            .collect();
+
+        out::stat("Avg Unpack Ratio", average(unpack));
+        out::stat("Avg Convert Ratio", average(convert));
+    }
+
+    out::blank();
     Ok(())
 }
 
@@ -119,7 +164,7 @@ fn list_processed(state: &cfg::StateData) -> Result<(), String> {
     }
 
     out::info(&format!("Total: {} wallpapers", state.processed_wallpapers.len()));
-    println!();
+    out::blank();
 
     out::table_header(&[
         ("ID", 12),
@@ -129,7 +174,7 @@ fn list_processed(state: &cfg::StateData) -> Result<(), String> {
     ]);
 
     let mut sorted: Vec<_> = state.processed_wallpapers.iter().collect();
-    sorted.sort_by(|a, b| b.processed_at.cmp(&a.processed_at));
+    sorted.sort_by_key(|w| std::cmp::Reverse(w.processed_at));
 
     for wp in sorted {
         let title = wp.title.as_deref().unwrap_or("(untitled)");
@@ -149,7 +194,7 @@ fn list_processed(state: &cfg::StateData) -> Result<(), String> {
         ]);
     }
 
-    println!();
+    out::blank();
     Ok(())
 }
 
@@ -177,8 +222,134 @@ fn clear_status(state_path: &std::path::Path, yes: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// 移除指定壁纸的处理记录（累计统计保持不变）
+fn reprocess_wallpapers(state_path: &std::path::Path, ids: &[String]) -> Result<(), String> {
+    let state_result = native::load_state(native::LoadStateInput {
+        state_path: state_path.to_path_buf(),
+    });
+    let mut state = state_result
+        .state
+        .ok_or_else(|| state_result.error.unwrap_or_else(|| "Failed to load state".to_string()))?;
+
+    let mut removed = 0;
+    let mut not_found = Vec::new();
+    for id in ids {
+        match native::remove_processed_wallpaper(&mut state, id) {
+            0 => not_found.push(id.as_str()),
+            n => removed += n,
+        }
+    }
+
+    if removed > 0 {
+        let save_result = native::save_state(native::SaveStateInput {
+            state_path: state_path.to_path_buf(),
+            state,
+        });
+        if !save_result.success {
+            return Err(save_result.error.unwrap_or_else(|| "Failed to save state".to_string()));
+        }
+    }
+
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "success": true,
+            "removed": removed,
+            "not_found": not_found,
+        }));
+        return Ok(());
+    }
+
+    if !not_found.is_empty() {
+        out::warning(&format!("Not found in state: {}", not_found.join(", ")));
+    }
+    if removed == 0 {
+        out::info("No processing records removed");
+    } else {
+        out::success(&format!(
+            "Removed {} processing records; run `lianpkg auto -I` to reprocess",
+            removed
+        ));
+    }
+    Ok(())
+}
+
+/// 移除 workshop 中已不存在的壁纸的处理记录
+fn prune_status(
+    state_path: &std::path::Path,
+    workshop_path: &std::path::Path,
+    dry_run: bool,
+) -> Result<(), String> {
+    let state_result = native::load_state(native::LoadStateInput {
+        state_path: state_path.to_path_buf(),
+    });
+    let mut state = state_result
+        .state
+        .ok_or_else(|| state_result.error.unwrap_or_else(|| "Failed to load state".to_string()))?;
+
+    out::debug_api_enter(
+        "native",
+        "find_unsubscribed_wallpapers",
+        &format!("workshop={}", workshop_path.display()),
+    );
+    let pruned = native::find_unsubscribed_wallpapers(&state, workshop_path)?;
+    out::debug_api_return(&format!("missing={}", pruned.len()));
+
+    let mut removed = 0;
+    if !dry_run {
+        for id in &pruned {
+            removed += native::remove_processed_wallpaper(&mut state, id);
+        }
+        if removed > 0 {
+            let save_result = native::save_state(native::SaveStateInput {
+                state_path: state_path.to_path_buf(),
+                state,
+            });
+            if !save_result.success {
+                return Err(save_result.error.unwrap_or_else(|| "Failed to save state".to_string()));
+            }
+        }
+    }
+
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "success": true,
+            "dry_run": dry_run,
+            "pruned": pruned,
+            "removed_records": removed,
+        }));
+        return Ok(());
+    }
+
+    out::title(if dry_run { "Prune State (Dry Run)" } else { "Prune State" });
+    out::path_info("Workshop", workshop_path);
+    out::blank();
+
+    if pruned.is_empty() {
+        out::info("No stale records: every processed wallpaper is still in the workshop");
+        return Ok(());
+    }
+
+    for id in &pruned {
+        out::info(&format!("  - {}", id));
+    }
+    out::blank();
+    if dry_run {
+        out::warning(&format!(
+            "{} wallpapers would be pruned. Run without --dry-run to apply.",
+            pruned.len()
+        ));
+    } else {
+        out::success(&format!(
+            "Pruned {} wallpapers ({} records)",
+            pruned.len(),
+            removed
+        ));
+    }
+    Ok(())
+}
+
 /// 格式化时间戳
-fn format_timestamp(timestamp: u64) -> String {
+pub(super) fn format_timestamp(timestamp: u64) -> String {
     use std::time::{UNIX_EPOCH, Duration};
     
     let datetime = UNIX_EPOCH + Duration::from_secs(timestamp);
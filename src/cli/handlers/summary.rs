@@ -0,0 +1,254 @@
+//! Summary 模式处理器（状态与 workshop 扫描的聚合概览）
+
+use super::super::args::SummaryArgs;
+use super::super::output as out;
+use super::status::format_timestamp;
+use lianpkg::api::native::{self, paper};
+use lianpkg::core::disk;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 视为图片产物的扩展名
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "tga", "tif", "webp"];
+
+/// 执行 summary 命令
+pub fn run(args: &SummaryArgs, config_path: Option<PathBuf>) -> Result<(), String> {
+    // 加载配置
+    out::debug_api_enter(
+        "native",
+        "init_config",
+        &format!("config_path={:?}", config_path),
+    );
+    let use_exe_dir = config_path.is_none();
+    let init_result = native::init_config(native::InitConfigInput {
+        config_dir: config_path.map(|p| p.parent().unwrap_or(&p).to_path_buf()),
+        use_exe_dir,
+    });
+    out::debug_api_return(&format!(
+        "config_path={}",
+        init_result.config_path.display()
+    ));
+
+    let config = native::load_config(native::LoadConfigInput {
+        config_path: init_result.config_path.clone(),
+    })
+    .config
+    .ok_or("Failed to load config")?;
+
+    let state = native::load_state(native::LoadStateInput {
+        state_path: init_result.state_path.clone(),
+    })
+    .state
+    .unwrap_or_default();
+
+    let workshop_path = args
+        .path
+        .clone()
+        .unwrap_or_else(|| config.workshop_path.clone());
+
+    out::debug_api_enter(
+        "paper",
+        "scan_wallpapers",
+        &format!("path={}", workshop_path.display()),
+    );
+    let scan_result = paper::scan_wallpapers(paper::ScanWallpapersInput {
+        workshop_path: workshop_path.clone(),
+        type_filter: None,
+        tag_filter: None,
+        generate_thumbnails: None,
+        cache: None,
+        pkg_depth: config.pkg_depth,
+    });
+    if !scan_result.success {
+        out::debug_api_error(scan_result.error.as_deref().unwrap_or("Unknown error"));
+        return Err(scan_result
+            .error
+            .unwrap_or_else(|| "Failed to scan wallpapers".to_string()));
+    }
+    out::debug_api_return(&format!("total={}", scan_result.stats.total_count));
+
+    out::title("LianPkg Summary");
+    out::path_info("Workshop", &workshop_path);
+    out::path_info("State File", &init_result.state_path);
+
+    // 订阅与处理情况
+    let processed = scan_result
+        .wallpapers
+        .iter()
+        .filter(|w| native::is_wallpaper_processed(&state, &w.wallpaper_id))
+        .count();
+    let orphaned = state
+        .processed_wallpapers
+        .iter()
+        .filter(|p| {
+            !scan_result
+                .wallpapers
+                .iter()
+                .any(|w| w.wallpaper_id == p.wallpaper_id)
+        })
+        .count();
+
+    out::subtitle("Wallpapers");
+    out::stat("Subscribed", scan_result.stats.total_count);
+    out::stat(
+        "PKG / Raw",
+        format!(
+            "{} / {}",
+            scan_result.stats.pkg_count, scan_result.stats.raw_count
+        ),
+    );
+    out::stat("Processed", processed);
+    out::stat("Unprocessed", scan_result.stats.total_count - processed);
+    if orphaned > 0 {
+        out::stat("Not in Workshop", orphaned);
+    }
+
+    // 按类型分布
+    let mut types: BTreeMap<String, usize> = BTreeMap::new();
+    for wp in &scan_result.wallpapers {
+        let key = wp
+            .wallpaper_type
+            .as_deref()
+            .map(|t| t.to_lowercase())
+            .unwrap_or_else(|| "unknown".to_string());
+        *types.entry(key).or_insert(0) += 1;
+    }
+    if !types.is_empty() {
+        out::subtitle("By Type");
+        out::format_distribution(&types);
+    }
+
+    // 累计产出：未配置独立转换目录时，转换结果位于 Pkg_Unpacked/<id>/tex_converted
+    let (images, videos) = match config.converted_output_path {
+        Some(ref p) => count_outputs(p),
+        None => std::fs::read_dir(&config.unpacked_output_path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| count_outputs(&e.path().join("tex_converted")))
+                    .fold((0, 0), |acc, (i, v)| (acc.0 + i, acc.1 + v))
+            })
+            .unwrap_or((0, 0)),
+    };
+
+    out::subtitle("Output");
+    out::stat("Total Runs", state.statistics.total_runs);
+    out::stat(
+        "Last Run",
+        state
+            .last_run
+            .map(format_timestamp)
+            .unwrap_or_else(|| "Never".to_string()),
+    );
+    out::stat("PKGs Unpacked", state.statistics.total_pkgs);
+    out::stat("TEXs Converted", state.statistics.total_texs);
+    out::stat("Images on Disk", images);
+    out::stat("Videos on Disk", videos);
+
+    // 输出目录占用
+    out::subtitle("Disk Usage");
+    let mut dirs = vec![
+        ("Raw Output", config.raw_output_path.clone()),
+        ("PKG Temp", config.pkg_temp_path.clone()),
+        ("Unpacked", config.unpacked_output_path.clone()),
+    ];
+    if let Some(ref p) = config.converted_output_path {
+        dirs.push(("TEX Output", p.clone()));
+    }
+    let mut total = 0;
+    let mut usage = serde_json::Map::new();
+    for (label, dir) in dirs {
+        if dir.exists() {
+            let size = disk::dir_size(disk::DirSizeInput { path: dir }).size;
+            total += size;
+            usage.insert(json_key(label), size.into());
+            out::stat(label, out::format_size(size));
+        } else {
+            usage.insert(json_key(label), serde_json::Value::Null);
+            out::stat(label, "(missing)");
+        }
+    }
+    out::stat("Total", out::format_size(total));
+    out::blank();
+
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "workshop_path": workshop_path,
+            "state_path": init_result.state_path,
+            "subscribed": scan_result.stats.total_count,
+            "pkg_count": scan_result.stats.pkg_count,
+            "raw_count": scan_result.stats.raw_count,
+            "processed": processed,
+            "unprocessed": scan_result.stats.total_count - processed,
+            "not_in_workshop": orphaned,
+            "types": types,
+            "total_runs": state.statistics.total_runs,
+            "last_run": state.last_run,
+            "pkgs_unpacked": state.statistics.total_pkgs,
+            "texs_converted": state.statistics.total_texs,
+            "images_on_disk": images,
+            "videos_on_disk": videos,
+            "disk_usage": usage,
+            "disk_total": total,
+        }));
+    }
+
+    Ok(())
+}
+
+/// 显示标签转为 JSON 键名（"Raw Output" → "raw_output"）
+fn json_key(label: &str) -> String {
+    label.to_lowercase().replace(' ', "_")
+}
+
+/// 递归统计目录下的图片与视频产物数量（跳过 thumbs/ 缩略图目录）
+fn count_outputs(dir: &Path) -> (usize, usize) {
+    let mut images = 0;
+    let mut videos = 0;
+    let mut stack = vec![dir.to_path_buf()];
+
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if entry.file_name() != "thumbs" {
+                    stack.push(path);
+                }
+                continue;
+            }
+            let ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase());
+            match ext.as_deref() {
+                Some("mp4") => videos += 1,
+                Some(e) if IMAGE_EXTENSIONS.contains(&e) => images += 1,
+                _ => {}
+            }
+        }
+    }
+
+    (images, videos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_outputs_skips_thumbnails() {
+        let dir = std::env::temp_dir().join("lianpkg_summary_count_outputs");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("scene/thumbs")).unwrap();
+        std::fs::write(dir.join("scene/a.png"), b"").unwrap();
+        std::fs::write(dir.join("scene/b.mp4"), b"").unwrap();
+        std::fs::write(dir.join("scene/thumbs/a.png"), b"").unwrap();
+
+        assert_eq!(count_outputs(&dir), (1, 1));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,8 +4,19 @@ use super::super::args::TexArgs;
 use super::super::output as out;
 use lianpkg::api::native::{self, tex};
 use lianpkg::core::path;
+use lianpkg::core::tex as core_tex;
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// --json 目录预览中的单个 TEX
+#[derive(Serialize)]
+struct TexPreviewEntry {
+    path: PathBuf,
+    #[serde(flatten)]
+    preview: tex::PreviewTexOutput,
+}
 
 /// 执行 tex 命令
 pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
@@ -43,7 +54,36 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         .clone()
         .unwrap_or_else(|| config.unpacked_output_path.clone());
 
-    let output_path = args.output.clone().or(config.converted_output_path.clone());
+    // 就地模式忽略配置中的输出路径
+    let output_path = if args.in_place {
+        None
+    } else {
+        args.output.clone().or(config.converted_output_path.clone())
+    };
+    let strict = args.strict || config.tex_strict;
+    let strict_lz4 = args.strict_lz4 || config.tex_strict_lz4;
+    let thumbnail_size = args
+        .thumbnail
+        .or(config.pipeline.thumbnail_size)
+        .filter(|&n| n > 0);
+    let mipmap = match args.mipmap.as_deref() {
+        Some(s) => core_tex::MipmapSelection::parse(s).ok_or_else(|| {
+            format!(
+                "Invalid --mipmap value '{}': expected first, largest, all or a level number",
+                s
+            )
+        })?,
+        None => core_tex::MipmapSelection::First,
+    };
+    let animate = args
+        .animate
+        .as_deref()
+        .and_then(|s| core_tex::AnimateOutput::parse(s, args.fps.unwrap_or(24)));
+    let output_format = args
+        .format
+        .as_deref()
+        .and_then(|s| core_tex::ImageOutputFormat::parse(s, args.quality.unwrap_or(85)))
+        .unwrap_or_default();
 
     // 判断输入类型
     if !input_path.exists() {
@@ -53,20 +93,35 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         ));
     }
 
+    // 格式信息：与 --preview --json 输出相同
+    if args.info {
+        return run_preview_json(&input_path, output_format);
+    }
+
     // 预览模式
     if args.preview {
-        return run_preview(&input_path, args.verbose);
+        return run_preview(&input_path, args.verbose, output_format);
+    }
+
+    // 转换后打开
+    if args.open {
+        return run_open(&input_path, strict, strict_lz4, args.flip);
     }
 
     // 执行转换
     out::title("TEX Conversion");
     out::path_info("Input", &input_path);
-    if let Some(ref out_path) = output_path {
+    if args.in_place {
+        out::info("Output: (in place - next to each .tex)");
+        if args.remove_tex {
+            out::warning("Source .tex files will be removed after successful conversion");
+        }
+    } else if let Some(ref out_path) = output_path {
         out::path_info("Output", out_path);
     } else {
         out::info("Output: (auto - tex_converted subdirectory)");
     }
-    println!();
+    out::blank();
 
     // 确保输出目录存在
     if let Some(ref out_path) = output_path {
@@ -76,19 +131,32 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
     // 判断是单文件还是目录
     if input_path.is_file() && input_path.extension().map(|e| e == "tex").unwrap_or(false) {
         // 单文件转换
-        let out_path = output_path.unwrap_or_else(|| {
-            input_path
-                .parent()
-                .unwrap_or(&input_path)
-                .join("tex_converted")
-        });
+        let out_path = if args.in_place {
+            input_path.with_extension("")
+        } else {
+            output_path.unwrap_or_else(|| {
+                input_path
+                    .parent()
+                    .unwrap_or(&input_path)
+                    .join("tex_converted")
+            })
+        };
 
         out::debug_api_enter(
             "tex",
             "convert_single",
             &format!("input={}", input_path.display()),
         );
-        let result = tex::convert_single(input_path.clone(), out_path);
+        let result = tex::convert_single(core_tex::ConvertTexInput {
+            file_path: input_path.clone(),
+            output_path: out_path,
+            strict,
+            strict_lz4,
+            mipmap,
+            animate,
+            output_format,
+            flip_vertical: args.flip,
+        });
 
         if !result.success {
             out::debug_api_error(result.error.as_deref().unwrap_or("Unknown error"));
@@ -96,13 +164,45 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         }
         out::debug_api_return(&format!("output={}", result.output_path.display()));
 
+        let removed = args.remove_tex
+            && result.output_path != input_path
+            && std::fs::remove_file(&input_path).is_ok();
+
+        let is_video = result.tex_info.as_ref().is_some_and(|i| i.is_video);
+        let thumbnail = match thumbnail_size {
+            Some(size) if !is_video => Some(tex::make_thumbnail(&result.output_path, size)),
+            _ => None,
+        };
+
+        if out::is_json() {
+            if let Some(Err(e)) = thumbnail {
+                out::warning(&format!("Failed to create thumbnail: {}", e));
+            }
+            out::json(&result);
+            return Ok(());
+        }
+
         out::subtitle("Results");
         out::stat("Output", result.output_path.display());
+        for extra in &result.extra_output_paths {
+            out::stat("Output", extra.display());
+        }
         out::stat("Format", result.format.as_deref().unwrap_or("unknown"));
+        if result.alpha_fallback {
+            out::warning("Texture has an alpha channel, saved as PNG instead of JPEG");
+        }
         if let Some(info) = result.tex_info {
             out::stat("Resolution", format!("{}×{}", info.width, info.height));
         }
-        println!();
+        if removed {
+            out::stat("Removed", input_path.display());
+        }
+        match thumbnail {
+            Some(Ok(p)) => out::stat("Thumbnail", p.display()),
+            Some(Err(e)) => out::warning(&format!("Failed to create thumbnail: {}", e)),
+            None => {}
+        }
+        out::blank();
         out::success("TEX conversion completed!");
     } else {
         // 目录批量转换
@@ -114,6 +214,27 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
         let result = tex::convert_all(tex::ConvertAllInput {
             unpacked_path: input_path,
             output_path,
+            strict,
+            strict_lz4,
+            in_place: args.in_place,
+            remove_source: args.remove_tex,
+            fail_threshold: args
+                .fail_threshold
+                .or(config.pipeline.effective_fail_threshold()),
+            thumbnail_size,
+            jobs: args.jobs,
+            max_memory_mb: args.max_memory.or(config.pipeline.max_memory_mb),
+            missing_only: args.missing_only,
+            on_conflict: args
+                .on_conflict
+                .as_deref()
+                .and_then(tex::ConflictPolicy::parse)
+                .unwrap_or(config.tex_on_conflict),
+            mipmap,
+            animate,
+            output_format,
+            flip_vertical: args.flip,
+            cancel: None,
         });
 
         if !result.success && result.stats.tex_success == 0 {
@@ -128,19 +249,57 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
             result.stats.video_count
         ));
 
+        if out::is_json() {
+            out::json(&result);
+            return Ok(());
+        }
+
         out::subtitle("Results");
         out::stat("TEX Processed", result.stats.tex_processed);
         out::stat("TEX Success", result.stats.tex_success);
         out::stat("TEX Failed", result.stats.tex_failed);
         out::stat("Images", result.stats.image_count);
         out::stat("Videos", result.stats.video_count);
-        println!();
+        if args.missing_only {
+            out::stat("TEX Skipped (exists)", result.stats.tex_skipped);
+        }
+        if args.remove_tex {
+            out::stat("TEX Removed", result.stats.tex_removed);
+        }
+        if thumbnail_size.is_some() {
+            out::stat("Thumbnails", result.stats.thumbnail_count);
+            if result.stats.thumbnail_failed > 0 {
+                out::stat("Thumbnails Failed", result.stats.thumbnail_failed);
+            }
+        }
+        if !result.stats.format_counts.is_empty() {
+            out::subtitle("Format Distribution");
+            out::format_distribution(&result.stats.format_counts);
+        }
+        print_conflicts(&result.conflicts);
+        out::blank();
 
+        if result.stats.alpha_fallback_count > 0 {
+            out::warning(&format!(
+                "{} textures have an alpha channel and were saved as PNG instead of JPEG",
+                result.stats.alpha_fallback_count
+            ));
+        }
+        if result.aborted {
+            out::error(result.error.as_deref().unwrap_or("Aborted"));
+        }
         if result.stats.tex_failed > 0 {
             out::warning(&format!(
                 "{} TEX files failed to convert",
                 result.stats.tex_failed
             ));
+            for failed in result.results.iter().filter(|r| !r.success) {
+                out::error(&format!(
+                    "{}: {}",
+                    failed.input_path.display(),
+                    failed.error.as_deref().unwrap_or("unknown error")
+                ));
+            }
         }
         out::success("TEX conversion completed!");
     }
@@ -148,27 +307,160 @@ pub fn run(args: &TexArgs, config_path: Option<PathBuf>) -> Result<(), String> {
     Ok(())
 }
 
+/// 列出写出前检测到的输出路径冲突
+pub(super) fn print_conflicts(conflicts: &[tex::OutputConflict]) {
+    if conflicts.is_empty() {
+        return;
+    }
+    out::subtitle("Output Conflicts");
+    for conflict in conflicts {
+        let action = match conflict.resolved_path {
+            Some(ref p) => format!("renamed to {}", p.display()),
+            None => "not written".to_string(),
+        };
+        out::warning(&format!(
+            "{} and {} → {} ({})",
+            conflict.existing_input.display(),
+            conflict.input_path.display(),
+            conflict.output_path.display(),
+            action
+        ));
+    }
+}
+
+/// 转换到临时目录并用系统默认程序打开
+fn run_open(
+    input_path: &Path,
+    strict: bool,
+    strict_lz4: bool,
+    flip_vertical: bool,
+) -> Result<(), String> {
+    if !input_path.is_file() || input_path.extension().is_none_or(|e| e != "tex") {
+        return Err("--open requires a single .tex file".to_string());
+    }
+
+    let temp_dir = std::env::temp_dir().join("lianpkg_tex_open");
+    path::ensure_dir_compat(&temp_dir).map_err(|e| e.to_string())?;
+
+    out::debug_api_enter(
+        "tex",
+        "convert_single",
+        &format!(
+            "input={}, output={}",
+            input_path.display(),
+            temp_dir.display()
+        ),
+    );
+    let result = tex::convert_single(core_tex::ConvertTexInput {
+        file_path: input_path.to_path_buf(),
+        output_path: temp_dir,
+        strict,
+        strict_lz4,
+        mipmap: core_tex::MipmapSelection::First,
+        animate: None,
+        output_format: core_tex::ImageOutputFormat::Png,
+        flip_vertical,
+    });
+    if !result.success {
+        out::debug_api_error(result.error.as_deref().unwrap_or("Unknown error"));
+        return Err(result.error.unwrap_or_else(|| "Unknown error".to_string()));
+    }
+    out::debug_api_return(&format!("output={}", result.output_path.display()));
+
+    out::path_info("Converted", &result.output_path);
+    open_with_system(&result.output_path)?;
+    out::success("Opened with the system default viewer");
+    Ok(())
+}
+
+/// 调用系统默认程序打开文件（Windows `start`、macOS `open`、其它 `xdg-open`）
+fn open_with_system(file: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]).arg(file);
+        c
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut c = Command::new("open");
+        c.arg(file);
+        c
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut c = Command::new("xdg-open");
+        c.arg(file);
+        c
+    };
+
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to launch viewer for {}: {}", file.display(), e))
+}
+
 /// 预览模式
-fn run_preview(input_path: &PathBuf, verbose: bool) -> Result<(), String> {
+fn run_preview(
+    input_path: &PathBuf,
+    verbose: bool,
+    output_format: core_tex::ImageOutputFormat,
+) -> Result<(), String> {
+    if out::is_json() {
+        return run_preview_json(input_path, output_format);
+    }
+
     out::title("TEX Preview");
     out::path_info("Input", input_path);
-    println!();
+    out::blank();
 
     if input_path.is_file() {
         // 单文件预览
-        preview_single_tex(input_path, verbose)?;
+        preview_single_tex(input_path, verbose, output_format)?;
     } else {
         // 目录预览
-        preview_directory(input_path, verbose)?;
+        preview_directory(input_path, verbose, output_format)?;
     }
 
     Ok(())
 }
 
+/// --json 预览：单文件输出预览结果，目录输出带路径的预览列表
+fn run_preview_json(
+    input_path: &PathBuf,
+    output_format: core_tex::ImageOutputFormat,
+) -> Result<(), String> {
+    let preview = |tex_path: &PathBuf| {
+        tex::preview_tex(tex::PreviewTexInput {
+            tex_path: tex_path.clone(),
+            output_format,
+        })
+    };
+
+    if input_path.is_file() {
+        out::json(&preview(input_path));
+    } else {
+        let entries: Vec<TexPreviewEntry> = find_tex_files(input_path)?
+            .into_iter()
+            .map(|path| TexPreviewEntry {
+                preview: preview(&path),
+                path,
+            })
+            .collect();
+        out::json(&entries);
+    }
+    Ok(())
+}
+
 /// 预览单个 TEX 文件
-fn preview_single_tex(tex_path: &std::path::Path, verbose: bool) -> Result<(), String> {
+fn preview_single_tex(
+    tex_path: &std::path::Path,
+    verbose: bool,
+    output_format: core_tex::ImageOutputFormat,
+) -> Result<(), String> {
     let result = tex::preview_tex(tex::PreviewTexInput {
         tex_path: tex_path.to_path_buf(),
+        output_format,
     });
 
     if !result.success {
@@ -187,6 +479,9 @@ fn preview_single_tex(tex_path: &std::path::Path, verbose: bool) -> Result<(), S
         out::box_line("Size", &format!("{} × {}", info.width, info.height));
         out::box_line("Images", &info.image_count.to_string());
         out::box_line("Mipmaps", &info.mipmap_count.to_string());
+        if info.frame_count > 1 {
+            out::box_line("Frames", &info.frame_count.to_string());
+        }
         out::box_line(
             "Compressed",
             if info.is_compressed {
@@ -219,7 +514,11 @@ fn preview_single_tex(tex_path: &std::path::Path, verbose: bool) -> Result<(), S
 }
 
 /// 预览目录中的所有 TEX
-fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
+fn preview_directory(
+    dir_path: &PathBuf,
+    verbose: bool,
+    output_format: core_tex::ImageOutputFormat,
+) -> Result<(), String> {
     let tex_files = find_tex_files(dir_path)?;
 
     if tex_files.is_empty() {
@@ -228,19 +527,19 @@ fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
     }
 
     out::info(&format!("Found {} TEX files", tex_files.len()));
-    println!();
+    out::blank();
 
     if verbose {
         // 详细模式：每个 TEX 单独显示
         for tex_path in &tex_files {
-            if let Err(e) = preview_single_tex(tex_path, true) {
+            if let Err(e) = preview_single_tex(tex_path, true, output_format) {
                 out::error(&format!(
                     "Failed to preview {}: {}",
                     tex_path.file_name().unwrap_or_default().to_string_lossy(),
                     e
                 ));
             }
-            println!();
+            out::blank();
         }
     } else {
         // 简洁模式：表格汇总
@@ -255,6 +554,7 @@ fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
         for tex_path in &tex_files {
             let result = tex::preview_tex(tex::PreviewTexInput {
                 tex_path: tex_path.clone(),
+                output_format,
             });
 
             if result.success {
@@ -278,7 +578,7 @@ fn preview_directory(dir_path: &PathBuf, verbose: bool) -> Result<(), String> {
         }
     }
 
-    println!();
+    out::blank();
     Ok(())
 }
 
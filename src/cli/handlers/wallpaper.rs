@@ -3,8 +3,22 @@
 use super::super::args::WallpaperArgs;
 use super::super::output as out;
 use lianpkg::api::native::{self, paper};
-use lianpkg::core::path;
-use std::path::PathBuf;
+use lianpkg::core::{paper as core_paper, path};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// --export 写出的壁纸清单
+#[derive(Serialize)]
+struct WallpaperManifest {
+    /// 生成时间（Unix 时间戳）
+    generated_at: u64,
+    /// 扫描的 workshop 目录
+    workshop_path: PathBuf,
+    /// 整个 workshop 的扫描统计
+    stats: paper::ScanStats,
+    /// 清单中的壁纸（已按 --ids / --max-rating 过滤）
+    wallpapers: Vec<paper::WallpaperInfo>,
+}
 
 /// 执行 wallpaper 命令
 pub fn run(args: &WallpaperArgs, config_path: Option<PathBuf>) -> Result<(), String> {
@@ -54,9 +68,48 @@ pub fn run(args: &WallpaperArgs, config_path: Option<PathBuf>) -> Result<(), Str
 
     let enable_raw = !args.no_raw && config.enable_raw_output;
 
+    // 内容分级：CLI 参数优先于配置文件
+    let max_rating = args
+        .max_rating
+        .as_deref()
+        .and_then(core_paper::ContentRating::parse)
+        .or(config.max_content_rating);
+
+    // 壁纸类型：CLI 参数优先于配置文件
+    let only_types = args.only_type.clone().or_else(|| config.only_types.clone());
+
+    // 类型 / 标签过滤：在扫描阶段生效，与 --ids 取交集
+    let filter = ScanFilter {
+        types: args.type_filter.as_deref(),
+        tags: args.tag_filter.as_deref(),
+        pkg_depth: config.pkg_depth,
+    };
+
     // 预览模式
     if args.preview {
-        return run_preview(&workshop_path, args.verbose, args.ids.as_ref());
+        return run_preview(
+            &workshop_path,
+            args.verbose,
+            args.ids.as_ref(),
+            max_rating,
+            &filter,
+        );
+    }
+
+    // 查重模式
+    if args.find_dupes {
+        return run_find_dupes(&workshop_path, args.ids.as_ref());
+    }
+
+    // 导出清单
+    if let Some(ref export_path) = args.export {
+        return run_export(
+            &workshop_path,
+            export_path,
+            args.ids.as_ref(),
+            max_rating,
+            &filter,
+        );
     }
 
     // 执行复制
@@ -71,12 +124,13 @@ pub fn run(args: &WallpaperArgs, config_path: Option<PathBuf>) -> Result<(), Str
         for id in ids {
             out::info(&format!("  - {}", id));
         }
-        println!();
+        out::blank();
     }
+    filter.show();
     out::path_info("Source", &workshop_path);
     out::path_info("Raw Output", &raw_output);
     out::path_info("PKG Temp", &pkg_temp);
-    println!();
+    out::blank();
 
     // 确保目录存在
     let _ = path::ensure_dir_compat(&raw_output);
@@ -86,18 +140,27 @@ pub fn run(args: &WallpaperArgs, config_path: Option<PathBuf>) -> Result<(), Str
         "paper",
         "copy_wallpapers",
         &format!(
-            "ids={:?}, workshop={}, enable_raw={}",
+            "ids={:?}, workshop={}, enable_raw={}, max_rating={:?}",
             args.ids.as_ref().map(|v| v.len()),
             workshop_path.display(),
-            enable_raw
+            enable_raw,
+            max_rating
         ),
     );
     let result = paper::copy_wallpapers(paper::CopyWallpapersInput {
         wallpaper_ids: args.ids.clone(),
-        workshop_path,
+        workshop_path: workshop_path.clone(),
         raw_output_path: raw_output,
         pkg_temp_path: pkg_temp,
         enable_raw,
+        max_rating,
+        only_types,
+        type_filter: args.type_filter.clone(),
+        tag_filter: args.tag_filter.clone(),
+        cache: None,
+        force: args.force,
+        pkg_depth: config.pkg_depth,
+        cancel: None,
     });
 
     if !result.success {
@@ -109,35 +172,252 @@ pub fn run(args: &WallpaperArgs, config_path: Option<PathBuf>) -> Result<(), Str
         result.stats.raw_copied, result.stats.pkg_copied, result.stats.skipped
     ));
 
+    if out::is_json() {
+        out::json(&result);
+        return Ok(());
+    }
+
     // 输出结果
     out::subtitle("Results");
     out::stat("Raw Copied", result.stats.raw_copied);
     out::stat("PKG Copied", result.stats.pkg_copied);
     out::stat("Skipped", result.stats.skipped);
+    out::stat("Filtered (rating)", result.stats.filtered);
+    if result.stats.type_skipped > 0 {
+        out::stat("Skipped (type)", result.stats.type_skipped);
+    }
+    if result.stats.presets > 0 {
+        out::stat("Skipped (preset)", result.stats.presets);
+    }
     out::stat("Total PKG Files", result.stats.total_pkg_files);
-    println!();
+    if result.stats.files_updated > 0 {
+        out::stat("Raw Files Updated", result.stats.files_updated);
+    }
+
+    if !result.filtered.is_empty() {
+        out::subtitle("Filtered by Content Rating");
+        for wp in &result.filtered {
+            out::info(&format!(
+                "{} {} [{}]",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                wp.content_rating.name()
+            ));
+        }
+    }
+
+    if !result.type_skipped.is_empty() {
+        out::subtitle("Skipped by Wallpaper Type");
+        for wp in &result.type_skipped {
+            out::info(&format!(
+                "{} {} [{}]",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                wp.wallpaper_type.as_deref().unwrap_or("unknown")
+            ));
+        }
+    }
+
+    if !result.presets.is_empty() {
+        out::subtitle("Skipped Presets");
+        for wp in &result.presets {
+            let base = match wp.preset_base {
+                Some(ref id) if workshop_path.join(id).is_dir() => format!("base {}", id),
+                Some(ref id) => format!("base {} not subscribed", id),
+                None => "base unknown".to_string(),
+            };
+            out::info(&format!(
+                "{} {} → {}",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                base
+            ));
+        }
+    }
+    out::blank();
 
     out::success("Wallpaper extraction completed!");
     Ok(())
 }
 
-/// 预览模式
-fn run_preview(
+/// 查重模式
+fn run_find_dupes(
     workshop_path: &std::path::Path,
-    verbose: bool,
     ids: Option<&Vec<String>>,
 ) -> Result<(), String> {
-    out::title("Wallpaper Preview");
+    out::title("Duplicate Wallpapers");
     out::path_info("Workshop", workshop_path);
-    println!();
+    out::blank();
 
     out::debug_api_enter(
         "paper",
-        "scan_wallpapers",
+        "find_duplicates",
         &format!("path={}", workshop_path.display()),
     );
+    let result = paper::find_duplicates(paper::FindDuplicatesInput {
+        workshop_path: workshop_path.to_path_buf(),
+        wallpaper_ids: ids.cloned(),
+    });
+
+    if !result.success {
+        out::debug_api_error(result.error.as_deref().unwrap_or("Failed to scan"));
+        return Err(result.error.unwrap_or_else(|| "Failed to scan".to_string()));
+    }
+    out::debug_api_return(&format!(
+        "checked={}, groups={}, unreadable={}",
+        result.checked,
+        result.groups.len(),
+        result.unreadable.len()
+    ));
+
+    if out::is_json() {
+        out::json(&result);
+        return Ok(());
+    }
+
+    for (index, group) in result.groups.iter().enumerate() {
+        out::subtitle(&format!(
+            "Group {} ({}, fingerprint {})",
+            index + 1,
+            out::format_size(group.total_size),
+            group.fingerprint
+        ));
+        for wp in &group.wallpapers {
+            out::info(&format!(
+                "{} {}",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)")
+            ));
+        }
+    }
+    for (id, reason) in &result.unreadable {
+        out::warning(&format!("{}: {}", id, reason));
+    }
+
+    out::subtitle("Summary");
+    out::stat("Checked", result.checked);
+    out::stat("Duplicate Groups", result.groups.len());
+    out::stat(
+        "Redundant Wallpapers",
+        result
+            .groups
+            .iter()
+            .map(|g| g.wallpapers.len() - 1)
+            .sum::<usize>(),
+    );
+    out::blank();
+
+    if result.groups.is_empty() {
+        out::success("No duplicate wallpapers found");
+    } else {
+        out::info("Report only: nothing was deleted");
+    }
+    Ok(())
+}
+
+/// 导出清单模式
+fn run_export(
+    workshop_path: &Path,
+    export_path: &Path,
+    ids: Option<&Vec<String>>,
+    max_rating: Option<core_paper::ContentRating>,
+    filter: &ScanFilter,
+) -> Result<(), String> {
+    out::title("Wallpaper Export");
+    out::path_info("Workshop", workshop_path);
+    filter.show();
+    out::blank();
+
+    let result = scan(workshop_path, filter)?;
+    let wallpapers: Vec<paper::WallpaperInfo> = filter_by_ids(&result, ids)?
+        .into_iter()
+        .filter(|w| paper::rating_allowed(w.content_rating, max_rating))
+        .map(|w| absolute_paths(w.clone()))
+        .collect();
+    let count = wallpapers.len();
+
+    let manifest = WallpaperManifest {
+        generated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        workshop_path: std::path::absolute(workshop_path)
+            .unwrap_or_else(|_| workshop_path.to_path_buf()),
+        stats: result.stats,
+        wallpapers,
+    };
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    if let Some(parent) = export_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        path::ensure_dir_compat(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(export_path, content)
+        .map_err(|e| format!("Failed to write {}: {}", export_path.display(), e))?;
+
+    if out::is_json() {
+        out::json(&serde_json::json!({
+            "success": true,
+            "export_path": export_path,
+            "wallpaper_count": count,
+        }));
+        return Ok(());
+    }
+
+    out::stat("Wallpapers", count);
+    out::path_info("Manifest", export_path);
+    out::blank();
+    out::success("Wallpaper manifest exported!");
+    Ok(())
+}
+
+/// 清单中的路径统一转为绝对路径，便于脱离当前目录使用
+fn absolute_paths(mut wp: paper::WallpaperInfo) -> paper::WallpaperInfo {
+    let absolute = |p: &Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    wp.folder_path = absolute(&wp.folder_path);
+    wp.preview_path = wp.preview_path.as_deref().map(absolute);
+    wp.pkg_files = wp.pkg_files.iter().map(|p| absolute(p)).collect();
+    wp
+}
+
+/// 扫描阶段的类型 / 标签过滤
+struct ScanFilter<'a> {
+    types: Option<&'a [String]>,
+    tags: Option<&'a [String]>,
+    /// 查找 .pkg 时向下搜索的子目录层数（来自配置）
+    pkg_depth: usize,
+}
+
+impl ScanFilter<'_> {
+    /// 显示生效中的过滤条件
+    fn show(&self) {
+        if let Some(types) = self.types {
+            out::info(&format!("Type filter: {}", types.join(", ")));
+        }
+        if let Some(tags) = self.tags {
+            out::info(&format!("Tag filter: {}", tags.join(", ")));
+        }
+    }
+}
+
+/// 扫描 workshop 目录
+fn scan(workshop_path: &Path, filter: &ScanFilter) -> Result<paper::ScanWallpapersOutput, String> {
+    out::debug_api_enter(
+        "paper",
+        "scan_wallpapers",
+        &format!(
+            "path={}, types={:?}, tags={:?}",
+            workshop_path.display(),
+            filter.types,
+            filter.tags
+        ),
+    );
     let result = paper::scan_wallpapers(paper::ScanWallpapersInput {
         workshop_path: workshop_path.to_path_buf(),
+        type_filter: filter.types.map(<[String]>::to_vec),
+        tag_filter: filter.tags.map(<[String]>::to_vec),
+        generate_thumbnails: None,
+        cache: None,
+        pkg_depth: filter.pkg_depth,
     });
 
     if !result.success {
@@ -145,23 +425,37 @@ fn run_preview(
         return Err(result.error.unwrap_or_else(|| "Failed to scan".to_string()));
     }
     out::debug_api_return(&format!(
-        "total={}, pkg={}, raw={}",
-        result.stats.total_count, result.stats.pkg_count, result.stats.raw_count
+        "total={}, pkg={}, raw={}, excluded={}",
+        result.stats.total_count,
+        result.stats.pkg_count,
+        result.stats.raw_count,
+        result.stats.excluded
     ));
+    Ok(result)
+}
 
-    // 过滤壁纸（如果指定了 ids）
-    let wallpapers: Vec<_> = match ids {
+/// 按 --ids 过滤扫描结果，提示未找到的 ID；全部未命中时报错
+fn filter_by_ids<'a>(
+    result: &'a paper::ScanWallpapersOutput,
+    ids: Option<&Vec<String>>,
+) -> Result<Vec<&'a paper::WallpaperInfo>, String> {
+    Ok(match ids {
         Some(filter_ids) => {
             let filtered: Vec<_> = result
                 .wallpapers
                 .iter()
-                .filter(|w| filter_ids.contains(&w.wallpaper_id))
+                .filter(|w| paper::id_matches(&w.wallpaper_id, filter_ids))
                 .collect();
 
             // 检查是否有未找到的 ID
             let not_found: Vec<&str> = filter_ids
                 .iter()
-                .filter(|id| !result.wallpapers.iter().any(|w| &w.wallpaper_id == *id))
+                .filter(|id| {
+                    !result
+                        .wallpapers
+                        .iter()
+                        .any(|w| paper::id_pattern_matches(&w.wallpaper_id, id))
+                })
                 .map(|s| s.as_str())
                 .collect();
 
@@ -179,7 +473,40 @@ fn run_preview(
             filtered
         }
         None => result.wallpapers.iter().collect(),
-    };
+    })
+}
+
+/// 预览模式
+fn run_preview(
+    workshop_path: &Path,
+    verbose: bool,
+    ids: Option<&Vec<String>>,
+    max_rating: Option<core_paper::ContentRating>,
+    filter: &ScanFilter,
+) -> Result<(), String> {
+    out::title("Wallpaper Preview");
+    out::path_info("Workshop", workshop_path);
+    filter.show();
+    out::blank();
+
+    let result = scan(workshop_path, filter)?;
+    let wallpapers = filter_by_ids(&result, ids)?;
+
+    // 按内容分级拆分
+    let (wallpapers, filtered): (Vec<_>, Vec<_>) = wallpapers
+        .into_iter()
+        .partition(|w| paper::rating_allowed(w.content_rating, max_rating));
+
+    // JSON 模式：只输出过滤后的壁纸，统计仍为整个 workshop
+    if out::is_json() {
+        out::json(&paper::ScanWallpapersOutput {
+            success: true,
+            wallpapers: wallpapers.into_iter().cloned().collect(),
+            stats: result.stats.clone(),
+            error: None,
+        });
+        return Ok(());
+    }
 
     out::info(&format!(
         "Found {} wallpapers ({} PKG, {} Raw){}",
@@ -192,7 +519,20 @@ fn run_preview(
             String::new()
         }
     ));
-    println!();
+    if result.stats.excluded > 0 {
+        out::info(&format!(
+            "{} wallpapers excluded by type/tag filter",
+            result.stats.excluded
+        ));
+    }
+    if !filtered.is_empty() {
+        out::warning(&format!(
+            "{} wallpapers filtered by content rating (max: {})",
+            filtered.len(),
+            max_rating.map(|r| r.name()).unwrap_or("-")
+        ));
+    }
+    out::blank();
 
     if verbose {
         // 详细模式：每个壁纸一个 box
@@ -200,6 +540,16 @@ fn run_preview(
             out::box_start(&wp.wallpaper_id);
             out::box_line("Title", wp.title.as_deref().unwrap_or("(untitled)"));
             out::box_line("Type", wp.wallpaper_type.as_deref().unwrap_or("unknown"));
+            if let Some(ref base) = wp.preset_base {
+                out::box_line("Preset Of", base);
+            }
+            out::box_line("Rating", wp.content_rating.name());
+            if !wp.tags.is_empty() {
+                out::box_line("Tags", &wp.tags.join(", "));
+            }
+            if let Some(ref desc) = wp.description {
+                out::box_line("Description", desc);
+            }
             out::box_line("PKG", &out::pkg_badge(wp.has_pkg, Some(wp.pkg_files.len())));
             if !wp.pkg_files.is_empty() {
                 let pkg_names: Vec<String> = wp
@@ -239,6 +589,18 @@ fn run_preview(
         }
     }
 
-    println!();
+    if !filtered.is_empty() {
+        out::subtitle("Filtered by Content Rating");
+        for wp in &filtered {
+            out::info(&format!(
+                "{} {} [{}]",
+                wp.wallpaper_id,
+                wp.title.as_deref().unwrap_or("(untitled)"),
+                wp.content_rating.name()
+            ));
+        }
+    }
+
+    out::blank();
     Ok(())
 }
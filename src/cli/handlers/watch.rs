@@ -0,0 +1,279 @@
+//! Watch 模式处理器（auto --watch）
+//!
+//! 监听 workshop 目录，新增或修改的壁纸在事件静默后自动执行增量流水线，
+//! 只处理发生变化的壁纸 ID；Ctrl+C 取消正在进行的运行（state.json 已保存）后退出
+
+use super::super::args::AutoArgs;
+use super::super::output as out;
+use super::super::signal;
+use lianpkg::api::native::{self, paper, pipeline};
+use lianpkg::core::cfg as core_cfg;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// 最后一个事件之后的静默时间，期间的事件合并为一次运行
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// 等待事件时检查 Ctrl+C 的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 监听 workshop 目录并在变化时执行增量流水线，直到 Ctrl+C
+pub fn run(
+    config: &native::RuntimeConfig,
+    args: &AutoArgs,
+    overrides: &pipeline::PipelineOverrides,
+    state_path: &Path,
+) -> Result<(), String> {
+    let workshop = &config.workshop_path;
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(workshop, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", workshop.display(), e))?;
+
+    out::info(&format!(
+        "Watching {} for changes (Ctrl+C to stop)",
+        workshop.display()
+    ));
+
+    let mut pending: BTreeSet<String> = BTreeSet::new();
+    let mut last_event = Instant::now();
+    signal::set_cancellable(true);
+    while !signal::is_cancelled() {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+                let ids = event
+                    .paths
+                    .iter()
+                    .filter_map(|p| wallpaper_id_of(workshop, p))
+                    .filter(|id| {
+                        args.ids
+                            .as_ref()
+                            .is_none_or(|patterns| paper::id_matches(id, patterns))
+                    });
+                for id in ids {
+                    pending.insert(id);
+                    last_event = Instant::now();
+                }
+            }
+            Ok(Err(e)) => out::warning(&format!("Watch error: {}", e)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if !pending.is_empty() && last_event.elapsed() >= DEBOUNCE {
+            let ids: Vec<String> = std::mem::take(&mut pending).into_iter().collect();
+            run_changed(config, args, overrides, state_path, ids);
+        }
+    }
+
+    signal::set_cancellable(false);
+    out::info("Stopped watching.");
+    Ok(())
+}
+
+/// 对变化的壁纸执行一次增量流水线
+///
+/// 已记录在 state.json 中、且之后有文件更新的壁纸重新处理，其余交给增量跳过
+fn run_changed(
+    config: &native::RuntimeConfig,
+    args: &AutoArgs,
+    overrides: &pipeline::PipelineOverrides,
+    state_path: &Path,
+    ids: Vec<String>,
+) {
+    let ids: Vec<String> = ids
+        .into_iter()
+        .filter(|id| config.workshop_path.join(id).is_dir())
+        .collect();
+    if ids.is_empty() {
+        return;
+    }
+
+    let state = native::load_state(native::LoadStateInput {
+        state_path: state_path.to_path_buf(),
+    })
+    .state
+    .unwrap_or_default();
+    let reprocess = select_reprocess(&config.workshop_path, &ids, &state);
+
+    out::info(&format!("Change detected: {}", ids.join(", ")));
+    let result = pipeline::run_pipeline(pipeline::RunPipelineInput {
+        config: config.clone(),
+        state_path: state_path.to_path_buf(),
+        wallpaper_ids: Some(ids),
+        reprocess_ids: (!reprocess.is_empty()).then_some(reprocess),
+        type_filter: args.type_filter.clone(),
+        tag_filter: args.tag_filter.clone(),
+        overrides: Some(overrides.clone()),
+        progress_callback: None,
+        debug_logger: None,
+        item_callback: None,
+        cancel: Some(signal::cancel_flag()),
+    });
+
+    let stats = &result.stats;
+    let summary = format!(
+        "{} processed, {} skipped, {} PKG unpacked, {} TEX converted ({:.1}s)",
+        stats.wallpapers_processed,
+        stats.wallpapers_skipped,
+        stats.pkgs_unpacked,
+        stats.texs_converted,
+        stats.elapsed_ms as f64 / 1000.0
+    );
+    if result.cancelled {
+        out::warning(&format!("{} (cancelled, progress saved)", summary));
+    } else if result.success {
+        out::success(&summary);
+    } else {
+        out::error(&format!(
+            "{}: {}",
+            summary,
+            result.error.as_deref().unwrap_or("Pipeline failed")
+        ));
+    }
+}
+
+/// ids 中需要强制重新处理的壁纸：已记录在 state.json 中、且之后有文件更新
+///
+/// 未记录的壁纸不在此列，交给增量流水线按新壁纸处理
+fn select_reprocess(workshop: &Path, ids: &[String], state: &core_cfg::StateData) -> Vec<String> {
+    ids.iter()
+        .filter(|id| {
+            state
+                .processed_wallpapers
+                .iter()
+                .find(|w| &w.wallpaper_id == *id)
+                .is_some_and(|w| newest_mtime(&workshop.join(id)) >= w.processed_at)
+        })
+        .cloned()
+        .collect()
+}
+
+/// 事件路径所属的壁纸 ID（workshop 下的第一级目录名）
+fn wallpaper_id_of(workshop: &Path, path: &Path) -> Option<String> {
+    match path.strip_prefix(workshop).ok()?.components().next()? {
+        Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
+/// 目录下所有文件中最新的修改时间（Unix 时间戳）
+fn newest_mtime(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                newest_mtime(&path)
+            } else {
+                entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把文件的修改时间设为 Unix 时间戳 secs
+    fn set_mtime(path: &Path, secs: u64) {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_wallpaper_id_of() {
+        let workshop = Path::new("/ws/431960");
+        assert_eq!(
+            wallpaper_id_of(workshop, Path::new("/ws/431960/100001/scene.pkg")).as_deref(),
+            Some("100001")
+        );
+        assert_eq!(
+            wallpaper_id_of(workshop, Path::new("/ws/431960/100001")).as_deref(),
+            Some("100001")
+        );
+        // workshop 本身与其外部的路径不属于任何壁纸
+        assert_eq!(wallpaper_id_of(workshop, workshop), None);
+        assert_eq!(wallpaper_id_of(workshop, Path::new("/ws/other/1")), None);
+    }
+
+    #[test]
+    fn test_newest_mtime() {
+        let dir = std::env::temp_dir().join("lianpkg_watch_newest_mtime");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("scene/materials")).unwrap();
+        fs::write(dir.join("project.json"), b"{}").unwrap();
+        fs::write(dir.join("scene/materials/a.tex"), b"tex").unwrap();
+        set_mtime(&dir.join("project.json"), 1_000);
+        set_mtime(&dir.join("scene/materials/a.tex"), 2_000);
+
+        // 取嵌套子目录中的最新文件
+        assert_eq!(newest_mtime(&dir), 2_000);
+        assert_eq!(newest_mtime(&dir.join("missing")), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_select_reprocess() {
+        let workshop = std::env::temp_dir().join("lianpkg_watch_reprocess");
+        let _ = fs::remove_dir_all(&workshop);
+        for id in ["100001", "100002", "100003"] {
+            fs::create_dir_all(workshop.join(id)).unwrap();
+            let file = workshop.join(id).join("project.json");
+            fs::write(&file, b"{}").unwrap();
+            set_mtime(&file, 5_000);
+        }
+
+        let processed = |id: &str, processed_at| core_cfg::ProcessedWallpaper {
+            wallpaper_id: id.to_string(),
+            title: None,
+            process_type: core_cfg::WallpaperProcessType::PkgTex,
+            processed_at,
+            output_path: None,
+            stage: Default::default(),
+        };
+        let state = core_cfg::StateData {
+            processed_wallpapers: vec![processed("100001", 4_000), processed("100002", 6_000)],
+            ..Default::default()
+        };
+        let ids: Vec<String> = ["100001", "100002", "100003"].map(String::from).to_vec();
+
+        // 100001 处理后有更新；100002 处理后未变化；100003 尚未处理，交给增量流水线
+        assert_eq!(select_reprocess(&workshop, &ids, &state), ["100001"]);
+
+        // 处理时间与文件时间相同（同一秒内修改）也重新处理
+        let state = core_cfg::StateData {
+            processed_wallpapers: vec![processed("100002", 5_000)],
+            ..Default::default()
+        };
+        assert_eq!(select_reprocess(&workshop, &ids, &state), ["100002"]);
+
+        let _ = fs::remove_dir_all(&workshop);
+    }
+}
@@ -1,9 +1,43 @@
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use chrono::Local;
 
 static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
 static QUIET_MODE: AtomicBool = AtomicBool::new(false);
-static INDENT_LEVEL: AtomicUsize = AtomicUsize::new(0);
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+static JSON_MODE: AtomicBool = AtomicBool::new(false);
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+/// 串行化整行输出，并行处理时终端与日志文件中的行不会交错
+static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// 缩进层级按线程独立计数，并行任务的 indent/outdent 互不干扰
+    static INDENT_LEVEL: Cell<usize> = const { Cell::new(0) };
+}
+
+/// println! 的同时把该行写入 --log-file
+macro_rules! outln {
+    () => {
+        $crate::cli::logger::emit_line("", false)
+    };
+    ($($arg:tt)*) => {{
+        $crate::cli::logger::emit_line(&format!($($arg)*), false);
+    }};
+}
+
+/// eprintln! 的同时把该行写入 --log-file
+macro_rules! errln {
+    ($($arg:tt)*) => {{
+        $crate::cli::logger::emit_line(&format!($($arg)*), true);
+    }};
+}
+
+pub(crate) use errln;
+pub(crate) use outln;
 
 pub fn set_debug(debug: bool) {
     DEBUG_MODE.store(debug, Ordering::Relaxed);
@@ -21,70 +55,214 @@ pub fn is_quiet() -> bool {
     QUIET_MODE.load(Ordering::Relaxed)
 }
 
+pub fn set_assume_yes(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::Relaxed);
+}
+
+pub fn is_assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+pub fn set_json(json: bool) {
+    JSON_MODE.store(json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON_MODE.load(Ordering::Relaxed)
+}
+
+/// 打开 --log-file（追加写入），此后的终端输出同时写入该文件
+pub fn set_log_file(path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let args: Vec<String> = std::env::args().collect();
+    writeln!(
+        file,
+        "===== {} {} =====",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        args.join(" ")
+    )?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
+}
+
+/// 持锁输出一整行到 stdout / stderr，并写入日志文件
+pub fn emit_line(line: &str, stderr: bool) {
+    let _guard = OUTPUT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if stderr {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+    tee_line(line);
+}
+
+/// 把一行输出去除 ANSI 后写入日志文件，逐行刷新以便崩溃时保留日志
+pub fn tee_line(line: &str) {
+    let mut guard = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(file) = guard.as_mut() {
+        let _ = writeln!(file, "{}", super::output::strip_ansi(line));
+        let _ = file.flush();
+    }
+}
+
+/// --debug 与 --json 同时开启时，日志改为逐行 JSON 输出到 stderr
+pub fn is_json_log() -> bool {
+    is_debug() && is_json()
+}
+
+/// 输出一条结构化日志：{"ts","level","func","args","msg","depth"}
+///
+/// depth 为当前线程的 INDENT_LEVEL，代替文本模式下的缩进空格
+pub fn json_log(level: &str, func: Option<&str>, args: Option<&str>, msg: &str) {
+    let record = serde_json::json!({
+        "ts": Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        "level": level,
+        "func": func,
+        "args": args,
+        "msg": msg,
+        "depth": INDENT_LEVEL.with(Cell::get),
+    });
+    errln!("{}", record);
+}
+
 #[allow(dead_code)]
 pub fn indent() {
-    INDENT_LEVEL.fetch_add(1, Ordering::Relaxed);
+    INDENT_LEVEL.with(|level| level.set(level.get() + 1));
 }
 
 #[allow(dead_code)]
 pub fn outdent() {
-    INDENT_LEVEL.fetch_sub(1, Ordering::Relaxed);
+    INDENT_LEVEL.with(|level| level.set(level.get().saturating_sub(1)));
 }
 
 fn get_indent_str() -> String {
-    let level = INDENT_LEVEL.load(Ordering::Relaxed);
-    "  ".repeat(level)
+    "  ".repeat(INDENT_LEVEL.with(Cell::get))
 }
 
 #[allow(dead_code)]
 pub fn title(msg: &str) {
     if !is_debug() {
-        println!("\n=== {} ===", msg);
+        outln!("\n=== {} ===", msg);
     } else {
         let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        println!("[{}] === {} ===", time, msg);
+        outln!("[{}] === {} ===", time, msg);
     }
 }
 
 #[allow(dead_code)]
 pub fn info(msg: &str) {
+    if is_json_log() {
+        return json_log("info", None, None, msg);
+    }
     let indent = get_indent_str();
     if !is_debug() {
-        println!("{}ℹ️  {}", indent, msg);
+        outln!("{}ℹ️  {}", indent, msg);
     } else {
         let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        println!("[{}] [INFO] {}{}", time, indent, msg);
+        outln!("[{}] [INFO] {}{}", time, indent, msg);
     }
 }
 
 #[allow(dead_code)]
 pub fn success(msg: &str) {
+    if is_json_log() {
+        return json_log("success", None, None, msg);
+    }
     let indent = get_indent_str();
     if !is_debug() {
-        println!("{}✅ {}", indent, msg);
+        outln!("{}✅ {}", indent, msg);
     } else {
         let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        println!("[{}] [SUCCESS] {}{}", time, indent, msg);
+        outln!("[{}] [SUCCESS] {}{}", time, indent, msg);
     }
 }
 
 #[allow(dead_code)]
 pub fn error(msg: &str) {
+    if is_json_log() {
+        return json_log("error", None, None, msg);
+    }
     let indent = get_indent_str();
     if !is_debug() {
-        eprintln!("{}❌ {}", indent, msg);
+        errln!("{}❌ {}", indent, msg);
     } else {
         let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        eprintln!("[{}] [ERROR] {}{}", time, indent, msg);
+        errln!("[{}] [ERROR] {}{}", time, indent, msg);
     }
 }
 
 #[allow(dead_code)]
 pub fn debug(func_name: &str, args: &str, msg: &str) {
-    if is_debug() {
+    if is_json_log() {
+        json_log("debug", Some(func_name), Some(args), msg);
+    } else if is_debug() {
         let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
         let indent = get_indent_str();
-        println!("<- [DEBUG] [{}] {}[Func: {}] [Args: {}] {}", time, indent, func_name, args, msg);
+        outln!("<- [DEBUG] [{}] {}[Func: {}] [Args: {}] {}", time, indent, func_name, args, msg);
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试期间把 --log-file 指向 path，离开作用域（包括 panic）时恢复原来的日志文件
+    struct LogFileGuard(Option<File>);
+
+    impl LogFileGuard {
+        fn install(path: &Path) -> Self {
+            let previous = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).take();
+            let guard = LogFileGuard(previous);
+            set_log_file(path).unwrap();
+            guard
+        }
+    }
+
+    impl Drop for LogFileGuard {
+        fn drop(&mut self) {
+            *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = self.0.take();
+        }
+    }
+
+    #[test]
+    fn test_concurrent_logging_keeps_lines_intact() {
+        let path = std::env::temp_dir().join("lianpkg_logger_threads.log");
+        let _ = std::fs::remove_file(&path);
+        let log_file = LogFileGuard::install(&path);
+
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    for _ in 0..t % 3 {
+                        indent();
+                    }
+                    for i in 0..50 {
+                        info(&format!("thread {} line {}", t, i));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(log_file);
+
+        // 跳过运行头；每行完整且缩进只取决于所在线程
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().skip(1).collect();
+        assert_eq!(lines.len(), 400);
+        for line in lines {
+            let msg = line.trim_start_matches(' ');
+            let parts: Vec<&str> = msg.split(' ').filter(|p| !p.is_empty()).collect();
+            assert_eq!(parts.len(), 5, "{:?}", line);
+            assert_eq!(parts[1], "thread");
+            let t: usize = parts[2].parse().unwrap();
+            assert_eq!(line.len() - msg.len(), 2 * (t % 3), "{:?}", line);
+        }
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -6,6 +6,7 @@ pub mod args;
 pub mod handlers;
 pub mod logger;
 pub mod output;
+pub mod signal;
 
 use args::{Cli, Command};
 use clap::Parser;
@@ -16,12 +17,29 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     // 设置调试模式
     logger::set_debug(cli.debug);
+    // 设置全局免确认
+    logger::set_assume_yes(cli.yes);
+    // 设置 JSON 输出模式
+    logger::set_json(cli.json);
+    // 打开日志文件
+    if let Some(ref path) = cli.log_file {
+        logger::set_log_file(path)
+            .map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))?;
+    }
+
+    // 安装失败时保持默认的 Ctrl+C 行为（直接终止）
+    let _ = signal::install();
 
     // 获取配置路径
     let config_path = cli.config.clone();
     // 保存一份用于最后显示
     let config_path_for_display = config_path.clone();
 
+    // 补全脚本只写 stdout，不经过 JSON 结果与 Windows 退出确认
+    if let Some(Command::Completions(ref args)) = cli.command {
+        return Ok(handlers::completions::run(args)?);
+    }
+
     // 分发命令
     let result = match cli.command {
         Some(Command::Wallpaper(ref args)) => handlers::wallpaper::run(args, config_path),
@@ -30,12 +48,18 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         Some(Command::Auto(ref args)) => handlers::auto::run(args, config_path),
         Some(Command::Config(ref args)) => handlers::config::run(args, config_path),
         Some(Command::Status(ref args)) => handlers::status::run(args, config_path),
+        Some(Command::Deps(ref args)) => handlers::deps::run(args),
+        Some(Command::Archive(ref args)) => handlers::archive::run(args, config_path),
+        Some(Command::Summary(ref args)) => handlers::summary::run(args, config_path),
+        Some(Command::Clean(ref args)) => handlers::clean::run(args, config_path),
+        // 已在上方提前返回
+        Some(Command::Completions(_)) => unreachable!("completions is handled before dispatch"),
         None => {
             // Windows 下无参数时，默认执行 auto 模式
             #[cfg(target_os = "windows")]
             {
                 output::info("No command specified, running in auto mode...");
-                println!();
+                logger::outln!();
                 let auto_args = args::AutoArgs::default();
                 handlers::auto::run(&auto_args, config_path)
             }
@@ -43,8 +67,9 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             {
                 // Linux 下显示帮助
                 use clap::CommandFactory;
-                let _ = Cli::command().print_help();
-                println!();
+                let help = Cli::command().render_help();
+                logger::outln!("{}", help);
+                logger::outln!();
                 Ok(())
             }
         }
@@ -52,11 +77,30 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     // 处理错误
     if let Err(ref e) = result {
-        output::error(e);
-        std::process::exit(1);
+        if !output::is_json() {
+            output::error(e);
+        } else if !output::json_emitted() {
+            // 已输出的 JSON 结果中包含错误信息时不再重复输出
+            output::json(&serde_json::json!({ "success": false, "error": e }));
+        }
+        std::process::exit(if signal::is_cancelled() {
+            signal::EXIT_CANCELLED
+        } else {
+            1
+        });
+    }
+
+    // JSON 模式下没有专门结果的命令输出统一的成功标记
+    if output::is_json() && !output::json_emitted() {
+        output::json(&serde_json::json!({ "success": true }));
     }
 
     // Windows 下等待用户确认（显示配置文件路径）
     output::press_enter_to_exit_with_config(config_path_for_display.as_deref());
+
+    // Ctrl+C 取消：处理器已输出完成情况并保存状态，以独立的退出码结束
+    if signal::is_cancelled() {
+        std::process::exit(signal::EXIT_CANCELLED);
+    }
     Ok(())
 }
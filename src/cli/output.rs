@@ -2,8 +2,10 @@
 //!
 //! 提供美化的终端输出，支持表格、颜色、Box 等
 
-use super::logger;
+use super::logger::{self, errln, outln};
+use serde::Serialize;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
@@ -38,7 +40,7 @@ fn char_width(c: char) -> usize {
 }
 
 /// 去除字符串中的 ANSI 转义序列
-fn strip_ansi(s: &str) -> String {
+pub(crate) fn strip_ansi(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars().peekable();
 
@@ -101,7 +103,6 @@ fn truncate_str(s: &str, max_width: usize) -> String {
 }
 
 /// 按显示宽度填充字符串（右侧补空格）
-#[allow(dead_code)]
 fn pad_str(s: &str, width: usize) -> String {
     let current_width = display_width(s);
     if current_width >= width {
@@ -158,9 +159,46 @@ fn colorize(text: &str, code: &str) -> String {
 // 基础输出函数
 // ============================================================================
 
-/// 检查是否为 quiet 模式
+/// 检查是否为 quiet 模式（JSON 模式下同样不输出装饰内容）
 pub fn is_quiet() -> bool {
-    logger::is_quiet()
+    logger::is_quiet() || logger::is_json()
+}
+
+/// 检查是否为 JSON 输出模式（全局 --json）
+pub fn is_json() -> bool {
+    logger::is_json()
+}
+
+/// 本次运行是否已输出过 JSON 结果
+static JSON_EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// 将结果序列化为 JSON 输出到 stdout（--json 模式下每个命令输出一次）
+pub fn json<T: Serialize>(value: &T) {
+    JSON_EMITTED.store(true, Ordering::Relaxed);
+    use std::io::Write;
+    match serde_json::to_string_pretty(value) {
+        // 下游提前关闭管道（如 `| head`）时忽略写入错误
+        Ok(text) => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", text);
+            let _ = stdout.flush();
+            logger::tee_line(&text);
+        }
+        Err(e) => errln!("Failed to serialize JSON: {}", e),
+    }
+}
+
+/// 是否已通过 `json` 输出过结果
+pub fn json_emitted() -> bool {
+    JSON_EMITTED.load(Ordering::Relaxed)
+}
+
+/// 输出空行 (JSON 模式下不输出)
+pub fn blank() {
+    if is_json() {
+        return;
+    }
+    outln!();
 }
 
 /// 输出标题 (quiet 模式下不输出)
@@ -170,16 +208,16 @@ pub fn title(text: &str) {
     }
     let text_width = display_width(text);
     let line = "═".repeat(text_width + 4);
-    println!();
-    println!("{}", colorize(&line, color::CYAN));
-    println!(
+    outln!();
+    outln!("{}", colorize(&line, color::CYAN));
+    outln!(
         "{}",
         colorize(
             &format!("  {}  ", text),
             &format!("{}{}", color::BOLD, color::CYAN)
         )
     );
-    println!("{}", colorize(&line, color::CYAN));
+    outln!("{}", colorize(&line, color::CYAN));
 }
 
 /// 输出子标题 (quiet 模式下不输出)
@@ -187,8 +225,8 @@ pub fn subtitle(text: &str) {
     if is_quiet() {
         return;
     }
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}  {}",
         colorize("▶", color::BLUE),
         colorize(text, color::BOLD)
@@ -200,21 +238,31 @@ pub fn info(text: &str) {
     if is_quiet() {
         return;
     }
-    println!("  {}  {}", colorize("ℹ", color::BLUE), text);
+    outln!("  {}  {}", colorize("ℹ", color::BLUE), text);
 }
 
-/// 输出成功 (quiet 模式下仍然输出)
+/// 输出成功 (quiet 模式下仍然输出，JSON 模式下不输出)
 pub fn success(text: &str) {
-    println!(
+    if is_json() {
+        return;
+    }
+    outln!(
         "  {}  {}",
         colorize("✓", color::GREEN),
         colorize(text, color::GREEN)
     );
 }
 
-/// 输出警告 (quiet 模式下仍然输出)
+/// 输出警告 (quiet 模式下仍然输出，JSON 模式下改写到 stderr)
 pub fn warning(text: &str) {
-    println!(
+    if logger::is_json_log() {
+        return logger::json_log("warning", None, None, text);
+    }
+    if is_json() {
+        errln!("  ⚠  {}", text);
+        return;
+    }
+    outln!(
         "  {}  {}",
         colorize("⚠", color::YELLOW),
         colorize(text, color::YELLOW)
@@ -223,7 +271,10 @@ pub fn warning(text: &str) {
 
 /// 输出错误 (quiet 模式下仍然输出)
 pub fn error(text: &str) {
-    eprintln!(
+    if logger::is_json_log() {
+        return logger::json_log("error", None, None, text);
+    }
+    errln!(
         "  {}  {}",
         colorize("✗", color::RED),
         colorize(text, color::RED)
@@ -233,8 +284,10 @@ pub fn error(text: &str) {
 /// 输出调试信息（仅在 debug 模式下）
 #[allow(dead_code)]
 pub fn debug(text: &str) {
-    if logger::is_debug() {
-        println!(
+    if logger::is_json_log() {
+        logger::json_log("debug", None, None, text);
+    } else if logger::is_debug() {
+        outln!(
             "  {}  {}",
             colorize("⋯", color::DIM),
             colorize(text, color::DIM)
@@ -244,10 +297,12 @@ pub fn debug(text: &str) {
 
 /// 输出详细调试信息（带时间戳，仅 debug 模式）
 pub fn debug_verbose(label: &str, text: &str) {
-    if logger::is_debug() {
+    if logger::is_json_log() {
+        logger::json_log("debug", Some(label), None, text);
+    } else if logger::is_debug() {
         use chrono::Local;
         let time = Local::now().format("%H:%M:%S%.3f");
-        println!(
+        outln!(
             "  {}  [{}] {}: {}",
             colorize("⋯", color::DIM),
             time,
@@ -261,10 +316,13 @@ pub fn debug_verbose(label: &str, text: &str) {
 /// 格式: [17:23:45.123] API → module::function(args)
 #[allow(dead_code)]
 pub fn debug_api_enter(module: &str, function: &str, args: &str) {
-    if logger::is_debug() {
+    if logger::is_json_log() {
+        let func = format!("{}::{}", module, function);
+        logger::json_log("debug", Some(&func), Some(args), "enter");
+    } else if logger::is_debug() {
         use chrono::Local;
         let time = Local::now().format("%H:%M:%S%.3f");
-        println!(
+        outln!(
             "[{}] {} → {}::{}({})",
             colorize(&time.to_string(), color::DIM),
             colorize("API", color::MAGENTA),
@@ -279,10 +337,12 @@ pub fn debug_api_enter(module: &str, function: &str, args: &str) {
 /// 格式: [17:23:45.456] API ← result_summary
 #[allow(dead_code)]
 pub fn debug_api_return(result: &str) {
-    if logger::is_debug() {
+    if logger::is_json_log() {
+        logger::json_log("debug", None, None, result);
+    } else if logger::is_debug() {
         use chrono::Local;
         let time = Local::now().format("%H:%M:%S%.3f");
-        println!(
+        outln!(
             "[{}] {} ← {}",
             colorize(&time.to_string(), color::DIM),
             colorize("API", color::MAGENTA),
@@ -294,10 +354,12 @@ pub fn debug_api_return(result: &str) {
 /// API 调用追踪 - 错误 (仅 debug 模式)
 #[allow(dead_code)]
 pub fn debug_api_error(error: &str) {
-    if logger::is_debug() {
+    if logger::is_json_log() {
+        logger::json_log("error", None, None, error);
+    } else if logger::is_debug() {
         use chrono::Local;
         let time = Local::now().format("%H:%M:%S%.3f");
-        eprintln!(
+        errln!(
             "[{}] {} ✗ {}",
             colorize(&time.to_string(), color::DIM),
             colorize("API", color::MAGENTA),
@@ -332,7 +394,7 @@ pub fn path_info(label: &str, path: &Path) {
     if is_quiet() {
         return;
     }
-    println!(
+    outln!(
         "  {}  {}: {}",
         colorize("📁", color::BLUE),
         colorize(label, color::DIM),
@@ -359,7 +421,7 @@ pub fn table_row(cols: &[(&str, usize)]) {
             format!("{}{}", s, " ".repeat(padding))
         })
         .collect();
-    println!("  {}", formatted.join("  "));
+    outln!("  {}", formatted.join("  "));
 }
 
 /// 表格分隔线 (quiet 模式下不输出)
@@ -372,7 +434,7 @@ pub fn table_separator(widths: &[usize]) {
         .map(|w| "─".repeat(*w))
         .collect::<Vec<_>>()
         .join("──");
-    println!("  {}", colorize(&line, color::DIM));
+    outln!("  {}", colorize(&line, color::DIM));
 }
 
 /// 表格标题行 (quiet 模式下不输出)
@@ -384,7 +446,7 @@ pub fn table_header(cols: &[(&str, usize)]) {
         .iter()
         .map(|(text, width)| format!("{:width$}", text, width = width))
         .collect();
-    println!("  {}", colorize(&formatted.join("  "), color::BOLD));
+    outln!("  {}", colorize(&formatted.join("  "), color::BOLD));
 
     let widths: Vec<usize> = cols.iter().map(|(_, w)| *w).collect();
     table_separator(&widths);
@@ -414,7 +476,7 @@ pub fn box_start(title: &str) {
     let padding_count = (BOX_INNER_WIDTH + 2).saturating_sub(used + 1); // -1 for ┐
     let padding = "─".repeat(padding_count);
 
-    println!(
+    outln!(
         "{}",
         colorize(
             &format!("{}{}{}{}┐", prefix, title, suffix, padding),
@@ -450,7 +512,7 @@ pub fn box_line(label: &str, value: &str) {
     let content_width = display_width(&label_part) + value_width;
     let right_padding = " ".repeat(BOX_INNER_WIDTH.saturating_sub(content_width));
 
-    println!(
+    outln!(
         "{} {}{} {}",
         colorize("│", color::CYAN),
         content,
@@ -466,7 +528,7 @@ pub fn box_end() {
     }
     // 格式: └──────────────────────────────────────────────────┘
     let inner = "─".repeat(BOX_INNER_WIDTH);
-    println!("{}", colorize(&format!("└{}┘", inner), color::CYAN));
+    outln!("{}", colorize(&format!("└{}┘", inner), color::CYAN));
 }
 
 // ============================================================================
@@ -505,10 +567,17 @@ pub fn progress(label: &str, current: usize, total: usize) {
     render_progress(label, current, total);
 }
 
+/// 进度条标签的显示宽度（固定宽度，避免标签变短时残留上一次的字符）
+const PROGRESS_LABEL_WIDTH: usize = 40;
+
 /// 内部渲染进度条（不更新状态）
 fn render_progress(label: &str, current: usize, total: usize) {
+    let label = pad_str(
+        &truncate_str(label, PROGRESS_LABEL_WIDTH),
+        PROGRESS_LABEL_WIDTH,
+    );
     let bar = progress_bar(current, total, 20);
-    let percent = if total > 0 { current * 100 / total } else { 0 };
+    let percent = (current * 100).checked_div(total).unwrap_or(0);
     print!(
         "\r  {}  {} [{}] {}%  ",
         colorize("⏳", color::YELLOW),
@@ -546,19 +615,36 @@ pub fn stat(label: &str, value: impl std::fmt::Display) {
     if is_quiet() {
         return;
     }
-    println!(
+    outln!(
         "  {:20} {}",
         colorize(&format!("{}:", label), color::DIM),
         colorize(&value.to_string(), color::BOLD)
     );
 }
 
+/// 输出格式分布，按数量降序 (quiet 模式下不输出)
+pub fn format_distribution(counts: &std::collections::BTreeMap<String, usize>) {
+    let total: usize = counts.values().sum();
+    let mut entries: Vec<_> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (format, count) in entries {
+        stat(
+            format,
+            format!(
+                "{} ({:.1}%)",
+                count,
+                *count as f64 * 100.0 / total.max(1) as f64
+            ),
+        );
+    }
+}
+
 /// 输出带图标的统计项 (quiet 模式下不输出)
 pub fn stat_icon(icon: &str, label: &str, value: impl std::fmt::Display) {
     if is_quiet() {
         return;
     }
-    println!(
+    outln!(
         "  {}  {:18} {}",
         colorize(icon, color::CYAN),
         colorize(&format!("{}:", label), color::DIM),
@@ -579,7 +665,7 @@ pub fn option_bool(label: &str, enabled: bool) {
     } else {
         (colorize("✗", color::DIM), colorize("disabled", color::DIM))
     };
-    println!(
+    outln!(
         "  {}  {:18} {}",
         icon,
         colorize(&format!("{}:", label), color::DIM),
@@ -592,7 +678,7 @@ pub fn step(num: usize, text: &str) {
     if is_quiet() {
         return;
     }
-    println!(
+    outln!(
         "  {}  {}",
         colorize(&format!("[{}]", num), color::MAGENTA),
         text
@@ -604,8 +690,8 @@ pub fn subtitle_icon(icon: &str, text: &str) {
     if is_quiet() {
         return;
     }
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}  {}",
         colorize(icon, color::BLUE),
         colorize(text, color::BOLD)
@@ -613,6 +699,9 @@ pub fn subtitle_icon(icon: &str, text: &str) {
 }
 
 /// 输出带单位的大小
+///
+/// 全程序统一的大小格式化入口，按 1024 进制换算（1 KB = 1024 B），
+/// 任何界面显示字节数都应使用此函数，避免同一数据在不同位置显示不同数值
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
@@ -637,7 +726,10 @@ pub fn format_size(bytes: u64) -> String {
 /// 格式: LianPkg v0.4.3 | 36 wallpapers | ~2.5 GB estimated
 #[allow(dead_code)]
 pub fn quiet_summary(version: &str, wallpaper_count: usize, estimated_size: u64) {
-    println!(
+    if is_json() {
+        return;
+    }
+    outln!(
         "LianPkg {} | {} wallpapers | ~{} estimated",
         version,
         wallpaper_count,
@@ -648,16 +740,24 @@ pub fn quiet_summary(version: &str, wallpaper_count: usize, estimated_size: u64)
 /// Quiet 模式下的路径输出 (始终输出)
 #[allow(dead_code)]
 pub fn quiet_path(label: &str, path: &Path) {
-    println!("{}: {}", label, path.display());
+    if is_json() {
+        return;
+    }
+    outln!("{}: {}", label, path.display());
 }
 
 /// Quiet 模式下的结果输出 (始终输出)
 /// 格式: Done in 45.2s (21 PKG → 156 images)
 #[allow(dead_code)]
 pub fn quiet_result(duration_secs: f64, pkg_count: usize, image_count: usize) {
-    println!(
+    if is_json() {
+        return;
+    }
+    outln!(
         "Done in {:.1}s ({} PKG → {} images)",
-        duration_secs, pkg_count, image_count
+        duration_secs,
+        pkg_count,
+        image_count
     );
 }
 
@@ -665,11 +765,29 @@ pub fn quiet_result(duration_secs: f64, pkg_count: usize, image_count: usize) {
 // 确认提示
 // ============================================================================
 
-/// 请求用户确认
+/// 请求用户确认（全局 --yes 时直接返回 true）
+///
+/// JSON 模式下提示写到 stderr，保持 stdout 只有 JSON 结果
 pub fn confirm(prompt: &str) -> bool {
     use std::io::Write;
-    print!("  {} {} [y/N]: ", colorize("?", color::YELLOW), prompt);
-    let _ = std::io::stdout().flush();
+    let mut writer: Box<dyn Write> = if is_json() {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    };
+    let _ = write!(
+        writer,
+        "  {} {} [y/N]: ",
+        colorize("?", color::YELLOW),
+        prompt
+    );
+
+    // 全局 --yes：直接确认
+    if logger::is_assume_yes() {
+        let _ = writeln!(writer, "y");
+        return true;
+    }
+    let _ = writer.flush();
 
     let mut input = String::new();
     if std::io::stdin().read_line(&mut input).is_err() {
@@ -683,8 +801,11 @@ pub fn confirm(prompt: &str) -> bool {
 #[cfg(windows)]
 pub fn press_enter_to_exit_with_config(config_path: Option<&Path>) {
     use std::io::Write;
+    if is_json() {
+        return;
+    }
     if let Some(path) = config_path {
-        println!("\n  配置文件路径: {}", path.display());
+        outln!("\n  配置文件路径: {}", path.display());
     }
     print!("\n  Press Enter to exit...");
     let _ = std::io::stdout().flush();
@@ -722,6 +843,46 @@ pub fn tex_badge(is_tex: bool) -> String {
     }
 }
 
+/// 缺失标记
+pub fn missing_badge(missing: bool) -> String {
+    if missing {
+        colorize("[MISSING]", color::RED)
+    } else {
+        String::new()
+    }
+}
+
+/// 路径状态标记：✓ 存在 / ✗ 缺失 / 🔒 不可写
+///
+/// `output` 为 true 时按输出目录判断：缺失但可创建只提示会自动创建
+pub fn path_status_badge(exists: bool, writable: bool, output: bool) -> String {
+    match (exists, writable, output) {
+        (true, _, false) | (true, true, true) => colorize("✓", color::GREEN),
+        (true, false, true) => colorize("🔒 not writable", color::RED),
+        (false, true, true) => colorize("✗ missing (will be created)", color::YELLOW),
+        (false, false, true) => colorize("🔒 cannot be created", color::RED),
+        (false, _, false) => colorize("✗ missing", color::RED),
+    }
+}
+
+/// LZ4 压缩条目标记
+pub fn compressed_badge(compressed: bool) -> String {
+    if compressed {
+        colorize(" [LZ4]", color::CYAN)
+    } else {
+        String::new()
+    }
+}
+
+/// 重复条目标记
+pub fn duplicate_badge(duplicate: bool) -> String {
+    if duplicate {
+        colorize(" [DUP]", color::YELLOW)
+    } else {
+        String::new()
+    }
+}
+
 /// 类型标记
 #[allow(dead_code)]
 pub fn type_badge(wallpaper_type: &str) -> String {
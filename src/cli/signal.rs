@@ -0,0 +1,45 @@
+//! Ctrl+C 处理
+//!
+//! 可取消的操作（auto 流水线、watch 监听）运行期间，第一次 Ctrl+C 只置位取消标志，
+//! 由流水线处理完当前项、保存 state.json 后返回；其余时间或再次按下时立即退出
+
+use super::logger::errln;
+use lianpkg::api::native::CancelFlag;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// 因 Ctrl+C 取消而退出时的退出码（128 + SIGINT）
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// 全局取消标志
+static CANCEL: OnceLock<CancelFlag> = OnceLock::new();
+
+/// 当前是否处于可取消的操作中
+static CANCELLABLE: AtomicBool = AtomicBool::new(false);
+
+/// 安装 Ctrl+C 处理器（进程内只能安装一次）
+pub fn install() -> Result<(), String> {
+    ctrlc::set_handler(|| {
+        if CANCELLABLE.load(Ordering::SeqCst) && !cancel_flag().swap(true, Ordering::SeqCst) {
+            errln!("\n  Cancelling after the current item... (press Ctrl+C again to quit now)");
+            return;
+        }
+        std::process::exit(EXIT_CANCELLED);
+    })
+    .map_err(|e| format!("Failed to install Ctrl+C handler: {}", e))
+}
+
+/// 取消标志，传给支持取消的 native 接口
+pub fn cancel_flag() -> CancelFlag {
+    CANCEL.get_or_init(CancelFlag::default).clone()
+}
+
+/// 标记进入 / 离开可取消的操作
+pub fn set_cancellable(enabled: bool) {
+    CANCELLABLE.store(enabled, Ordering::SeqCst);
+}
+
+/// 是否已请求取消
+pub fn is_cancelled() -> bool {
+    cancel_flag().load(Ordering::SeqCst)
+}
@@ -4,8 +4,9 @@ use std::fs;
 use toml::Value;
 
 use crate::core::cfg::structs::{
-    CreateConfigInput, CreateConfigOutput, DeleteConfigInput, DeleteConfigOutput, ReadConfigInput,
-    ReadConfigOutput, UpdateConfigInput, UpdateConfigOutput,
+    BackupConfigInput, BackupConfigOutput, CreateConfigInput, CreateConfigOutput,
+    DeleteConfigInput, DeleteConfigOutput, ReadConfigInput, ReadConfigOutput, UpdateConfigInput,
+    UpdateConfigOutput,
 };
 use crate::core::cfg::utl::{default_config_template, ensure_dir};
 use crate::core::error::{CoreError, CoreResult};
@@ -120,6 +121,50 @@ pub fn delete_config_toml(input: DeleteConfigInput) -> CoreResult<DeleteConfigOu
     })
 }
 
+/// 备份配置文件
+/// 写入同目录下的 `config.toml.bak-YYYYMMDD-HHMMSS`，同一秒内重复备份时追加序号
+pub fn backup_config(input: BackupConfigInput) -> CoreResult<BackupConfigOutput> {
+    let path = input.path;
+    let content = read_config_toml(ReadConfigInput { path: path.clone() })?.content;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config.toml".to_string());
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let base = format!("{}.bak-{}", file_name, stamp);
+
+    let mut backup_path = path.with_file_name(&base);
+    let mut n = 1;
+    while backup_path.exists() {
+        backup_path = path.with_file_name(format!("{}-{}", base, n));
+        n += 1;
+    }
+
+    fs::write(&backup_path, content)
+        .map_err(|e| CoreError::io_with_path(e.to_string(), backup_path.display().to_string()))?;
+
+    Ok(BackupConfigOutput { backup_path })
+}
+
+/// 深度合并两份 TOML
+/// overlay 中的表逐键合并进 base，其余值（含数组）整体覆盖；用于 default.toml + config.toml 分层
+pub fn merge_toml_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base_table), Value::Table(overlay_table)) => {
+            for (key, value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(existing) => merge_toml_values(existing, value),
+                    None => {
+                        base_table.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 /// 设置嵌套键的值
 /// 支持点号分隔的键路径
 fn set_nested_value(root: &mut Value, keys: &[&str], new_value: &str) -> bool {
@@ -174,3 +219,99 @@ fn parse_value_string(s: &str) -> Value {
     // 默认作为字符串
     Value::String(s.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::CoreErrorKind;
+
+    #[test]
+    fn test_config_error_kinds() {
+        let dir = std::env::temp_dir().join("lianpkg_config_error_kinds");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let update = |key: &str| {
+            update_config_toml(UpdateConfigInput {
+                path: path.clone(),
+                key: key.to_string(),
+                value: "1".to_string(),
+            })
+        };
+
+        let err = read_config_toml(ReadConfigInput { path: path.clone() }).unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::NotFound);
+
+        fs::write(&path, "[wallpaper").unwrap();
+        assert_eq!(update("a").unwrap_err().kind(), CoreErrorKind::Parse);
+
+        // 中间键不是表时无法写入
+        fs::write(&path, "a = 1\n").unwrap();
+        assert_eq!(update("a.b").unwrap_err().kind(), CoreErrorKind::Validation);
+
+        let err = backup_config(BackupConfigInput {
+            path: dir.join("missing.toml"),
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::NotFound);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_toml_values_deep() {
+        let mut base: Value = toml::from_str(
+            "[wallpaper]\nworkshop_path = \"/team/ws\"\nenable_raw_output = true\n\
+             [unpack]\nonly_types = [\"scene\", \"video\"]\n",
+        )
+        .unwrap();
+        let overlay: Value = toml::from_str(
+            "[wallpaper]\nenable_raw_output = false\n\
+             [unpack]\nonly_types = [\"scene\"]\n[pipeline]\nincremental = true\n",
+        )
+        .unwrap();
+
+        merge_toml_values(&mut base, overlay);
+
+        assert_eq!(
+            base["wallpaper"]["workshop_path"].as_str(),
+            Some("/team/ws")
+        );
+        assert_eq!(
+            base["wallpaper"]["enable_raw_output"].as_bool(),
+            Some(false)
+        );
+        assert_eq!(base["unpack"]["only_types"].as_array().unwrap().len(), 1);
+        assert_eq!(base["pipeline"]["incremental"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn test_backup_config_keeps_content() {
+        let dir = std::env::temp_dir().join("lianpkg_backup_config");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "# my paths\n[wallpaper]\n").unwrap();
+
+        let first = backup_config(BackupConfigInput { path: path.clone() }).unwrap();
+        let second = backup_config(BackupConfigInput { path: path.clone() }).unwrap();
+
+        assert_ne!(first.backup_path, second.backup_path);
+        for backup in [&first.backup_path, &second.backup_path] {
+            assert_eq!(backup.parent(), Some(dir.as_path()));
+            let name = backup.file_name().unwrap().to_string_lossy();
+            assert!(name.starts_with("config.toml.bak-"), "{}", name);
+            assert_eq!(
+                fs::read_to_string(backup).unwrap(),
+                "# my paths\n[wallpaper]\n"
+            );
+        }
+
+        let missing = backup_config(BackupConfigInput {
+            path: dir.join("absent.toml"),
+        });
+        assert!(missing.is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,8 @@
 //! cfg 模块 - 配置文件与状态文件的 CRUD 操作
 //!
-//! 本模块提供 9 个核心接口：
-//! - config.toml: create_config_toml, read_config_toml, update_config_toml, delete_config_toml
-//! - state.json: create_state_json, read_state_json, write_state_json, delete_state_json
+//! 本模块提供 13 个核心接口：
+//! - config.toml: create_config_toml, read_config_toml, update_config_toml, delete_config_toml, backup_config, merge_toml_values, layered_config_template
+//! - state.json: create_state_json, read_state_json, write_state_json, delete_state_json, migrate_state
 //! - 清理: clear_lianpkg
 
 mod structs;  // 结构体定义
@@ -24,12 +24,16 @@ pub use structs::UpdateConfigInput;
 pub use structs::UpdateConfigOutput;
 pub use structs::DeleteConfigInput;
 pub use structs::DeleteConfigOutput;
+pub use structs::BackupConfigInput;
+pub use structs::BackupConfigOutput;
 
 // State.json 相关结构体
 pub use structs::StateData;
+pub use structs::ProcessStage;
 pub use structs::ProcessedWallpaper;
 pub use structs::WallpaperProcessType;
 pub use structs::StateStatistics;
+pub use structs::EstimateRecord;
 pub use structs::CreateStateInput;
 pub use structs::CreateStateOutput;
 pub use structs::ReadStateInput;
@@ -38,6 +42,9 @@ pub use structs::WriteStateInput;
 pub use structs::WriteStateOutput;
 pub use structs::DeleteStateInput;
 pub use structs::DeleteStateOutput;
+pub use structs::MigrateStateInput;
+pub use structs::MigrateStateOutput;
+pub use structs::STATE_SCHEMA_VERSION;
 
 // Clear 相关结构体
 pub use structs::ClearInput;
@@ -46,7 +53,7 @@ pub use structs::DeletedItem;
 pub use structs::ItemType;
 
 // ============================================================================
-// 导出 9 个接口函数
+// 导出 13 个接口函数
 // ============================================================================
 
 // config.toml 操作接口
@@ -54,12 +61,16 @@ pub use config::create_config_toml;
 pub use config::read_config_toml;
 pub use config::update_config_toml;
 pub use config::delete_config_toml;
+pub use config::backup_config;
+pub use config::merge_toml_values;
+pub use utl::layered_config_template;
 
 // state.json 操作接口
 pub use state::create_state_json;
 pub use state::read_state_json;
 pub use state::write_state_json;
 pub use state::delete_state_json;
+pub use state::migrate_state;
 
 // 目录清理接口
 pub use clear::clear_lianpkg;
@@ -3,8 +3,9 @@
 use std::fs;
 
 use crate::core::cfg::structs::{
-    CreateStateInput, CreateStateOutput, DeleteStateInput, DeleteStateOutput, ReadStateInput,
-    ReadStateOutput, WriteStateInput, WriteStateOutput,
+    CreateStateInput, CreateStateOutput, DeleteStateInput, DeleteStateOutput, MigrateStateInput,
+    MigrateStateOutput, ReadStateInput, ReadStateOutput, StateData, WriteStateInput,
+    WriteStateOutput, STATE_SCHEMA_VERSION,
 };
 use crate::core::cfg::utl::{default_state_template, ensure_dir};
 use crate::core::error::{CoreError, CoreResult};
@@ -99,3 +100,118 @@ pub fn delete_state_json(input: DeleteStateInput) -> CoreResult<DeleteStateOutpu
         path,
     })
 }
+
+/// 解析状态文件内容并升级到当前结构版本
+/// 旧版本逐级迁移；比当前更新的版本按原样尽力解析，不降低版本号
+pub fn migrate_state(input: MigrateStateInput) -> CoreResult<MigrateStateOutput> {
+    let mut value: serde_json::Value =
+        serde_json::from_str(&input.content).map_err(|e| CoreError::Parse {
+            message: e.to_string(),
+            source: Some("state.json".to_string()),
+        })?;
+
+    let from_version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    let mut version = from_version;
+    while version < STATE_SCHEMA_VERSION {
+        value = match version {
+            0 => migrate_v0_to_v1(value),
+            _ => value,
+        };
+        version += 1;
+    }
+
+    let mut state: StateData = serde_json::from_value(value).map_err(|e| CoreError::Parse {
+        message: e.to_string(),
+        source: Some("state.json".to_string()),
+    })?;
+    state.schema_version = from_version.max(STATE_SCHEMA_VERSION);
+
+    Ok(MigrateStateOutput {
+        state,
+        from_version,
+        migrated: from_version < STATE_SCHEMA_VERSION,
+    })
+}
+
+/// v0 → v1：v0 没有版本字段，其余字段均可由默认值补齐
+fn migrate_v0_to_v1(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("schema_version".to_string(), serde_json::json!(1));
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cfg::structs::{ProcessStage, WallpaperProcessType};
+    use crate::core::error::CoreErrorKind;
+
+    #[test]
+    fn test_state_error_kinds() {
+        let path = std::env::temp_dir().join("lianpkg_state_error_kinds.json");
+        let _ = fs::remove_file(&path);
+        let err = read_state_json(ReadStateInput { path }).unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::NotFound);
+
+        for content in ["not json", r#"{"processed_wallpapers": 5}"#] {
+            let err = migrate_state(MigrateStateInput {
+                content: content.to_string(),
+            })
+            .unwrap_err();
+            assert_eq!(err.kind(), CoreErrorKind::Parse, "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_migrate_state_from_v0() {
+        let content = r#"{
+            "processed_wallpapers": [
+                {"wallpaper_id": "123", "title": "a", "process_type": "Pkg", "processed_at": 10}
+            ],
+            "last_run": 10,
+            "statistics": {"total_runs": 2, "total_wallpapers": 1, "total_pkgs": 3, "total_texs": 4}
+        }"#;
+
+        let output = migrate_state(MigrateStateInput {
+            content: content.to_string(),
+        })
+        .unwrap();
+
+        assert_eq!(output.from_version, 0);
+        assert!(output.migrated);
+        assert_eq!(output.state.schema_version, STATE_SCHEMA_VERSION);
+        let record = &output.state.processed_wallpapers[0];
+        assert_eq!(record.wallpaper_id, "123");
+        assert_eq!(record.process_type, WallpaperProcessType::Pkg);
+        assert_eq!(record.stage, ProcessStage::Complete);
+        assert_eq!(output.state.statistics.total_runs, 2);
+        assert!(output.state.estimate_history.is_empty());
+
+        let saved = serde_json::to_value(&output.state).unwrap();
+        assert_eq!(saved["schema_version"], STATE_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_state_current_and_empty() {
+        let output = migrate_state(MigrateStateInput {
+            content: "{}".to_string(),
+        })
+        .unwrap();
+        assert!(output.migrated);
+        assert!(output.state.processed_wallpapers.is_empty());
+
+        let current = format!(r#"{{"schema_version": {}}}"#, STATE_SCHEMA_VERSION);
+        let output = migrate_state(MigrateStateInput { content: current }).unwrap();
+        assert!(!output.migrated);
+
+        assert!(migrate_state(MigrateStateInput {
+            content: "not json".to_string(),
+        })
+        .is_err());
+    }
+}
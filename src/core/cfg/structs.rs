@@ -73,13 +73,33 @@ pub struct DeleteConfigOutput {
     pub path: PathBuf,
 }
 
+/// backup_config 接口入参
+#[derive(Debug, Clone)]
+pub struct BackupConfigInput {
+    /// 配置文件路径
+    pub path: PathBuf,
+}
+
+/// backup_config 接口返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupConfigOutput {
+    /// 备份文件路径（与原文件同目录）
+    pub backup_path: PathBuf,
+}
+
 // ============================================================================
 // State.json 相关结构体
 // ============================================================================
 
+/// 当前 state.json 结构版本
+pub const STATE_SCHEMA_VERSION: u32 = 1;
+
 /// State.json 完整结构（用于序列化/反序列化）
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateData {
+    /// 结构版本（缺失视为 0，读取时由 migrate_state 升级）
+    #[serde(default)]
+    pub schema_version: u32,
     /// 已处理的壁纸列表
     #[serde(default)]
     pub processed_wallpapers: Vec<ProcessedWallpaper>,
@@ -89,6 +109,51 @@ pub struct StateData {
     /// 统计信息
     #[serde(default)]
     pub statistics: StateStatistics,
+    /// 磁盘预估对账记录（最近若干次运行）
+    #[serde(default)]
+    pub estimate_history: Vec<EstimateRecord>,
+}
+
+impl Default for StateData {
+    /// 新建的状态总是当前版本
+    fn default() -> Self {
+        Self {
+            schema_version: STATE_SCHEMA_VERSION,
+            processed_wallpapers: Vec::new(),
+            last_run: None,
+            statistics: StateStatistics::default(),
+            estimate_history: Vec::new(),
+        }
+    }
+}
+
+/// 单次运行的磁盘预估对账记录
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct EstimateRecord {
+    /// 记录时间（Unix 时间戳）
+    pub recorded_at: u64,
+    /// 本次复制到 Pkg_Temp 的 PKG 大小（字节）
+    pub pkg_size: u64,
+    /// 预估解包大小（字节）
+    pub estimated_unpacked: u64,
+    /// 实际解包大小（字节）
+    pub actual_unpacked: u64,
+    /// 预估转换产物大小（字节）
+    pub estimated_converted: u64,
+    /// 实际转换产物大小（字节）
+    pub actual_converted: u64,
+}
+
+impl EstimateRecord {
+    /// 实际解包膨胀系数（实际解包 / PKG）
+    pub fn unpack_ratio(&self) -> Option<f64> {
+        (self.pkg_size > 0).then(|| self.actual_unpacked as f64 / self.pkg_size as f64)
+    }
+
+    /// 实际转换膨胀系数（实际转换 / PKG）
+    pub fn convert_ratio(&self) -> Option<f64> {
+        (self.pkg_size > 0).then(|| self.actual_converted as f64 / self.pkg_size as f64)
+    }
 }
 
 /// 已处理的壁纸记录
@@ -104,6 +169,23 @@ pub struct ProcessedWallpaper {
     pub processed_at: u64,
     /// 输出路径
     pub output_path: Option<String>,
+    /// 已完成的处理阶段（中断后据此决定从哪个阶段继续）
+    #[serde(default)]
+    pub stage: ProcessStage,
+}
+
+/// 壁纸处理阶段
+///
+/// 旧版 state.json 没有该字段，视为已完成
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ProcessStage {
+    /// 已复制到 Pkg_Temp，尚未解包
+    Copied,
+    /// 已解包，尚未转换 TEX
+    Unpacked,
+    /// 全部阶段已完成
+    #[default]
+    Complete,
 }
 
 /// 壁纸处理类型
@@ -200,6 +282,24 @@ pub struct DeleteStateOutput {
     pub path: PathBuf,
 }
 
+/// migrate_state 接口入参
+#[derive(Debug, Clone)]
+pub struct MigrateStateInput {
+    /// state.json 原始内容
+    pub content: String,
+}
+
+/// migrate_state 接口返回值
+#[derive(Debug, Clone)]
+pub struct MigrateStateOutput {
+    /// 升级到当前版本后的状态
+    pub state: StateData,
+    /// 文件中记录的原始版本
+    pub from_version: u32,
+    /// 是否执行了升级（保存后文件才会更新）
+    pub migrated: bool,
+}
+
 // ============================================================================
 // Clear 相关结构体
 // ============================================================================
@@ -12,7 +12,7 @@ fn escape_path_for_toml(path: &str) -> String {
 /// 生成 config.toml 的默认模板内容
 /// 使用 core/path 模块获取平台相关的默认路径
 pub fn default_config_template() -> String {
-    let wp = escape_path_for_toml(&path::default_workshop_path());
+    let wp = escape_path_for_toml(&path::default_workshop_path(None));
     let raw_out = escape_path_for_toml(&path::default_raw_output_path());
     let pkg_temp = escape_path_for_toml(&path::default_pkg_temp_path());
     let enable_raw = true;
@@ -39,8 +39,15 @@ pub fn default_config_template() -> String {
 #     本程序将会从这个路径下扫描 wallpaper 壁纸
 #         - Windows 默认: C:\\Program Files (x86)\\Steam\\steamapps\\workshop\\content\\431960
 #         - Linux 默认: ~/.local/share/Steam/steamapps/workshop/content/431960
+#         - macOS 默认: ~/Library/Application Support/Steam/steamapps/workshop/content/431960
 workshop_path = "{wp}"
 
+# === Steam App ID ===
+#     workshop_path 留空或为默认值时，按此 App ID 定位 workshop/content/<app_id>
+#     用于指向其它 app 的 workshop 内容（如在未安装 Wallpaper Engine 的机器上测试）
+#     Default/默认: "431960" (Wallpaper Engine)
+# app_id = "431960"
+
 # === 不需要解包的壁纸输出路径 ===
 #     有些 wallpaper 壁纸不需要解包, 就会放到这个路径下
 #         - Windows 默认: .\\Wallpapers_Raw
@@ -52,6 +59,17 @@ raw_output_path = "{raw_out}"
 #     Default/默认: true
 enable_raw_output = {enable_raw}
 
+# === 允许的最高内容分级 ===
+#     根据 project.json 的 contentrating 字段过滤壁纸，超过该分级的壁纸将被跳过
+#     可选: everyone / questionable / mature，留空则不过滤
+#     Default/默认: "" (不过滤)
+max_content_rating = ""
+
+# === 查找 .pkg 时向下搜索的子目录层数 ===
+#     部分壁纸把 .pkg 放在子目录中，0 表示只看壁纸文件夹顶层
+#     Default/默认: 2
+pkg_depth = 2
+
 # === 需要解包的 .pkg 文件暂存路径 === 
 #     为了不影响 wallpaper 结构, 本程序将会复制一份 .pkg 到这个临时文件夹
 #     解包完成后就会清空, 如果你需要保留 .pkg 源文件可以在下面配置 clean_pkg_temp = false
@@ -73,6 +91,16 @@ clean_pkg_temp = {clean_pkg_temp}
 # === 是否在结束时清理 Pkg_Unpacked 中除 tex_converted 以外的内容 ===
 clean_unpacked = {clean_unpacked}
 
+# === .pkg 归档目录 ===
+#     配置后会在清理前把 Pkg_Temp 中的 .pkg 移动到这里, 按壁纸 ID 分目录保存
+#     与 clean_pkg_temp = false 不同, 原始 .pkg 会集中归档而不是留在临时目录
+# archive_pkg_path = ""
+
+# === 只解包指定类型的壁纸 ===
+#     按 project.json 中的 type 过滤含 .pkg 的壁纸, 例如只处理 scene
+#     不匹配的壁纸不会复制到 Pkg_Temp, 也不会被解包; 原始壁纸不受影响
+# only_types = ["scene"]
+
 
 [tex]
 # === .tex 转换后的图片输出路径 (输出 3) ===
@@ -80,6 +108,23 @@ clean_unpacked = {clean_unpacked}
 #     如果留空，则默认在解包路径下的 tex_converted 子目录中
 # converted_output_path = "{converted_hint}"
 
+# === 是否严格校验内嵌图片 ===
+#     部分 tex 内嵌 PNG/JPEG 等已编码图片，转换时会直接写出
+#     默认只检查文件头；启用后会尝试完整解码，无法校验或损坏的图片将报告为失败
+#     Default/默认: false
+strict = false
+
+# === LZ4 解压失败时是否直接报告失败 ===
+#     LZ4 数据按头部声明大小解压失败时，默认会尝试其他解压策略挽救；启用后直接报告失败
+#     Default/默认: false
+strict_lz4 = false
+
+# === 输出路径冲突的处理策略 ===
+#     默认的 tex_converted 目录会把子目录展平, 不同目录下的同名 tex 会映射到同一文件
+#     写出前会检测这类冲突并在报告中列出: rename 在文件名后追加 _1、_2; error 跳过后者并报告失败
+#     Default/默认: "rename"
+on_conflict = "rename"
+
 
 [pipeline]
 # === 是否启用增量处理 ===
@@ -87,6 +132,11 @@ clean_unpacked = {clean_unpacked}
 #     Default/默认: false
 incremental = false
 
+# === 增量处理时是否校验输出目录 ===
+#     启用后，state.json 中已记录但输出目录已被删除的壁纸会重新处理
+#     Default/默认: false
+strict_incremental = false
+
 # === 是否在流水线中自动执行 pkg 解包 ===
 #     Default/默认: true
 auto_unpack_pkg = true
@@ -94,10 +144,74 @@ auto_unpack_pkg = true
 # === 是否在流水线中自动执行 tex 转换 ===
 #     Default/默认: true
 auto_convert_tex = true
+
+# === 批处理失败熔断阈值 ===
+#     pkg 解包 / tex 转换时，连续失败达到 N 次，或累计失败达到 N 次且多于成功数，
+#     即提前中止该批处理（疑似整批格式不兼容）。0 表示不启用
+#     Default/默认: 0
+fail_threshold = 0
+
+# === 转换后生成缩略图 ===
+#     为每张输出图片在其所在目录的 thumbs/ 下生成缩略图，最长边缩放到该值（像素），保持宽高比
+#     视频不生成缩略图。0 表示不启用
+#     Default/默认: 0
+thumbnail_size = 0
+
+# === 并行转换 tex 的内存预算（MB） ===
+#     tex 默认按 CPU 核心数多线程并行转换，启用后同时驻留的解码缓冲总量按此预算限制，预算越小并发越低
+#     0 表示不限制
+#     Default/默认: 0
+max_memory_mb = 0
+
+# === 阶段失败后继续执行 ===
+#     默认某阶段整体失败（如熔断中止）后跳过后续处理阶段；
+#     启用后尽量跑完所有阶段，最后汇总各阶段错误，有错误时以非零状态退出
+#     Default/默认: false
+keep_going = false
+
+# === 首个失败即中止 ===
+#     默认首个 PKG / TEX 失败即中止该批处理（等同 fail_threshold = 1），后续阶段也不再执行；
+#     设为 false 时单个失败只记录到报告中并继续处理其余文件（此时 fail_threshold 生效）
+#     Default/默认: true
+fail_fast = true
+
+# === state.json 检查点间隔 ===
+#     每复制 / 解包 N 个壁纸保存一次 state.json，流水线中途被中断（Ctrl+C、崩溃）后，
+#     下次增量运行会从中断的阶段继续，不再重复复制已完成的壁纸
+#     间隔越小中断后重做的工作越少，但写盘更频繁（壁纸很多时 state.json 较大）
+#     0 表示只在流水线结束时保存
+#     Default/默认: 1
+checkpoint_interval = 1
 "#
     )
 }
 
+/// 生成叠加在基础配置（default.toml）之上的 config.toml 模板
+/// 与默认模板相同，但所有配置项都被注释掉：未设置的项取自 base_file，取消注释即可覆盖
+pub fn layered_config_template(base_file: &str) -> String {
+    let body: Vec<String> = default_config_template()
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('[') {
+                line.to_string()
+            } else {
+                format!("# {}", line)
+            }
+        })
+        .collect();
+
+    format!(
+        "# 同目录存在 {base}，未设置的配置项取自其中；取消注释即可覆盖对应的值
+         # {base} is present in this directory; unset keys come from it, uncomment a key to override it
+         #
+{body}
+",
+        base = base_file,
+        body = body.join("\n")
+    )
+}
+
 /// 生成 state.json 的默认模板内容
 pub fn default_state_template() -> String {
     "{}".to_string()
@@ -0,0 +1,80 @@
+//! 路径访问检查接口
+
+use std::path::Path;
+
+use crate::core::disk::space::find_existing_parent;
+use crate::core::disk::structs::{CheckAccessInput, CheckAccessOutput};
+
+/// 检查路径是否存在、是否可写
+///
+/// 只读取权限信息，不在磁盘上写入任何文件；
+/// 路径不存在时检查最近的已存在父目录，即“能否被创建”
+pub fn check_access(input: CheckAccessInput) -> CheckAccessOutput {
+    let path = input.path;
+    let exists = path.exists();
+
+    let writable = match find_existing_parent(&path) {
+        Some(ref p) => is_writable(p),
+        None => false,
+    };
+
+    CheckAccessOutput {
+        exists,
+        is_dir: path.is_dir(),
+        writable,
+    }
+}
+
+/// 当前进程能否写入该路径
+///
+/// Unix 下使用 access(W_OK)，按当前用户的权限判断（含 ACL 与只读挂载）；其他平台检查只读属性
+#[cfg(unix)]
+fn is_writable(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: c_path 是以 NUL 结尾的有效路径字符串，access 不会保留该指针
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// 当前进程能否写入该路径（检查只读属性）
+#[cfg(not(unix))]
+fn is_writable(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|m| !m.permissions().readonly())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check_access() {
+        let dir = std::env::temp_dir().join("lianpkg_check_access");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let output = check_access(CheckAccessInput { path: dir.clone() });
+        assert!(output.exists && output.is_dir && output.writable);
+        // 检查不在目录中留下任何文件
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        // 不存在的路径：按父目录判断能否创建
+        let output = check_access(CheckAccessInput {
+            path: dir.join("missing/child"),
+        });
+        assert!(!output.exists && !output.is_dir && output.writable);
+
+        let output = check_access(CheckAccessInput {
+            path: PathBuf::from("relative_missing_dir_12345"),
+        });
+        assert!(!output.exists && !output.writable);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
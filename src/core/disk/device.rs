@@ -0,0 +1,79 @@
+//! 文件系统归属检测接口
+
+use std::path::Path;
+
+use crate::core::disk::space::find_existing_parent;
+use crate::core::disk::structs::{SameDeviceInput, SameDeviceOutput};
+use crate::core::error::{CoreError, CoreResult};
+
+/// 检测两个路径是否位于同一文件系统
+///
+/// 路径不存在时使用最近的已存在父目录判断
+/// - Unix: 比较设备号
+/// - Windows: 比较卷前缀（盘符或 UNC 共享）
+pub fn same_device(input: SameDeviceInput) -> CoreResult<SameDeviceOutput> {
+    let check_a = existing(&input.path_a)?;
+    let check_b = existing(&input.path_b)?;
+
+    let same_device = device_id(&check_a)? == device_id(&check_b)?;
+
+    Ok(SameDeviceOutput {
+        same_device,
+        check_path_a: check_a,
+        check_path_b: check_b,
+    })
+}
+
+fn existing(path: &Path) -> CoreResult<std::path::PathBuf> {
+    find_existing_parent(path).ok_or_else(|| CoreError::NotFound {
+        message: "Cannot find existing directory to check device".to_string(),
+        path: Some(path.display().to_string()),
+    })
+}
+
+#[cfg(unix)]
+fn device_id(path: &Path) -> CoreResult<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path)
+        .map(|m| m.dev().to_string())
+        .map_err(|e| CoreError::Io {
+            message: format!("Failed to read metadata: {}", e),
+            path: Some(path.display().to_string()),
+        })
+}
+
+#[cfg(not(unix))]
+fn device_id(path: &Path) -> CoreResult<String> {
+    use std::path::Component;
+
+    let absolute = std::path::absolute(path).map_err(|e| CoreError::Io {
+        message: format!("Failed to resolve absolute path: {}", e),
+        path: Some(path.display().to_string()),
+    })?;
+
+    Ok(match absolute.components().next() {
+        Some(Component::Prefix(prefix)) => {
+            prefix.as_os_str().to_string_lossy().to_ascii_uppercase()
+        }
+        _ => String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_same_device_uses_existing_parent() {
+        let tmp = std::env::temp_dir();
+        let output = same_device(SameDeviceInput {
+            path_a: tmp.clone(),
+            path_b: tmp.join("lianpkg_non_existing_dir_12345"),
+        })
+        .unwrap();
+        assert!(output.same_device);
+        assert_eq!(output.check_path_b, PathBuf::from(&tmp));
+    }
+}
@@ -3,18 +3,33 @@
 //! 提供磁盘空间查询的原子接口：
 //! - check_space: 检查指定路径的磁盘可用空间
 //! - find_existing_parent: 查找存在的父目录
+//! - dir_size: 递归统计目录大小
+//! - same_device: 检测两个路径是否位于同一文件系统
+//! - check_access: 检查路径是否存在、是否可写
 
+mod access;
+mod device;
 mod space;
 mod structs;
+mod usage;
 
 // ============================================================================
 // 导出 Input/Output 结构体
 // ============================================================================
+pub use structs::CheckAccessInput;
+pub use structs::CheckAccessOutput;
 pub use structs::CheckSpaceInput;
 pub use structs::CheckSpaceOutput;
+pub use structs::DirSizeInput;
+pub use structs::DirSizeOutput;
+pub use structs::SameDeviceInput;
+pub use structs::SameDeviceOutput;
 
 // ============================================================================
 // 导出接口函数
 // ============================================================================
+pub use access::check_access;
+pub use device::same_device;
 pub use space::check_space;
 pub use space::find_existing_parent;
+pub use usage::dir_size;
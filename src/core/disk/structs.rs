@@ -14,6 +14,29 @@ pub struct CheckSpaceInput {
     pub path: PathBuf,
 }
 
+/// 统计目录大小入参
+#[derive(Debug, Clone)]
+pub struct DirSizeInput {
+    /// 要统计的目录（不存在时大小为 0）
+    pub path: PathBuf,
+}
+
+/// 检查路径访问入参
+#[derive(Debug, Clone)]
+pub struct CheckAccessInput {
+    /// 要检查的路径（不存在时检查父目录能否写入）
+    pub path: PathBuf,
+}
+
+/// 检测同一文件系统入参
+#[derive(Debug, Clone)]
+pub struct SameDeviceInput {
+    /// 第一个路径（不存在时查找父目录）
+    pub path_a: PathBuf,
+    /// 第二个路径（不存在时查找父目录）
+    pub path_b: PathBuf,
+}
+
 // ============================================================================
 // Output 结构体
 // ============================================================================
@@ -28,3 +51,34 @@ pub struct CheckSpaceOutput {
     /// 实际检查的路径（可能是输入路径的父目录）
     pub check_path: PathBuf,
 }
+
+/// 统计目录大小返回值
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct DirSizeOutput {
+    /// 目录内所有文件的总大小（字节）
+    pub size: u64,
+    /// 文件数量
+    pub file_count: usize,
+}
+
+/// 检查路径访问返回值
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CheckAccessOutput {
+    /// 路径是否存在
+    pub exists: bool,
+    /// 是否为目录
+    pub is_dir: bool,
+    /// 是否可写（不存在时为能否创建）
+    pub writable: bool,
+}
+
+/// 检测同一文件系统返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SameDeviceOutput {
+    /// 是否位于同一文件系统
+    pub same_device: bool,
+    /// 实际检查的第一个路径
+    pub check_path_a: PathBuf,
+    /// 实际检查的第二个路径
+    pub check_path_b: PathBuf,
+}
@@ -0,0 +1,33 @@
+//! 目录占用统计接口
+
+use std::fs;
+use std::path::Path;
+
+use crate::core::disk::structs::{DirSizeInput, DirSizeOutput};
+
+/// 递归统计目录大小
+///
+/// 目录不存在或无法读取的条目计为 0，不返回错误
+pub fn dir_size(input: DirSizeInput) -> DirSizeOutput {
+    let mut output = DirSizeOutput::default();
+    accumulate(&input.path, &mut output);
+    output
+}
+
+fn accumulate(path: &Path, output: &mut DirSizeOutput) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            accumulate(&entry.path(), output);
+        } else if let Ok(meta) = entry.metadata() {
+            output.size += meta.len();
+            output.file_count += 1;
+        }
+    }
+}
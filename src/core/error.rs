@@ -28,6 +28,29 @@ pub enum CoreError {
     },
     /// 格式不支持 (未知的文件格式等)
     Unsupported { message: String },
+    /// PKG 文件损坏 (索引截断、条目数量异常等)
+    InvalidPkg {
+        message: String,
+        path: Option<String>,
+    },
+    /// TEX 文件损坏 (结构无法读取、像素数据解压失败等)
+    InvalidTex {
+        message: String,
+        path: Option<String>,
+    },
+}
+
+/// 错误类别（不含消息），供调用方按类别分支，序列化为 snake_case 字符串
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreErrorKind {
+    Io,
+    Parse,
+    Validation,
+    NotFound,
+    Unsupported,
+    InvalidPkg,
+    InvalidTex,
 }
 
 impl fmt::Display for CoreError {
@@ -40,8 +63,14 @@ impl fmt::Display for CoreError {
                     write!(f, "IO error: {}", message)
                 }
             }
-            CoreError::Parse { message, source } => {
-                if let Some(s) = source {
+            // 损坏的 PKG / TEX 与一般解析错误输出相同的文案
+            CoreError::Parse {
+                message,
+                source: path,
+            }
+            | CoreError::InvalidPkg { message, path }
+            | CoreError::InvalidTex { message, path } => {
+                if let Some(s) = path {
                     write!(f, "Parse error in '{}': {}", s, message)
                 } else {
                     write!(f, "Parse error: {}", message)
@@ -135,6 +164,64 @@ impl CoreError {
             message: msg.into(),
         }
     }
+
+    /// 创建 PKG 损坏错误
+    pub fn invalid_pkg(msg: impl Into<String>) -> Self {
+        CoreError::InvalidPkg {
+            message: msg.into(),
+            path: None,
+        }
+    }
+
+    /// 创建 TEX 损坏错误
+    pub fn invalid_tex(msg: impl Into<String>) -> Self {
+        CoreError::InvalidTex {
+            message: msg.into(),
+            path: None,
+        }
+    }
+
+    /// 创建带路径的 TEX 损坏错误
+    pub fn invalid_tex_with_path(msg: impl Into<String>, path: impl Into<String>) -> Self {
+        CoreError::InvalidTex {
+            message: msg.into(),
+            path: Some(path.into()),
+        }
+    }
+
+    /// 为尚未记录路径的错误补上路径
+    ///
+    /// 没有路径字段的变体（Validation / Unsupported）把路径作为消息前缀
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        match &mut self {
+            CoreError::Io { path: slot, .. }
+            | CoreError::Parse { source: slot, .. }
+            | CoreError::NotFound { path: slot, .. }
+            | CoreError::InvalidPkg { path: slot, .. }
+            | CoreError::InvalidTex { path: slot, .. } => {
+                if slot.is_none() {
+                    *slot = Some(path.into());
+                }
+            }
+            CoreError::Validation { message } | CoreError::Unsupported { message } => {
+                *message = format!("{}: {}", path.into(), message);
+            }
+        }
+        self
+    }
+
+    /// 错误类别
+    pub fn kind(&self) -> CoreErrorKind {
+        match self {
+            CoreError::Io { .. } => CoreErrorKind::Io,
+            CoreError::Parse { .. } => CoreErrorKind::Parse,
+            CoreError::Validation { .. } => CoreErrorKind::Validation,
+            CoreError::NotFound { .. } => CoreErrorKind::NotFound,
+            CoreError::Unsupported { .. } => CoreErrorKind::Unsupported,
+            CoreError::InvalidPkg { .. } => CoreErrorKind::InvalidPkg,
+            CoreError::InvalidTex { .. } => CoreErrorKind::InvalidTex,
+        }
+    }
 }
 
 // ============================================================================
@@ -167,3 +254,37 @@ impl From<serde_json::Error> for CoreError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_variants_keep_parse_message() {
+        let tex = CoreError::invalid_tex_with_path("bad magic", "a.tex");
+        assert_eq!(tex.to_string(), "Parse error in 'a.tex': bad magic");
+        assert_eq!(tex.kind(), CoreErrorKind::InvalidTex);
+
+        let pkg = CoreError::invalid_pkg("PKG index is truncated");
+        assert_eq!(pkg.to_string(), "Parse error: PKG index is truncated");
+        assert_eq!(pkg.kind(), CoreErrorKind::InvalidPkg);
+    }
+
+    #[test]
+    fn test_with_path_fills_missing_path_only() {
+        let tex = CoreError::invalid_tex("LZ4 failed").with_path("a.tex");
+        assert_eq!(tex.to_string(), "Parse error in 'a.tex': LZ4 failed");
+
+        let io = CoreError::io_with_path("denied", "out.png").with_path("a.tex");
+        assert_eq!(io.to_string(), "IO error at 'out.png': denied");
+
+        let validation = CoreError::validation("empty").with_path("a.tex");
+        assert_eq!(validation.to_string(), "Validation error: a.tex: empty");
+    }
+
+    #[test]
+    fn test_kind_serializes_as_snake_case() {
+        let kind = CoreError::not_found("x").kind();
+        assert_eq!(serde_json::to_string(&kind).unwrap(), "\"not_found\"");
+    }
+}
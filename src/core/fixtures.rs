@@ -0,0 +1,81 @@
+//! 测试样本构造（仅测试编译）
+//!
+//! 各模块测试共用的最小 PKG / TEX 二进制样本
+
+/// 写入 u32 长度前缀的字符串（PKG 魔数与条目名的编码方式）
+pub fn push_str(b: &mut Vec<u8>, s: &str) {
+    b.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    b.extend_from_slice(s.as_bytes());
+}
+
+/// PKG 文件头与条目索引（名称, 偏移, 大小），不含数据区
+pub fn pkg_index(version: &str, entries: &[(&str, u32, u32)]) -> Vec<u8> {
+    let mut b = Vec::new();
+    push_str(&mut b, version);
+    b.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (name, offset, size) in entries {
+        push_str(&mut b, name);
+        b.extend_from_slice(&offset.to_le_bytes());
+        b.extend_from_slice(&size.to_le_bytes());
+    }
+    b
+}
+
+/// 包含若干文件的 PKGV0001，数据按条目顺序紧密排列
+pub fn pkg_bytes<D: AsRef<[u8]>>(files: &[(&str, D)]) -> Vec<u8> {
+    let mut offset = 0u32;
+    let entries: Vec<(&str, u32, u32)> = files
+        .iter()
+        .map(|(name, data)| {
+            let size = data.as_ref().len() as u32;
+            offset += size;
+            (*name, offset - size, size)
+        })
+        .collect();
+
+    let mut b = pkg_index("PKGV0001", &entries);
+    for (_, data) in files {
+        b.extend_from_slice(data.as_ref());
+    }
+    b
+}
+
+/// TEX 文件头 + 图像容器头（container 含结尾 `\0`），之后由调用方追加 mipmap
+pub fn tex_head(version: &str, header: &[u32], container: &[u8], image_count: i32) -> Vec<u8> {
+    let mut b = version.as_bytes().to_vec();
+    b.push(0);
+    b.extend_from_slice(b"TEXI0001\0");
+    for v in header {
+        b.extend_from_slice(&v.to_le_bytes());
+    }
+    b.extend_from_slice(container);
+    b.extend_from_slice(&image_count.to_le_bytes());
+    b
+}
+
+/// 追加一个未压缩 mipmap（TEXB0002 布局：宽、高、lz4 标志、解压大小、数据长度、数据）
+pub fn push_mipmap(b: &mut Vec<u8>, width: u32, height: u32, data: &[u8]) {
+    for v in [width, height, 0, 0, data.len() as u32] {
+        b.extend_from_slice(&v.to_le_bytes());
+    }
+    b.extend_from_slice(data);
+}
+
+/// TEXV0005 RGBA8888 纹理：size 为文件头中的纹理尺寸，每个图像是若干 (宽, 高, 像素) mipmap
+pub fn rgba_tex(size: (u32, u32), images: &[Vec<(u32, u32, Vec<u8>)>]) -> Vec<u8> {
+    let (width, height) = size;
+    let header = [0, 0, width, height, width, height, 0];
+    let mut b = tex_head("TEXV0005", &header, b"TEXB0002\0", images.len() as i32);
+    for mipmaps in images {
+        b.extend_from_slice(&(mipmaps.len() as i32).to_le_bytes());
+        for (w, h, pixels) in mipmaps {
+            push_mipmap(&mut b, *w, *h, pixels);
+        }
+    }
+    b
+}
+
+/// 2×2 纯红 RGBA8888 的最小 TEX
+pub fn tex_bytes() -> Vec<u8> {
+    rgba_tex((2, 2), &[vec![(2, 2, [255u8, 0, 0, 255].repeat(4))]])
+}
@@ -1,3 +0,0 @@
-use super::operations::*;
-use super::types::*;
-use async_trait::async_trait;
@@ -12,6 +12,8 @@
 pub mod cfg; // 配置文件与状态文件操作
 pub mod disk; // 磁盘空间检查
 pub mod error; // 统一错误处理（其他模块依赖此模块，必须首先声明）
+#[cfg(test)]
+pub(crate) mod fixtures; // 测试样本构造
 pub mod paper; // Wallpaper 壁纸扫描与复制
 pub mod path; // 路径处理与解析
 pub mod pkg; // Pkg 文件解析与解包
@@ -0,0 +1,139 @@
+//! 打包接口 - 将目录写入 zip 文件
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::paper::structs::{ZipDirInput, ZipDirOutput};
+use crate::core::path::long_path;
+
+/// 将目录递归打包为 zip 文件
+/// 条目路径相对于 src_dir，统一使用 `/` 分隔；输出文件位于 src_dir 内时自动跳过自身
+pub fn zip_dir(input: ZipDirInput) -> CoreResult<ZipDirOutput> {
+    let src_dir = input.src_dir;
+    let zip_path = input.zip_path;
+
+    if !src_dir.is_dir() {
+        return Err(CoreError::NotFound {
+            message: "Source directory not found".to_string(),
+            path: Some(src_dir.display().to_string()),
+        });
+    }
+
+    if let Some(parent) = zip_path.parent() {
+        fs::create_dir_all(long_path(parent)).map_err(|e| io_error(e, parent))?;
+    }
+
+    let file = File::create(long_path(&zip_path)).map_err(|e| io_error(e, &zip_path))?;
+    let mut writer = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut file_count = 0;
+    add_dir(
+        &mut writer,
+        &src_dir,
+        &src_dir,
+        &zip_path,
+        options,
+        &mut file_count,
+    )?;
+
+    writer
+        .finish()
+        .map_err(|e| io_error(io::Error::other(e), &zip_path))?;
+
+    let zip_size = fs::metadata(&zip_path).map(|m| m.len()).unwrap_or(0);
+
+    Ok(ZipDirOutput {
+        file_count,
+        zip_size,
+    })
+}
+
+/// 递归写入目录内容（按名称排序，保证输出稳定）
+fn add_dir(
+    writer: &mut ZipWriter<BufWriter<File>>,
+    root: &Path,
+    dir: &Path,
+    zip_path: &Path,
+    options: SimpleFileOptions,
+    file_count: &mut usize,
+) -> CoreResult<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| io_error(e, dir))?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if path.is_dir() {
+            writer
+                .add_directory(format!("{}/", name), options)
+                .map_err(|e| io_error(io::Error::other(e), &path))?;
+            add_dir(writer, root, &path, zip_path, options, file_count)?;
+        } else if path != zip_path {
+            writer
+                .start_file(name, options)
+                .map_err(|e| io_error(io::Error::other(e), &path))?;
+            let mut src = File::open(long_path(&path)).map_err(|e| io_error(e, &path))?;
+            io::copy(&mut src, writer).map_err(|e| io_error(e, &path))?;
+            *file_count += 1;
+        }
+    }
+
+    Ok(())
+}
+
+fn io_error(e: io::Error, path: &Path) -> CoreError {
+    CoreError::Io {
+        message: e.to_string(),
+        path: Some(path.display().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_zip_dir_relative_entries() {
+        let dir = std::env::temp_dir().join("lianpkg_zip_dir");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/materials")).unwrap();
+        fs::write(dir.join("src/project.json"), b"{}").unwrap();
+        fs::write(dir.join("src/materials/bg.png"), b"png").unwrap();
+
+        let zip_path = dir.join("out.zip");
+        let output = zip_dir(ZipDirInput {
+            src_dir: dir.join("src"),
+            zip_path: zip_path.clone(),
+        })
+        .unwrap();
+        assert_eq!(output.file_count, 2);
+
+        let mut archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("materials/bg.png")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "png");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -9,7 +9,7 @@ use crate::core::paper::structs::{
     CheckPkgInput,
 };
 use crate::core::paper::scan::check_pkg;
-use crate::core::paper::utl::copy_dir_recursive;
+use crate::core::paper::utl::sync_dir_recursive;
 use crate::core::path;
 
 /// 处理单个壁纸文件夹
@@ -30,12 +30,16 @@ pub fn process_folder(input: ProcessFolderInput) -> ProcessFolderOutput {
                 skipped: true,
                 result_type: ProcessResultType::Skipped,
                 pkg_files: Vec::new(),
+                files_updated: 0,
             };
         }
     };
 
     // 检查是否有 pkg 文件
-    let check_result = check_pkg(CheckPkgInput { folder: folder.clone() });
+    let check_result = check_pkg(CheckPkgInput {
+        folder: folder.clone(),
+        max_depth: input.pkg_depth,
+    });
 
     if check_result.has_pkg {
         // 有 pkg 文件，复制 pkg 到临时目录
@@ -50,15 +54,22 @@ pub fn process_folder(input: ProcessFolderInput) -> ProcessFolderOutput {
                 skipped: true,
                 result_type: ProcessResultType::Skipped,
                 pkg_files: Vec::new(),
+                files_updated: 0,
             };
         }
 
-        for pkg_path in &check_result.pkg_files {
+        let pkgs = check_result.pkg_files.iter().zip(&check_result.relative_paths);
+        for (pkg_path, relative) in pkgs {
             if let Some(file_name) = pkg_path.file_name().and_then(|n| n.to_str()) {
+                // 嵌套在子目录中的 pkg 保持相对位置：<pkg_temp>/<子目录>/<ID>_<文件名>
                 let new_name = path::pkg_temp_dest(&dir_name, file_name);
-                let dest = pkg_temp_output.join(&new_name);
-                
-                if fs::copy(pkg_path, &dest).is_ok() {
+                let dest_dir = match relative.parent() {
+                    Some(parent) => pkg_temp_output.join(parent),
+                    None => pkg_temp_output.clone(),
+                };
+                let dest = dest_dir.join(&new_name);
+
+                if fs::create_dir_all(&dest_dir).is_ok() && fs::copy(pkg_path, &dest).is_ok() {
                     copied_pkgs += 1;
                     copied_files.push(dest);
                 }
@@ -71,22 +82,12 @@ pub fn process_folder(input: ProcessFolderInput) -> ProcessFolderOutput {
             skipped: copied_pkgs == 0,
             result_type: if copied_pkgs > 0 { ProcessResultType::Pkg } else { ProcessResultType::Skipped },
             pkg_files: copied_files,
+            files_updated: 0,
         }
     } else if enable_raw {
-        // 无 pkg 文件，复制整个目录作为原始壁纸
+        // 无 pkg 文件，把整个目录同步为原始壁纸
         let dest_dir = raw_output.join(&dir_name);
 
-        // 如果目标已存在，跳过
-        if dest_dir.exists() {
-            return ProcessFolderOutput {
-                copied_raw: false,
-                copied_pkgs: 0,
-                skipped: true,
-                result_type: ProcessResultType::Skipped,
-                pkg_files: Vec::new(),
-            };
-        }
-
         // 确保父目录存在
         if fs::create_dir_all(raw_output).is_err() {
             return ProcessFolderOutput {
@@ -95,26 +96,29 @@ pub fn process_folder(input: ProcessFolderInput) -> ProcessFolderOutput {
                 skipped: true,
                 result_type: ProcessResultType::Skipped,
                 pkg_files: Vec::new(),
+                files_updated: 0,
             };
         }
 
-        // 递归复制目录
-        if copy_dir_recursive(folder, &dest_dir).is_ok() {
-            ProcessFolderOutput {
+        // 目标已存在时只复制新增或变化的文件（force 时完整重新复制）
+        let existed = dest_dir.exists();
+        match sync_dir_recursive(folder, &dest_dir, input.force) {
+            Ok(files_updated) if files_updated > 0 || !existed => ProcessFolderOutput {
                 copied_raw: true,
                 copied_pkgs: 0,
                 skipped: false,
                 result_type: ProcessResultType::Raw,
                 pkg_files: Vec::new(),
-            }
-        } else {
-            ProcessFolderOutput {
+                files_updated,
+            },
+            _ => ProcessFolderOutput {
                 copied_raw: false,
                 copied_pkgs: 0,
                 skipped: true,
                 result_type: ProcessResultType::Skipped,
                 pkg_files: Vec::new(),
-            }
+                files_updated: 0,
+            },
         }
     } else {
         // 不启用原始壁纸提取，跳过
@@ -124,6 +128,7 @@ pub fn process_folder(input: ProcessFolderInput) -> ProcessFolderOutput {
             skipped: true,
             result_type: ProcessResultType::Skipped,
             pkg_files: Vec::new(),
+            files_updated: 0,
         }
     }
 }
@@ -163,9 +168,12 @@ pub fn extract_all(input: ExtractInput) -> ExtractOutput {
             raw_output: config.raw_output.clone(),
             pkg_temp_output: config.pkg_temp_output.clone(),
             enable_raw: config.enable_raw,
+            force: config.force,
+            pkg_depth: config.pkg_depth,
         });
 
         // 更新统计
+        stats.files_updated += result.files_updated;
         match result.result_type {
             ProcessResultType::Raw => stats.raw_count += 1,
             ProcessResultType::Pkg => stats.pkg_count += result.copied_pkgs,
@@ -186,3 +194,166 @@ pub fn extract_all(input: ExtractInput) -> ExtractOutput {
         processed_folders,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::paper::structs::{PaperConfig, DEFAULT_PKG_DEPTH};
+
+    #[test]
+    fn test_disabled_raw_skips_pkgless_folder() {
+        let root = std::env::temp_dir().join("lianpkg_copy_no_raw");
+        let _ = fs::remove_dir_all(&root);
+        let folder = root.join("workshop").join("100001");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("project.json"), r#"{"type": "video"}"#).unwrap();
+        fs::write(folder.join("clip.mp4"), b"video").unwrap();
+        let raw_output = root.join("raw");
+
+        let result = process_folder(ProcessFolderInput {
+            folder: folder.clone(),
+            raw_output: raw_output.clone(),
+            pkg_temp_output: root.join("pkg_temp"),
+            enable_raw: false,
+            force: false,
+            pkg_depth: DEFAULT_PKG_DEPTH,
+        });
+        assert!(result.skipped);
+        assert!(!result.copied_raw);
+        assert_eq!(result.result_type, ProcessResultType::Skipped);
+
+        let output = extract_all(ExtractInput {
+            config: PaperConfig {
+                search_path: root.join("workshop"),
+                raw_output: raw_output.clone(),
+                pkg_temp_output: root.join("pkg_temp"),
+                enable_raw: false,
+                force: false,
+                pkg_depth: DEFAULT_PKG_DEPTH,
+            },
+        });
+        assert_eq!(output.stats.raw_count, 0);
+        assert!(!raw_output.exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_nested_pkg_routed_to_pkg_temp() {
+        let root = std::env::temp_dir().join("lianpkg_copy_nested_pkg");
+        let _ = fs::remove_dir_all(&root);
+        let folder = root.join("workshop").join("100004");
+        fs::create_dir_all(folder.join("content").join("scene")).unwrap();
+        fs::write(folder.join("project.json"), r#"{"type": "scene"}"#).unwrap();
+        fs::write(folder.join("content").join("scene").join("scene.pkg"), b"pkg").unwrap();
+
+        let check = check_pkg(CheckPkgInput {
+            folder: folder.clone(),
+            max_depth: DEFAULT_PKG_DEPTH,
+        });
+        assert!(check.has_pkg);
+        assert_eq!(
+            check.relative_paths,
+            vec![std::path::Path::new("content").join("scene").join("scene.pkg")]
+        );
+        // 只下探一层时找不到
+        assert!(!check_pkg(CheckPkgInput { folder: folder.clone(), max_depth: 1 }).has_pkg);
+
+        let result = process_folder(ProcessFolderInput {
+            folder: folder.clone(),
+            raw_output: root.join("raw"),
+            pkg_temp_output: root.join("pkg_temp"),
+            enable_raw: true,
+            force: false,
+            pkg_depth: DEFAULT_PKG_DEPTH,
+        });
+        assert_eq!(result.result_type, ProcessResultType::Pkg);
+        assert!(!result.copied_raw);
+        assert!(!root.join("raw").exists());
+        let dest = root.join("pkg_temp").join("content").join("scene").join("100004_scene.pkg");
+        assert_eq!(result.pkg_files, vec![dest.clone()]);
+        assert_eq!(fs::read(dest).unwrap(), b"pkg");
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_raw_copy_only_updates_changed_files() {
+        let root = std::env::temp_dir().join("lianpkg_copy_sync_raw");
+        let _ = fs::remove_dir_all(&root);
+        let folder = root.join("workshop").join("100002");
+        fs::create_dir_all(folder.join("media")).unwrap();
+        fs::write(folder.join("project.json"), r#"{"type": "video"}"#).unwrap();
+        fs::write(folder.join("media").join("clip.mp4"), b"video").unwrap();
+
+        let process = |force: bool| {
+            process_folder(ProcessFolderInput {
+                folder: folder.clone(),
+                raw_output: root.join("raw"),
+                pkg_temp_output: root.join("pkg_temp"),
+                enable_raw: true,
+                force,
+                pkg_depth: DEFAULT_PKG_DEPTH,
+            })
+        };
+
+        let first = process(false);
+        assert_eq!(first.result_type, ProcessResultType::Raw);
+        assert_eq!(first.files_updated, 2);
+
+        // 未变化时不重复复制
+        let unchanged = process(false);
+        assert!(unchanged.skipped);
+        assert_eq!(unchanged.files_updated, 0);
+
+        // 新版本只同步变化的文件
+        fs::write(folder.join("media").join("clip.mp4"), b"video v2").unwrap();
+        let updated = process(false);
+        assert_eq!(updated.result_type, ProcessResultType::Raw);
+        assert_eq!(updated.files_updated, 1);
+        let copied = root.join("raw").join("100002").join("media").join("clip.mp4");
+        assert_eq!(fs::read(copied).unwrap(), b"video v2");
+
+        assert_eq!(process(true).files_updated, 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_raw_copy_preserves_mtime() {
+        let root = std::env::temp_dir().join("lianpkg_copy_mtime");
+        let _ = fs::remove_dir_all(&root);
+        let folder = root.join("workshop").join("100003");
+        fs::create_dir_all(&folder).unwrap();
+        let source = folder.join("clip.mp4");
+        fs::write(&source, b"video").unwrap();
+        let old =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let result = process_folder(ProcessFolderInput {
+            folder: folder.clone(),
+            raw_output: root.join("raw"),
+            pkg_temp_output: root.join("pkg_temp"),
+            enable_raw: true,
+            force: false,
+            pkg_depth: DEFAULT_PKG_DEPTH,
+        });
+        assert_eq!(result.result_type, ProcessResultType::Raw);
+
+        let copied = root.join("raw").join("100003").join("clip.mp4");
+        let modified = fs::metadata(copied).unwrap().modified().unwrap();
+        let diff = modified
+            .duration_since(old)
+            .unwrap_or_else(|e| e.duration());
+        // 允许部分文件系统的时间精度误差（如 FAT 为 2 秒）
+        assert!(diff <= std::time::Duration::from_secs(2), "{:?}", diff);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,219 @@
+//! 场景依赖解析接口
+//!
+//! 解析解包后的场景 JSON 引用关系：场景 → 模型/特效 → 材质 → 贴图
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::paper::structs::{DepKind, DepNode, ResolveDepsInput, ResolveDepsOutput};
+use crate::core::paper::utl::{parse_json_lenient, parse_meta_lenient};
+
+/// 解析壁纸目录的依赖树
+///
+/// 入口文件优先取 project.json 的 `file` 字段，否则为 scene.json。
+/// 引用的文件不存在时节点标记为缺失，不中断解析。
+pub fn resolve_deps(input: ResolveDepsInput) -> CoreResult<ResolveDepsOutput> {
+    let root_dir = &input.root;
+
+    let scene_file = fs::read(root_dir.join("project.json"))
+        .ok()
+        .and_then(|bytes| parse_meta_lenient(&bytes).ok())
+        .and_then(|meta| meta.file)
+        .filter(|f| f.to_lowercase().ends_with(".json"))
+        .unwrap_or_else(|| "scene.json".to_string());
+
+    if !root_dir.join(&scene_file).is_file() {
+        return Err(CoreError::NotFound {
+            message: format!("{} not found (unpack the scene.pkg first)", scene_file),
+            path: Some(root_dir.display().to_string()),
+        });
+    }
+
+    let mut resolver = Resolver {
+        root: root_dir,
+        visited: HashSet::new(),
+    };
+    let root = resolver.node(DepKind::Scene, &scene_file);
+    let missing_count = root.missing_count();
+
+    Ok(ResolveDepsOutput {
+        root,
+        missing_count,
+    })
+}
+
+// ============================================================================
+// 内部实现
+// ============================================================================
+
+struct Resolver<'a> {
+    root: &'a Path,
+    /// 已展开的文件，避免重复展开与循环引用
+    visited: HashSet<String>,
+}
+
+impl Resolver<'_> {
+    /// 构建节点并按类型展开子引用
+    fn node(&mut self, kind: DepKind, rel_path: &str) -> DepNode {
+        let rel_path = rel_path.replace('\\', "/");
+        let exists = self.root.join(&rel_path).is_file();
+        let mut node = DepNode {
+            kind,
+            path: rel_path.clone(),
+            exists,
+            children: Vec::new(),
+        };
+
+        if !exists || kind == DepKind::Texture || !self.visited.insert(rel_path.clone()) {
+            return node;
+        }
+
+        let Some(json) = fs::read(self.root.join(&rel_path))
+            .ok()
+            .and_then(|bytes| parse_json_lenient(&bytes).ok())
+        else {
+            return node;
+        };
+
+        node.children = match kind {
+            DepKind::Scene => self.scene_children(&json),
+            DepKind::Model => self.model_children(&json),
+            DepKind::Effect => self.effect_children(&json),
+            DepKind::Material => self.material_children(&json),
+            DepKind::Texture => Vec::new(),
+        };
+        node
+    }
+
+    /// 场景：objects[].image / model → 模型，objects[].effects[].file → 特效
+    fn scene_children(&mut self, json: &Value) -> Vec<DepNode> {
+        let mut children = Vec::new();
+        let objects = json.get("objects").and_then(|v| v.as_array());
+
+        for object in objects.into_iter().flatten() {
+            for key in ["image", "model"] {
+                if let Some(path) = object.get(key).and_then(|v| v.as_str()) {
+                    if path.to_lowercase().ends_with(".json") {
+                        children.push(self.node(DepKind::Model, path));
+                    }
+                }
+            }
+
+            let effects = object.get("effects").and_then(|v| v.as_array());
+            for effect in effects.into_iter().flatten() {
+                let Some(file) = effect.get("file").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let mut node = self.node(DepKind::Effect, file);
+                // 对象上覆盖的特效贴图
+                node.children.extend(self.pass_textures(effect));
+                children.push(node);
+            }
+        }
+        children
+    }
+
+    /// 模型：material → 材质
+    fn model_children(&mut self, json: &Value) -> Vec<DepNode> {
+        json.get("material")
+            .and_then(|v| v.as_str())
+            .map(|path| vec![self.node(DepKind::Material, path)])
+            .unwrap_or_default()
+    }
+
+    /// 特效：passes[].material → 材质
+    fn effect_children(&mut self, json: &Value) -> Vec<DepNode> {
+        let passes = json.get("passes").and_then(|v| v.as_array());
+        passes
+            .into_iter()
+            .flatten()
+            .filter_map(|pass| pass.get("material").and_then(|v| v.as_str()))
+            .map(|path| self.node(DepKind::Material, path))
+            .collect()
+    }
+
+    /// 材质：passes[].textures[] → 贴图
+    fn material_children(&mut self, json: &Value) -> Vec<DepNode> {
+        self.pass_textures(json)
+    }
+
+    /// 收集 passes[].textures[] 中的贴图引用（跳过 null 与渲染目标）
+    fn pass_textures(&mut self, json: &Value) -> Vec<DepNode> {
+        let passes = json.get("passes").and_then(|v| v.as_array());
+        passes
+            .into_iter()
+            .flatten()
+            .filter_map(|pass| pass.get("textures").and_then(|v| v.as_array()))
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .filter(|name| !name.is_empty() && !name.starts_with("_rt_"))
+            .map(|name| self.node(DepKind::Texture, &texture_path(name)))
+            .collect()
+    }
+}
+
+/// 贴图名 → materials/<name>.tex
+fn texture_path(name: &str) -> String {
+    let name = name.trim_start_matches("materials/");
+    if name.to_lowercase().ends_with(".tex") {
+        format!("materials/{}", name)
+    } else {
+        format!("materials/{}.tex", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_deps_marks_missing() {
+        let dir = std::env::temp_dir().join("lianpkg_resolve_deps");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("models")).unwrap();
+        fs::create_dir_all(dir.join("materials")).unwrap();
+
+        fs::write(
+            dir.join("scene.json"),
+            r#"{"objects": [{"image": "models/bg.json"}, {"image": "models/gone.json"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("models/bg.json"),
+            r#"{"material": "materials/bg.json"}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("materials/bg.json"),
+            r#"{"passes": [{"textures": ["bg", null, "_rt_FullFrameBuffer", "mask",]}]}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("materials/bg.tex"), b"").unwrap();
+
+        let output = resolve_deps(ResolveDepsInput { root: dir.clone() }).unwrap();
+        let root = &output.root;
+        assert_eq!(root.kind, DepKind::Scene);
+        assert_eq!(root.children.len(), 2);
+
+        let material = &root.children[0].children[0];
+        assert_eq!(material.path, "materials/bg.json");
+        let textures: Vec<_> = material
+            .children
+            .iter()
+            .map(|t| (t.path.as_str(), t.exists))
+            .collect();
+        assert_eq!(
+            textures,
+            vec![("materials/bg.tex", true), ("materials/mask.tex", false)]
+        );
+
+        assert!(!root.children[1].exists);
+        assert_eq!(output.missing_count, 2);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,153 @@
+//! 指纹接口 - 基于内容文件的快速指纹，用于识别重复发布的壁纸
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::paper::structs::{FingerprintInput, FingerprintOutput};
+use crate::core::path::long_path;
+
+/// 每个文件采样的首尾字节数
+const SAMPLE_SIZE: u64 = 64 * 1024;
+
+/// 计算壁纸文件夹的内容指纹
+/// 有 pkg 时只取 pkg 文件，否则取顶层全部文件（排除 project.json 与 preview.*）；
+/// 每个文件只读取大小与首尾各 64 KiB，文件名不参与计算
+pub fn fingerprint(input: FingerprintInput) -> CoreResult<FingerprintOutput> {
+    let folder = input.folder;
+
+    let entries = fs::read_dir(&folder).map_err(|e| io_error(e, &folder))?;
+    let files: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+
+    let is_pkg = |p: &Path| {
+        p.extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pkg"))
+    };
+    let is_meta = |p: &Path| {
+        let name = p
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        name == "project.json" || name.starts_with("preview.")
+    };
+
+    let content: Vec<_> = if files.iter().any(|p| is_pkg(p)) {
+        files.into_iter().filter(|p| is_pkg(p)).collect()
+    } else {
+        files.into_iter().filter(|p| !is_meta(p)).collect()
+    };
+
+    if content.is_empty() {
+        return Err(CoreError::NotFound {
+            message: "No content files to fingerprint".to_string(),
+            path: Some(folder.display().to_string()),
+        });
+    }
+
+    // 逐文件采样后排序合并，与文件名、目录遍历顺序无关
+    let mut samples = Vec::with_capacity(content.len());
+    let mut total_size = 0;
+    for path in &content {
+        let (size, hash) = sample_file(path)?;
+        total_size += size;
+        samples.push((size, hash));
+    }
+    samples.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    for (size, hash) in &samples {
+        hasher.write_u64(*size);
+        hasher.write_u64(*hash);
+    }
+
+    Ok(FingerprintOutput {
+        fingerprint: hasher.finish(),
+        total_size,
+        file_count: content.len(),
+    })
+}
+
+/// 读取文件大小与首尾采样，返回 (大小, 采样哈希)
+fn sample_file(path: &Path) -> CoreResult<(u64, u64)> {
+    let mut file = File::open(long_path(path)).map_err(|e| io_error(e, path))?;
+    let size = file.metadata().map_err(|e| io_error(e, path))?.len();
+
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0u8; SAMPLE_SIZE.min(size) as usize];
+    file.read_exact(&mut buf).map_err(|e| io_error(e, path))?;
+    hasher.write(&buf);
+
+    if size > SAMPLE_SIZE {
+        let tail = SAMPLE_SIZE.min(size - SAMPLE_SIZE);
+        file.seek(SeekFrom::End(-(tail as i64)))
+            .map_err(|e| io_error(e, path))?;
+        buf.truncate(tail as usize);
+        file.read_exact(&mut buf).map_err(|e| io_error(e, path))?;
+        hasher.write(&buf);
+    }
+
+    Ok((size, hasher.finish()))
+}
+
+fn io_error(e: io::Error, path: &Path) -> CoreError {
+    CoreError::Io {
+        message: e.to_string(),
+        path: Some(path.display().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_names_and_meta() {
+        let dir = std::env::temp_dir().join("lianpkg_fingerprint");
+        let _ = fs::remove_dir_all(&dir);
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+        for (id, pkg_name, title) in [("1", "scene.pkg", "A"), ("2", "other.pkg", "B")] {
+            let folder = dir.join(id);
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join(pkg_name), &data).unwrap();
+            fs::write(
+                folder.join("project.json"),
+                format!(r#"{{"title":"{}"}}"#, title),
+            )
+            .unwrap();
+        }
+        // 仅中间字节不同，不在采样范围内
+        let folder = dir.join("3");
+        fs::create_dir_all(&folder).unwrap();
+        let mut changed = data.clone();
+        changed[100_000] ^= 0xFF;
+        fs::write(folder.join("scene.pkg"), &changed).unwrap();
+        // 尾部不同
+        let folder = dir.join("4");
+        fs::create_dir_all(&folder).unwrap();
+        let mut changed = data.clone();
+        *changed.last_mut().unwrap() ^= 0xFF;
+        fs::write(folder.join("scene.pkg"), &changed).unwrap();
+
+        let fp = |id: &str| {
+            fingerprint(FingerprintInput {
+                folder: dir.join(id),
+            })
+            .unwrap()
+        };
+        assert_eq!(fp("1").fingerprint, fp("2").fingerprint);
+        assert_eq!(fp("1").total_size, data.len() as u64);
+        assert_eq!(fp("1").fingerprint, fp("3").fingerprint);
+        assert_ne!(fp("1").fingerprint, fp("4").fingerprint);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -7,16 +7,25 @@
 //! 主要接口：
 //! - 扫描: list_dirs, read_meta, check_pkg, estimate
 //! - 复制: process_folder, extract_all
+//! - 依赖: resolve_deps
+//! - 打包: zip_dir
+//! - 指纹: fingerprint
+//! - 缩略图: generate_thumbnail
 
 mod structs;
 mod scan;
 mod copy;
+mod deps;
+mod archive;
+mod fingerprint;
+mod thumb;
 mod utl;
 
 // ============================================================================
 // 导出配置结构体
 // ============================================================================
 pub use structs::PaperConfig;
+pub use structs::DEFAULT_PKG_DEPTH;
 
 // ============================================================================
 // 导出 Input/Output 结构体
@@ -38,13 +47,32 @@ pub use structs::ProcessFolderOutput;
 pub use structs::ExtractInput;
 pub use structs::ExtractOutput;
 
+// 依赖相关
+pub use structs::ResolveDepsInput;
+pub use structs::ResolveDepsOutput;
+
+// 打包相关
+pub use structs::ZipDirInput;
+pub use structs::ZipDirOutput;
+
+// 指纹相关
+pub use structs::FingerprintInput;
+pub use structs::FingerprintOutput;
+
+// 缩略图相关
+pub use structs::ThumbnailInput;
+pub use structs::ThumbnailOutput;
+
 // ============================================================================
 // 导出运行时结构体
 // ============================================================================
 pub use structs::ProjectMeta;
+pub use structs::ContentRating;
 pub use structs::WallpaperStats;
 pub use structs::ProcessedFolder;
 pub use structs::ProcessResultType;
+pub use structs::DepKind;
+pub use structs::DepNode;
 
 // ============================================================================
 // 导出扫描接口
@@ -59,3 +87,23 @@ pub use scan::estimate;
 // ============================================================================
 pub use copy::process_folder;
 pub use copy::extract_all;
+
+// ============================================================================
+// 导出依赖接口
+// ============================================================================
+pub use deps::resolve_deps;
+
+// ============================================================================
+// 导出打包接口
+// ============================================================================
+pub use archive::zip_dir;
+
+// ============================================================================
+// 导出指纹接口
+// ============================================================================
+pub use fingerprint::fingerprint;
+
+// ============================================================================
+// 导出缩略图接口
+// ============================================================================
+pub use thumb::generate_thumbnail;
@@ -1,13 +1,14 @@
 //! 扫描相关接口 - 目录列举、元数据读取、pkg检查、空间估算
 
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::core::error::{CoreError, CoreResult};
 use crate::core::paper::structs::{
     CheckPkgInput, CheckPkgOutput, EstimateInput, EstimateOutput, ListDirsInput, ListDirsOutput,
-    ProjectMeta, ReadMetaInput, ReadMetaOutput,
+    ReadMetaInput, ReadMetaOutput,
 };
-use crate::core::paper::utl::get_dir_size;
+use crate::core::paper::utl::{get_dir_size, parse_meta_lenient};
 
 /// 列出指定目录下的所有子目录
 pub fn list_dirs(input: ListDirsInput) -> CoreResult<ListDirsOutput> {
@@ -42,41 +43,59 @@ pub fn read_meta(input: ReadMetaInput) -> CoreResult<ReadMetaOutput> {
         });
     }
 
-    let content = fs::read_to_string(&meta_path).map_err(|e| CoreError::Io {
+    let bytes = fs::read(&meta_path).map_err(|e| CoreError::Io {
         message: e.to_string(),
         path: Some(meta_path.display().to_string()),
     })?;
 
-    let meta: ProjectMeta = serde_json::from_str(&content).map_err(|e| CoreError::Parse {
-        message: e.to_string(),
-        source: Some(meta_path.display().to_string()),
-    })?;
+    let meta =
+        parse_meta_lenient(&bytes).map_err(|e| e.with_path(meta_path.display().to_string()))?;
 
     Ok(ReadMetaOutput { meta })
 }
 
 /// 检查文件夹是否包含 .pkg 文件
+///
+/// 除顶层外向下搜索至多 `max_depth` 层子目录，结果按相对路径排序
 pub fn check_pkg(input: CheckPkgInput) -> CheckPkgOutput {
-    let folder = input.folder;
-    let mut pkg_files = Vec::new();
+    let mut relative_paths = Vec::new();
+    collect_pkg_files(
+        &input.folder,
+        Path::new(""),
+        input.max_depth,
+        &mut relative_paths,
+    );
+    relative_paths.sort();
 
-    if let Ok(entries) = fs::read_dir(&folder) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-                    if ext.eq_ignore_ascii_case("pkg") {
-                        pkg_files.push(path);
-                    }
+    CheckPkgOutput {
+        has_pkg: !relative_paths.is_empty(),
+        pkg_files: relative_paths
+            .iter()
+            .map(|rel| input.folder.join(rel))
+            .collect(),
+        relative_paths,
+    }
+}
+
+/// 收集 dir 下的 .pkg 文件（相对壁纸文件夹的路径），depth 为剩余可下探的层数
+fn collect_pkg_files(dir: &Path, relative: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let rel = relative.join(entry.file_name());
+        if path.is_file() {
+            if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+                if ext.eq_ignore_ascii_case("pkg") {
+                    out.push(rel);
                 }
             }
+        } else if path.is_dir() && depth > 0 {
+            collect_pkg_files(&path, &rel, depth - 1, out);
         }
     }
-
-    CheckPkgOutput {
-        has_pkg: !pkg_files.is_empty(),
-        pkg_files,
-    }
 }
 
 /// 估算处理所需的磁盘空间
@@ -88,6 +107,7 @@ pub fn estimate(input: EstimateInput) -> EstimateOutput {
     let mut raw_size: u64 = 0;
     let mut pkg_count: usize = 0;
     let mut raw_count: usize = 0;
+    let mut pkg_files = Vec::new();
 
     if let Ok(entries) = fs::read_dir(&search_path) {
         for entry in entries.flatten() {
@@ -99,14 +119,16 @@ pub fn estimate(input: EstimateInput) -> EstimateOutput {
             // 检查是否有 pkg 文件
             let check_result = check_pkg(CheckPkgInput {
                 folder: path.clone(),
+                max_depth: input.pkg_depth,
             });
 
             if check_result.has_pkg {
                 pkg_count += 1;
-                for pkg_path in &check_result.pkg_files {
-                    if let Ok(meta) = fs::metadata(pkg_path) {
+                for pkg_path in check_result.pkg_files {
+                    if let Ok(meta) = fs::metadata(&pkg_path) {
                         pkg_size += meta.len();
                     }
+                    pkg_files.push(pkg_path);
                 }
             } else if enable_raw {
                 raw_count += 1;
@@ -120,5 +142,137 @@ pub fn estimate(input: EstimateInput) -> EstimateOutput {
         raw_size,
         pkg_count,
         raw_count,
+        pkg_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_meta(name: &str, bytes: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lianpkg_read_meta_{}", name));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("project.json"), bytes).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_scan_error_kinds() {
+        use crate::core::error::CoreErrorKind;
+
+        let missing = std::env::temp_dir().join("lianpkg_read_meta_missing");
+        let _ = fs::remove_dir_all(&missing);
+        let err = read_meta(ReadMetaInput {
+            folder: missing.clone(),
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::NotFound);
+        let err = list_dirs(ListDirsInput { path: missing }).unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::Io);
+
+        let dir = write_meta("garbage", b"not json at all");
+        let err = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::Parse);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_read_meta_with_bom() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(br#"{"title": "BOM Wallpaper", "type": "scene"}"#);
+        let dir = write_meta("bom", &bytes);
+
+        let meta = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        })
+        .unwrap()
+        .meta;
+        assert_eq!(meta.title.as_deref(), Some("BOM Wallpaper"));
+        assert_eq!(meta.wallpaper_type.as_deref(), Some("scene"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_read_meta_trailing_comma_and_loose_types() {
+        let content =
+            "{\n  \"title\": \"a, b}\",\n  \"version\": \"3\",\n  \"tags\": [\"Anime\",],\n}";
+        let dir = write_meta("trailing", content.as_bytes());
+
+        let meta = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        })
+        .unwrap()
+        .meta;
+        assert_eq!(meta.title.as_deref(), Some("a, b}"));
+        assert_eq!(meta.version, Some(3));
+        assert_eq!(meta.tags, Some(vec!["Anime".to_string()]));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_read_meta_garbage_is_parse_error() {
+        let dir = write_meta("garbage", b"not json at all");
+        let result = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        });
+        assert!(matches!(result, Err(CoreError::Parse { .. })));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_read_meta_preset() {
+        let content = br#"{"title": "My Preset", "type": "scene", "dependency": "123456", "preset": {"color": "1 0 0"}}"#;
+        let dir = write_meta("preset", content);
+
+        let meta = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        })
+        .unwrap()
+        .meta;
+        assert!(meta.is_preset());
+        assert_eq!(meta.dependency.as_deref(), Some("123456"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_read_meta_full_metadata() {
+        let content = br#"{
+            "title": "Full",
+            "description": "A long description",
+            "tags": ["Anime", 42, "Landscape"],
+            "contentrating": "Mature",
+            "workshopid": 2876543210,
+            "file": "scene.json",
+            "approved": true,
+            "visibility": "public"
+        }"#;
+        let dir = write_meta("full", content);
+
+        let meta = read_meta(ReadMetaInput {
+            folder: dir.clone(),
+        })
+        .unwrap()
+        .meta;
+        assert_eq!(meta.description.as_deref(), Some("A long description"));
+        assert_eq!(
+            meta.tags,
+            Some(vec!["Anime".to_string(), "Landscape".to_string()])
+        );
+        assert_eq!(meta.contentrating.as_deref(), Some("Mature"));
+        assert_eq!(meta.workshopid.as_deref(), Some("2876543210"));
+        assert_eq!(meta.file.as_deref(), Some("scene.json"));
+        assert!(meta.preview.is_none());
+
+        let _ = fs::remove_dir_all(dir);
     }
 }
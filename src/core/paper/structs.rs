@@ -21,8 +21,15 @@ pub struct PaperConfig {
     pub pkg_temp_output: PathBuf,
     /// 是否提取原始壁纸
     pub enable_raw: bool,
+    /// 忽略已有的原始壁纸输出，完整重新复制
+    pub force: bool,
+    /// 查找 .pkg 时向下搜索的子目录层数（0 表示只看壁纸文件夹顶层）
+    pub pkg_depth: usize,
 }
 
+/// 查找 .pkg 时默认向下搜索的子目录层数
+pub const DEFAULT_PKG_DEPTH: usize = 2;
+
 // ============================================================================
 // Input 结构体
 // ============================================================================
@@ -46,6 +53,8 @@ pub struct ReadMetaInput {
 pub struct CheckPkgInput {
     /// 壁纸文件夹路径
     pub folder: PathBuf,
+    /// 向下搜索的子目录层数（0 表示只看顶层）
+    pub max_depth: usize,
 }
 
 /// estimate 接口入参
@@ -55,6 +64,8 @@ pub struct EstimateInput {
     pub search_path: PathBuf,
     /// 是否计算原始壁纸大小
     pub enable_raw: bool,
+    /// 查找 .pkg 时向下搜索的子目录层数
+    pub pkg_depth: usize,
 }
 
 /// process_folder 接口入参
@@ -68,6 +79,10 @@ pub struct ProcessFolderInput {
     pub pkg_temp_output: PathBuf,
     /// 是否提取原始壁纸
     pub enable_raw: bool,
+    /// 忽略已有的原始壁纸输出，完整重新复制
+    pub force: bool,
+    /// 查找 .pkg 时向下搜索的子目录层数（0 表示只看顶层）
+    pub pkg_depth: usize,
 }
 
 /// extract_all 接口入参
@@ -77,6 +92,40 @@ pub struct ExtractInput {
     pub config: PaperConfig,
 }
 
+/// resolve_deps 接口入参
+#[derive(Debug, Clone)]
+pub struct ResolveDepsInput {
+    /// 解包后的壁纸目录（包含 scene.json）
+    pub root: PathBuf,
+}
+
+/// zip_dir 接口入参
+#[derive(Debug, Clone)]
+pub struct ZipDirInput {
+    /// 要打包的目录
+    pub src_dir: PathBuf,
+    /// 输出 zip 文件路径
+    pub zip_path: PathBuf,
+}
+
+/// fingerprint 接口入参
+#[derive(Debug, Clone)]
+pub struct FingerprintInput {
+    /// 壁纸文件夹路径
+    pub folder: PathBuf,
+}
+
+/// generate_thumbnail 接口入参
+#[derive(Debug, Clone)]
+pub struct ThumbnailInput {
+    /// 预览图路径（jpg/png/gif 等，动图只取首帧）
+    pub source: PathBuf,
+    /// 最长边上限（像素），不放大
+    pub max_dim: u32,
+    /// 缩略图输出路径，扩展名为 jpg/jpeg 时写 JPEG，否则写 PNG
+    pub output: PathBuf,
+}
+
 // ============================================================================
 // Output 结构体
 // ============================================================================
@@ -102,6 +151,8 @@ pub struct CheckPkgOutput {
     pub has_pkg: bool,
     /// pkg 文件列表
     pub pkg_files: Vec<PathBuf>,
+    /// pkg 文件相对壁纸文件夹的路径（与 pkg_files 一一对应）
+    pub relative_paths: Vec<PathBuf>,
 }
 
 /// estimate 接口返回值
@@ -115,6 +166,8 @@ pub struct EstimateOutput {
     pub pkg_count: usize,
     /// 原始壁纸数量
     pub raw_count: usize,
+    /// 找到的 pkg 文件路径（供进一步按内容预估）
+    pub pkg_files: Vec<PathBuf>,
 }
 
 /// process_folder 接口返回值
@@ -130,6 +183,8 @@ pub struct ProcessFolderOutput {
     pub result_type: ProcessResultType,
     /// 复制的 pkg 文件路径列表
     pub pkg_files: Vec<PathBuf>,
+    /// 原始壁纸中新复制或更新的文件数
+    pub files_updated: usize,
 }
 
 /// extract_all 接口返回值
@@ -141,6 +196,46 @@ pub struct ExtractOutput {
     pub processed_folders: Vec<ProcessedFolder>,
 }
 
+/// resolve_deps 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveDepsOutput {
+    /// 依赖树根节点（场景文件）
+    pub root: DepNode,
+    /// 缺失的引用数量
+    pub missing_count: usize,
+}
+
+/// zip_dir 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZipDirOutput {
+    /// 写入的文件数量
+    pub file_count: usize,
+    /// zip 文件大小（字节）
+    pub zip_size: u64,
+}
+
+/// fingerprint 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintOutput {
+    /// 内容指纹
+    pub fingerprint: u64,
+    /// 参与计算的文件总大小（字节）
+    pub total_size: u64,
+    /// 参与计算的文件数
+    pub file_count: usize,
+}
+
+/// generate_thumbnail 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailOutput {
+    /// 缩略图路径
+    pub output: PathBuf,
+    /// 缩略图宽度
+    pub width: u32,
+    /// 缩略图高度
+    pub height: u32,
+}
+
 // ============================================================================
 // 运行时结构体
 // ============================================================================
@@ -161,6 +256,23 @@ pub struct ProjectMeta {
     pub workshopurl: Option<String>,
     #[serde(default)]
     pub general: Option<serde_json::Value>,
+    /// preset 引用的 base 壁纸 workshop ID
+    #[serde(default)]
+    pub dependency: Option<String>,
+    /// preset 覆盖的参数
+    #[serde(default)]
+    pub preset: Option<serde_json::Value>,
+}
+
+impl ProjectMeta {
+    /// 是否为 preset 壁纸（引用另一个壁纸并覆盖参数）
+    pub fn is_preset(&self) -> bool {
+        self.preset.is_some()
+            || self
+                .wallpaper_type
+                .as_deref()
+                .is_some_and(|t| t.eq_ignore_ascii_case("preset"))
+    }
 }
 
 /// 壁纸统计信息
@@ -172,6 +284,9 @@ pub struct WallpaperStats {
     pub pkg_count: usize,
     /// 总处理大小（字节）
     pub total_size: u64,
+    /// 原始壁纸中新复制或更新的文件数
+    #[serde(default)]
+    pub files_updated: usize,
 }
 
 /// 处理结果详情（用于复合流程传递）
@@ -187,6 +302,48 @@ pub struct ProcessedFolder {
     pub pkg_files: Vec<PathBuf>,
 }
 
+/// 壁纸内容分级（对应 project.json 的 contentrating 字段）
+///
+/// 按严格程度排序：Everyone < Questionable < Mature
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContentRating {
+    /// 全年龄
+    Everyone,
+    /// 擦边/存疑
+    Questionable,
+    /// 成人
+    Mature,
+}
+
+impl ContentRating {
+    /// 从字符串解析分级（大小写不敏感）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "everyone" => Some(ContentRating::Everyone),
+            "questionable" => Some(ContentRating::Questionable),
+            "mature" => Some(ContentRating::Mature),
+            _ => None,
+        }
+    }
+
+    /// 从 project.json 的 contentrating 字段解析
+    /// 字段缺失或无法识别时视为 Everyone
+    pub fn from_meta(contentrating: Option<&str>) -> Self {
+        contentrating
+            .and_then(Self::parse)
+            .unwrap_or(ContentRating::Everyone)
+    }
+
+    /// 获取分级名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentRating::Everyone => "everyone",
+            ContentRating::Questionable => "questionable",
+            ContentRating::Mature => "mature",
+        }
+    }
+}
+
 /// 处理结果类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ProcessResultType {
@@ -197,3 +354,95 @@ pub enum ProcessResultType {
     /// 跳过（已存在等）
     Skipped,
 }
+
+/// 依赖节点类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DepKind {
+    /// 场景文件（scene.json）
+    Scene,
+    /// 模型（models/*.json）
+    Model,
+    /// 特效（effects/*/effect.json）
+    Effect,
+    /// 材质（materials/*.json）
+    Material,
+    /// 贴图（materials/*.tex）
+    Texture,
+}
+
+impl DepKind {
+    /// 获取类型名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            DepKind::Scene => "scene",
+            DepKind::Model => "model",
+            DepKind::Effect => "effect",
+            DepKind::Material => "material",
+            DepKind::Texture => "texture",
+        }
+    }
+}
+
+/// 依赖树节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepNode {
+    /// 节点类型
+    pub kind: DepKind,
+    /// 相对于壁纸目录的路径（统一使用 `/`）
+    pub path: String,
+    /// 引用的文件是否存在
+    pub exists: bool,
+    /// 子引用（重复引用的文件只在首次出现时展开）
+    pub children: Vec<DepNode>,
+}
+
+impl DepNode {
+    /// 统计自身及子树中缺失的引用数量
+    pub fn missing_count(&self) -> usize {
+        let own = usize::from(!self.exists);
+        own + self
+            .children
+            .iter()
+            .map(DepNode::missing_count)
+            .sum::<usize>()
+    }
+
+    /// 导出为 Graphviz DOT 格式，缺失节点标红
+    pub fn to_dot(&self) -> String {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        self.collect_dot(&mut nodes, &mut edges);
+
+        let mut dot = String::from("digraph deps {\n    rankdir=LR;\n    node [shape=box];\n");
+        for line in nodes.iter().chain(edges.iter()) {
+            dot.push_str("    ");
+            dot.push_str(line);
+            dot.push('\n');
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn collect_dot(&self, nodes: &mut Vec<String>, edges: &mut Vec<String>) {
+        let color = if self.exists { "black" } else { "red" };
+        let node = format!(
+            "\"{}\" [label=\"{}\\n{}\", color={}, fontcolor={}];",
+            self.path,
+            self.kind.name(),
+            self.path,
+            color,
+            color
+        );
+        if !nodes.contains(&node) {
+            nodes.push(node);
+        }
+
+        for child in &self.children {
+            let edge = format!("\"{}\" -> \"{}\";", self.path, child.path);
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+            child.collect_dot(nodes, edges);
+        }
+    }
+}
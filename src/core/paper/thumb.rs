@@ -0,0 +1,63 @@
+//! 缩略图接口 - 为壁纸预览图生成等比缩放的小图，供 GUI 列表展示
+
+use image::imageops::FilterType;
+
+use crate::core::error::CoreResult;
+use crate::core::paper::structs::{ThumbnailInput, ThumbnailOutput};
+use crate::core::tex::write_thumbnail;
+
+/// 生成预览图缩略图
+/// 最长边缩放到 max_dim（不放大），保持宽高比；GIF 动图只取首帧
+pub fn generate_thumbnail(input: ThumbnailInput) -> CoreResult<ThumbnailOutput> {
+    let (width, height) = write_thumbnail(
+        &input.source,
+        &input.output,
+        input.max_dim,
+        FilterType::Triangle,
+    )?;
+
+    Ok(ThumbnailOutput {
+        output: input.output,
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_generate_thumbnail_from_gif_first_frame() {
+        let dir = std::env::temp_dir().join("lianpkg_paper_thumbnail");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // 两帧 GIF：首帧红色，第二帧蓝色
+        let source = dir.join("preview.gif");
+        {
+            let file = fs::File::create(&source).unwrap();
+            let mut encoder = image::codecs::gif::GifEncoder::new(file);
+            for color in [[255, 0, 0, 255], [0, 0, 255, 255]] {
+                let frame = image::RgbaImage::from_pixel(400, 200, image::Rgba(color));
+                encoder.encode_frame(image::Frame::new(frame)).unwrap();
+            }
+        }
+
+        let output = generate_thumbnail(ThumbnailInput {
+            source,
+            max_dim: 100,
+            output: dir.join("cache/123.png"),
+        })
+        .unwrap();
+
+        assert_eq!((output.width, output.height), (100, 50));
+        let thumb = image::open(&output.output).unwrap().to_rgba8();
+        assert_eq!(thumb.dimensions(), (100, 50));
+        let pixel = thumb.get_pixel(50, 25);
+        assert!(pixel[0] > 200 && pixel[2] < 50, "{:?}", pixel);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -3,24 +3,62 @@
 use std::fs;
 use std::path::Path;
 
-/// 递归复制目录
-pub(crate) fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::paper::structs::ProjectMeta;
+use crate::core::path::long_path;
+
+/// 递归同步目录，返回实际复制的文件数
+///
+/// 目标文件已存在且大小相同、修改时间不早于源文件时跳过；`force` 时全部重新复制
+pub(crate) fn sync_dir_recursive(src: &Path, dst: &Path, force: bool) -> std::io::Result<usize> {
+    // Windows 下使用长路径形式，避免深层目录超过 MAX_PATH
+    let dst = &long_path(dst);
     if !dst.exists() {
         fs::create_dir_all(dst)?;
     }
 
+    let mut copied = 0;
     for entry in fs::read_dir(src)? {
         let entry = entry?;
         let file_type = entry.file_type()?;
         let dest_path = dst.join(entry.file_name());
 
         if file_type.is_dir() {
-            copy_dir_recursive(&entry.path(), &dest_path)?;
-        } else {
-            fs::copy(entry.path(), dest_path)?;
+            copied += sync_dir_recursive(&entry.path(), &dest_path, force)?;
+        } else if force || file_changed(&entry.metadata()?, &dest_path) {
+            copy_file_with_mtime(&entry.path(), &dest_path)?;
+            copied += 1;
         }
     }
-    Ok(())
+    Ok(copied)
+}
+
+/// 复制文件并保留源文件的修改时间
+///
+/// 部分平台上 `fs::copy` 不保留 mtime；设置失败时只保留复制结果
+pub(crate) fn copy_file_with_mtime(src: &Path, dst: &Path) -> std::io::Result<u64> {
+    let size = fs::copy(src, dst)?;
+    if let Ok(modified) = fs::metadata(src).and_then(|m| m.modified()) {
+        let _ = fs::OpenOptions::new()
+            .write(true)
+            .open(dst)
+            .and_then(|f| f.set_modified(modified));
+    }
+    Ok(size)
+}
+
+/// 目标文件缺失、大小不同或比源文件旧时视为需要复制
+fn file_changed(src_meta: &fs::Metadata, dest: &Path) -> bool {
+    let Ok(dest_meta) = fs::metadata(dest) else {
+        return true;
+    };
+    if src_meta.len() != dest_meta.len() {
+        return true;
+    }
+    match (src_meta.modified(), dest_meta.modified()) {
+        (Ok(src_time), Ok(dest_time)) => src_time > dest_time,
+        _ => true,
+    }
 }
 
 /// 递归计算目录大小
@@ -38,3 +76,103 @@ pub(crate) fn get_dir_size(path: &Path) -> u64 {
     }
     size
 }
+
+/// 宽松解析 project.json
+///
+/// 依次尝试：剥离 BOM 后严格解析 → 去除尾随逗号 → 按字段逐个提取（容忍类型不符）
+pub(crate) fn parse_meta_lenient(bytes: &[u8]) -> CoreResult<ProjectMeta> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+
+    let strict_err = match serde_json::from_str::<ProjectMeta>(text) {
+        Ok(meta) => return Ok(meta),
+        Err(e) => CoreError::parse(e.to_string()),
+    };
+
+    let cleaned = strip_trailing_commas(text);
+    let value: serde_json::Value = serde_json::from_str(&cleaned).map_err(|_| strict_err)?;
+
+    if let Ok(meta) = serde_json::from_value::<ProjectMeta>(value.clone()) {
+        return Ok(meta);
+    }
+    Ok(meta_from_value(&value))
+}
+
+/// 宽松解析任意 JSON（剥离 BOM，容忍尾随逗号）
+pub(crate) fn parse_json_lenient(bytes: &[u8]) -> CoreResult<serde_json::Value> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+
+    serde_json::from_str(text).or_else(|e| {
+        serde_json::from_str(&strip_trailing_commas(text))
+            .map_err(|_| CoreError::parse(e.to_string()))
+    })
+}
+
+/// 去除 JSON 对象/数组中的尾随逗号（忽略字符串内部）
+fn strip_trailing_commas(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            ',' => {
+                let next = chars[i + 1..].iter().find(|ch| !ch.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 从任意 JSON 值中逐字段提取元数据，类型不符的字段尽量转换或忽略
+fn meta_from_value(value: &serde_json::Value) -> ProjectMeta {
+    let get_str = |key: &str| -> Option<String> {
+        match value.get(key)? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    };
+
+    ProjectMeta {
+        contentrating: get_str("contentrating"),
+        description: get_str("description"),
+        file: get_str("file"),
+        preview: get_str("preview"),
+        tags: value.get("tags").and_then(|v| v.as_array()).map(|arr| {
+            arr.iter()
+                .filter_map(|t| t.as_str().map(str::to_string))
+                .collect()
+        }),
+        title: get_str("title"),
+        wallpaper_type: get_str("type"),
+        version: get_str("version").and_then(|v| v.parse().ok()),
+        workshopid: get_str("workshopid"),
+        workshopurl: get_str("workshopurl"),
+        general: value.get("general").cloned(),
+        dependency: get_str("dependency"),
+        preset: value.get("preset").cloned(),
+    }
+}
@@ -5,10 +5,13 @@
 //! | 接口 | 功能 |
 //! |------|------|
 //! | `ensure_dir` | 确保目录存在，不存在则递归创建 |
-//! | `expand_path` | 展开路径中的 `~` 为用户主目录 |
+//! | `expand_path` | 展开路径中的环境变量与 `~` |
 //! | `resolve_path` | 统一路径解析（配置、输出、Workshop 等） |
 //! | `scan_files` | 扫描目标文件（递归，支持扩展名过滤） |
 //!
+//! 另提供 `long_path` 工具函数：Windows 下为绝对路径加 `\\?\` 前缀，突破 MAX_PATH 限制
+//! 以及 `sanitize_filename`：把标题等任意文本转为跨平台安全的文件名
+//!
 //! ## 路径类型 (PathType)
 //!
 //! `resolve_path` 通过 `PathType` 枚举支持多种路径解析：
@@ -27,6 +30,7 @@ mod resolve;
 mod scan;
 mod types;
 mod utl;
+mod vdf;
 
 // ============================================================================
 // 导出 Input/Output 结构体
@@ -37,6 +41,7 @@ pub use types::ExpandPathInput;
 pub use types::ExpandPathOutput;
 pub use types::ScanFilesInput;
 pub use types::ScanFilesOutput;
+pub use types::SteamLibrary;
 
 // ============================================================================
 // 导出 resolve_path 相关
@@ -45,6 +50,7 @@ pub use resolve::resolve_path;
 pub use resolve::PathType;
 pub use resolve::ResolvePathInput;
 pub use resolve::ResolvePathOutput;
+pub use resolve::DEFAULT_APP_ID;
 
 // ============================================================================
 // 导出核心接口
@@ -52,6 +58,8 @@ pub use resolve::ResolvePathOutput;
 pub use scan::scan_files;
 pub use utl::ensure_dir;
 pub use utl::expand_path;
+pub use utl::long_path;
+pub use utl::sanitize_filename;
 
 // ============================================================================
 // 兼容层（供 api/native 和 cli 过渡使用）
@@ -60,6 +68,8 @@ pub use utl::expand_path;
 
 use std::path::{Path, PathBuf};
 
+use crate::core::error::CoreResult;
+
 /// 兼容层：展开路径中的 ~
 pub fn expand_path_compat(path_str: &str) -> PathBuf {
     expand_path(ExpandPathInput {
@@ -70,12 +80,11 @@ pub fn expand_path_compat(path_str: &str) -> PathBuf {
 }
 
 /// 兼容层：确保目录存在
-pub fn ensure_dir_compat(path: &Path) -> Result<(), String> {
+pub fn ensure_dir_compat(path: &Path) -> CoreResult<()> {
     ensure_dir(EnsureDirInput {
         path: path.to_path_buf(),
     })
     .map(|_| ())
-    .map_err(|e| e.to_string())
 }
 
 /// 兼容层：获取默认配置目录
@@ -88,12 +97,10 @@ pub fn default_config_dir() -> PathBuf {
 }
 
 /// 兼容层：获取默认 workshop 路径
-pub fn default_workshop_path() -> String {
-    resolve_path(ResolvePathInput {
-        path_type: PathType::Workshop,
-    })
-    .map(|o| o.path_str)
-    .unwrap_or_else(|_| "~/.local/share/Steam/steamapps/workshop/content/431960".to_string())
+///
+/// app_id 为 None 时使用 Wallpaper Engine 的 `431960`
+pub fn default_workshop_path(app_id: Option<&str>) -> String {
+    resolve::get_workshop_path_impl(app_id.unwrap_or(DEFAULT_APP_ID))
 }
 
 /// 兼容层：获取默认原始壁纸输出路径
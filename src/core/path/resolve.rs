@@ -111,7 +111,7 @@ fn resolve_state_json() -> CoreResult<ResolvePathOutput> {
 }
 
 fn resolve_workshop() -> CoreResult<ResolvePathOutput> {
-    let path_str = get_workshop_path_impl();
+    let path_str = get_workshop_path_impl(DEFAULT_APP_ID);
     let path = PathBuf::from(&path_str);
     Ok(ResolvePathOutput { path, path_str })
 }
@@ -250,36 +250,39 @@ fn windows_appdata_path(_name: &str) -> String {
     unreachable!()
 }
 
-/// 获取 Steam Workshop 路径实现
-fn get_workshop_path_impl() -> String {
-    const WALLPAPER_ENGINE_APP_ID: &str = "431960";
+/// Wallpaper Engine 的 Steam App ID（未配置 app_id 时使用）
+pub const DEFAULT_APP_ID: &str = "431960";
 
+/// 获取指定 app 的 Steam Workshop 内容路径实现
+pub(crate) fn get_workshop_path_impl(app_id: &str) -> String {
     if let Some(base_path) = get_steam_base_path() {
-        if let Some(lib_path) = find_library_path(&base_path) {
-            return lib_path
-                .join("steamapps")
-                .join("workshop")
-                .join("content")
-                .join(WALLPAPER_ENGINE_APP_ID)
-                .display()
-                .to_string();
-        }
-        return base_path
+        let library = find_library_path(&base_path, app_id).unwrap_or(base_path);
+        return library
             .join("steamapps")
             .join("workshop")
             .join("content")
-            .join(WALLPAPER_ENGINE_APP_ID)
+            .join(app_id)
             .display()
             .to_string();
     }
 
     #[cfg(target_os = "windows")]
     {
-        r"C:\Program Files (x86)\Steam\steamapps\workshop\content\431960".to_string()
+        format!(
+            r"C:\Program Files (x86)\Steam\steamapps\workshop\content\{}",
+            app_id
+        )
+    }
+    #[cfg(target_os = "macos")]
+    {
+        format!(
+            "~/Library/Application Support/Steam/steamapps/workshop/content/{}",
+            app_id
+        )
     }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
-        "~/.local/share/Steam/steamapps/workshop/content/431960".to_string()
+        format!("~/.local/share/Steam/steamapps/workshop/content/{}", app_id)
     }
 }
 
@@ -288,7 +291,11 @@ fn get_steam_base_path() -> Option<PathBuf> {
     {
         get_steam_path_windows()
     }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "macos")]
+    {
+        get_steam_path_macos()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
         get_steam_path_linux()
     }
@@ -306,7 +313,23 @@ fn get_steam_path_windows() -> Option<PathBuf> {
         .map(PathBuf::from)
 }
 
-#[cfg(not(target_os = "windows"))]
+/// macOS 下 Steam 的默认安装目录（~/Library/Application Support/Steam）
+#[cfg(target_os = "macos")]
+fn macos_steam_dir() -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join("Library")
+            .join("Application Support")
+            .join("Steam"),
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn get_steam_path_macos() -> Option<PathBuf> {
+    macos_steam_dir().filter(|path| path.exists())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn get_steam_path_linux() -> Option<PathBuf> {
     let home = dirs::home_dir()?;
     let candidates = [
@@ -324,46 +347,120 @@ fn get_steam_path_linux() -> Option<PathBuf> {
     None
 }
 
-fn find_library_path(steam_base: &std::path::Path) -> Option<PathBuf> {
+/// 从 libraryfolders.vdf 中查找安装了 Wallpaper Engine 的库
+///
+/// 优先返回 apps 中包含该 app id 的库；旧版布局没有 apps 表，
+/// 改为检查库目录下是否有该 app 的 appmanifest 或 workshop 目录。
+/// 都不匹配时返回 None，由调用方回退到 Steam 主目录
+fn find_library_path(steam_base: &std::path::Path, app_id: &str) -> Option<PathBuf> {
     let vdf_path = steam_base.join("steamapps").join("libraryfolders.vdf");
-    if !vdf_path.exists() {
-        return None;
-    }
-
     let content = std::fs::read_to_string(&vdf_path).ok()?;
+    let libraries = super::vdf::parse_library_folders(&content).ok()?;
+
+    libraries
+        .iter()
+        .find(|lib| lib.has_app(app_id) && lib.path.exists())
+        .or_else(|| {
+            libraries
+                .iter()
+                .find(|lib| library_has_app_on_disk(&lib.path, app_id))
+        })
+        .map(|lib| lib.path.clone())
+}
 
-    // 简单解析 VDF 格式查找包含 431960 的库
-    for line in content.lines() {
-        if line.contains("\"path\"") {
-            if let Some(start) = line.find('"') {
-                let rest = &line[start + 1..];
-                if let Some(end) = rest.find('"') {
-                    let path_str = &rest[..end];
-                    // 跳过 "path" 本身，取下一个值
-                    if path_str != "path" {
-                        continue;
-                    }
-                }
-            }
-        }
+/// 库目录下是否存在该 app 的安装清单或 workshop 目录
+fn library_has_app_on_disk(library: &std::path::Path, app_id: &str) -> bool {
+    let steamapps = library.join("steamapps");
+    steamapps
+        .join(format!("appmanifest_{}.acf", app_id))
+        .is_file()
+        || steamapps
+            .join("workshop")
+            .join("content")
+            .join(app_id)
+            .is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 在临时目录下建立 Steam 主目录与两个库，写入 libraryfolders.vdf
+    fn steam_layout(name: &str, vdf: impl Fn(&Path, &Path) -> String) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&root);
+        let base = root.join("Steam");
+        let games = root.join("Games");
+        std::fs::create_dir_all(base.join("steamapps")).unwrap();
+        std::fs::create_dir_all(games.join("steamapps")).unwrap();
+        std::fs::write(
+            base.join("steamapps").join("libraryfolders.vdf"),
+            vdf(&base, &games),
+        )
+        .unwrap();
+        (root, games)
     }
 
-    // 简化处理：返回第一个有效库路径
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("\"path\"") {
-            if let Some(start) = trimmed.rfind('"') {
-                let before = &trimmed[..start];
-                if let Some(second_last) = before.rfind('"') {
-                    let path_str = &before[second_last + 1..];
-                    let lib_path = PathBuf::from(path_str);
-                    if lib_path.exists() {
-                        return Some(lib_path);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_find_library_path_picks_library_with_app() {
+        let (root, games) = steam_layout("lianpkg_find_library_apps", |base, games| {
+            format!(
+                r#""libraryfolders"
+{{
+	"0"
+	{{
+		"path"		"{}"
+		"apps"
+		{{
+			"228980"		"1"
+		}}
+	}}
+	"1"
+	{{
+		"path"		"{}"
+		"apps"
+		{{
+			"431960"		"2"
+		}}
+	}}
+}}"#,
+                base.display(),
+                games.display()
+            )
+        });
+
+        let base = root.join("Steam");
+        assert_eq!(find_library_path(&base, "431960"), Some(games));
+        // 没有任何库包含该 app 时不再返回第一个库
+        assert_eq!(find_library_path(&base, "999999"), None);
+
+        let _ = std::fs::remove_dir_all(&root);
     }
 
-    None
+    #[test]
+    fn test_find_library_path_legacy_checks_disk() {
+        let (root, games) = steam_layout("lianpkg_find_library_legacy", |base, games| {
+            format!(
+                "\"LibraryFolders\"\n{{\n\t\"1\"\t\t\"{}\"\n\t\"2\"\t\t\"{}\"\n}}",
+                base.display(),
+                games.display()
+            )
+        });
+        std::fs::write(games.join("steamapps").join("appmanifest_431960.acf"), "").unwrap();
+
+        let base = root.join("Steam");
+        assert_eq!(find_library_path(&base, "431960"), Some(games));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_macos_steam_dir_under_library() {
+        let dir: PathBuf = macos_steam_dir().unwrap();
+        let library = dirs::home_dir().unwrap().join("Library");
+        assert!(dir.starts_with(&library));
+        assert!(dir.ends_with("Application Support/Steam"));
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! 精简后的核心接口：
 //! - ensure_dir: 确保目录存在
-//! - expand_path: 展开 ~ 路径
+//! - expand_path: 展开环境变量与 ~ 路径
 //! - resolve_path: 统一路径解析
 //! - scan_files: 扫描目标文件
 
@@ -36,7 +36,7 @@ pub struct EnsureDirOutput {
 /// expand_path 接口入参
 #[derive(Debug, Clone)]
 pub struct ExpandPathInput {
-    /// 待展开的路径字符串（可能包含 ~）
+    /// 待展开的路径字符串（可能包含 ~ 或 %VAR% / $VAR / ${VAR}）
     pub path: String,
 }
 
@@ -66,3 +66,23 @@ pub struct ScanFilesOutput {
     /// 目标文件列表
     pub files: Vec<PathBuf>,
 }
+
+// ============================================================================
+// Steam 库信息
+// ============================================================================
+
+/// libraryfolders.vdf 中的单个 Steam 库
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SteamLibrary {
+    /// 库根目录
+    pub path: PathBuf,
+    /// 该库中安装的 app id 列表
+    pub apps: Vec<String>,
+}
+
+impl SteamLibrary {
+    /// 该库是否包含指定 app
+    pub fn has_app(&self, app_id: &str) -> bool {
+        self.apps.iter().any(|a| a == app_id)
+    }
+}
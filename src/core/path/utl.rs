@@ -3,6 +3,7 @@
 use super::types::*;
 use crate::core::error::{CoreError, CoreResult};
 use std::fs;
+use std::path::{Path, PathBuf};
 
 /// 确保目录存在，不存在则递归创建
 pub fn ensure_dir(input: EnsureDirInput) -> CoreResult<EnsureDirOutput> {
@@ -21,22 +22,210 @@ pub fn ensure_dir(input: EnsureDirInput) -> CoreResult<EnsureDirOutput> {
     })
 }
 
-/// 展开路径中的 `~` 为用户主目录
+/// 展开路径中的环境变量与 `~`
+///
+/// 先替换 `%VAR%`、`$VAR`、`${VAR}`（各平台均识别，未定义的变量原样保留），
+/// 再把开头的 `~` 展开为用户主目录
 pub fn expand_path(input: ExpandPathInput) -> CoreResult<ExpandPathOutput> {
-    let path = if input.path.starts_with("~") {
+    let expanded = expand_env_vars(&input.path);
+    let path = if expanded.starts_with("~") {
         let home =
             dirs::home_dir().ok_or_else(|| CoreError::not_found("Home directory not found"))?;
 
-        if input.path == "~" {
+        if expanded == "~" {
             home
-        } else if input.path.starts_with("~/") {
-            home.join(&input.path[2..])
+        } else if let Some(relative) = expanded.strip_prefix("~/") {
+            home.join(relative)
         } else {
-            std::path::PathBuf::from(&input.path)
+            std::path::PathBuf::from(&expanded)
         }
     } else {
-        std::path::PathBuf::from(&input.path)
+        std::path::PathBuf::from(&expanded)
     };
 
     Ok(ExpandPathOutput { path })
 }
+
+/// 替换字符串中的 `%VAR%`、`$VAR`、`${VAR}`，未定义的变量原样保留
+fn expand_env_vars(input: &str) -> String {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let lookup = |name: &str| {
+        if name.is_empty() {
+            None
+        } else {
+            std::env::var(name).ok()
+        }
+    };
+
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find(['$', '%']) {
+        out.push_str(&rest[..pos]);
+        let marker = &rest[pos..pos + 1];
+        let after = &rest[pos + 1..];
+
+        // (变量值, 变量引用之后的剩余部分)
+        let found = if marker == "%" {
+            after.find('%').and_then(|end| {
+                let name = &after[..end];
+                if name.contains(['/', '\\']) || name.chars().any(char::is_whitespace) {
+                    return None;
+                }
+                lookup(name).map(|value| (value, &after[end + 1..]))
+            })
+        } else if let Some(braced) = after.strip_prefix('{') {
+            braced
+                .find('}')
+                .and_then(|end| lookup(&braced[..end]).map(|value| (value, &braced[end + 1..])))
+        } else {
+            let end = after
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(after.len());
+            if after.starts_with(|c: char| c.is_ascii_digit()) {
+                None
+            } else {
+                lookup(&after[..end]).map(|value| (value, &after[end..]))
+            }
+        };
+
+        match found {
+            Some((value, remaining)) => {
+                out.push_str(&value);
+                rest = remaining;
+            }
+            None => {
+                out.push_str(marker);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 为 Windows 绝对路径添加 `\\?\` 前缀以突破 MAX_PATH（260）限制
+///
+/// - 已带前缀的路径原样返回
+/// - UNC 路径 `\\server\share` 转为 `\\?\UNC\server\share`
+/// - 非 Windows 平台原样返回
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        windows_long_path(path)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn windows_long_path(path: &Path) -> PathBuf {
+    // `\\?\` 前缀会关闭路径规范化，先转为规范的绝对路径
+    let Ok(abs) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let s = abs.to_string_lossy().into_owned();
+
+    if s.starts_with(r"\\?\") {
+        abs
+    } else if let Some(unc) = s.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", unc))
+    } else {
+        PathBuf::from(format!(r"\\?\{}", s))
+    }
+}
+
+/// 文件名最大字符数（留出余量给 ID 前缀与扩展名）
+const MAX_FILENAME_CHARS: usize = 120;
+
+/// Windows 保留设备名（不区分大小写，带扩展名同样保留）
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 将任意文本（如壁纸标题）转为跨平台安全的文件名
+///
+/// - `/\:*?"<>|` 与控制字符替换为 `_`
+/// - 去除首尾空白，以及末尾的 `.` 和空格（Windows 不允许）
+/// - 超过 120 个字符时截断
+/// - Windows 保留名（CON、NUL、COM1 等）在主名后追加 `_`，扩展名保持不变
+/// - 结果为空时返回 `_`
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let truncated: String = replaced.trim().chars().take(MAX_FILENAME_CHARS).collect();
+    let mut safe = truncated.trim_end_matches(['.', ' ']).to_string();
+
+    if safe.is_empty() {
+        return "_".to_string();
+    }
+
+    let stem = safe.split('.').next().unwrap_or("").trim_end();
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(stem))
+    {
+        // 在主名后插入，保留扩展名（NUL.txt → NUL_.txt）
+        safe.insert(stem.len(), '_');
+    }
+
+    safe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename_replaces_invalid_chars() {
+        assert_eq!(
+            sanitize_filename("a/b\\c:d*e?f\"g<h>i|j"),
+            "a_b_c_d_e_f_g_h_i_j"
+        );
+        assert_eq!(sanitize_filename("tab\there"), "tab_here");
+        assert_eq!(sanitize_filename("  Sunset Vibes.  "), "Sunset Vibes");
+    }
+
+    #[test]
+    fn test_sanitize_filename_reserved_and_empty() {
+        assert_eq!(sanitize_filename("con"), "con_");
+        assert_eq!(sanitize_filename("NUL.txt"), "NUL_.txt");
+        assert_eq!(sanitize_filename("com1.tar.gz"), "com1_.tar.gz");
+        assert_eq!(sanitize_filename("Console"), "Console");
+        assert_eq!(sanitize_filename(" ... "), "_");
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        std::env::set_var("LIANPKG_TEST_EXPAND", "/data/lian");
+        std::env::remove_var("LIANPKG_TEST_MISSING");
+
+        assert_eq!(expand_env_vars("$LIANPKG_TEST_EXPAND/ws"), "/data/lian/ws");
+        assert_eq!(expand_env_vars("${LIANPKG_TEST_EXPAND}_x"), "/data/lian_x");
+        assert_eq!(
+            expand_env_vars("%LIANPKG_TEST_EXPAND%\\ws"),
+            "/data/lian\\ws"
+        );
+        // 未定义的变量与孤立符号原样保留
+        assert_eq!(
+            expand_env_vars("$LIANPKG_TEST_MISSING/%LIANPKG_TEST_MISSING%/${}"),
+            "$LIANPKG_TEST_MISSING/%LIANPKG_TEST_MISSING%/${}"
+        );
+        assert_eq!(expand_env_vars("100% $5 a%b"), "100% $5 a%b");
+    }
+
+    #[test]
+    fn test_sanitize_filename_truncates() {
+        let long = "字".repeat(300);
+        assert_eq!(sanitize_filename(&long).chars().count(), MAX_FILENAME_CHARS);
+    }
+}
@@ -0,0 +1,331 @@
+//! Steam VDF (KeyValues) 文本格式解析
+//!
+//! 仅实现读取 libraryfolders.vdf 所需的子集：
+//! - `"key" "value"` 键值对
+//! - `"key" { ... }` 嵌套块
+//! - `//` 行注释与常见转义（`\\`、`\"`、`\n`、`\t`）
+
+use super::types::SteamLibrary;
+use crate::core::error::{CoreError, CoreResult};
+use std::path::PathBuf;
+
+// ============================================================================
+// 数据结构
+// ============================================================================
+
+/// VDF 节点值
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VdfValue {
+    /// 字符串值
+    Str(String),
+    /// 嵌套块（保持原始顺序）
+    Block(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+    /// 获取字符串值
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::Str(s) => Some(s),
+            VdfValue::Block(_) => None,
+        }
+    }
+
+    /// 获取块内的键值对
+    pub(crate) fn as_block(&self) -> Option<&[(String, VdfValue)]> {
+        match self {
+            VdfValue::Block(items) => Some(items),
+            VdfValue::Str(_) => None,
+        }
+    }
+
+    /// 按键名（大小写不敏感）查找子节点
+    pub(crate) fn get(&self, key: &str) -> Option<&VdfValue> {
+        self.as_block()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+}
+
+/// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+// ============================================================================
+// 解析接口
+// ============================================================================
+
+/// 解析 VDF 文本，返回顶层块
+pub(crate) fn parse_vdf(content: &str) -> CoreResult<VdfValue> {
+    let tokens = tokenize(content)?;
+    let mut pos = 0;
+    let root = parse_block(&tokens, &mut pos, false)?;
+    Ok(VdfValue::Block(root))
+}
+
+/// 解析 libraryfolders.vdf，返回所有库路径及其包含的 app id
+///
+/// 同时兼容新版（`"0" { "path" ... "apps" { ... } }`）
+/// 与旧版（`"1" "D:\\SteamLibrary"`）两种布局
+pub(crate) fn parse_library_folders(content: &str) -> CoreResult<Vec<SteamLibrary>> {
+    let root = parse_vdf(content)?;
+    let folders = root
+        .get("libraryfolders")
+        .or_else(|| root.get("LibraryFolders"))
+        .ok_or_else(|| {
+            CoreError::parse_with_source("Missing 'libraryfolders' block", "libraryfolders.vdf")
+        })?;
+
+    let mut libraries = Vec::new();
+    for (key, value) in folders.as_block().unwrap_or(&[]) {
+        // 库条目的键是数字索引，其余键（contentstatsid 等）忽略
+        if key.parse::<u32>().is_err() {
+            continue;
+        }
+
+        match value {
+            VdfValue::Str(path) => libraries.push(SteamLibrary {
+                path: PathBuf::from(path),
+                apps: Vec::new(),
+            }),
+            VdfValue::Block(_) => {
+                let Some(path) = value.get("path").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let apps = value
+                    .get("apps")
+                    .and_then(|v| v.as_block())
+                    .map(|items| items.iter().map(|(id, _)| id.clone()).collect())
+                    .unwrap_or_default();
+                libraries.push(SteamLibrary {
+                    path: PathBuf::from(path),
+                    apps,
+                });
+            }
+        }
+    }
+
+    Ok(libraries)
+}
+
+// ============================================================================
+// 内部实现
+// ============================================================================
+
+/// 解析块内容，直到遇到 `}` 或输入结束
+fn parse_block(
+    tokens: &[Token],
+    pos: &mut usize,
+    nested: bool,
+) -> CoreResult<Vec<(String, VdfValue)>> {
+    let mut items = Vec::new();
+
+    while *pos < tokens.len() {
+        let key = match &tokens[*pos] {
+            Token::Str(s) => s.clone(),
+            Token::Close if nested => {
+                *pos += 1;
+                return Ok(items);
+            }
+            Token::Close => return Err(CoreError::parse("Unexpected '}' in VDF")),
+            Token::Open => return Err(CoreError::parse("Unexpected '{' in VDF")),
+        };
+        *pos += 1;
+
+        let value = match tokens.get(*pos) {
+            Some(Token::Str(s)) => {
+                *pos += 1;
+                VdfValue::Str(s.clone())
+            }
+            Some(Token::Open) => {
+                *pos += 1;
+                VdfValue::Block(parse_block(tokens, pos, true)?)
+            }
+            Some(Token::Close) | None => {
+                return Err(CoreError::parse(format!(
+                    "Missing value for key '{}' in VDF",
+                    key
+                )))
+            }
+        };
+
+        items.push((key, value));
+    }
+
+    if nested {
+        return Err(CoreError::parse("Unclosed block in VDF"));
+    }
+    Ok(items)
+}
+
+/// 将 VDF 文本切分为词法单元
+fn tokenize(content: &str) -> CoreResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '/' => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // 行注释
+                    for ch in chars.by_ref() {
+                        if ch == '\n' {
+                            break;
+                        }
+                    }
+                } else {
+                    return Err(CoreError::parse("Unexpected '/' in VDF"));
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                let mut closed = false;
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => break,
+                        },
+                        _ => s.push(ch),
+                    }
+                }
+                if !closed {
+                    return Err(CoreError::parse("Unterminated string in VDF"));
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ => {
+                // 无引号的裸字符串
+                let mut s = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ch == '{' || ch == '}' || ch == '"' {
+                        break;
+                    }
+                    s.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Str(s));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_library_folders_modern() {
+        let content = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/user/.local/share/Steam"
+		"label"		""
+		"contentid"		"4417329467429618375"
+		"totalsize"		"0"
+		"apps"
+		{
+			"228980"		"229566447"
+			"1070560"		"8364221"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+		"label"		""
+		"apps"
+		{
+			"431960"		"312938271"
+		}
+	}
+}
+"#;
+        let libs = parse_library_folders(content).unwrap();
+        assert_eq!(libs.len(), 2);
+        assert_eq!(libs[0].path, PathBuf::from("/home/user/.local/share/Steam"));
+        assert_eq!(libs[0].apps, vec!["228980", "1070560"]);
+        assert_eq!(libs[1].path, PathBuf::from("/mnt/games/SteamLibrary"));
+        assert!(libs[1].apps.iter().any(|a| a == "431960"));
+    }
+
+    #[test]
+    fn test_parse_library_folders_windows_escapes() {
+        let content = r#"
+"libraryfolders"
+{
+	"contentstatsid"		"-123456789"
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"apps"
+		{
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"apps"
+		{
+			"431960"		"1"
+		}
+	}
+}
+"#;
+        let libs = parse_library_folders(content).unwrap();
+        assert_eq!(libs.len(), 2);
+        assert_eq!(libs[0].path, PathBuf::from(r"C:\Program Files (x86)\Steam"));
+        assert!(libs[0].apps.is_empty());
+        assert_eq!(libs[1].path, PathBuf::from(r"D:\SteamLibrary"));
+    }
+
+    #[test]
+    fn test_parse_library_folders_legacy() {
+        let content = r#"
+"LibraryFolders"
+{
+	// 旧版格式：库路径直接作为值
+	"TimeNextStatsReport"		"1600000000"
+	"ContentStatsID"		"-42"
+	"1"		"D:\\SteamLibrary"
+}
+"#;
+        let libs = parse_library_folders(content).unwrap();
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].path, PathBuf::from(r"D:\SteamLibrary"));
+        assert!(libs[0].apps.is_empty());
+    }
+
+    #[test]
+    fn test_parse_vdf_rejects_unclosed_block() {
+        assert!(parse_vdf("\"libraryfolders\" { \"0\" { \"path\" \"/x\" }").is_err());
+        assert!(parse_library_folders("\"other\" { }").is_err());
+    }
+}
@@ -6,7 +6,7 @@
 //!
 //! 主要接口：
 //! - 解析: parse_pkg
-//! - 解包: unpack_pkg, unpack_entry
+//! - 解包: unpack_pkg, unpack_pkg_with_progress, unpack_entry
 
 mod structs;
 mod parse;
@@ -33,6 +33,10 @@ pub use structs::UnpackEntryOutput;
 pub use structs::PkgInfo;
 pub use structs::PkgEntry;
 pub use structs::ExtractedFile;
+pub use structs::DuplicateStrategy;
+pub use structs::EntryFilter;
+pub use structs::UnpackProgress;
+pub use structs::UnpackProgressCallback;
 
 // ============================================================================
 // 导出解析接口
@@ -43,4 +47,6 @@ pub use parse::parse_pkg;
 // 导出解包接口
 // ============================================================================
 pub use unpack::unpack_pkg;
+pub use unpack::unpack_pkg_with_progress;
 pub use unpack::unpack_entry;
+pub(crate) use unpack::is_safe_entry_name;
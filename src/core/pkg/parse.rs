@@ -1,8 +1,10 @@
 //! 解析接口 - 只读取元数据，不写入文件
 
-use std::fs;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
 
 use crate::core::error::{CoreError, CoreResult};
+use crate::core::path::long_path;
 use crate::core::pkg::structs::{ParsePkgInput, ParsePkgOutput, PkgEntry, PkgInfo};
 use crate::core::pkg::utl::Reader;
 
@@ -11,33 +13,109 @@ use crate::core::pkg::utl::Reader;
 pub fn parse_pkg(input: ParsePkgInput) -> CoreResult<ParsePkgOutput> {
     let file_path = input.file_path;
 
-    // 读取文件
-    let data = fs::read(&file_path).map_err(|e| CoreError::Io {
-        message: e.to_string(),
-        path: Some(file_path.display().to_string()),
+    // 只读取头部与索引，数据区只探测每个条目开头的几个字节
+    let (file, len) = open_pkg(&file_path)?;
+    let mut header = Reader::new(BufReader::new(file), len);
+    let mut output = parse_pkg_header(&mut header)?;
+    detect_compressed(&mut header.into_inner(), &mut output.pkg_info).map_err(|e| {
+        CoreError::Io {
+            message: e.to_string(),
+            path: Some(file_path.display().to_string()),
+        }
     })?;
+    Ok(output)
+}
+
+/// LZ4 帧魔数（小端序 0x184D2204）
+pub(crate) const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+/// 探测每个条目的数据是否以 LZ4 帧魔数开头，结果写入 `PkgEntry::compressed`
+///
+/// pkg 索引本身不带压缩标记，只能按内容嗅探；越界的条目保持未压缩，由解包时报错
+pub(crate) fn detect_compressed<R: Read + Seek>(
+    reader: &mut R,
+    pkg_info: &mut PkgInfo,
+) -> std::io::Result<()> {
+    let mut magic = [0u8; 4];
+    for entry in &mut pkg_info.entries {
+        if (entry.size as usize) < magic.len() {
+            continue;
+        }
+        let start = pkg_info.data_start as u64 + entry.offset as u64;
+        reader.seek(SeekFrom::Start(start))?;
+        entry.compressed = reader.read_exact(&mut magic).is_ok() && magic == LZ4_FRAME_MAGIC;
+    }
+    Ok(())
+}
 
-    // 解析文件
-    parse_pkg_data(&data)
+/// 版本号是否为可识别的格式：`PKGV` 加 4 位数字（如 `PKGV0001`、`PKGV0022`）
+///
+/// 各版本的头部与索引结构相同，只校验格式不限定具体编号
+fn is_known_version(version: &str) -> bool {
+    version
+        .strip_prefix("PKGV")
+        .is_some_and(|n| n.len() == 4 && n.bytes().all(|b| b.is_ascii_digit()))
 }
 
-/// 从字节数据解析 pkg 信息（内部函数，供 unpack 复用）
-pub(crate) fn parse_pkg_data(data: &[u8]) -> CoreResult<ParsePkgOutput> {
-    let mut r = Reader::new(data);
+/// 打开 pkg 文件，返回文件句柄与文件长度
+pub(crate) fn open_pkg(file_path: &std::path::Path) -> CoreResult<(File, usize)> {
+    let io_err = |e: std::io::Error| CoreError::Io {
+        message: e.to_string(),
+        path: Some(file_path.display().to_string()),
+    };
+    let file = File::open(long_path(file_path)).map_err(io_err)?;
+    let len = file.metadata().map_err(io_err)?.len() as usize;
+    Ok((file, len))
+}
+
+/// 每个条目索引的最小字节数（名称长度 + 偏移 + 大小）
+const MIN_ENTRY_SIZE: usize = 12;
 
-    // 读取版本
+/// 从读取器解析 pkg 头部与条目索引（内部函数，供 unpack 复用）
+///
+/// 读取结束时读取器恰好位于数据区起始位置。
+/// 版本号不可识别、文件数量超出文件大小能容纳的范围或索引被截断时返回错误，
+/// 避免把非 pkg 文件当作 pkg 解析出无意义的条目
+pub(crate) fn parse_pkg_header<R: Read>(r: &mut Reader<R>) -> CoreResult<ParsePkgOutput> {
+    // 读取并校验版本
     let version = r.read_string();
+    if !is_known_version(&version) {
+        return Err(CoreError::unsupported(format!(
+            "Unrecognized PKG version: {}",
+            version
+        )));
+    }
 
-    // 读取文件数量
+    // 读取文件数量（每个条目至少占 MIN_ENTRY_SIZE 字节）
     let file_count = r.read_u32();
+    let max_entries = r.len().saturating_sub(r.position()) / MIN_ENTRY_SIZE;
+    if file_count as usize > max_entries {
+        return Err(CoreError::invalid_pkg(format!(
+            "Invalid PKG file count {} for a {} byte file",
+            file_count,
+            r.len()
+        )));
+    }
 
     // 读取文件条目
     let mut entries = Vec::with_capacity(file_count as usize);
     for _ in 0..file_count {
+        if r.len().saturating_sub(r.position()) < MIN_ENTRY_SIZE {
+            return Err(CoreError::invalid_pkg(format!(
+                "PKG index is truncated ({} of {} entries)",
+                entries.len(),
+                file_count
+            )));
+        }
         let name = r.read_string();
         let offset = r.read_u32();
         let size = r.read_u32();
-        entries.push(PkgEntry { name, offset, size });
+        entries.push(PkgEntry {
+            name,
+            offset,
+            size,
+            compressed: false,
+        });
     }
 
     // 记录数据区起始位置
@@ -52,3 +130,62 @@ pub(crate) fn parse_pkg_data(data: &[u8]) -> CoreResult<ParsePkgOutput> {
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::error::CoreErrorKind;
+    use crate::core::fixtures::{pkg_index, push_str};
+
+    fn parse_bytes(data: &[u8]) -> CoreResult<ParsePkgOutput> {
+        parse_pkg_header(&mut Reader::new(data, data.len()))
+    }
+
+    #[test]
+    fn test_parse_pkg_rejects_bogus_magic() {
+        let mut data = Vec::new();
+        push_str(&mut data, "NOTAPKG!");
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[0u8; 32]);
+
+        let err = parse_bytes(&data).unwrap_err();
+        assert!(matches!(err, CoreError::Unsupported { .. }));
+        assert!(err
+            .to_string()
+            .contains("Unrecognized PKG version: NOTAPKG!"));
+
+        // 完全不是 pkg 的数据（长度前缀无效）同样被拒绝
+        assert!(matches!(
+            parse_bytes(b"\x89PNG\r\n\x1a\n").unwrap_err(),
+            CoreError::Unsupported { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_pkg_rejects_truncated_file() {
+        let mut data = pkg_index(
+            "PKGV0019",
+            &[("scene.json", 0, 2), ("materials/a.tex", 2, 4)],
+        );
+        data.extend_from_slice(b"{}abcd");
+        assert_eq!(parse_bytes(&data).unwrap().pkg_info.entries.len(), 2);
+
+        // 截断在第二个条目的索引中间
+        let cut = 4 + 8 + 4 + 4 + 10 + 8 + 6;
+        let err = parse_bytes(&data[..cut]).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+        assert_eq!(err.kind(), CoreErrorKind::InvalidPkg);
+
+        // 文件数量远超文件大小能容纳的范围
+        let mut huge = Vec::new();
+        push_str(&mut huge, "PKGV0001");
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        let err = parse_bytes(&huge).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid PKG file count"),
+            "{}",
+            err
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! 结构体定义 - Input/Output、运行时结构体
 
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::core::error::{CoreError, CoreResult};
+
 // ============================================================================
 // Input 结构体
 // ============================================================================
@@ -21,6 +24,12 @@ pub struct UnpackPkgInput {
     pub file_path: PathBuf,
     /// 输出目录
     pub output_base: PathBuf,
+    /// 同名条目的处理策略
+    pub duplicate_strategy: DuplicateStrategy,
+    /// 只解包匹配这些 glob 的条目，None 表示全部
+    pub include: Option<Vec<String>>,
+    /// 跳过匹配这些 glob 的条目
+    pub exclude: Option<Vec<String>>,
 }
 
 /// unpack_entry 接口入参
@@ -54,6 +63,14 @@ pub struct UnpackPkgOutput {
     pub pkg_info: PkgInfo,
     /// 解包的文件列表
     pub extracted_files: Vec<ExtractedFile>,
+    /// 同名条目数量（不含首次出现）
+    pub duplicate_count: usize,
+    /// 被 include/exclude 过滤跳过的条目数量
+    pub skipped_count: usize,
+    /// 名称为空或会写到输出目录之外（`..`、绝对路径、盘符）而被拒绝的条目名
+    pub rejected_entries: Vec<String>,
+    /// 看似 LZ4 压缩但解压失败、按原始数据写出的条目名
+    pub undecoded_entries: Vec<String>,
 }
 
 /// unpack_entry 接口返回值
@@ -80,6 +97,89 @@ pub struct PkgInfo {
     pub data_start: usize,
 }
 
+impl PkgInfo {
+    /// 查找重复出现的条目名（按首次重复的顺序，每个名字只列一次）
+    pub fn duplicate_names(&self) -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates: Vec<String> = Vec::new();
+        for entry in &self.entries {
+            if !seen.insert(entry.name.as_str()) && !duplicates.contains(&entry.name) {
+                duplicates.push(entry.name.clone());
+            }
+        }
+        duplicates
+    }
+}
+
+/// 条目名过滤器（glob，大小写不敏感）
+///
+/// 设置 include 时只保留匹配任一模式的条目；匹配 exclude 任一模式的条目总是跳过。
+/// `*` 可跨越 `/`，因此 `*.tex` 匹配任意目录下的 .tex
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl EntryFilter {
+    /// 编译 glob 模式，模式无效时返回 Validation 错误
+    pub fn new(include: Option<&[String]>, exclude: Option<&[String]>) -> CoreResult<Self> {
+        Ok(Self {
+            include: include.map(build_globset).transpose()?,
+            exclude: exclude.map(build_globset).transpose()?,
+        })
+    }
+
+    /// 条目是否应当保留
+    pub fn allows(&self, name: &str) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(name))
+            && !self.exclude.as_ref().is_some_and(|set| set.is_match(name))
+    }
+}
+
+/// 编译一组大小写不敏感的 glob
+fn build_globset(patterns: &[String]) -> CoreResult<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| CoreError::Validation {
+                message: format!("Invalid pattern '{}': {}", pattern, e),
+            })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| CoreError::Validation {
+        message: e.to_string(),
+    })
+}
+
+/// 单个 PKG 的解包进度（每写完一个条目报告一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackProgress {
+    /// 刚写完的条目序号（从 0 开始，按 PKG 内顺序）
+    pub entry_index: usize,
+    /// PKG 条目总数（含被过滤跳过的条目）
+    pub total_entries: usize,
+    /// 刚写完的条目名
+    pub entry_name: String,
+    /// 本 PKG 至此累计写入的字节数
+    pub bytes_written: u64,
+}
+
+/// 解包进度回调函数类型（批量解包时可能在工作线程上调用，需可跨线程共享）
+pub type UnpackProgressCallback<'a> = &'a (dyn Fn(UnpackProgress) + Sync);
+
+/// 同名条目的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateStrategy {
+    /// 后出现的条目覆盖先出现的（与旧行为一致）
+    #[default]
+    Overwrite,
+    /// 后出现的条目加序号保存，如 `a_1.tex`；序号会跳过已有条目名和磁盘上已存在的文件
+    Rename,
+}
+
 /// 文件条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PkgEntry {
@@ -89,6 +189,9 @@ pub struct PkgEntry {
     pub offset: u32,
     /// 文件大小
     pub size: u32,
+    /// 条目数据是否为 LZ4 帧（按数据起始的帧魔数判断，解包时自动解压）
+    #[serde(default)]
+    pub compressed: bool,
 }
 
 /// 解包后的文件信息
@@ -98,6 +201,6 @@ pub struct ExtractedFile {
     pub entry_name: String,
     /// 输出路径
     pub output_path: PathBuf,
-    /// 文件大小
+    /// 写出的文件大小（LZ4 条目为解压后大小）
     pub size: u32,
 }
@@ -1,90 +1,623 @@
 //! 解包接口 - 解析并解包 pkg 文件
 
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 use crate::core::error::{CoreError, CoreResult};
-use crate::core::pkg::parse::parse_pkg_data;
+use crate::core::path::long_path;
+use crate::core::pkg::parse::{detect_compressed, open_pkg, parse_pkg_header, LZ4_FRAME_MAGIC};
 use crate::core::pkg::structs::{
-    ExtractedFile, UnpackEntryInput, UnpackEntryOutput, UnpackPkgInput, UnpackPkgOutput,
+    DuplicateStrategy, EntryFilter, ExtractedFile, PkgEntry, UnpackEntryInput, UnpackEntryOutput,
+    UnpackPkgInput, UnpackPkgOutput, UnpackProgress, UnpackProgressCallback,
 };
+use crate::core::pkg::utl::Reader;
+
+/// 流式解包时每次复制的块大小
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// LZ4 解压结果相对压缩数据的最大倍数
+const MAX_LZ4_RATIO: u64 = 256;
 
 /// 解包整个 pkg 文件
 /// 解析元数据并提取所有文件到输出目录
+///
+/// 只读取头部与索引，条目数据按块从文件复制到输出，不把整个 pkg 载入内存
 pub fn unpack_pkg(input: UnpackPkgInput) -> CoreResult<UnpackPkgOutput> {
+    unpack_pkg_with_progress(input, None)
+}
+
+/// 解包整个 pkg 文件（带进度回调）
+///
+/// 每写完一个条目调用一次 `on_progress`，被过滤跳过的条目不报告
+pub fn unpack_pkg_with_progress(
+    input: UnpackPkgInput,
+    on_progress: Option<UnpackProgressCallback>,
+) -> CoreResult<UnpackPkgOutput> {
     let file_path = input.file_path;
     let output_base = input.output_base;
+    let filter = EntryFilter::new(input.include.as_deref(), input.exclude.as_deref())?;
 
-    // 读取文件
-    let data = fs::read(&file_path).map_err(|e| CoreError::Io {
-        message: e.to_string(),
-        path: Some(file_path.display().to_string()),
-    })?;
-
-    // 解析 pkg
-    let parse_result = parse_pkg_data(&data)?;
-    let pkg_info = parse_result.pkg_info;
+    // 解析 pkg 头部
+    let (file, len) = open_pkg(&file_path)?;
+    let mut header = Reader::new(BufReader::new(file), len);
+    let parse_result = parse_pkg_header(&mut header)?;
+    let mut pkg_info = parse_result.pkg_info;
     let data_start = pkg_info.data_start;
     let mut extracted_files = Vec::new();
+    // 条目名 → 已出现次数
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut duplicate_count = 0;
+    let mut skipped_count = 0;
+    let mut rejected_entries = Vec::new();
+    let mut undecoded_entries = Vec::new();
+    let mut bytes_written: u64 = 0;
+    let total_entries = pkg_info.entries.len();
+
+    // 以文件句柄继续读取数据区（每个条目 seek 到自身位置）
+    let mut reader = header.into_inner();
+    let io_err = |e: std::io::Error| CoreError::Io {
+        message: e.to_string(),
+        path: Some(file_path.display().to_string()),
+    };
+    detect_compressed(&mut reader, &mut pkg_info).map_err(io_err)?;
+    // 所有条目的原始输出路径，加序号时需避开
+    let entry_paths: HashSet<PathBuf> = pkg_info
+        .entries
+        .iter()
+        .map(|e| output_base.join(&e.name))
+        .collect();
+    // 本次已分配的加序号路径
+    let mut renamed_paths: HashSet<PathBuf> = HashSet::new();
 
     // 解包每个条目
-    for entry in &pkg_info.entries {
-        let output_path = output_base.join(&entry.name);
-
-        let result = unpack_entry(UnpackEntryInput {
-            pkg_data: data.clone(),
-            data_start,
-            entry: entry.clone(),
-            output_path: output_path.clone(),
-        })?;
+    for (entry_index, entry) in pkg_info.entries.iter().enumerate() {
+        if !is_safe_entry_name(&entry.name) {
+            rejected_entries.push(entry.name.clone());
+            continue;
+        }
+        if !filter.allows(&entry.name) {
+            skipped_count += 1;
+            continue;
+        }
+
+        let occurrence = seen.entry(entry.name.as_str()).or_insert(0);
+        let output_path = match (*occurrence, input.duplicate_strategy) {
+            (0, _) | (_, DuplicateStrategy::Overwrite) => output_base.join(&entry.name),
+            (n, DuplicateStrategy::Rename) => {
+                let base = output_base.join(&entry.name);
+                let mut n = n;
+                let mut path = numbered_path(&base, n);
+                while entry_paths.contains(&path)
+                    || renamed_paths.contains(&path)
+                    || long_path(&path).exists()
+                {
+                    n += 1;
+                    path = numbered_path(&base, n);
+                }
+                renamed_paths.insert(path.clone());
+                path
+            }
+        };
+        if *occurrence > 0 {
+            duplicate_count += 1;
+        }
+        *occurrence += 1;
+
+        let (start, end) = entry_range(data_start, entry, len)?;
+        reader.seek(SeekFrom::Start(start as u64)).map_err(io_err)?;
+        let mut out = create_output(&output_path)?;
+        let out_err = |e: std::io::Error| CoreError::Io {
+            message: e.to_string(),
+            path: Some(output_path.display().to_string()),
+        };
+        let size = if entry.compressed {
+            // LZ4 条目边解压边写出；无法解压或超出大小上限时回到条目开头按原始数据写出
+            let limit = lz4_output_limit(end - start);
+            match copy_lz4_chunked(&mut reader, &mut out, end - start, limit).map_err(out_err)? {
+                Some(size) => size,
+                None => {
+                    undecoded_entries.push(entry.name.clone());
+                    reader.seek(SeekFrom::Start(start as u64)).map_err(io_err)?;
+                    out.set_len(0).and_then(|_| out.rewind()).map_err(out_err)?;
+                    copy_chunked(&mut reader, &mut out, end - start, COPY_CHUNK_SIZE)
+                        .map_err(out_err)?;
+                    (end - start) as u64
+                }
+            }
+        } else {
+            copy_chunked(&mut reader, &mut out, end - start, COPY_CHUNK_SIZE).map_err(out_err)?;
+            (end - start) as u64
+        };
 
         extracted_files.push(ExtractedFile {
             entry_name: entry.name.clone(),
-            output_path: result.output_path,
-            size: entry.size,
+            output_path,
+            size: u32::try_from(size).unwrap_or(u32::MAX),
         });
+
+        bytes_written += size;
+        if let Some(callback) = on_progress {
+            callback(UnpackProgress {
+                entry_index,
+                total_entries,
+                entry_name: entry.name.clone(),
+                bytes_written,
+            });
+        }
     }
 
     Ok(UnpackPkgOutput {
         pkg_info,
         extracted_files,
+        duplicate_count,
+        skipped_count,
+        rejected_entries,
+        undecoded_entries,
     })
 }
 
-/// 解包单个条目
-/// 用于精细控制，选择性解包特定文件
-pub fn unpack_entry(input: UnpackEntryInput) -> CoreResult<UnpackEntryOutput> {
-    let data = &input.pkg_data;
-    let data_start = input.data_start;
-    let entry = &input.entry;
-    let output_path = input.output_path;
+/// 解压完整的 LZ4 帧，数据不是有效的 LZ4 帧或解压结果超出上限时返回 None
+fn decode_lz4_frame(raw: &[u8]) -> Option<Vec<u8>> {
+    let limit = lz4_output_limit(raw.len());
+    let mut data = Vec::new();
+    lz4_flex::frame::FrameDecoder::new(raw)
+        .take(limit + 1)
+        .read_to_end(&mut data)
+        .ok()?;
+    (data.len() as u64 <= limit).then_some(data)
+}
 
-    // 计算数据位置
-    let start = data_start + entry.offset as usize;
-    let end = start + entry.size as usize;
+/// 压缩数据长度为 `compressed_len` 的 LZ4 帧允许解压出的最大字节数
+///
+/// LZ4 的压缩比理论上不超过约 255:1，超出即视为损坏或恶意构造（解压炸弹）；
+/// 条目大小以 u32 记录，同时不超过 u32::MAX
+fn lz4_output_limit(compressed_len: usize) -> u64 {
+    (compressed_len as u64)
+        .saturating_mul(MAX_LZ4_RATIO)
+        .min(u64::from(u32::MAX))
+}
 
-    // 边界检查
-    if end > data.len() {
-        return Err(CoreError::Validation {
-            message: format!("Entry {} out of bounds", entry.name),
-        });
+/// 从 reader 读取 len 字节的 LZ4 帧，按块解压写入 writer，返回解压后的大小
+///
+/// 数据不是有效的 LZ4 帧或解压结果超过 limit 时返回 `Ok(None)`，
+/// 此时 writer 中可能已有部分数据，由调用方负责丢弃；写入失败返回 Err
+fn copy_lz4_chunked<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    len: usize,
+    limit: u64,
+) -> std::io::Result<Option<u64>> {
+    let mut decoder = lz4_flex::frame::FrameDecoder::new(reader.take(len as u64));
+    let mut buf = vec![0u8; COPY_CHUNK_SIZE];
+    let mut written: u64 = 0;
+    loop {
+        let n = match decoder.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return Ok(None),
+        };
+        written += n as u64;
+        if written > limit {
+            return Ok(None);
+        }
+        writer.write_all(&buf[..n])?;
+    }
+    writer.flush()?;
+    Ok(Some(written))
+}
+
+/// 条目名能否安全地拼接到输出目录下
+///
+/// 拒绝空名称、绝对路径（`/x`、`\\x`）、盘符（`C:`）以及任何 `..` 组成部分，
+/// 防止恶意 pkg 把文件写到输出目录之外。两种分隔符都检查，与运行平台无关
+pub(crate) fn is_safe_entry_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    if name.starts_with(['/', '\\']) || has_drive || Path::new(name).is_absolute() {
+        return false;
     }
+    let mut parts = name
+        .split(['/', '\\'])
+        .filter(|p| !p.is_empty() && *p != ".")
+        .peekable();
+    parts.peek().is_some() && parts.all(|p| p != "..")
+}
+
+/// 为重复条目生成带序号的路径：`dir/name.ext` → `dir/name_N.ext`
+fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, n),
+    };
+    path.with_file_name(file_name)
+}
 
-    // 提取内容
-    let content = &data[start..end];
+/// 计算条目在 pkg 中的字节范围 `[start, end)`
+///
+/// 损坏或恶意构造的 offset/size 可能溢出或越界，统一报告为无效条目
+fn entry_range(data_start: usize, entry: &PkgEntry, len: usize) -> CoreResult<(usize, usize)> {
+    data_start
+        .checked_add(entry.offset as usize)
+        .and_then(|start| Some((start, start.checked_add(entry.size as usize)?)))
+        .filter(|&(_, end)| end <= len)
+        .ok_or_else(|| {
+            CoreError::invalid_pkg(format!("Entry {} has invalid offset/size", entry.name))
+        })
+}
 
-    // 确保父目录存在
+/// 创建输出文件（确保父目录存在，Windows 下使用长路径形式，避免超过 MAX_PATH）
+fn create_output(output_path: &Path) -> CoreResult<File> {
     if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| CoreError::Io {
+        fs::create_dir_all(long_path(parent)).map_err(|e| CoreError::Io {
             message: e.to_string(),
             path: Some(parent.display().to_string()),
         })?;
     }
 
-    // 写入文件
-    fs::write(&output_path, content).map_err(|e| CoreError::Io {
+    File::create(long_path(output_path)).map_err(|e| CoreError::Io {
         message: e.to_string(),
         path: Some(output_path.display().to_string()),
-    })?;
+    })
+}
+
+/// 按块从 reader 复制 size 字节到 writer
+fn copy_chunked<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    size: usize,
+    chunk_size: usize,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; chunk_size.min(size).max(1)];
+    let mut remaining = size;
+    while remaining > 0 {
+        let n = remaining.min(buf.len());
+        reader.read_exact(&mut buf[..n])?;
+        writer.write_all(&buf[..n])?;
+        remaining -= n;
+    }
+    writer.flush()
+}
+
+/// 解包单个条目
+/// 用于精细控制，选择性解包特定文件
+pub fn unpack_entry(input: UnpackEntryInput) -> CoreResult<UnpackEntryOutput> {
+    let data = &input.pkg_data;
+    let (start, end) = entry_range(input.data_start, &input.entry, data.len())?;
+    let output_path = input.output_path;
+
+    // LZ4 条目先解压，失败时按原始数据写出
+    let raw = &data[start..end];
+    let decoded = (input.entry.compressed || raw.starts_with(&LZ4_FRAME_MAGIC))
+        .then(|| decode_lz4_frame(raw))
+        .flatten();
+
+    // 写入文件
+    create_output(&output_path)?
+        .write_all(decoded.as_deref().unwrap_or(raw))
+        .map_err(|e| CoreError::Io {
+            message: e.to_string(),
+            path: Some(output_path.display().to_string()),
+        })?;
 
     Ok(UnpackEntryOutput { output_path })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{pkg_bytes, pkg_index};
+
+    #[test]
+    fn test_unpack_pkg_renames_duplicates() {
+        let data = pkg_bytes(&[
+            ("materials/a.tex", b"1"),
+            ("scene.json", b"2"),
+            ("materials/a.tex", b"3"),
+        ]);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_duplicates");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Rename,
+            include: None,
+            exclude: None,
+        })
+        .unwrap();
+
+        assert_eq!(output.duplicate_count, 1);
+        assert_eq!(output.pkg_info.duplicate_names(), vec!["materials/a.tex"]);
+        assert_eq!(fs::read(dir.join("out/materials/a.tex")).unwrap(), b"1");
+        assert_eq!(fs::read(dir.join("out/materials/a_1.tex")).unwrap(), b"3");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_rename_skips_existing_names() {
+        let data = pkg_bytes(&[
+            ("foo.png", b"1"),
+            ("foo_1.png", b"2"),
+            ("foo.png", b"3"),
+            ("foo.png", b"4"),
+        ]);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_rename_collision");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("out")).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+        // 上一次运行遗留的文件
+        fs::write(dir.join("out/foo_2.png"), b"old").unwrap();
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Rename,
+            include: None,
+            exclude: None,
+        })
+        .unwrap();
+
+        assert_eq!(output.duplicate_count, 2);
+        assert_eq!(fs::read(dir.join("out/foo.png")).unwrap(), b"1");
+        assert_eq!(fs::read(dir.join("out/foo_1.png")).unwrap(), b"2");
+        assert_eq!(fs::read(dir.join("out/foo_2.png")).unwrap(), b"old");
+        assert_eq!(fs::read(dir.join("out/foo_3.png")).unwrap(), b"3");
+        assert_eq!(fs::read(dir.join("out/foo_4.png")).unwrap(), b"4");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_rejects_overflowing_offset() {
+        let mut data = pkg_index("PKGV0001", &[("scene.json", 0xFFFF_FFF0, 0x20)]);
+        data.extend_from_slice(b"{}");
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_overflow");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let err = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Overwrite,
+            include: None,
+            exclude: None,
+        })
+        .unwrap_err();
+
+        assert_eq!(err.kind(), crate::core::error::CoreErrorKind::InvalidPkg);
+        assert!(err
+            .to_string()
+            .contains("scene.json has invalid offset/size"));
+        assert!(!dir.join("out/scene.json").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_streams_large_entry() {
+        // 大于单个复制块的条目，内容带位置信息以便校验块拼接顺序
+        let big: Vec<u8> = (0..COPY_CHUNK_SIZE * 2 + 123)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let data = pkg_bytes(&[("scene.json", &b"{}"[..]), ("materials/big.tex", &big)]);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_stream");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Overwrite,
+            include: None,
+            exclude: None,
+        })
+        .unwrap();
+
+        assert_eq!(output.extracted_files.len(), 2);
+        assert_eq!(fs::read(dir.join("out/scene.json")).unwrap(), b"{}");
+        assert_eq!(fs::read(dir.join("out/materials/big.tex")).unwrap(), big);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_filters_entries() {
+        let data = pkg_bytes(&[
+            ("materials/A.TEX", b"1"),
+            ("materials/b.tex", b"2"),
+            ("scene.json", b"3"),
+        ]);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_filter");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Overwrite,
+            include: Some(vec!["*.tex".to_string()]),
+            exclude: Some(vec!["**/b.*".to_string()]),
+        })
+        .unwrap();
+
+        assert_eq!(output.extracted_files.len(), 1);
+        assert_eq!(output.skipped_count, 2);
+        assert!(dir.join("out/materials/A.TEX").exists());
+        assert!(!dir.join("out/materials/b.tex").exists());
+        assert!(!dir.join("out/scene.json").exists());
+
+        let err = EntryFilter::new(Some(&["[".to_string()]), None).unwrap_err();
+        assert!(err.to_string().contains("Invalid pattern '['"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_rejects_path_traversal() {
+        let names = [
+            "../evil.tex",
+            "/abs.tex",
+            "",
+            "C:\\win.tex",
+            "a\\..\\..\\x",
+            "ok.txt",
+        ];
+        let files: Vec<(&str, [u8; 1])> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, [b'1' + i as u8]))
+            .collect();
+        let data = pkg_bytes(&files);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_traversal");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Overwrite,
+            include: None,
+            exclude: None,
+        })
+        .unwrap();
+
+        assert_eq!(output.rejected_entries, &names[..5]);
+        assert_eq!(output.extracted_files.len(), 1);
+        assert_eq!(fs::read(dir.join("out/ok.txt")).unwrap(), b"6");
+        // 输出目录之外没有多出任何文件
+        assert!(!dir.join("evil.tex").exists());
+        let mut outside: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        outside.sort();
+        assert_eq!(outside, ["out", "scene.pkg"]);
+        assert_eq!(fs::read_dir(dir.join("out")).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unpack_pkg_decompresses_lz4_entries() {
+        let plain = b"scene data ".repeat(50);
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(&plain).unwrap();
+        let frame = encoder.finish().unwrap();
+        let mut broken = LZ4_FRAME_MAGIC.to_vec();
+        broken.extend_from_slice(b"not a frame");
+
+        let blobs: [&[u8]; 3] = [&frame, &broken, b"{}"];
+        let names = ["scene.json.lz4", "broken.bin", "project.json"];
+        let files: Vec<(&str, &[u8])> = names.into_iter().zip(blobs).collect();
+        let data = pkg_bytes(&files);
+
+        let dir = std::env::temp_dir().join("lianpkg_unpack_lz4");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pkg_path = dir.join("scene.pkg");
+        fs::write(&pkg_path, &data).unwrap();
+
+        let parsed = crate::core::pkg::parse_pkg(crate::core::pkg::ParsePkgInput {
+            file_path: pkg_path.clone(),
+        })
+        .unwrap();
+        let flags: Vec<bool> = parsed
+            .pkg_info
+            .entries
+            .iter()
+            .map(|e| e.compressed)
+            .collect();
+        assert_eq!(flags, [true, true, false]);
+
+        let output = unpack_pkg(UnpackPkgInput {
+            file_path: pkg_path,
+            output_base: dir.join("out"),
+            duplicate_strategy: DuplicateStrategy::Overwrite,
+            include: None,
+            exclude: None,
+        })
+        .unwrap();
+
+        assert_eq!(fs::read(dir.join("out/scene.json.lz4")).unwrap(), plain);
+        assert_eq!(output.extracted_files[0].size as usize, plain.len());
+        // 无法解压的条目原样写出并报告
+        assert_eq!(fs::read(dir.join("out/broken.bin")).unwrap(), broken);
+        assert_eq!(output.undecoded_entries, ["broken.bin"]);
+        assert_eq!(fs::read(dir.join("out/project.json")).unwrap(), b"{}");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_safe_entry_name() {
+        for name in ["scene.json", "materials/a.tex", "./a/b", "a..b/c"] {
+            assert!(is_safe_entry_name(name), "{}", name);
+        }
+        for name in ["", ".", "/", "..", "a/../../b", "\\\\server\\x", "d:x"] {
+            assert!(!is_safe_entry_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_copy_chunked_small_chunks() {
+        let source: Vec<u8> = (0..100u8).collect();
+        let mut reader = &source[10..];
+        let mut out = Vec::new();
+        copy_chunked(&mut reader, &mut out, 37, 8).unwrap();
+        assert_eq!(out, &source[10..47]);
+
+        // 数据不足时报错而不是静默截断
+        let mut short = &source[..5];
+        assert!(copy_chunked(&mut short, &mut Vec::new(), 6, 4).is_err());
+    }
+
+    #[test]
+    fn test_copy_lz4_chunked_caps_output() {
+        let plain = vec![0u8; COPY_CHUNK_SIZE * 3];
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(&plain).unwrap();
+        let frame = encoder.finish().unwrap();
+
+        // 跨多个块解压，只消费帧本身的字节
+        let mut source = frame.clone();
+        source.extend_from_slice(b"tail");
+        let mut reader = source.as_slice();
+        let mut out = Vec::new();
+        let size = copy_lz4_chunked(&mut reader, &mut out, frame.len(), u64::MAX).unwrap();
+        assert_eq!(size, Some(plain.len() as u64));
+        assert_eq!(out, plain);
+        assert_eq!(reader, b"tail");
+
+        // 超出上限时中止解压
+        let mut reader = frame.as_slice();
+        let limit = plain.len() as u64 - 1;
+        let size = copy_lz4_chunked(&mut reader, &mut Vec::new(), frame.len(), limit).unwrap();
+        assert_eq!(size, None);
+    }
+}
@@ -1,15 +1,21 @@
 //! 内部工具函数（不对外导出）
 
+use std::io::Read;
+
 /// 二进制数据读取器
-pub(crate) struct Reader<'a> {
-    buf: &'a [u8],
+///
+/// 包装任意 `Read`（字节切片或带缓冲的文件），按已知总长度做边界检查：
+/// 剩余数据不足时返回 0 / 空字符串，与数据截断的 pkg 宽松兼容
+pub(crate) struct Reader<R> {
+    inner: R,
     pos: usize,
+    len: usize,
 }
 
-impl<'a> Reader<'a> {
-    /// 创建新的读取器
-    pub(crate) fn new(buf: &'a [u8]) -> Self {
-        Self { buf, pos: 0 }
+impl<R: Read> Reader<R> {
+    /// 创建新的读取器，len 为数据总长度
+    pub(crate) fn new(inner: R, len: usize) -> Self {
+        Self { inner, pos: 0, len }
     }
 
     /// 获取当前读取位置
@@ -17,31 +23,54 @@ impl<'a> Reader<'a> {
         self.pos
     }
 
+    /// 取回内部读取器（位置与 position() 一致）
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// 数据总长度
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
     /// 读取 u32（小端序）
     pub(crate) fn read_u32(&mut self) -> u32 {
-        if self.pos + 4 > self.buf.len() {
-            return 0;
+        let mut buf = [0u8; 4];
+        if self.read_exact(&mut buf) {
+            u32::from_le_bytes(buf)
+        } else {
+            0
         }
-        let v = u32::from_le_bytes(
-            self.buf[self.pos..self.pos + 4]
-                .try_into()
-                .unwrap(),
-        );
-        self.pos += 4;
-        v
     }
 
     /// 读取字符串（长度前缀 + UTF-8 内容）
     pub(crate) fn read_string(&mut self) -> String {
         let len = self.read_u32() as usize;
-        if self.pos + len > self.buf.len() {
+        // 先检查剩余长度，避免按损坏的长度前缀分配内存
+        if self.len.saturating_sub(self.pos) < len {
             return String::new();
         }
-        let s = String::from_utf8(
-            self.buf[self.pos..self.pos + len].to_vec(),
-        )
-        .unwrap_or_else(|_| "<invalid utf8>".to_string());
-        self.pos += len;
-        s
+        let mut buf = vec![0u8; len];
+        if !self.read_exact(&mut buf) {
+            return String::new();
+        }
+        String::from_utf8(buf).unwrap_or_else(|_| "<invalid utf8>".to_string())
+    }
+
+    /// 读取定长数据，剩余不足时不读取并返回 false；读取出错视为数据已耗尽
+    fn read_exact(&mut self, buf: &mut [u8]) -> bool {
+        if self.len.saturating_sub(self.pos) < buf.len() {
+            return false;
+        }
+        match self.inner.read_exact(buf) {
+            Ok(()) => {
+                self.pos += buf.len();
+                true
+            }
+            Err(_) => {
+                self.pos = self.len;
+                false
+            }
+        }
     }
 }
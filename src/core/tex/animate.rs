@@ -0,0 +1,154 @@
+//! 动图合成（内部使用）
+//!
+//! 把多帧 TEX（多个图像，或按 TEXS 帧信息切分的精灵图）编码为 GIF / APNG
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{imageops, Delay, Frame, RgbaImage};
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::tex::decoder::{decode_rgba, mipmap_data};
+use crate::core::tex::structs::{AnimateOutput, MipmapFormat, TexFile, TexFrame, TexImage};
+
+/// 收集动画帧（RGBA）
+///
+/// 有 TEXS 帧信息时按帧矩形从对应精灵图裁切，否则每个图像的第一层 mipmap 作为一帧
+pub(crate) fn collect_frames(
+    tex_file: &TexFile,
+    format: MipmapFormat,
+    strict_lz4: bool,
+) -> CoreResult<Vec<RgbaImage>> {
+    let sheets = tex_file
+        .images
+        .iter()
+        .map(|image| decode_image(image, format, strict_lz4))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match &tex_file.frame_info {
+        Some(info) if !info.frames.is_empty() => info
+            .frames
+            .iter()
+            .map(|frame| {
+                let sheet = sheets.get(frame.image_id).ok_or_else(|| {
+                    CoreError::invalid_tex(format!(
+                        "Frame references missing image {}",
+                        frame.image_id
+                    ))
+                })?;
+                Ok(crop_frame(sheet, frame))
+            })
+            .collect(),
+        _ => Ok(sheets),
+    }
+}
+
+/// 把帧编码为动图写入 path，返回画布尺寸
+///
+/// 帧尺寸不一致时以最大宽高为画布，较小的帧放在左上角
+pub(crate) fn encode_animation(
+    frames: &[RgbaImage],
+    animate: AnimateOutput,
+    path: &Path,
+) -> CoreResult<(u32, u32)> {
+    let width = frames.iter().map(|f| f.width()).max().unwrap_or(0);
+    let height = frames.iter().map(|f| f.height()).max().unwrap_or(0);
+    if width == 0 || height == 0 {
+        return Err(CoreError::validation("No frames to encode"));
+    }
+
+    let canvas_frames = frames.iter().map(|frame| {
+        if frame.dimensions() == (width, height) {
+            frame.clone()
+        } else {
+            let mut canvas = RgbaImage::new(width, height);
+            imageops::replace(&mut canvas, frame, 0, 0);
+            canvas
+        }
+    });
+
+    let io_err = CoreError::io;
+    let file = File::create(path).map_err(|e| io_err(format!("Failed to create file: {}", e)))?;
+    let writer = BufWriter::new(file);
+    let fps = animate.fps();
+
+    match animate {
+        AnimateOutput::Gif { .. } => {
+            let mut encoder = GifEncoder::new(writer);
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(|e| io_err(format!("Failed to encode GIF: {}", e)))?;
+            let delay = Delay::from_numer_denom_ms(1000, fps as u32);
+            encoder
+                .encode_frames(canvas_frames.map(|f| Frame::from_parts(f, 0, 0, delay)))
+                .map_err(|e| io_err(format!("Failed to encode GIF: {}", e)))?;
+        }
+        AnimateOutput::Apng { .. } => {
+            let apng_err = |e: png::EncodingError| io_err(format!("Failed to encode APNG: {}", e));
+            let mut encoder = png::Encoder::new(writer, width, height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .set_animated(frames.len() as u32, 0)
+                .map_err(apng_err)?;
+            encoder.set_frame_delay(1, fps).map_err(apng_err)?;
+            let mut writer = encoder.write_header().map_err(apng_err)?;
+            for frame in canvas_frames {
+                writer.write_image_data(frame.as_raw()).map_err(apng_err)?;
+            }
+            writer.finish().map_err(apng_err)?;
+        }
+    }
+
+    Ok((width, height))
+}
+
+/// 解码图像第一层 mipmap 为 RGBA
+fn decode_image(image: &TexImage, format: MipmapFormat, strict_lz4: bool) -> CoreResult<RgbaImage> {
+    let mipmap = image
+        .mipmaps
+        .first()
+        .ok_or_else(|| CoreError::validation("No mipmaps found in TEX image"))?;
+
+    let data = mipmap_data(mipmap, strict_lz4)?;
+    decode_rgba(&data, mipmap.width, mipmap.height, format)
+}
+
+/// 从精灵图裁切一帧（负宽高取绝对值，超出边界的部分截断；矩形为空时使用整张图）
+fn crop_frame(sheet: &RgbaImage, frame: &TexFrame) -> RgbaImage {
+    let x = (frame.x.max(0.0).round() as u32).min(sheet.width());
+    let y = (frame.y.max(0.0).round() as u32).min(sheet.height());
+    let width = (frame.width.abs().round() as u32).min(sheet.width() - x);
+    let height = (frame.height.abs().round() as u32).min(sheet.height() - y);
+    if width == 0 || height == 0 {
+        return sheet.clone();
+    }
+    imageops::crop_imm(sheet, x, y, width, height).to_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop_frame_clamps_to_sheet() {
+        let mut sheet = RgbaImage::new(4, 2);
+        sheet.put_pixel(2, 0, image::Rgba([9, 9, 9, 255]));
+        let frame = |x, width| TexFrame {
+            image_id: 0,
+            frametime: 0.1,
+            x,
+            y: 0.0,
+            width,
+            height: 2.0,
+        };
+
+        let right = crop_frame(&sheet, &frame(2.0, 2.0));
+        assert_eq!(right.dimensions(), (2, 2));
+        assert_eq!(right.get_pixel(0, 0).0, [9, 9, 9, 255]);
+        assert_eq!(crop_frame(&sheet, &frame(3.0, 8.0)).dimensions(), (1, 2));
+        assert_eq!(crop_frame(&sheet, &frame(0.0, 0.0)).dimensions(), (4, 2));
+    }
+}
@@ -1,22 +1,32 @@
 //! 转换接口 - 解析并转换 TEX 文件
 
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
-use image::RgbaImage;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{imageops, DynamicImage, ExtendedColorType, RgbaImage};
 
 use crate::core::error::{CoreError, CoreResult};
-use crate::core::tex::decoder::{decode_mipmap, determine_format};
+use crate::core::path::long_path;
+use crate::core::tex::animate::{collect_frames, encode_animation};
+use crate::core::tex::decoder::{
+    decode_rgba, determine_format, flip_image_data, mipmap_data, validate_image_data,
+};
+use crate::core::tex::parse::build_tex_info;
 use crate::core::tex::reader;
 use crate::core::tex::structs::{
-    ConvertTexInput, ConvertTexOutput, ConvertedFile, MipmapFormat, TexInfo,
+    ConvertTexInput, ConvertTexOutput, ConvertedFile, ImageOutputFormat, MipmapFormat,
+    MipmapSelection, TexMipmap,
 };
 
 /// 解析并转换 TEX 文件
 pub fn convert_tex(input: ConvertTexInput) -> CoreResult<ConvertTexOutput> {
     let file_path = input.file_path;
     let output_path = input.output_path;
+    let strict = input.strict;
+    let strict_lz4 = input.strict_lz4;
 
     // 打开文件
     let mut file = File::open(&file_path).map_err(|e| CoreError::Io {
@@ -25,12 +35,11 @@ pub fn convert_tex(input: ConvertTexInput) -> CoreResult<ConvertTexOutput> {
     })?;
 
     // 读取 TEX 结构
-    let tex_file = reader::read_tex(&mut file).map_err(|e| CoreError::Parse {
-        message: e.to_string(),
-        source: Some(file_path.display().to_string()),
+    let tex_file = reader::read_tex(&mut file).map_err(|e| {
+        CoreError::invalid_tex_with_path(e.to_string(), file_path.display().to_string())
     })?;
 
-    // 获取第一个图像和 mipmap
+    // 所有图像（纹理数组 / 帧）共用容器格式，以第一个图像确定
     let first_image = tex_file
         .images
         .first()
@@ -38,48 +47,20 @@ pub fn convert_tex(input: ConvertTexInput) -> CoreResult<ConvertTexOutput> {
             message: "No images found in TEX file".to_string(),
         })?;
 
-    let first_mipmap = first_image
-        .mipmaps
-        .first()
-        .ok_or_else(|| CoreError::Validation {
-            message: "No mipmaps found in TEX image".to_string(),
-        })?;
-
     // 确定格式
     let format = determine_format(&tex_file, first_image);
-    let width = first_mipmap.width;
-    let height = first_mipmap.height;
-
-    // 构建 TexInfo
-    let tex_info = TexInfo {
-        version: "TEXV0005".to_string(),
-        format: format.name().to_string(),
-        width,
-        height,
-        image_count: tex_file.images.len(),
-        mipmap_count: first_image.mipmaps.len(),
-        is_compressed: first_mipmap.is_lz4_compressed,
-        is_video: first_image.is_video_mp4 || (tex_file.header.flags & 32) != 0,
-        data_size: first_mipmap.data.len(),
-    };
 
-    // 解压 LZ4（如果需要）
-    let data = if first_mipmap.is_lz4_compressed {
-        lz4_flex::decompress(
-            &first_mipmap.data,
-            first_mipmap.decompressed_bytes_count as usize,
-        )
-        .map_err(|e| CoreError::Parse {
-            message: format!("LZ4 decompression failed: {}", e),
-            source: Some(file_path.display().to_string()),
-        })?
-    } else {
-        first_mipmap.data.clone()
-    };
+    // 构建 TexInfo（与 parse_tex 共用，避免两边判定分歧）
+    let tex_info = build_tex_info(&tex_file);
 
     // 确定输出路径
     let mut final_output_path = output_path.clone();
-    let ext = format.extension();
+    let output_format = input.output_format;
+    let ext = if tex_info.is_texture {
+        output_format.extension()
+    } else {
+        format.extension()
+    };
 
     // 如果输出路径是目录，使用输入文件名
     if output_path.is_dir() || !output_path.to_string_lossy().contains('.') {
@@ -98,53 +79,498 @@ pub fn convert_tex(input: ConvertTexInput) -> CoreResult<ConvertTexOutput> {
 
     // 确保输出目录存在
     if let Some(parent) = final_output_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| CoreError::Io {
+        std::fs::create_dir_all(long_path(parent)).map_err(|e| CoreError::Io {
             message: e.to_string(),
             path: Some(parent.display().to_string()),
         })?;
     }
 
-    // 处理不同格式
-    let result = match format {
-        MipmapFormat::VideoMp4 => save_raw_data(&final_output_path, &data),
-        f if f.is_image() => save_raw_data(&final_output_path, &data),
-        _ => match decode_mipmap(&data, width as usize, height as usize, format) {
-            Ok(decoded) => save_as_png(&final_output_path, &decoded, width, height),
-            Err(e) => Err(e),
-        },
-    };
+    // 合成动图：只对多帧的非视频 TEX 生效，单帧时按普通图片导出
+    if let Some(animate) = input.animate {
+        if tex_info.frame_count > 1 && format != MipmapFormat::VideoMp4 {
+            let animated_path = final_output_path.with_extension(animate.extension());
+            let mut frames = collect_frames(&tex_file, format, strict_lz4)
+                .map_err(|e| e.with_path(file_path.display().to_string()))?;
+            if input.flip_vertical {
+                frames.iter_mut().for_each(imageops::flip_vertical_in_place);
+            }
+            let (width, height) = encode_animation(&frames, animate, &long_path(&animated_path))
+                .map_err(|e| e.with_path(animated_path.display().to_string()))?;
 
-    result.map_err(|e| CoreError::Io {
-        message: e,
-        path: Some(final_output_path.display().to_string()),
-    })?;
+            return Ok(ConvertTexOutput {
+                converted_files: vec![ConvertedFile {
+                    output_path: animated_path,
+                    format: animate.name().to_string(),
+                    width,
+                    height,
+                    image_index: 0,
+                    mipmap_level: 0,
+                    alpha_fallback: false,
+                }],
+                tex_info,
+            });
+        }
+    }
 
-    Ok(ConvertTexOutput {
-        converted_file: ConvertedFile {
-            output_path: final_output_path,
-            format: ext.to_string(),
+    // 多图像时每个图像写为 `{stem}-img{i}`，再按 mipmap 选择展开
+    let multi_image = tex_file.images.len() > 1;
+    let mut jobs = Vec::new();
+    for (image_index, image) in tex_file.images.iter().enumerate() {
+        if image.mipmaps.is_empty() {
+            return Err(CoreError::Validation {
+                message: format!("No mipmaps found in TEX image {}", image_index),
+            });
+        }
+        let image_output_path = if multi_image {
+            image_output_path(&final_output_path, image_index)
+        } else {
+            final_output_path.clone()
+        };
+        for level in select_mipmaps(&image.mipmaps, input.mipmap)? {
+            let level_output_path = if input.mipmap == MipmapSelection::All {
+                mipmap_output_path(&image_output_path, level)
+            } else {
+                image_output_path.clone()
+            };
+            jobs.push((image_index, level, level_output_path));
+        }
+    }
+
+    let mut converted_files = Vec::with_capacity(jobs.len());
+    for (image_index, level, level_output_path) in jobs {
+        let mipmap = &tex_file.images[image_index].mipmaps[level];
+        let width = mipmap.width;
+        let height = mipmap.height;
+
+        // 解压 LZ4（如果需要）
+        let with_input_path = |e: CoreError| e.with_path(file_path.display().to_string());
+        let data = mipmap_data(mipmap, strict_lz4).map_err(with_input_path)?;
+
+        // 直通图片写出前先校验，避免产出无法打开的文件
+        let data = if format.is_image() {
+            validate_image_data(&data, format, strict).map_err(with_input_path)?;
+            if input.flip_vertical {
+                flip_image_data(&data, format).map_err(with_input_path)?
+            } else {
+                data
+            }
+        } else {
+            data
+        };
+
+        // 处理不同格式
+        // Windows 下使用长路径形式写入，避免超过 MAX_PATH
+        let mut level_output_path = level_output_path;
+        let mut level_ext = ext;
+        let mut alpha_fallback = false;
+        let result = match format {
+            MipmapFormat::VideoMp4 => save_raw_data(&long_path(&level_output_path), &data),
+            f if f.is_image() => save_raw_data(&long_path(&level_output_path), &data),
+            _ => {
+                let mut img = decode_rgba(&data, width, height, format).map_err(with_input_path)?;
+                if input.flip_vertical {
+                    imageops::flip_vertical_in_place(&mut img);
+                }
+                // JPEG 无法保存透明度，含透明像素时改写 PNG
+                let mut target = output_format;
+                if matches!(target, ImageOutputFormat::Jpeg { .. }) && has_alpha(img.as_raw()) {
+                    target = ImageOutputFormat::Png;
+                    level_ext = target.extension();
+                    level_output_path.set_extension(level_ext);
+                    alpha_fallback = true;
+                }
+                save_image(&long_path(&level_output_path), img, target)
+            }
+        };
+
+        result.map_err(|e| e.with_path(level_output_path.display().to_string()))?;
+
+        converted_files.push(ConvertedFile {
+            output_path: level_output_path,
+            format: level_ext.to_string(),
             width,
             height,
-        },
+            image_index,
+            mipmap_level: level,
+            alpha_fallback,
+        });
+    }
+
+    Ok(ConvertTexOutput {
+        converted_files,
         tex_info,
     })
 }
 
+/// 导出全部 mipmap 时每层的输出路径：`dir/name.ext` → `dir/name_mip{n}.ext`
+pub fn mipmap_output_path(path: &Path, level: usize) -> PathBuf {
+    suffixed_path(path, &format!("_mip{}", level))
+}
+
+/// 多图像 TEX 中每个图像的输出路径：`dir/name.ext` → `dir/name-img{i}.ext`
+///
+/// 不使用 `_{i}`，以免与冲突重命名（ConflictPolicy::Rename）产生的 `name_{n}` 撞名
+pub fn image_output_path(path: &Path, index: usize) -> PathBuf {
+    suffixed_path(path, &format!("-img{}", index))
+}
+
+/// 在文件名主干后追加后缀，保留扩展名
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    path.with_file_name(file_name)
+}
+
+/// 按选择返回要导出的 mipmap 层级序号（mipmaps 非空）
+pub(crate) fn select_mipmaps(
+    mipmaps: &[TexMipmap],
+    selection: MipmapSelection,
+) -> CoreResult<Vec<usize>> {
+    match selection {
+        MipmapSelection::First => Ok(vec![0]),
+        MipmapSelection::Largest => Ok(mipmaps
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, m)| (m.width as u64 * m.height as u64, std::cmp::Reverse(*i)))
+            .map(|(i, _)| vec![i])
+            .unwrap_or_default()),
+        MipmapSelection::All => Ok((0..mipmaps.len()).collect()),
+        MipmapSelection::Index(n) if n < mipmaps.len() => Ok(vec![n]),
+        MipmapSelection::Index(n) => Err(CoreError::Validation {
+            message: format!("Mipmap index {} out of range ({} levels)", n, mipmaps.len()),
+        }),
+    }
+}
+
 /// 保存原始数据到文件
-fn save_raw_data(path: &PathBuf, data: &[u8]) -> Result<(), String> {
-    let mut file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+fn save_raw_data(path: &PathBuf, data: &[u8]) -> CoreResult<()> {
+    let mut file =
+        File::create(path).map_err(|e| CoreError::io(format!("Failed to create file: {}", e)))?;
     file.write_all(data)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+        .map_err(|e| CoreError::io(format!("Failed to write file: {}", e)))?;
     Ok(())
 }
 
-/// 保存为 PNG 图片
-fn save_as_png(path: &PathBuf, data: &[u8], width: u32, height: u32) -> Result<(), String> {
-    let img = RgbaImage::from_raw(width, height, data.to_vec())
-        .ok_or_else(|| "Failed to create image buffer".to_string())?;
+/// RGBA 数据中是否有不透明度低于 255 的像素
+fn has_alpha(data: &[u8]) -> bool {
+    data.chunks_exact(4).any(|p| p[3] != 255)
+}
 
-    img.save(path)
-        .map_err(|e| format!("Failed to save image: {}", e))?;
+/// 按输出格式编码 RGBA 图像并保存
+fn save_image(path: &PathBuf, img: RgbaImage, format: ImageOutputFormat) -> CoreResult<()> {
+    let file =
+        File::create(path).map_err(|e| CoreError::io(format!("Failed to create file: {}", e)))?;
+    let mut writer = BufWriter::new(file);
+    let result = match format {
+        ImageOutputFormat::Png => img.write_to(&mut writer, image::ImageFormat::Png),
+        ImageOutputFormat::Jpeg { .. } => {
+            // JPEG 不支持 alpha 通道，丢弃后编码
+            let rgb = DynamicImage::ImageRgba8(img).to_rgb8();
+            let quality = format.quality().unwrap_or(85);
+            JpegEncoder::new_with_quality(&mut writer, quality).encode_image(&rgb)
+        }
+        ImageOutputFormat::WebP { .. } => WebPEncoder::new_lossless(&mut writer).encode(
+            img.as_raw(),
+            img.width(),
+            img.height(),
+            ExtendedColorType::Rgba8,
+        ),
+        ImageOutputFormat::Tiff => img.write_to(&mut writer, image::ImageFormat::Tiff),
+    };
+    result.map_err(|e| CoreError::io(format!("Failed to save image: {}", e)))?;
+    writer
+        .flush()
+        .map_err(|e| CoreError::io(format!("Failed to write file: {}", e)))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::rgba_tex;
+    use crate::core::tex::structs::AnimateOutput;
+    use image::AnimationDecoder;
+
+    /// RGBA8888 TEX，mipmap 依次为 1×1、2×2（第二层更大，便于区分 First / Largest）
+    fn two_level_tex() -> Vec<u8> {
+        let green = [0u8, 255, 0, 255];
+        rgba_tex(
+            (2, 2),
+            &[vec![(1, 1, green.to_vec()), (2, 2, green.repeat(4))]],
+        )
+    }
+
+    fn convert(dir: &Path, mipmap: MipmapSelection) -> CoreResult<ConvertTexOutput> {
+        convert_tex(ConvertTexInput {
+            file_path: dir.join("mips.tex"),
+            output_path: dir.join("out"),
+            strict: false,
+            strict_lz4: false,
+            mipmap,
+            animate: None,
+            output_format: ImageOutputFormat::Png,
+            flip_vertical: false,
+        })
+    }
+
+    #[test]
+    fn test_convert_tex_mipmap_selection() {
+        let dir = std::env::temp_dir().join("lianpkg_convert_mipmaps");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("out")).unwrap();
+        std::fs::write(dir.join("mips.tex"), two_level_tex()).unwrap();
+
+        let first = convert(&dir, MipmapSelection::First).unwrap();
+        assert_eq!(first.converted_files.len(), 1);
+        assert_eq!(first.converted_files[0].width, 1);
+        assert_eq!(
+            first.converted_files[0].output_path,
+            dir.join("out/mips.png")
+        );
+
+        let largest = convert(&dir, MipmapSelection::Largest).unwrap();
+        assert_eq!(largest.converted_files[0].mipmap_level, 1);
+        assert_eq!(
+            image::image_dimensions(dir.join("out/mips.png")).unwrap(),
+            (2, 2)
+        );
+
+        let all = convert(&dir, MipmapSelection::All).unwrap();
+        let paths: Vec<_> = all
+            .converted_files
+            .iter()
+            .map(|f| f.output_path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![dir.join("out/mips_mip0.png"), dir.join("out/mips_mip1.png")]
+        );
+        assert_eq!(image::image_dimensions(&paths[1]).unwrap(), (2, 2));
+
+        let err = convert(&dir, MipmapSelection::Index(2)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Mipmap index 2 out of range (2 levels)"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_convert_tex_output_format() {
+        let dir = std::env::temp_dir().join("lianpkg_convert_output_format");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("out")).unwrap();
+        std::fs::write(dir.join("opaque.tex"), two_level_tex()).unwrap();
+        std::fs::write(dir.join("alpha.tex"), two_image_tex()).unwrap();
+
+        let convert = |name: &str, output_format| {
+            convert_tex(ConvertTexInput {
+                file_path: dir.join(name),
+                output_path: dir.join("out"),
+                strict: false,
+                strict_lz4: false,
+                mipmap: MipmapSelection::First,
+                animate: None,
+                output_format,
+                flip_vertical: false,
+            })
+            .unwrap()
+        };
+
+        for (output_format, expected) in [
+            (
+                ImageOutputFormat::Jpeg { quality: 85 },
+                image::ImageFormat::Jpeg,
+            ),
+            (
+                ImageOutputFormat::WebP { quality: 85 },
+                image::ImageFormat::WebP,
+            ),
+            (ImageOutputFormat::Tiff, image::ImageFormat::Tiff),
+        ] {
+            let output = convert("opaque.tex", output_format);
+            let file = &output.converted_files[0];
+            assert_eq!(file.format, output_format.extension());
+            assert!(!file.alpha_fallback);
+            let data = std::fs::read(&file.output_path).unwrap();
+            assert_eq!(image::guess_format(&data).unwrap(), expected);
+        }
+
+        // 含透明像素的纹理不写 JPEG，回退为 PNG
+        let output = convert("alpha.tex", ImageOutputFormat::Jpeg { quality: 85 });
+        assert!(output.converted_files.iter().all(|f| f.alpha_fallback));
+        assert_eq!(
+            output.converted_files[0].output_path,
+            dir.join("out/alpha-img0.png")
+        );
+        assert!(!dir.join("out/alpha-img0.jpg").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_convert_tex_flip_vertical() {
+        // 1×2 RGBA8888：上行红色，下行蓝色
+        let pixels = vec![255, 0, 0, 255, 0, 0, 255, 255];
+        let data = rgba_tex((1, 2), &[vec![(1, 2, pixels)]]);
+
+        let dir = std::env::temp_dir().join("lianpkg_convert_flip");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("out")).unwrap();
+        std::fs::write(dir.join("rows.tex"), data).unwrap();
+
+        let rows = |flip_vertical| {
+            let output = convert_tex(ConvertTexInput {
+                file_path: dir.join("rows.tex"),
+                output_path: dir.join("out"),
+                strict: false,
+                strict_lz4: false,
+                mipmap: MipmapSelection::First,
+                animate: None,
+                output_format: ImageOutputFormat::Png,
+                flip_vertical,
+            })
+            .unwrap();
+            let img = image::open(&output.converted_files[0].output_path)
+                .unwrap()
+                .to_rgba8();
+            (img.get_pixel(0, 0).0, img.get_pixel(0, 1).0)
+        };
+
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        assert_eq!(rows(false), (red, blue));
+        assert_eq!(rows(true), (blue, red));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_image_output_format_parse() {
+        assert_eq!(
+            ImageOutputFormat::parse("JPG", 70),
+            Some(ImageOutputFormat::Jpeg { quality: 70 })
+        );
+        assert_eq!(
+            ImageOutputFormat::parse("tif", 0),
+            Some(ImageOutputFormat::Tiff)
+        );
+        assert_eq!(ImageOutputFormat::parse("bmp", 0), None);
+        assert_eq!(ImageOutputFormat::Jpeg { quality: 0 }.quality(), Some(1));
+        assert_eq!(ImageOutputFormat::Png.quality(), None);
+    }
+
+    /// 两个 2×1 图像（纹理数组 / 帧），各 1 层 mipmap
+    fn two_image_tex() -> Vec<u8> {
+        rgba_tex(
+            (2, 1),
+            &[vec![(2, 1, vec![0x11; 8])], vec![(2, 1, vec![0xEE; 8])]],
+        )
+    }
+
+    #[test]
+    fn test_convert_tex_writes_every_image() {
+        let data = two_image_tex();
+        let dir = std::env::temp_dir().join("lianpkg_convert_images");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("out")).unwrap();
+        std::fs::write(dir.join("frames.tex"), data).unwrap();
+
+        let output = convert_tex(ConvertTexInput {
+            file_path: dir.join("frames.tex"),
+            output_path: dir.join("out"),
+            strict: false,
+            strict_lz4: false,
+            mipmap: MipmapSelection::First,
+            animate: None,
+            output_format: ImageOutputFormat::Png,
+            flip_vertical: false,
+        })
+        .unwrap();
+
+        assert_eq!(output.tex_info.image_count, 2);
+        assert_eq!(output.converted_files.len(), 2);
+        assert_eq!(output.converted_files[1].image_index, 1);
+        assert!(dir.join("out/frames-img0.png").is_file());
+        assert!(dir.join("out/frames-img1.png").is_file());
+        assert!(!dir.join("out/frames.png").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_convert_tex_animate() {
+        let dir = std::env::temp_dir().join("lianpkg_convert_animate");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("out")).unwrap();
+        std::fs::write(dir.join("anim.tex"), two_image_tex()).unwrap();
+
+        let animate = |animate| {
+            convert_tex(ConvertTexInput {
+                file_path: dir.join("anim.tex"),
+                output_path: dir.join("out"),
+                strict: false,
+                strict_lz4: false,
+                mipmap: MipmapSelection::First,
+                animate: Some(animate),
+                output_format: ImageOutputFormat::Png,
+                flip_vertical: false,
+            })
+            .unwrap()
+        };
+
+        let gif = animate(AnimateOutput::Gif { fps: 24 });
+        assert_eq!(gif.converted_files.len(), 1);
+        assert_eq!(gif.converted_files[0].output_path, dir.join("out/anim.gif"));
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(
+            File::open(dir.join("out/anim.gif")).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(decoder.into_frames().count(), 2);
+
+        let apng = animate(AnimateOutput::Apng { fps: 10 });
+        assert_eq!(apng.converted_files[0].format, "apng");
+        let reader = png::Decoder::new(std::io::BufReader::new(
+            File::open(dir.join("out/anim.png")).unwrap(),
+        ))
+        .read_info()
+        .unwrap();
+        assert_eq!(reader.info().animation_control.unwrap().num_frames, 2);
+        assert_eq!((reader.info().width, reader.info().height), (2, 1));
+
+        // 单帧 TEX 不受影响，按普通图片导出
+        std::fs::write(dir.join("still.tex"), two_level_tex()).unwrap();
+        let still = convert_tex(ConvertTexInput {
+            file_path: dir.join("still.tex"),
+            output_path: dir.join("out"),
+            strict: false,
+            strict_lz4: false,
+            mipmap: MipmapSelection::First,
+            animate: Some(AnimateOutput::Gif { fps: 24 }),
+            output_format: ImageOutputFormat::Png,
+            flip_vertical: false,
+        })
+        .unwrap();
+        assert_eq!(
+            still.converted_files[0].output_path,
+            dir.join("out/still.png")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_mipmap_selection_parse() {
+        assert_eq!(MipmapSelection::parse("ALL"), Some(MipmapSelection::All));
+        assert_eq!(MipmapSelection::parse("3"), Some(MipmapSelection::Index(3)));
+        assert_eq!(MipmapSelection::parse("biggest"), None);
+        assert_eq!(MipmapSelection::Index(3).name(), "3");
+    }
+}
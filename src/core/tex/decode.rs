@@ -0,0 +1,119 @@
+//! 内存解码接口 - 把 TEX 解码为 RGBA 像素，不写文件
+
+use std::fs::File;
+
+use image::imageops;
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::tex::convert::select_mipmaps;
+use crate::core::tex::decoder::{decode_rgba, determine_format, mipmap_data};
+use crate::core::tex::reader;
+use crate::core::tex::structs::{DecodeTexInput, DecodeTexOutput, MipmapFormat};
+
+/// 解码 TEX 第一个图像的指定 mipmap 为 RGBA 像素
+///
+/// 与 convert_tex 共用读取、格式判定、LZ4 解压与像素解码，只是不编码写盘。
+/// 直通图片（PNG / JPEG 等）同样解码为 RGBA；视频返回 `CoreError::Unsupported`
+pub fn decode_tex_to_rgba(input: DecodeTexInput) -> CoreResult<DecodeTexOutput> {
+    let file_path = input.file_path;
+    let with_input_path = |e: CoreError| e.with_path(file_path.display().to_string());
+
+    let mut file = File::open(&file_path).map_err(|e| CoreError::Io {
+        message: e.to_string(),
+        path: Some(file_path.display().to_string()),
+    })?;
+    let tex_file = reader::read_tex(&mut file).map_err(|e| {
+        CoreError::invalid_tex_with_path(e.to_string(), file_path.display().to_string())
+    })?;
+
+    let image = tex_file
+        .images
+        .first()
+        .ok_or_else(|| CoreError::Validation {
+            message: "No images found in TEX file".to_string(),
+        })?;
+    if image.mipmaps.is_empty() {
+        return Err(CoreError::Validation {
+            message: "No mipmaps found in TEX image 0".to_string(),
+        });
+    }
+
+    let format = determine_format(&tex_file, image);
+    if format == MipmapFormat::VideoMp4 {
+        return Err(CoreError::unsupported(format!(
+            "{}: video textures cannot be decoded to RGBA",
+            file_path.display()
+        )));
+    }
+
+    let level = select_mipmaps(&image.mipmaps, input.mipmap)?[0];
+    let mipmap = &image.mipmaps[level];
+    let data = mipmap_data(mipmap, input.strict_lz4).map_err(with_input_path)?;
+    let mut img =
+        decode_rgba(&data, mipmap.width, mipmap.height, format).map_err(with_input_path)?;
+    if input.flip_vertical {
+        imageops::flip_vertical_in_place(&mut img);
+    }
+
+    Ok(DecodeTexOutput {
+        width: img.width(),
+        height: img.height(),
+        rgba: img.into_raw(),
+        format: format.name().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::CoreErrorKind;
+    use crate::core::fixtures::rgba_tex;
+    use crate::core::tex::structs::MipmapSelection;
+
+    #[test]
+    fn test_decode_tex_to_rgba() {
+        let dir = std::env::temp_dir().join("lianpkg_decode_rgba");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // RGBA8888，mipmap 依次为 2×1、1×1
+        let data = rgba_tex(
+            (2, 1),
+            &[vec![
+                (2, 1, vec![1, 2, 3, 255, 4, 5, 6, 255]),
+                (1, 1, vec![9; 4]),
+            ]],
+        );
+        let path = dir.join("pixels.tex");
+        std::fs::write(&path, &data).unwrap();
+
+        let decode = |mipmap| {
+            decode_tex_to_rgba(DecodeTexInput {
+                file_path: path.clone(),
+                strict_lz4: false,
+                mipmap,
+                flip_vertical: false,
+            })
+        };
+
+        let first = decode(MipmapSelection::First).unwrap();
+        assert_eq!((first.width, first.height), (2, 1));
+        assert_eq!(first.rgba, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+        assert_eq!(first.format, "RGBA8888");
+
+        let second = decode(MipmapSelection::Index(1)).unwrap();
+        assert_eq!(second.rgba, vec![9; 4]);
+        assert!(decode(MipmapSelection::Index(2)).is_err());
+        // 只写了 tex 文件，没有产生其它输出
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // 截断的 TEX 报告为损坏的 TEX，而不是一般解析错误
+        let mut truncated = data;
+        truncated.truncate(truncated.len() - 6);
+        std::fs::write(&path, truncated).unwrap();
+        let err = decode(MipmapSelection::First).unwrap_err();
+        assert_eq!(err.kind(), CoreErrorKind::InvalidTex);
+        assert!(err.to_string().starts_with("Parse error in '"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
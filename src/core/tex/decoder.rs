@@ -1,7 +1,9 @@
 //! 格式解码器（内部使用）
 
+use image::RgbaImage;
 use texture2ddecoder::{decode_bc1, decode_bc2, decode_bc3};
-use crate::core::tex::structs::{TexFile, TexImage, MipmapFormat};
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::tex::structs::{TexFile, TexImage, TexMipmap, MipmapFormat};
 
 /// 确定 Mipmap 格式
 pub(crate) fn determine_format(tex_file: &TexFile, image: &TexImage) -> MipmapFormat {
@@ -21,6 +23,7 @@ pub(crate) fn determine_format(tex_file: &TexFile, image: &TexImage) -> MipmapFo
     }
 
     // 否则使用 header format
+    // 未知取值一律视为 Invalid，不按猜测解码
     match tex_file.header.format {
         0 => MipmapFormat::RGBA8888,
         4 => MipmapFormat::CompressedDXT5,
@@ -76,24 +79,24 @@ fn free_image_format_to_mipmap_format(fif: i32) -> MipmapFormat {
 }
 
 /// 解码 Mipmap 数据为 RGBA
-pub(crate) fn decode_mipmap(data: &[u8], width: usize, height: usize, format: MipmapFormat) -> Result<Vec<u8>, String> {
+pub(crate) fn decode_mipmap(data: &[u8], width: usize, height: usize, format: MipmapFormat) -> CoreResult<Vec<u8>> {
     match format {
         MipmapFormat::CompressedDXT1 => {
             let mut pixels = vec![0u32; width * height];
             decode_bc1(data, width, height, &mut pixels)
-                .map_err(|e| format!("DXT1 decode failed: {}", e))?;
+                .map_err(|e| CoreError::invalid_tex(format!("DXT1 decode failed: {}", e)))?;
             Ok(pixels.iter().flat_map(|&p| p.to_le_bytes()).collect())
         }
         MipmapFormat::CompressedDXT3 => {
             let mut pixels = vec![0u32; width * height];
             decode_bc2(data, width, height, &mut pixels)
-                .map_err(|e| format!("DXT3 decode failed: {}", e))?;
+                .map_err(|e| CoreError::invalid_tex(format!("DXT3 decode failed: {}", e)))?;
             Ok(pixels.iter().flat_map(|&p| p.to_le_bytes()).collect())
         }
         MipmapFormat::CompressedDXT5 => {
             let mut pixels = vec![0u32; width * height];
             decode_bc3(data, width, height, &mut pixels)
-                .map_err(|e| format!("DXT5 decode failed: {}", e))?;
+                .map_err(|e| CoreError::invalid_tex(format!("DXT5 decode failed: {}", e)))?;
             Ok(pixels.iter().flat_map(|&p| p.to_le_bytes()).collect())
         }
         MipmapFormat::RGBA8888 => {
@@ -124,7 +127,278 @@ pub(crate) fn decode_mipmap(data: &[u8], width: usize, height: usize, format: Mi
             Ok(new_data)
         }
         _ => {
-            Err(format!("Unsupported format for decoding: {:?}", format))
+            Err(CoreError::unsupported(format!("Unsupported format for decoding: {:?}", format)))
         }
     }
 }
+
+/// 取出 mipmap 数据，LZ4 压缩时先解压（内部使用）
+pub(crate) fn mipmap_data(mipmap: &TexMipmap, strict_lz4: bool) -> CoreResult<Vec<u8>> {
+    if mipmap.is_lz4_compressed {
+        decompress_lz4(
+            &mipmap.data,
+            mipmap.decompressed_bytes_count as usize,
+            strict_lz4,
+        )
+    } else {
+        Ok(mipmap.data.clone())
+    }
+}
+
+/// 把 mipmap 数据解码为 RGBA 图像（内部使用）
+///
+/// 直通图片由 image crate 解码，纹理格式走 decode_mipmap；视频无法解码
+pub(crate) fn decode_rgba(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: MipmapFormat,
+) -> CoreResult<RgbaImage> {
+    if format == MipmapFormat::VideoMp4 {
+        return Err(CoreError::unsupported("Cannot decode video texture to RGBA"));
+    }
+
+    if format.is_image() {
+        return image::load_from_memory(data)
+            .map(|img| img.to_rgba8())
+            .map_err(|e| {
+                CoreError::invalid_tex(format!("Failed to decode embedded {} data: {}", format.name(), e))
+            });
+    }
+
+    let pixels = decode_mipmap(data, width as usize, height as usize, format)?;
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| CoreError::invalid_tex("Failed to create image buffer"))
+}
+
+/// 容量增长解压的倍率上限（LZ4 单块最大压缩比约 255 倍）
+const LZ4_MAX_RATIO: usize = 255;
+/// 容量增长解压的绝对上限，避免异常数据触发超大分配
+const LZ4_MAX_GROW_BYTES: usize = 512 * 1024 * 1024;
+
+/// 解压 mipmap 的 LZ4 数据（内部使用）
+///
+/// 按声明大小解压失败时，未启用 strict_lz4 则依次降级：
+/// 1. 按数据头部的 4 字节大小前缀解压
+/// 2. 忽略声明大小，从声明值起成倍扩容缓冲区重试
+///
+/// 全部失败时，错误信息中列出每个尝试过的策略及其失败原因
+pub(crate) fn decompress_lz4(data: &[u8], declared_size: usize, strict_lz4: bool) -> CoreResult<Vec<u8>> {
+    let mut attempts = Vec::new();
+
+    match lz4_flex::block::decompress(data, declared_size) {
+        Ok(out) => return Ok(out),
+        Err(e) => attempts.push(format!("declared size {}: {}", declared_size, e)),
+    }
+
+    if !strict_lz4 {
+        match lz4_flex::block::decompress_size_prepended(data) {
+            Ok(out) => return Ok(out),
+            Err(e) => attempts.push(format!("size-prepended: {}", e)),
+        }
+
+        let limit = data
+            .len()
+            .saturating_mul(LZ4_MAX_RATIO)
+            .min(LZ4_MAX_GROW_BYTES)
+            .max(declared_size);
+        let mut capacity = declared_size.max(data.len()).max(1);
+        let mut last_error = None;
+        while capacity <= limit {
+            let mut buf = vec![0u8; capacity];
+            match lz4_flex::block::decompress_into(data, &mut buf) {
+                Ok(n) => {
+                    buf.truncate(n);
+                    return Ok(buf);
+                }
+                Err(e) => last_error = Some(e.to_string()),
+            }
+            capacity = capacity.saturating_mul(2);
+        }
+        attempts.push(format!(
+            "growing buffer up to {} bytes: {}",
+            limit,
+            last_error.unwrap_or_else(|| "no attempt".to_string())
+        ));
+    }
+
+    Err(CoreError::invalid_tex(format!("LZ4 decompression failed ({})", attempts.join("; "))))
+}
+
+/// 校验直通图片数据的有效性（内部使用）
+///
+/// - 常规模式：检查文件头 magic 是否与声明的格式一致
+/// - 严格模式：额外尝试完整解码（仅限已启用解码器的格式），
+///   无法校验的格式在严格模式下视为失败
+pub(crate) fn validate_image_data(
+    data: &[u8],
+    format: MipmapFormat,
+    strict: bool,
+) -> CoreResult<()> {
+    if data.is_empty() {
+        return Err(CoreError::validation(format!("Embedded {} data is empty", format.name())));
+    }
+
+    let Some(expected) = image_crate_format(format) else {
+        // 没有可识别 magic 的格式（如 TGA）
+        return if strict {
+            Err(CoreError::validation(format!(
+                "Cannot verify embedded {} data (format not checkable)",
+                format.extension()
+            )))
+        } else {
+            Ok(())
+        };
+    };
+
+    match image::guess_format(data) {
+        Ok(actual) if actual == expected => {}
+        Ok(actual) => {
+            return Err(CoreError::validation(format!(
+                "Embedded data is {:?}, expected {}",
+                actual,
+                format.name()
+            )))
+        }
+        Err(_) => {
+            return Err(CoreError::validation(format!(
+                "Embedded {} data has an invalid header",
+                format.name()
+            )))
+        }
+    }
+
+    if strict {
+        if !expected.reading_enabled() {
+            return Err(CoreError::validation(format!(
+                "Cannot fully decode embedded {} data (decoder not available)",
+                format.name()
+            )));
+        }
+        image::load_from_memory_with_format(data, expected)
+            .map_err(|e| {
+                CoreError::validation(format!("Embedded {} data is corrupted: {}", format.name(), e))
+            })?;
+    }
+
+    Ok(())
+}
+
+/// 上下翻转直通图片数据（内部使用）
+///
+/// 解码后翻转，再按原格式重新编码；没有可用编解码器的格式返回错误
+pub(crate) fn flip_image_data(data: &[u8], format: MipmapFormat) -> CoreResult<Vec<u8>> {
+    let target = image_crate_format(format)
+        .filter(|f| f.reading_enabled() && f.writing_enabled())
+        .ok_or_else(|| {
+            CoreError::validation(format!(
+                "Cannot flip embedded {} data (re-encoding not supported)",
+                format.extension()
+            ))
+        })?;
+
+    let img = image::load_from_memory_with_format(data, target)
+        .map_err(|e| {
+            CoreError::validation(format!("Failed to decode embedded {} data: {}", format.name(), e))
+        })?;
+    let mut out = std::io::Cursor::new(Vec::new());
+    img.flipv()
+        .write_to(&mut out, target)
+        .map_err(|e| {
+            CoreError::validation(format!("Failed to re-encode flipped {} data: {}", format.name(), e))
+        })?;
+    Ok(out.into_inner())
+}
+
+/// MipmapFormat 到 image crate 格式的映射（仅限可通过 magic 识别的格式）
+fn image_crate_format(format: MipmapFormat) -> Option<image::ImageFormat> {
+    use image::ImageFormat;
+    match format {
+        MipmapFormat::ImagePNG => Some(ImageFormat::Png),
+        MipmapFormat::ImageJPEG => Some(ImageFormat::Jpeg),
+        MipmapFormat::ImageGIF => Some(ImageFormat::Gif),
+        MipmapFormat::ImageBMP => Some(ImageFormat::Bmp),
+        MipmapFormat::ImageICO => Some(ImageFormat::Ico),
+        MipmapFormat::ImageTIFF => Some(ImageFormat::Tiff),
+        MipmapFormat::ImageDDS => Some(ImageFormat::Dds),
+        MipmapFormat::ImageHDR => Some(ImageFormat::Hdr),
+        MipmapFormat::ImageEXR => Some(ImageFormat::OpenExr),
+        MipmapFormat::ImagePBMRAW | MipmapFormat::ImagePGMRAW | MipmapFormat::ImagePPMRAW => {
+            Some(ImageFormat::Pnm)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_header_format_is_invalid() {
+        use crate::core::fixtures::{rgba_tex, tex_head};
+        use crate::core::tex::reader::read_tex;
+
+        let image = TexImage { image_format: -1, is_video_mp4: false, mipmaps: Vec::new() };
+        for format in [10, 11, 12, 99] {
+            let bytes = tex_head("TEXV0005", &[format, 0, 4, 4, 4, 4, 0], b"TEXB0002\0", 0);
+            let tex = read_tex(std::io::Cursor::new(bytes)).unwrap();
+            assert_eq!(determine_format(&tex, &image), MipmapFormat::Invalid, "format {}", format);
+        }
+
+        let tex = read_tex(std::io::Cursor::new(rgba_tex((1, 1), &[]))).unwrap();
+        assert_eq!(determine_format(&tex, &image), MipmapFormat::RGBA8888);
+    }
+
+    #[test]
+    fn test_flip_image_data_reencodes_png() {
+        let mut img = image::RgbaImage::new(1, 2);
+        img.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+        let flipped = flip_image_data(png.get_ref(), MipmapFormat::ImagePNG).unwrap();
+        let flipped = image::load_from_memory(&flipped).unwrap().to_rgba8();
+        assert_eq!(flipped.get_pixel(0, 1).0, [255, 0, 0, 255]);
+        assert_eq!(flipped.get_pixel(0, 0).0, [0, 0, 0, 0]);
+
+        let err = flip_image_data(b"TGA", MipmapFormat::ImageTARGA).unwrap_err();
+        assert!(err.to_string().contains("Cannot flip embedded tga data"), "{}", err);
+    }
+
+    #[test]
+    fn test_decompress_lz4_declared_size() {
+        let raw = vec![7u8; 4096];
+        let compressed = lz4_flex::block::compress(&raw);
+        assert_eq!(decompress_lz4(&compressed, raw.len(), true).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_decompress_lz4_fallback_on_wrong_size() {
+        let raw: Vec<u8> = (0..8192u32).map(|i| (i % 251) as u8).collect();
+        let compressed = lz4_flex::block::compress(&raw);
+
+        // 声明大小偏小：非严格模式扩容重试成功
+        assert_eq!(decompress_lz4(&compressed, 16, false).unwrap(), raw);
+
+        // 严格模式不降级，错误中只包含声明大小这一种策略
+        let err = decompress_lz4(&compressed, 16, true).unwrap_err().to_string();
+        assert!(err.contains("declared size 16"), "{}", err);
+        assert!(!err.contains("size-prepended"), "{}", err);
+    }
+
+    #[test]
+    fn test_decompress_lz4_size_prepended() {
+        let raw = b"size prepended payload ".repeat(64);
+        let compressed = lz4_flex::block::compress_prepend_size(&raw);
+        assert_eq!(decompress_lz4(&compressed, 0, false).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_decompress_lz4_reports_all_attempts() {
+        let err = decompress_lz4(&[0xff; 32], 64, false).unwrap_err().to_string();
+        assert!(err.contains("declared size 64"), "{}", err);
+        assert!(err.contains("size-prepended"), "{}", err);
+        assert!(err.contains("growing buffer"), "{}", err);
+    }
+}
@@ -0,0 +1,81 @@
+//! 预估接口 - 只读取文件头，预估转换产物大小
+
+use std::io::Cursor;
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::tex::decoder::determine_format;
+use crate::core::tex::reader;
+use crate::core::tex::structs::{EstimateTexInput, EstimateTexOutput, MipmapFormat};
+
+/// 根据 TEX 文件头预估转换产物大小，不读取像素数据
+///
+/// 视频与内嵌图片按原数据写出，输出约等于 TEX 大小；
+/// 其余格式解码为 RGBA，按 宽 × 高 × 4 × 图像数 计算（PNG 压缩后通常更小，可视为上限）
+pub fn estimate_tex_output(input: EstimateTexInput) -> CoreResult<EstimateTexOutput> {
+    let (tex_file, image, image_count) =
+        reader::read_tex_head(Cursor::new(&input.head)).map_err(|e| CoreError::InvalidTex {
+            message: e.to_string(),
+            path: None,
+        })?;
+
+    let format = determine_format(&tex_file, &image);
+    let passthrough = format == MipmapFormat::VideoMp4 || format.is_image();
+    let output_size = if passthrough {
+        input.size
+    } else {
+        let header = &tex_file.header;
+        let (width, height) = if header.image_width > 0 && header.image_height > 0 {
+            (header.image_width, header.image_height)
+        } else {
+            (header.texture_width, header.texture_height)
+        };
+        width as u64 * height as u64 * 4 * image_count.max(1) as u64
+    };
+
+    Ok(EstimateTexOutput {
+        format: format.name().to_string(),
+        passthrough,
+        output_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::tex_head;
+
+    /// TEXV0005 文件头 + 图像容器头（不含 mipmap 数据）
+    fn head(format: u32, flags: u32, container: &[u8], image_count: i32) -> Vec<u8> {
+        let header = [format, flags, 256, 128, 200, 100, 0];
+        tex_head("TEXV0005", &header, container, image_count)
+    }
+
+    #[test]
+    fn test_estimate_tex_output() {
+        // DXT5 纹理数组：按图像尺寸解码为 RGBA
+        let dxt = estimate_tex_output(EstimateTexInput {
+            head: head(4, 0, b"TEXB0002\0", 2),
+            size: 10_000,
+        })
+        .unwrap();
+        assert_eq!(dxt.format, "DXT5");
+        assert!(!dxt.passthrough);
+        assert_eq!(dxt.output_size, 200 * 100 * 4 * 2);
+
+        // 视频纹理（flags 含 IsVideoTexture）：按原大小直通
+        let video = estimate_tex_output(EstimateTexInput {
+            head: head(0, 32, b"TEXB0002\0", 1),
+            size: 5_000_000,
+        })
+        .unwrap();
+        assert!(video.passthrough);
+        assert_eq!(video.output_size, 5_000_000);
+
+        let err = estimate_tex_output(EstimateTexInput {
+            head: b"NOTATEX".to_vec(),
+            size: 7,
+        })
+        .unwrap_err();
+        assert!(matches!(err, CoreError::InvalidTex { .. }));
+    }
+}
@@ -3,6 +3,9 @@
 //! 支持两种使用模式：
 //! - 单独使用：parse_tex 预览，convert_tex 一键转换
 //! - 复合流程：parse_tex → 判断格式 → convert_tex
+//! - 缩略图：make_thumbnail 为转换后的图片生成缩略图
+//! - 内存解码：decode_tex_to_rgba 返回 RGBA 像素，不写文件（供 GUI 预览）
+//! - 大小预估：estimate_tex_output 只读文件头，预估转换产物大小
 //!
 //! 支持的格式：
 //! - 压缩格式: DXT1, DXT3, DXT5
@@ -13,8 +16,12 @@
 mod structs;
 mod parse;
 mod convert;
+mod decode;
+mod estimate;
 mod reader;
 mod decoder;
+mod thumb;
+mod animate;
 
 // ============================================================================
 // 导出 Input/Output 结构体
@@ -23,6 +30,12 @@ pub use structs::ParseTexInput;
 pub use structs::ParseTexOutput;
 pub use structs::ConvertTexInput;
 pub use structs::ConvertTexOutput;
+pub use structs::DecodeTexInput;
+pub use structs::DecodeTexOutput;
+pub use structs::EstimateTexInput;
+pub use structs::EstimateTexOutput;
+pub use structs::MakeThumbnailInput;
+pub use structs::MakeThumbnailOutput;
 
 // ============================================================================
 // 导出运行时结构体
@@ -30,6 +43,10 @@ pub use structs::ConvertTexOutput;
 pub use structs::TexInfo;
 pub use structs::ConvertedFile;
 pub use structs::MipmapFormat;
+pub use structs::FormatKind;
+pub use structs::MipmapSelection;
+pub use structs::AnimateOutput;
+pub use structs::ImageOutputFormat;
 
 // ============================================================================
 // 导出解析接口
@@ -40,3 +57,21 @@ pub use parse::parse_tex;
 // 导出转换接口
 // ============================================================================
 pub use convert::convert_tex;
+pub use convert::image_output_path;
+pub use convert::mipmap_output_path;
+
+// ============================================================================
+// 导出内存解码接口
+// ============================================================================
+pub use decode::decode_tex_to_rgba;
+
+// ============================================================================
+// 导出预估接口
+// ============================================================================
+pub use estimate::estimate_tex_output;
+
+// ============================================================================
+// 导出缩略图接口
+// ============================================================================
+pub use thumb::make_thumbnail;
+pub(crate) use thumb::write_thumbnail;
@@ -5,7 +5,7 @@ use std::fs::File;
 use crate::core::error::{CoreError, CoreResult};
 use crate::core::tex::decoder::determine_format;
 use crate::core::tex::reader;
-use crate::core::tex::structs::{ParseTexInput, ParseTexOutput, TexInfo};
+use crate::core::tex::structs::{MipmapFormat, ParseTexInput, ParseTexOutput, TexFile, TexInfo};
 
 /// 解析 TEX 文件，只读取元数据不进行转换
 pub fn parse_tex(input: ParseTexInput) -> CoreResult<ParseTexOutput> {
@@ -18,42 +18,103 @@ pub fn parse_tex(input: ParseTexInput) -> CoreResult<ParseTexOutput> {
     })?;
 
     // 读取 TEX 结构
-    let tex_file = reader::read_tex(&mut file).map_err(|e| CoreError::Parse {
-        message: e.to_string(),
-        source: Some(file_path.display().to_string()),
+    let tex_file = reader::read_tex(&mut file).map_err(|e| {
+        CoreError::invalid_tex_with_path(e.to_string(), file_path.display().to_string())
     })?;
 
-    // 提取信息
+    let tex_info = build_tex_info(&tex_file);
+
+    Ok(ParseTexOutput { tex_info })
+}
+
+/// 从已读取的 TEX 结构提取元数据
+///
+/// parse_tex 与 convert_tex 共用，保证预览结果与实际转换一致
+pub(crate) fn build_tex_info(tex_file: &TexFile) -> TexInfo {
     let first_image = tex_file.images.first();
     let first_mipmap = first_image.and_then(|img| img.mipmaps.first());
 
     let format = first_image
-        .map(|img| determine_format(&tex_file, img))
-        .unwrap_or(crate::core::tex::structs::MipmapFormat::Invalid);
+        .map(|img| determine_format(tex_file, img))
+        .unwrap_or(MipmapFormat::Invalid);
 
     let (width, height) = first_mipmap.map(|m| (m.width, m.height)).unwrap_or((0, 0));
 
-    let is_compressed = first_mipmap.map(|m| m.is_lz4_compressed).unwrap_or(false);
-
-    let is_video = first_image
-        .map(|img| img.is_video_mp4 || (tex_file.header.flags & 32) != 0)
-        .unwrap_or(false);
-
-    let mipmap_count = first_image.map(|img| img.mipmaps.len()).unwrap_or(0);
-
-    let data_size = first_mipmap.map(|m| m.data.len()).unwrap_or(0);
-
-    let tex_info = TexInfo {
-        version: "TEXV0005".to_string(),
+    TexInfo {
+        version: tex_file.version.clone(),
         format: format.name().to_string(),
+        header_format: tex_file.header.format,
+        image_format: first_image.map(|img| img.image_format).unwrap_or(-1),
+        format_kind: format.kind(),
         width,
         height,
         image_count: tex_file.images.len(),
-        mipmap_count,
-        is_compressed,
-        is_video,
-        data_size,
-    };
+        mipmap_count: first_image.map(|img| img.mipmaps.len()).unwrap_or(0),
+        frame_count: tex_file
+            .frame_info
+            .as_ref()
+            .map(|info| info.frames.len())
+            .filter(|&n| n > 0)
+            .unwrap_or(tex_file.images.len()),
+        is_compressed: first_mipmap.map(|m| m.is_lz4_compressed).unwrap_or(false),
+        is_video: format == MipmapFormat::VideoMp4,
+        data_size: first_mipmap.map(|m| m.data.len()).unwrap_or(0),
+        output_extension: format.extension().to_string(),
+        is_texture: !format.is_image() && format != MipmapFormat::VideoMp4,
+    }
+}
 
-    Ok(ParseTexOutput { tex_info })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tex::structs::{FormatKind, TexHeader, TexImage, TexMipmap};
+
+    fn tex_file(header_format: u32, image_format: i32) -> TexFile {
+        TexFile {
+            version: "TEXV0005".to_string(),
+            header: TexHeader {
+                format: header_format,
+                flags: 0,
+                texture_width: 4,
+                texture_height: 4,
+                image_width: 4,
+                image_height: 4,
+                unk_int0: 0,
+            },
+            images: vec![TexImage {
+                image_format,
+                is_video_mp4: false,
+                mipmaps: vec![TexMipmap {
+                    width: 4,
+                    height: 4,
+                    is_lz4_compressed: false,
+                    decompressed_bytes_count: 0,
+                    data: vec![0; 16],
+                }],
+            }],
+            frame_info: None,
+        }
+    }
+
+    #[test]
+    fn test_build_tex_info_format_kind() {
+        // 块压缩纹理：由文件头 format 决定
+        let dxt = build_tex_info(&tex_file(4, -1));
+        assert_eq!((dxt.format.as_str(), dxt.header_format), ("DXT5", 4));
+        assert_eq!((dxt.image_format, dxt.format_kind), (-1, FormatKind::Bcn));
+
+        // 未压缩纹理
+        let raw = build_tex_info(&tex_file(0, -1));
+        assert_eq!(raw.format_kind, FormatKind::Raw);
+
+        // 内嵌 PNG：FreeImage 格式优先，原样写出
+        let png = build_tex_info(&tex_file(0, 13));
+        assert_eq!((png.format.as_str(), png.image_format), ("PNG", 13));
+        assert_eq!(png.format_kind, FormatKind::Passthrough);
+        assert!(!png.is_texture);
+
+        // JSON 中为 snake_case 字符串
+        let json = serde_json::to_value(&png).unwrap();
+        assert_eq!(json["format_kind"], "passthrough");
+    }
 }
@@ -6,25 +6,63 @@ use crate::core::tex::structs::*;
 
 /// 读取 TEX 文件结构
 pub(crate) fn read_tex<R: Read + Seek>(mut reader: R) -> io::Result<TexFile> {
-    let magic1 = read_n_string(&mut reader, 16)?;
+    let (version, header) = read_file_head(&mut reader)?;
+    let images = read_image_container(&mut reader, &header)?;
+
+    // IsGif 标志 (bit 2 = 4)：图像之后跟随 TEXS 帧信息；读取失败时按静态纹理处理
+    let frame_info = if (header.flags & 4) != 0 {
+        read_frame_info(&mut reader).ok()
+    } else {
+        None
+    };
+
+    Ok(TexFile {
+        version,
+        header,
+        images,
+        frame_info,
+    })
+}
+
+/// 只读取文件头与图像容器头部，不读取任何 mipmap 数据
+///
+/// 返回的 TexFile.images 为空，第二项为携带容器格式信息（无 mipmap）的图像，第三项为图像数量
+pub(crate) fn read_tex_head<R: Read + Seek>(mut reader: R) -> io::Result<(TexFile, TexImage, i32)> {
+    let (version, header) = read_file_head(&mut reader)?;
+    let (_, image_count, image_format, is_video_mp4) = read_container_head(&mut reader)?;
+
+    let tex_file = TexFile {
+        version,
+        header,
+        images: Vec::new(),
+        frame_info: None,
+    };
+    let image = TexImage {
+        image_format,
+        is_video_mp4,
+        mipmaps: Vec::new(),
+    };
+    Ok((tex_file, image, image_count))
+}
+
+/// 校验 TEXV0005 / TEXI0001 两个魔数并读取文件头，返回 (版本魔数, 文件头)
+fn read_file_head<R: Read + Seek>(reader: &mut R) -> io::Result<(String, TexHeader)> {
+    let magic1 = read_n_string(reader, 16)?;
     if magic1 != "TEXV0005" {
-        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid Magic1: {}", magic1)));
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unsupported TEX version: {}", magic1)));
     }
 
-    let magic2 = read_n_string(&mut reader, 16)?;
+    let magic2 = read_n_string(reader, 16)?;
     if magic2 != "TEXI0001" {
         return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid Magic2: {}", magic2)));
     }
 
-    let header = read_header(&mut reader)?;
-    let images = read_image_container(&mut reader, &header)?;
-
-    Ok(TexFile {
-        header,
-        images,
-    })
+    Ok((magic1, read_header(reader)?))
 }
 
+/// 读取 TEXV0005 文件头：format, flags, texture w/h, image w/h, unk_int0
+///
+/// 旧版本（TEXV0001–0003）的字段布局没有可核对的样本，read_tex 直接拒绝而不按猜测解析
 fn read_header<R: Read + Seek>(reader: &mut R) -> io::Result<TexHeader> {
     let format = reader.read_u32::<LittleEndian>()?;
     let flags = reader.read_u32::<LittleEndian>()?;
@@ -46,6 +84,19 @@ fn read_header<R: Read + Seek>(reader: &mut R) -> io::Result<TexHeader> {
 }
 
 fn read_image_container<R: Read + Seek>(reader: &mut R, _header: &TexHeader) -> io::Result<Vec<TexImage>> {
+    let (version, image_count, image_format, is_video_mp4) = read_container_head(reader)?;
+    let effective_version = if version == 4 && !is_video_mp4 { 3 } else { version };
+
+    let mut images = Vec::new();
+    for _ in 0..image_count {
+        images.push(read_image(reader, effective_version, image_format, is_video_mp4)?);
+    }
+
+    Ok(images)
+}
+
+/// 读取图像容器头部：(容器版本, 图像数量, image_format, is_video_mp4)
+fn read_container_head<R: Read + Seek>(reader: &mut R) -> io::Result<(i32, i32, i32, bool)> {
     let magic = read_n_string(reader, 16)?;
     let image_count = reader.read_i32::<LittleEndian>()?;
 
@@ -71,14 +122,7 @@ fn read_image_container<R: Read + Seek>(reader: &mut R, _header: &TexHeader) ->
         _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unknown ImageContainer Magic: {}", magic))),
     }
 
-    let effective_version = if version == 4 && !is_video_mp4 { 3 } else { version };
-
-    let mut images = Vec::new();
-    for _ in 0..image_count {
-        images.push(read_image(reader, effective_version, image_format, is_video_mp4)?);
-    }
-
-    Ok(images)
+    Ok((version, image_count, image_format, is_video_mp4))
 }
 
 fn read_image<R: Read + Seek>(reader: &mut R, version: i32, image_format: i32, is_video_mp4: bool) -> io::Result<TexImage> {
@@ -132,6 +176,54 @@ fn read_mipmap<R: Read + Seek>(reader: &mut R, version: i32) -> io::Result<TexMi
     })
 }
 
+/// 读取 TEXS 帧信息容器
+///
+/// - TEXS0001: 帧坐标为 i32
+/// - TEXS0002: 帧坐标为 f32
+/// - TEXS0003: 同 0002，帧数之后额外记录动图画布宽高
+fn read_frame_info<R: Read + Seek>(reader: &mut R) -> io::Result<TexFrameInfo> {
+    let magic = read_n_string(reader, 16)?;
+    if !matches!(magic.as_str(), "TEXS0001" | "TEXS0002" | "TEXS0003") {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unknown FrameInfo Magic: {}", magic)));
+    }
+
+    let frame_count = reader.read_i32::<LittleEndian>()?.max(0) as usize;
+    let (gif_width, gif_height) = if magic == "TEXS0003" {
+        (reader.read_u32::<LittleEndian>()?, reader.read_u32::<LittleEndian>()?)
+    } else {
+        (0, 0)
+    };
+
+    let mut frames = Vec::with_capacity(frame_count.min(4096));
+    for _ in 0..frame_count {
+        let image_id = reader.read_i32::<LittleEndian>()?.max(0) as usize;
+        let frametime = reader.read_f32::<LittleEndian>()?;
+        // x, y, width, width_y, height_x, height（width_y / height_x 为旋转分量，不使用）
+        let mut values = [0f32; 6];
+        for value in values.iter_mut() {
+            *value = if magic == "TEXS0001" {
+                reader.read_i32::<LittleEndian>()? as f32
+            } else {
+                reader.read_f32::<LittleEndian>()?
+            };
+        }
+        frames.push(TexFrame {
+            image_id,
+            frametime,
+            x: values[0],
+            y: values[1],
+            width: values[2],
+            height: values[5],
+        });
+    }
+
+    Ok(TexFrameInfo {
+        gif_width,
+        gif_height,
+        frames,
+    })
+}
+
 fn read_n_string<R: Read + Seek>(reader: &mut R, max_length: usize) -> io::Result<String> {
     let mut bytes = Vec::new();
     let mut c = [0u8; 1];
@@ -149,3 +241,76 @@ fn read_n_string<R: Read + Seek>(reader: &mut R, max_length: usize) -> io::Resul
 
     Ok(String::from_utf8_lossy(&bytes).to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{push_mipmap, tex_head};
+    use std::io::Cursor;
+
+    /// 构造最小样本：指定版本的文件头 + TEXB0002 容器 + 1 个 2×1 mipmap
+    fn sample(version: &str, header: &[u32]) -> Vec<u8> {
+        let mut b = tex_head(version, header, b"TEXB0002\0", 1);
+        b.extend_from_slice(&1i32.to_le_bytes()); // mipmap_count
+        push_mipmap(&mut b, 2, 1, &[0xAB; 8]);
+        b
+    }
+
+    fn assert_layout(tex: &TexFile, version: &str, flags: u32, image: (u32, u32)) {
+        assert_eq!(tex.version, version);
+        assert_eq!(tex.header.format, 0);
+        assert_eq!(tex.header.flags, flags);
+        assert_eq!((tex.header.texture_width, tex.header.texture_height), (4, 2));
+        assert_eq!((tex.header.image_width, tex.header.image_height), image);
+        let mipmap = &tex.images[0].mipmaps[0];
+        assert_eq!((mipmap.width, mipmap.height, mipmap.data.len()), (2, 1, 8));
+    }
+
+    #[test]
+    fn test_read_tex_v5() {
+        let data = sample("TEXV0005", &[0, 32, 4, 2, 3, 1, 7]);
+        let tex = read_tex(Cursor::new(data)).unwrap();
+        assert_layout(&tex, "TEXV0005", 32, (3, 1));
+        assert_eq!(tex.header.unk_int0, 7);
+    }
+
+    #[test]
+    fn test_read_tex_sprite_frames() {
+        // flags 含 IsGif (4)，图像之后跟随 TEXS0003 帧信息
+        let mut data = sample("TEXV0005", &[0, 4, 4, 2, 3, 1, 7]);
+        data.extend_from_slice(b"TEXS0003\0");
+        data.extend_from_slice(&2i32.to_le_bytes()); // frame count
+        data.extend_from_slice(&8u32.to_le_bytes()); // gif width
+        data.extend_from_slice(&4u32.to_le_bytes()); // gif height
+        for x in [0f32, 1.0] {
+            data.extend_from_slice(&0i32.to_le_bytes()); // image id
+            data.extend_from_slice(&0.5f32.to_le_bytes()); // frametime
+            for v in [x, 0.0, 1.0, 0.0, 0.0, 1.0] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+
+        let tex = read_tex(Cursor::new(data)).unwrap();
+        let info = tex.frame_info.expect("frame info");
+        assert_eq!((info.gif_width, info.gif_height), (8, 4));
+        assert_eq!(info.frames.len(), 2);
+        assert_eq!(info.frames[1].x, 1.0);
+        assert_eq!((info.frames[1].width, info.frames[1].height), (1.0, 1.0));
+
+        // 没有 IsGif 标志时不读取帧信息
+        let still = read_tex(Cursor::new(sample("TEXV0005", &[0, 0, 4, 2, 3, 1, 7]))).unwrap();
+        assert!(still.frame_info.is_none());
+    }
+
+    #[test]
+    fn test_read_tex_rejects_unknown_version() {
+        // 旧版本布局未经样本核对，与未知版本一样拒绝，不按猜测的字段顺序解析
+        for version in ["TEXV0001", "TEXV0002", "TEXV0003", "TEXV0009"] {
+            let data = sample(version, &[0, 32, 4, 2, 3, 1, 7]);
+            let err = read_tex(Cursor::new(data.clone())).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains(version), "{}", err);
+            assert!(read_tex_head(Cursor::new(data)).is_err());
+        }
+    }
+}
@@ -21,6 +21,53 @@ pub struct ConvertTexInput {
     pub file_path: PathBuf,
     /// 输出路径（目录或文件）
     pub output_path: PathBuf,
+    /// 严格校验直通图片（完整解码验证，无法校验的格式视为失败）
+    pub strict: bool,
+    /// LZ4 按声明大小解压失败时直接报告错误，不尝试其他解压策略
+    pub strict_lz4: bool,
+    /// 导出哪些 mipmap 层级（默认只导出第一层）
+    pub mipmap: MipmapSelection,
+    /// 将多帧 TEX 合成为动图，None 或只有一帧时按普通图片导出
+    pub animate: Option<AnimateOutput>,
+    /// 纹理格式（DXT / BC / RGBA8888 等）解码后的编码格式，直通图片与视频不受影响
+    pub output_format: ImageOutputFormat,
+    /// 上下翻转输出图片（TEX 头部没有翻转标志位，默认 false）
+    ///
+    /// 直通图片需解码后按原格式重新编码，无法重新编码的格式返回错误；视频不受影响
+    pub flip_vertical: bool,
+}
+
+/// decode_tex_to_rgba 接口入参
+#[derive(Debug, Clone)]
+pub struct DecodeTexInput {
+    /// TEX 文件路径
+    pub file_path: PathBuf,
+    /// LZ4 按声明大小解压失败时直接报告错误，不尝试其他解压策略
+    pub strict_lz4: bool,
+    /// 解码哪一层 mipmap（All 按第一层处理）
+    pub mipmap: MipmapSelection,
+    /// 上下翻转解码结果
+    pub flip_vertical: bool,
+}
+
+/// estimate_tex_output 接口入参
+#[derive(Debug, Clone)]
+pub struct EstimateTexInput {
+    /// TEX 开头的若干字节（至少包含文件头与图像容器头，4 KiB 足够）
+    pub head: Vec<u8>,
+    /// TEX 文件完整大小（字节）
+    pub size: u64,
+}
+
+/// make_thumbnail 接口入参
+#[derive(Debug, Clone)]
+pub struct MakeThumbnailInput {
+    /// 已转换的图片路径
+    pub image_path: PathBuf,
+    /// 缩略图输出目录
+    pub output_dir: PathBuf,
+    /// 最大边长（像素）
+    pub max_size: u32,
 }
 
 // ============================================================================
@@ -37,12 +84,47 @@ pub struct ParseTexOutput {
 /// convert_tex 接口返回值
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConvertTexOutput {
-    /// 转换后的文件信息
-    pub converted_file: ConvertedFile,
+    /// 写出的文件列表（按图像、mipmap 层级顺序；单图像且非 All 时只有一个）
+    pub converted_files: Vec<ConvertedFile>,
     /// TEX 文件信息
     pub tex_info: TexInfo,
 }
 
+/// decode_tex_to_rgba 接口返回值
+#[derive(Debug, Clone)]
+pub struct DecodeTexOutput {
+    /// 图像宽度
+    pub width: u32,
+    /// 图像高度
+    pub height: u32,
+    /// RGBA8 像素数据（逐行，每像素 4 字节）
+    pub rgba: Vec<u8>,
+    /// TEX 格式名称（如 "DXT5"、"PNG"）
+    pub format: String,
+}
+
+/// estimate_tex_output 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateTexOutput {
+    /// TEX 格式名称（如 "DXT5"、"MP4"）
+    pub format: String,
+    /// 是否为直通格式（视频 / 内嵌图片，转换时按原数据写出）
+    pub passthrough: bool,
+    /// 预估输出大小（字节）：直通格式为 TEX 大小，其余为解码后的 RGBA 大小
+    pub output_size: u64,
+}
+
+/// make_thumbnail 接口返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakeThumbnailOutput {
+    /// 缩略图路径
+    pub thumbnail_path: PathBuf,
+    /// 缩略图宽度
+    pub width: u32,
+    /// 缩略图高度
+    pub height: u32,
+}
+
 // ============================================================================
 // 运行时结构体（对外导出）
 // ============================================================================
@@ -54,6 +136,12 @@ pub struct TexInfo {
     pub version: String,
     /// 格式类型
     pub format: String,
+    /// 文件头中的原始 format 值（0 RGBA8888、4 DXT5、7 DXT1 等）
+    pub header_format: u32,
+    /// 第一个图像的 FreeImage 格式值（-1 表示非内嵌图片，由 header_format 决定）
+    pub image_format: i32,
+    /// 格式类别
+    pub format_kind: FormatKind,
     /// 图像宽度
     pub width: u32,
     /// 图像高度
@@ -62,12 +150,18 @@ pub struct TexInfo {
     pub image_count: usize,
     /// Mipmap 数量
     pub mipmap_count: usize,
+    /// 动画帧数（TEXS 精灵帧数，否则为图像数量）
+    pub frame_count: usize,
     /// 是否 LZ4 压缩
     pub is_compressed: bool,
     /// 是否视频
     pub is_video: bool,
     /// 数据大小（字节）
     pub data_size: usize,
+    /// convert_tex 实际写出的扩展名（纹理格式为默认 PNG 输出时的扩展名）
+    pub output_extension: String,
+    /// 是否为需要解码的纹理格式（输出格式由 ImageOutputFormat 决定）
+    pub is_texture: bool,
 }
 
 /// 转换后的文件信息
@@ -81,6 +175,148 @@ pub struct ConvertedFile {
     pub width: u32,
     /// 高度
     pub height: u32,
+    /// 来源图像序号（多图像 TEX 的纹理数组 / 帧，0 为第一个）
+    pub image_index: usize,
+    /// 来源 mipmap 层级（0 为第一层）
+    pub mipmap_level: usize,
+    /// 请求 JPEG 但纹理含透明像素，改为写出 PNG
+    pub alpha_fallback: bool,
+}
+
+/// 导出的 mipmap 层级
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MipmapSelection {
+    /// 第一层（与旧行为一致）
+    #[default]
+    First,
+    /// 面积（width*height）最大的一层
+    Largest,
+    /// 全部层级，每层写为 `{stem}_mip{n}.{ext}`（多图像时为 `{stem}-img{i}_mip{n}.{ext}`）
+    All,
+    /// 指定层级（从 0 开始）
+    Index(usize),
+}
+
+impl MipmapSelection {
+    /// 从字符串解析：first / largest / all / 层级序号（大小写不敏感）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "first" => Some(MipmapSelection::First),
+            "largest" => Some(MipmapSelection::Largest),
+            "all" => Some(MipmapSelection::All),
+            n => n.parse().ok().map(MipmapSelection::Index),
+        }
+    }
+
+    /// 获取选择的名称（parse 的逆操作）
+    pub fn name(&self) -> String {
+        match self {
+            MipmapSelection::First => "first".to_string(),
+            MipmapSelection::Largest => "largest".to_string(),
+            MipmapSelection::All => "all".to_string(),
+            MipmapSelection::Index(n) => n.to_string(),
+        }
+    }
+}
+
+/// 动图输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimateOutput {
+    /// GIF（256 色，兼容性最好）
+    Gif { fps: u16 },
+    /// APNG（保留完整颜色与透明度）
+    Apng { fps: u16 },
+}
+
+impl AnimateOutput {
+    /// 按格式名（gif / apng，大小写不敏感）与帧率构造
+    pub fn parse(s: &str, fps: u16) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "gif" => Some(AnimateOutput::Gif { fps }),
+            "apng" => Some(AnimateOutput::Apng { fps }),
+            _ => None,
+        }
+    }
+
+    /// 每秒帧数（至少为 1）
+    pub fn fps(&self) -> u16 {
+        match self {
+            AnimateOutput::Gif { fps } | AnimateOutput::Apng { fps } => (*fps).max(1),
+        }
+    }
+
+    /// 格式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimateOutput::Gif { .. } => "gif",
+            AnimateOutput::Apng { .. } => "apng",
+        }
+    }
+
+    /// 输出文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AnimateOutput::Gif { .. } => "gif",
+            AnimateOutput::Apng { .. } => "png",
+        }
+    }
+}
+
+/// 纹理解码后的输出图片格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageOutputFormat {
+    /// PNG（默认，无损并保留透明度）
+    #[default]
+    Png,
+    /// JPEG（有损，不支持透明度；含透明像素的纹理回退为 PNG）
+    Jpeg { quality: u8 },
+    /// WebP（image crate 的编码器只支持无损，quality 暂不生效）
+    WebP { quality: u8 },
+    /// TIFF（无损）
+    Tiff,
+}
+
+impl ImageOutputFormat {
+    /// 按格式名（png / jpeg / jpg / webp / tiff / tif，大小写不敏感）与质量构造
+    pub fn parse(s: &str, quality: u8) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "png" => Some(ImageOutputFormat::Png),
+            "jpeg" | "jpg" => Some(ImageOutputFormat::Jpeg { quality }),
+            "webp" => Some(ImageOutputFormat::WebP { quality }),
+            "tiff" | "tif" => Some(ImageOutputFormat::Tiff),
+            _ => None,
+        }
+    }
+
+    /// 编码质量（1-100），无损格式为 None
+    pub fn quality(&self) -> Option<u8> {
+        match self {
+            ImageOutputFormat::Jpeg { quality } | ImageOutputFormat::WebP { quality } => {
+                Some((*quality).clamp(1, 100))
+            }
+            _ => None,
+        }
+    }
+
+    /// 格式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Png => "png",
+            ImageOutputFormat::Jpeg { .. } => "jpeg",
+            ImageOutputFormat::WebP { .. } => "webp",
+            ImageOutputFormat::Tiff => "tiff",
+        }
+    }
+
+    /// 输出文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Png => "png",
+            ImageOutputFormat::Jpeg { .. } => "jpg",
+            ImageOutputFormat::WebP { .. } => "webp",
+            ImageOutputFormat::Tiff => "tif",
+        }
+    }
 }
 
 // ============================================================================
@@ -90,8 +326,36 @@ pub struct ConvertedFile {
 /// TEX 文件完整结构（内部使用）
 #[derive(Debug, Clone)]
 pub struct TexFile {
+    /// 文件版本（目前只支持 TEXV0005）
+    pub version: String,
     pub header: TexHeader,
     pub images: Vec<TexImage>,
+    /// 精灵动画帧信息（flags 含 IsGif 时存在）
+    pub frame_info: Option<TexFrameInfo>,
+}
+
+/// TEXS 精灵动画帧信息
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct TexFrameInfo {
+    /// 动图画布宽度（仅 TEXS0003）
+    pub gif_width: u32,
+    /// 动图画布高度（仅 TEXS0003）
+    pub gif_height: u32,
+    pub frames: Vec<TexFrame>,
+}
+
+/// 单个精灵帧：在第 image_id 个图像（精灵图）中的矩形区域
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct TexFrame {
+    pub image_id: usize,
+    /// 帧时长（秒）
+    pub frametime: f32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
 }
 
 /// TEX 文件头
@@ -125,6 +389,22 @@ pub struct TexMipmap {
     pub data: Vec<u8>,
 }
 
+/// TEX 格式类别，序列化为 snake_case 字符串
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatKind {
+    /// 内嵌图片（PNG / JPEG 等），原样写出
+    Passthrough,
+    /// 内嵌 MP4 视频
+    Video,
+    /// 块压缩纹理（DXT / BC），需要解码
+    Bcn,
+    /// 未压缩纹理，需要解码
+    Raw,
+    /// 无法识别的格式
+    Invalid,
+}
+
 /// Mipmap 格式枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -194,6 +474,17 @@ impl MipmapFormat {
         )
     }
 
+    /// 格式类别：内嵌图片不经解码原样写出，BCn 与未压缩纹理需要解码
+    pub fn kind(&self) -> FormatKind {
+        match self {
+            MipmapFormat::Invalid => FormatKind::Invalid,
+            MipmapFormat::VideoMp4 => FormatKind::Video,
+            f if f.is_image() => FormatKind::Passthrough,
+            f if f.is_compressed() => FormatKind::Bcn,
+            _ => FormatKind::Raw,
+        }
+    }
+
     /// 获取格式名称
     pub fn name(&self) -> &'static str {
         match self {
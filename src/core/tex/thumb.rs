@@ -0,0 +1,122 @@
+//! 缩略图接口 - 为转换后的图片生成等比缩放的缩略图
+
+use std::fs;
+use std::path::Path;
+
+use image::imageops::FilterType;
+
+use crate::core::error::{CoreError, CoreResult};
+use crate::core::path::long_path;
+use crate::core::tex::structs::{MakeThumbnailInput, MakeThumbnailOutput};
+
+/// 生成缩略图
+/// 最长边缩放到 max_size（不放大），保持宽高比；JPEG 输出 `.jpg`，其余输出 `.png`
+pub fn make_thumbnail(input: MakeThumbnailInput) -> CoreResult<MakeThumbnailOutput> {
+    let image_path = input.image_path;
+
+    let is_jpeg = image_path
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"))
+        .unwrap_or(false);
+    let ext = if is_jpeg { "jpg" } else { "png" };
+    let stem = image_path.file_stem().unwrap_or_default().to_string_lossy();
+    let thumbnail_path = input.output_dir.join(format!("{}.{}", stem, ext));
+
+    let (width, height) = write_thumbnail(
+        &image_path,
+        &thumbnail_path,
+        input.max_size,
+        FilterType::Lanczos3,
+    )?;
+
+    Ok(MakeThumbnailOutput {
+        thumbnail_path,
+        width,
+        height,
+    })
+}
+
+/// 读取图片并写出缩略图，返回缩略图尺寸（TEX 产物与壁纸预览图共用）
+///
+/// 最长边缩放到 max_dim（不放大）；多帧图片只取首帧；
+/// output 扩展名为 jpg/jpeg 时按 JPEG 编码（去掉 alpha），其余一律按 PNG 编码
+pub(crate) fn write_thumbnail(
+    source: &Path,
+    output: &Path,
+    max_dim: u32,
+    filter: FilterType,
+) -> CoreResult<(u32, u32)> {
+    if max_dim == 0 {
+        return Err(CoreError::Validation {
+            message: "Thumbnail size must be greater than 0".to_string(),
+        });
+    }
+
+    // image::open 对 GIF 只解码第一帧
+    let img = image::open(long_path(source)).map_err(|e| CoreError::Parse {
+        message: e.to_string(),
+        source: Some(source.display().to_string()),
+    })?;
+
+    let thumb = if img.width().max(img.height()) > max_dim {
+        img.resize(max_dim, max_dim, filter)
+    } else {
+        img
+    };
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(long_path(parent)).map_err(|e| CoreError::Io {
+            message: e.to_string(),
+            path: Some(parent.display().to_string()),
+        })?;
+    }
+
+    let is_jpeg = output
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"))
+        .unwrap_or(false);
+
+    // JPEG 不支持 alpha 通道
+    let saved = if is_jpeg {
+        image::DynamicImage::ImageRgb8(thumb.to_rgb8())
+            .save_with_format(long_path(output), image::ImageFormat::Jpeg)
+    } else {
+        thumb.save_with_format(long_path(output), image::ImageFormat::Png)
+    };
+    saved.map_err(|e| CoreError::Io {
+        message: e.to_string(),
+        path: Some(output.display().to_string()),
+    })?;
+
+    Ok((thumb.width(), thumb.height()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_thumbnail_keeps_aspect_ratio() {
+        let dir = std::env::temp_dir().join("lianpkg_make_thumbnail");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("wide.png");
+        image::RgbaImage::new(1024, 256).save(&image_path).unwrap();
+
+        let output = make_thumbnail(MakeThumbnailInput {
+            image_path,
+            output_dir: dir.join("thumbs"),
+            max_size: 512,
+        })
+        .unwrap();
+
+        assert_eq!((output.width, output.height), (512, 128));
+        assert_eq!(output.thumbnail_path, dir.join("thumbs/wide.png"));
+        assert_eq!(
+            image::image_dimensions(&output.thumbnail_path).unwrap(),
+            (512, 128)
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,43 @@
-pub mod core;
+//! lianpkg - Wallpaper Engine 壁纸资源提取与转换库
+//!
+//! ## 入口
+//!
+//! | 路径 | 说明 |
+//! |------|------|
+//! | `lianpkg::native` | **推荐入口**，高级 API（配置、壁纸、PKG、TEX、流水线） |
+//! | `lianpkg::core` | 底层模块，Input/Output 风格的原子操作 |
+//! | `lianpkg::prelude` | 常用类型与函数的预导出 |
+//!
+//! `lianpkg::native` 等价于 `lianpkg::api::native`。
+//!
+//! ## 示例
+//!
+//! 完整示例见 `examples/pipeline.rs`：
+//! ```bash
+//! cargo run --example pipeline -- [配置目录]
+//! ```
+
 pub mod api;
+pub mod core;
+
+// 推荐入口：原生 API
+pub use api::native;
+
+// 兼容旧路径（lianpkg::ffi / lianpkg::types，后者已弃用）
+pub use api::ffi;
+#[allow(deprecated)]
+pub use api::types;
 
-// Re-export API for external use
-pub use api::*;
+/// 常用类型与函数预导出
+///
+/// ```rust,ignore
+/// use lianpkg::prelude::*;
+/// ```
+pub mod prelude {
+    pub use crate::api::native::{
+        init_config, load_config, progress_callback, run_pipeline, InitConfigInput,
+        LoadConfigInput, PipelineOverrides, PipelineProgress, PipelineStats, RunPipelineInput,
+        RunPipelineOutput, RuntimeConfig,
+    };
+    pub use crate::core::error::{CoreError, CoreResult};
+}